@@ -0,0 +1,92 @@
+//! Turns the embedded word lists and the glossary in `data/` into static
+//! slices, so starting a game does not parse them. Without the
+//! `bundled-words` feature the slices are empty.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Lowercased words of a list, one per line, in the order of the file.
+fn read_list(path: &str) -> Vec<String> {
+    println!("cargo:rerun-if-changed={}", path);
+    let contents = fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    contents.lines().map(|line| line.to_lowercase()).collect()
+}
+
+fn write_slice(code: &mut String, doc: &str, name: &str, words: &[&str]) {
+    writeln!(code, "/// {}", doc).unwrap();
+    writeln!(code, "pub static {}: &[&str] = &[", name).unwrap();
+    for word in words {
+        writeln!(code, "    {:?},", word).unwrap();
+    }
+    writeln!(code, "];").unwrap();
+}
+
+fn main() {
+    let bundled = env::var_os("CARGO_FEATURE_BUNDLED_WORDS").is_some();
+    let list = |path| {
+        if bundled {
+            read_list(path)
+        } else {
+            Vec::new()
+        }
+    };
+    let solutions = list("data/picked_words.txt");
+    let valid = list("data/valid_words.txt");
+    let solutions: Vec<&str> = solutions.iter().map(String::as_str).collect();
+    let words: Vec<&str> = solutions
+        .iter()
+        .copied()
+        .chain(valid.iter().map(String::as_str))
+        .collect();
+
+    let mut code = String::new();
+    write_slice(
+        &mut code,
+        "Words a solution is picked from, in the order of the list.",
+        "SOLUTIONS",
+        &solutions,
+    );
+    write_slice(
+        &mut code,
+        "All valid guesses, the solutions first.",
+        "WORDS",
+        &words,
+    );
+
+    // the glossary only explains solutions, sorted for binary search
+    let path = "data/glossary.txt";
+    let contents = if bundled {
+        println!("cargo:rerun-if-changed={}", path);
+        fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+    } else {
+        String::new()
+    };
+    let mut glossary: Vec<(&str, &str)> = contents
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (word, gloss) = line
+                .split_once('\t')
+                .unwrap_or_else(|| panic!("{}: expected a word and a tab: {}", path, line));
+            assert!(
+                solutions.contains(&word),
+                "{}: {} is not a solution",
+                path,
+                word
+            );
+            (word, gloss)
+        })
+        .collect();
+    glossary.sort_unstable();
+    writeln!(code, "/// Explanations of solutions, sorted by word.").unwrap();
+    writeln!(code, "pub static GLOSSARY: &[(&str, &str)] = &[").unwrap();
+    for (word, gloss) in glossary {
+        writeln!(code, "    ({:?}, {:?}),", word, gloss).unwrap();
+    }
+    writeln!(code, "];").unwrap();
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_words.rs");
+    fs::write(out, code).unwrap();
+}
@@ -0,0 +1,398 @@
+//! `rordle battle`: two players at one keyboard take turns setting a secret
+//! word for the other one to guess. The guesser scores the tries left over
+//! when they find the word, whoever has more points after all rounds wins.
+
+use crate::cli::{game_options, hint_budget, interface, language, Interface, ListArgs};
+use crate::error::RordleError;
+use crate::render::tile_word;
+use crate::terminal::Terminal;
+use crate::{key_loop, key_name, load_word_list, new_game, on_terminal};
+use clap::ArgMatches;
+use rordle_core::config::{Config, KeyName};
+use rordle_core::game::{GameOptions, GameState, Outcome};
+use rordle_core::i18n::{self, tr};
+use rordle_core::words::MergedWordList;
+use std::io::{stdin, Write};
+use termion::event::Key;
+use termion::input::TermRead;
+
+/// Points for a round: the tries left when the word was found, counting the
+/// one finding it, nothing if it was not found.
+fn points(game_state: &GameState) -> u32 {
+    if game_state.won() {
+        (game_state.max_tries as usize + 1 - game_state.guesses.len()) as u32
+    } else {
+        0
+    }
+}
+
+/// The running score of a battle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Score {
+    players: [String; 2],
+    points: [u32; 2],
+    /// Rounds played to the end.
+    played: usize,
+}
+
+impl Score {
+    fn new(players: [String; 2]) -> Score {
+        Score {
+            players,
+            points: [0, 0],
+            played: 0,
+        }
+    }
+
+    /// The score of both players, e.g. `Player 1: 5  Player 2: 3`.
+    fn banner(&self) -> String {
+        format!(
+            "{}: {}  {}: {}",
+            self.players[0], self.points[0], self.players[1], self.points[1]
+        )
+    }
+
+    /// Index of the player with more points, `None` for a draw.
+    fn leader(&self) -> Option<usize> {
+        match self.points[0].cmp(&self.points[1]) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Message announcing the winner, or the partial score if the battle
+    /// was stopped before all `rounds` were played.
+    fn result(&self, rounds: usize) -> String {
+        if self.played < rounds {
+            return tr(
+                "battle-stopped",
+                &[("played", &self.played), ("rounds", &rounds)],
+            );
+        }
+        match self.leader() {
+            Some(player) => tr("battle-winner", &[("player", &self.players[player])]),
+            None => tr("battle-draw", &[]),
+        }
+    }
+}
+
+/// Clears the terminal and writes `lines` from the top left. Lines are
+/// positioned explicitly, as raw mode does not return to the left.
+fn screen<W: Write>(output: &mut W, lines: &[String]) -> std::io::Result<()> {
+    write!(output, "{}", termion::clear::All)?;
+    for (y, line) in lines.iter().enumerate() {
+        write!(output, "{}{}", termion::cursor::Goto(1, y as u16 + 1), line)?;
+    }
+    output.flush()
+}
+
+/// Reads a secret word typed after `prompt`, showing a `*` for each letter.
+/// Words rejected by `check` show its error and can be corrected. Returns
+/// `None` if the quit key was pressed or the keys ran out.
+fn read_secret<K, W>(
+    keys: &mut K,
+    output: &mut W,
+    interface: &Interface,
+    prompt: &[String],
+    check: impl Fn(&str) -> Result<(), String>,
+) -> std::io::Result<Option<String>>
+where
+    K: Iterator<Item = std::io::Result<Key>>,
+    W: Write,
+{
+    let bindings = &interface.keybindings;
+    let mut secret = String::new();
+    let mut error = None;
+    loop {
+        let mut lines = prompt.to_vec();
+        lines.push("*".repeat(secret.chars().count()));
+        lines.extend(error.take());
+        screen(output, &lines)?;
+        let key = match keys.next() {
+            Some(key) => key_name(key?),
+            None => return Ok(None),
+        };
+        match key {
+            Some(key) if key == bindings.quit => return Ok(None),
+            Some(key) if key == bindings.delete => {
+                secret.pop();
+            }
+            Some(key) if key == bindings.submit => match check(&secret) {
+                Ok(()) => return Ok(Some(secret)),
+                Err(message) => error = Some(message),
+            },
+            Some(KeyName::Char(c)) if c.is_alphabetic() => secret.extend(c.to_lowercase()),
+            _ => {}
+        }
+    }
+}
+
+/// Waits for the submit key, `false` if the quit key was pressed or the
+/// keys ran out.
+fn confirm<K>(keys: &mut K, interface: &Interface) -> std::io::Result<bool>
+where
+    K: Iterator<Item = std::io::Result<Key>>,
+{
+    for key in keys {
+        match key_name(key?) {
+            Some(key) if key == interface.keybindings.submit => return Ok(true),
+            Some(key) if key == interface.keybindings.quit => return Ok(false),
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+fn terminal(error: std::io::Error) -> RordleError {
+    RordleError::Terminal(error)
+}
+
+/// Plays `rounds` rounds, the players setting the word in turns, starting
+/// with the first one. Stops early if a player quits, `score` then holds
+/// the rounds played so far.
+fn battle_loop<K, W>(
+    keys: &mut K,
+    output: &mut W,
+    word_list: &MergedWordList,
+    options: &GameOptions,
+    interface: &Interface,
+    rounds: usize,
+    score: &mut Score,
+) -> Result<(), RordleError>
+where
+    K: Iterator<Item = std::io::Result<Key>>,
+    W: Write,
+{
+    for round in 0..rounds {
+        let setter = round % 2;
+        let guesser = 1 - setter;
+        let heading = tr(
+            "battle-round",
+            &[("round", &(round + 1)), ("rounds", &rounds)],
+        );
+        let prompt = [
+            heading.clone(),
+            score.banner(),
+            String::new(),
+            tr(
+                "battle-setter",
+                &[
+                    ("setter", &score.players[setter]),
+                    ("guesser", &score.players[guesser]),
+                ],
+            ),
+        ];
+        // only words the guesser could also guess are accepted
+        let check = |word: &str| {
+            if word_list.words.iter().any(|w| w == word) {
+                Ok(())
+            } else {
+                Err(tr("invalid-word", &[]))
+            }
+        };
+        let secret = match read_secret(keys, output, interface, &prompt, check).map_err(terminal)? {
+            Some(secret) => secret,
+            None => return Ok(()),
+        };
+
+        let mut game_state = new_game(options.clone(), word_list.clone(), secret)?;
+        game_state.list_name = Some(format!(
+            "{}: {}",
+            heading,
+            tr("battle-guesses", &[("guesser", &score.players[guesser])])
+        ));
+        // the board replaces the prompt, the secret was never shown
+        key_loop(&mut game_state, &mut *keys, output, interface).map_err(terminal)?;
+        if game_state.outcome() == Outcome::Abandoned {
+            return Ok(());
+        }
+        let points = points(&game_state);
+        score.points[guesser] += points;
+        score.played += 1;
+        if round + 1 == rounds {
+            return Ok(());
+        }
+
+        let word: &dyn std::fmt::Display = &tile_word(&game_state.word, interface.render.uppercase);
+        let player: &dyn std::fmt::Display = &score.players[guesser];
+        let result = if game_state.won() {
+            tr(
+                "battle-round-won",
+                &[
+                    ("player", player),
+                    ("word", word),
+                    ("guesses", &game_state.guesses.len()),
+                    ("points", &points),
+                ],
+            )
+        } else {
+            tr("battle-round-lost", &[("player", player), ("word", word)])
+        };
+        let lines = [
+            result,
+            score.banner(),
+            String::new(),
+            tr("battle-next", &[]),
+        ];
+        screen(output, &lines).map_err(terminal)?;
+        if !confirm(keys, interface).map_err(terminal)? {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// `rordle battle`: plays a battle on the terminal. Exits with 0 once all
+/// rounds were played, with 4 if it was stopped early.
+pub(crate) fn battle(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    i18n::set_language(language(matches, config));
+    let word_list = load_word_list(&ListArgs::from_matches(matches), config, false)?;
+    if word_list.words.is_empty() {
+        return Err(RordleError::EmptyWordList);
+    }
+    let options = game_options(matches, config).hint_budget(hint_budget(matches));
+    let interface = on_terminal(&interface(matches, config));
+    let rounds: usize = matches.value_of("rounds").unwrap().parse().unwrap();
+    let players = [1, 2].map(|number| tr("battle-player", &[("number", &number)]));
+    let mut score = Score::new(players);
+
+    let mut output = Terminal::open(interface.altscreen).map_err(terminal)?;
+    let result = battle_loop(
+        &mut stdin().keys(),
+        &mut output,
+        &word_list,
+        &options,
+        &interface,
+        rounds,
+        &mut score,
+    );
+    // with `--no-altscreen` the last board stays visible above the score
+    drop(output);
+    result?;
+    println!();
+    println!("{}", score.banner());
+    println!("{}", score.result(rounds));
+    Ok(if score.played == rounds { 0 } else { 4 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rordle_core::words::ListHeader;
+
+    fn word_list() -> MergedWordList {
+        let words: Vec<String> = ["hello", "jolly", "world"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        MergedWordList {
+            header: ListHeader::default(),
+            solutions: words.clone(),
+            words,
+            ..MergedWordList::default()
+        }
+    }
+
+    fn test_interface() -> Interface {
+        let matches = crate::cli::app().get_matches_from(["rordle", "--no-animations"]);
+        interface(&matches, &Config::default())
+    }
+
+    /// Keys typing `text`, `\x7f` is Backspace and `\x1b` is Esc.
+    fn keys(text: &str) -> impl Iterator<Item = std::io::Result<Key>> + '_ {
+        text.chars()
+            .map(|c| match c {
+                '\x7f' => Key::Backspace,
+                '\x1b' => Key::Esc,
+                c => Key::Char(c),
+            })
+            .map(Ok)
+    }
+
+    fn players() -> [String; 2] {
+        ["Ann".to_string(), "Bob".to_string()]
+    }
+
+    #[test]
+    fn test_secret_is_not_shown() {
+        let mut output = Vec::new();
+        let prompt = ["Ann, type a word".to_string()];
+        let secret = read_secret(
+            &mut keys("hELlo\n"),
+            &mut output,
+            &test_interface(),
+            &prompt,
+            |_| Ok(()),
+        )
+        .unwrap();
+        assert_eq!(secret.as_deref(), Some("hello"));
+        let output = String::from_utf8(output).unwrap();
+        // neither the prompt nor the escape codes contain these letters
+        assert!(!output.contains(['h', 'l']));
+        assert!(output.contains("*****"));
+    }
+
+    #[test]
+    fn test_rejected_secret() {
+        let mut output = Vec::new();
+        let check = |word: &str| match word {
+            "hello" => Ok(()),
+            _ => Err("not a word".to_string()),
+        };
+        let secret = read_secret(
+            &mut keys("zz\n\x7fa\x1b"),
+            &mut output,
+            &test_interface(),
+            &[],
+            check,
+        )
+        .unwrap();
+        assert_eq!(secret, None);
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("not a word").count(), 1);
+        assert!(output.ends_with("**"));
+    }
+
+    #[test]
+    fn test_battle() {
+        // Bob finds hello at once, Ann finds jolly with the second guess;
+        // a key leaves each finished board
+        let input = "hello\nhello\n \njolly\nhello\njolly\n ";
+        let mut score = Score::new(players());
+        battle_loop(
+            &mut keys(input),
+            &mut Vec::new(),
+            &word_list(),
+            &GameOptions::default(),
+            &test_interface(),
+            2,
+            &mut score,
+        )
+        .unwrap();
+        assert_eq!(score.points, [5, 6]);
+        assert_eq!(score.played, 2);
+        assert_eq!(score.banner(), "Ann: 5  Bob: 6");
+        assert_eq!(score.result(2), "Bob wins the battle!");
+    }
+
+    #[test]
+    fn test_stopped_battle() {
+        // the first word of Ann is rejected, Ann gives up on the second round
+        let input = "hellp\n\x7fo\nhello\n \nworld\nhello\n\x1b";
+        let mut score = Score::new(players());
+        battle_loop(
+            &mut keys(input),
+            &mut Vec::new(),
+            &word_list(),
+            &GameOptions::default(),
+            &test_interface(),
+            3,
+            &mut score,
+        )
+        .unwrap();
+        assert_eq!(score.points, [0, 6]);
+        assert_eq!(score.played, 1);
+        assert_eq!(score.result(3), "Battle stopped after 1 of 3 rounds.");
+    }
+}
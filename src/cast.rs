@@ -0,0 +1,147 @@
+//! `--record`: the session on the terminal as an asciinema v2 cast, which
+//! `asciinema play` replays. A cast is a JSON header followed by one JSON
+//! array per chunk of output, `[seconds, "o", text]`.
+
+use rordle_core::json::Json;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+/// Where a session is recorded.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Recording<'a> {
+    pub(crate) path: &'a Path,
+    /// The game is a daily puzzle, whose solution is only recorded once it
+    /// is finished.
+    pub(crate) daily: bool,
+}
+
+/// Passes output on to the terminal and, if recording, keeps a copy of it
+/// with the time it was written.
+pub(crate) struct Tee<W: Write> {
+    inner: W,
+    start: Instant,
+    /// `None` if not recording.
+    events: Option<Vec<(f64, String)>>,
+    /// The start of a character split between two writes.
+    partial: Vec<u8>,
+}
+
+impl<W: Write> Tee<W> {
+    pub(crate) fn new(inner: W, recording: bool) -> Tee<W> {
+        Tee {
+            inner,
+            start: Instant::now(),
+            events: recording.then(Vec::new),
+            partial: Vec::new(),
+        }
+    }
+
+    /// Records `text` as written now, without writing it, for output which
+    /// bypasses the terminal stream.
+    pub(crate) fn record(&mut self, text: &str) {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        if let Some(events) = &mut self.events {
+            events.push(((elapsed * 1000.0).round() / 1000.0, text.to_string()));
+        }
+    }
+
+    /// The stream written to and the recorded output.
+    pub(crate) fn into_parts(self) -> (W, Option<Vec<(f64, String)>>) {
+        (self.inner, self.events)
+    }
+}
+
+impl<W: Write> Write for Tee<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if self.events.is_some() {
+            self.partial.extend_from_slice(&buf[..written]);
+            // a character may be split by the buffering, its rest follows
+            let valid = match std::str::from_utf8(&self.partial) {
+                Ok(_) => self.partial.len(),
+                Err(error) if error.error_len().is_none() => error.valid_up_to(),
+                Err(_) => self.partial.len(),
+            };
+            let bytes: Vec<u8> = self.partial.drain(..valid).collect();
+            if !bytes.is_empty() {
+                self.record(&String::from_utf8_lossy(&bytes));
+            }
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The cast of `events` on a terminal of `size`, started at the unix time
+/// `timestamp`.
+pub(crate) fn cast(events: &[(f64, String)], size: (u16, u16), timestamp: i64) -> String {
+    let header = Json::object([
+        ("version", Json::from(2)),
+        ("width", Json::from(size.0 as usize)),
+        ("height", Json::from(size.1 as usize)),
+        ("timestamp", Json::Number(timestamp as f64)),
+    ]);
+    let mut cast = format!("{}\n", header);
+    for (time, text) in events {
+        let event = Json::Array(vec![
+            Json::Number(*time),
+            Json::from("o"),
+            Json::from(text.as_str()),
+        ]);
+        cast.push_str(&format!("{}\n", event));
+    }
+    cast
+}
+
+/// Writes the cast of `events` on a terminal of `size` to `path`.
+pub(crate) fn write_cast(
+    path: &Path,
+    events: &[(f64, String)],
+    size: (u16, u16),
+    timestamp: i64,
+) -> std::io::Result<()> {
+    std::fs::write(path, cast(events, size, timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tee() {
+        let mut tee = Tee::new(Vec::new(), true);
+        let text = "Grüße\r\n".as_bytes();
+        // the ü is split between two writes
+        tee.write_all(&text[..3]).unwrap();
+        tee.write_all(&text[3..]).unwrap();
+        tee.record("You won!");
+        let (written, events) = tee.into_parts();
+        assert_eq!(written, text);
+        let events = events.unwrap();
+        let texts: Vec<&str> = events.iter().map(|(_, text)| text.as_str()).collect();
+        assert_eq!(texts, ["Gr", "üße\r\n", "You won!"]);
+        assert!(events.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+        let mut tee = Tee::new(Vec::new(), false);
+        tee.write_all(b"hello").unwrap();
+        assert_eq!(tee.into_parts(), (b"hello".to_vec(), None));
+    }
+
+    #[test]
+    fn test_cast() {
+        let events = [
+            (0.0, "\u{1b}[2J".to_string()),
+            (1.25, "hello\r\n".to_string()),
+        ];
+        assert_eq!(
+            cast(&events, (120, 40), 1645747200),
+            "{\"version\":2,\"width\":120,\"height\":40,\"timestamp\":1645747200}\n\
+             [0,\"o\",\"\\u001b[2J\"]\n\
+             [1.25,\"o\",\"hello\\r\\n\"]\n"
+        );
+    }
+}
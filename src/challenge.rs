@@ -0,0 +1,103 @@
+//! Challenge codes, which let a player share a solution without spoiling
+//! it. `rordle play --challenge CODE` plays the encoded word.
+//!
+//! A code is not encryption, it only keeps the word from being read at a
+//! glance. The letters are scrambled depending on their position and a
+//! checksum catches mistyped codes.
+
+/// A solution shared as a code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    /// The solution.
+    pub word: String,
+}
+
+/// Scrambles byte `i` of a word, applying it twice restores the byte.
+fn scramble(i: usize, byte: u8) -> u8 {
+    byte ^ 0x9e_u8.wrapping_add((i as u8).wrapping_mul(0x3b))
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0xa5, |sum, byte| sum.wrapping_add(*byte))
+}
+
+impl std::fmt::Display for Challenge {
+    /// Writes the code of the solution.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let bytes = self.word.as_bytes();
+        for (i, byte) in bytes.iter().enumerate() {
+            write!(f, "{:02x}", scramble(i, *byte))?;
+        }
+        write!(f, "{:02x}", checksum(bytes))
+    }
+}
+
+impl std::str::FromStr for Challenge {
+    type Err = String;
+
+    /// Decodes a code written by `Display`.
+    fn from_str(code: &str) -> Result<Challenge, String> {
+        let invalid = || format!("invalid challenge code `{}`", code);
+        if code.len() < 4 || !code.len().is_multiple_of(2) || !code.is_ascii() {
+            return Err(invalid());
+        }
+        let mut bytes = (0..code.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&code[i..i + 2], 16).map_err(|_| invalid()))
+            .collect::<Result<Vec<u8>, String>>()?;
+        let sum = bytes.pop().unwrap();
+        let bytes: Vec<u8> = bytes
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| scramble(i, *byte))
+            .collect();
+        if checksum(&bytes) != sum {
+            return Err(invalid());
+        }
+        match String::from_utf8(bytes) {
+            Ok(word) => Ok(Challenge { word }),
+            Err(_) => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for word in ["hello", "sheep", "käse", "a"] {
+            let challenge = Challenge {
+                word: word.to_string(),
+            };
+            let code = challenge.to_string();
+            assert!(!code.contains(word));
+            assert_eq!(code.parse(), Ok(challenge));
+        }
+    }
+
+    #[test]
+    fn test_repeated_letters_are_hidden() {
+        let code = Challenge {
+            word: "lllll".to_string(),
+        }
+        .to_string();
+        let pairs: Vec<&str> = (0..10).step_by(2).map(|i| &code[i..i + 2]).collect();
+        assert!(pairs.windows(2).all(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn test_invalid_codes() {
+        let mut code = Challenge {
+            word: "hello".to_string(),
+        }
+        .to_string();
+        // a typo in the last digit breaks the checksum
+        let last = code.pop().unwrap();
+        code.push(if last == '0' { '1' } else { '0' });
+        for code in [code.as_str(), "", "ab", "abc", "zzzz", "ääää"] {
+            assert!(code.parse::<Challenge>().is_err(), "{}", code);
+        }
+    }
+}
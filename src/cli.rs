@@ -0,0 +1,1523 @@
+use crate::completions::SHELLS;
+use crate::define::Define;
+use crate::error::RordleError;
+use crate::notify::Notifier;
+use crate::output::OutputFormat;
+use crate::render::{Layout, RenderOptions, DEFAULT_SIZE};
+use crate::summary::SummaryFormat;
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches};
+use rordle_core::challenge::Challenge;
+use rordle_core::config::{Align, Config, Keybindings, Theme};
+use rordle_core::date::{DailyEpoch, Date};
+use rordle_core::digraph::Digraphs;
+use rordle_core::game::GameOptions;
+use rordle_core::i18n::Language;
+use rordle_core::parallel;
+use rordle_core::paths::{self, validate_profile, DataFile};
+use rordle_core::pattern::Pattern;
+use rordle_core::solver::{Feedback, Weighting};
+use rordle_core::words::{
+    named_source, word_file_source, EmbeddedSource, InlineSource, KeyboardLayout, SystemDictSource,
+    TagFilter, WordFileError, WordSource,
+};
+use std::path::PathBuf;
+
+/// Arguments selecting the word list, shared by all subcommands using one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ListArgs<'a> {
+    pub(crate) list: Option<&'a str>,
+    pub(crate) word_files: Vec<&'a str>,
+    pub(crate) words: Option<&'a str>,
+    pub(crate) digraphs: Option<&'a str>,
+    pub(crate) solutions_from_all: bool,
+    pub(crate) stream: bool,
+    pub(crate) length: Option<usize>,
+    pub(crate) system_dict: bool,
+    pub(crate) no_plurals: bool,
+    pub(crate) clean: bool,
+}
+
+impl<'a> ListArgs<'a> {
+    /// Definition of the arguments.
+    pub(crate) fn args() -> Vec<Arg<'static>> {
+        vec![
+            Arg::new("word-file")
+                .short('w')
+                .long("word-file")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .help("Use a word list from a file or http:// URL, may be given multiple times"),
+            Arg::new("words")
+                .long("words")
+                .takes_value(true)
+                .value_name("WORDS")
+                .conflicts_with_all(&["word-file", "list", "system-dict"])
+                .help("Use these comma separated words as the word list, e.g. 'apple,bread,crane'"),
+            Arg::new("digraphs")
+                .long("digraphs")
+                .takes_value(true)
+                .value_name("DIGRAPHS")
+                .validator(|v| v.parse::<Digraphs>())
+                .help("Play these comma separated digraphs as single letters, e.g. 'ch,ll,rr'"),
+            Arg::new("solutions-from-all")
+                .long("solutions-from-all")
+                .takes_value(false)
+                .requires("word-file")
+                .help("Pick the solution from all word files, not only the first"),
+            Arg::new("list")
+                .short('l')
+                .long("list")
+                .visible_alias("language")
+                .takes_value(true)
+                .conflicts_with("word-file")
+                .help("Use a built-in word list or one registered in the config file"),
+            Arg::new("stream-wordlist")
+                .long("stream-wordlist")
+                .takes_value(false)
+                .requires("word-file")
+                .help("Stream word files instead of reading them into memory"),
+            Arg::new("length")
+                .long("length")
+                .takes_value(true)
+                .validator(|v| v.parse::<usize>())
+                .conflicts_with("stream-wordlist")
+                .help("Only use words with this number of letters"),
+            Arg::new("system-dict")
+                .long("system-dict")
+                .takes_value(false)
+                .conflicts_with("list")
+                .help("Add the words of /usr/share/dict/words"),
+            Arg::new("clean")
+                .long("clean")
+                .takes_value(false)
+                .requires("word-file")
+                .conflicts_with("stream-wordlist")
+                .help("Skip names, abbreviations and single letters of word files, like --system-dict does"),
+            Arg::new("no-plurals")
+                .long("no-plurals")
+                .takes_value(false)
+                .conflicts_with("stream-wordlist")
+                .help("Skip solutions which look like the plural of another word, they can still be guessed"),
+        ]
+    }
+
+    /// Reads the arguments from the matches of a command defined with `args`.
+    pub(crate) fn from_matches(matches: &'a ArgMatches) -> ListArgs<'a> {
+        ListArgs {
+            list: matches.value_of("list"),
+            word_files: matches
+                .values_of("word-file")
+                .map(|files| files.collect())
+                .unwrap_or_default(),
+            words: matches.value_of("words"),
+            digraphs: matches.value_of("digraphs"),
+            solutions_from_all: matches.is_present("solutions-from-all"),
+            stream: matches.is_present("stream-wordlist"),
+            length: matches.value_of("length").map(|v| v.parse().unwrap()),
+            system_dict: matches.is_present("system-dict"),
+            no_plurals: matches.is_present("no-plurals"),
+            clean: matches.is_present("clean"),
+        }
+    }
+
+    /// Sources of the selected list in the order they are merged in. The
+    /// built-in list is used if nothing is selected.
+    pub(crate) fn sources(
+        &self,
+        config: &Config,
+    ) -> Result<Vec<Box<dyn WordSource>>, WordFileError> {
+        let mut sources = Vec::new();
+        if let Some(name) = self.list {
+            sources.push(named_source(name, config)?);
+        }
+        if let Some(words) = self.words {
+            sources.push(Box::new(InlineSource {
+                words: words.to_string(),
+            }));
+        }
+        sources.extend(
+            self.word_files
+                .iter()
+                .map(|file| word_file_source(file, self.clean)),
+        );
+        if self.system_dict {
+            sources.push(Box::new(SystemDictSource::default()));
+        }
+        if sources.is_empty() {
+            sources.push(Box::new(EmbeddedSource));
+        }
+        Ok(sources)
+    }
+}
+
+/// `--format` of the commands listing stats.
+fn format_arg() -> Arg<'static> {
+    Arg::new("format")
+        .long("format")
+        .takes_value(true)
+        .possible_values(OutputFormat::NAMES)
+        .default_value("table")
+        .help("Print an aligned table, JSON or CSV")
+}
+
+/// `--notify` of the commands which may take a while.
+fn notify_arg() -> Arg<'static> {
+    Arg::new("notify")
+        .long("notify")
+        .takes_value(true)
+        .value_name("HOW")
+        .min_values(0)
+        .require_equals(true)
+        .default_missing_value("terminal")
+        .possible_values(Notifier::NAMES)
+        .help("Show a desktop notification when done, --notify=send uses notify-send")
+}
+
+/// `--solver-weighting` of the commands ranking words.
+fn weighting_arg() -> Arg<'static> {
+    Arg::new("solver-weighting")
+        .long("solver-weighting")
+        .takes_value(true)
+        .possible_values(Weighting::NAMES)
+        .default_value("balanced")
+}
+
+/// `--jobs` of the commands running the solver.
+fn jobs_arg() -> Arg<'static> {
+    Arg::new("jobs")
+        .short('j')
+        .long("jobs")
+        .takes_value(true)
+        .value_name("N")
+        .validator(|v| match v.parse::<usize>() {
+            Ok(0) => Err("expected at least 1".to_string()),
+            Ok(_) => Ok(()),
+            Err(error) => Err(error.to_string()),
+        })
+        .help("Number of threads the solver uses [default: one per core]")
+}
+
+/// Sets the threads of the solver to `--jobs`, if given.
+pub(crate) fn set_jobs(matches: &ArgMatches) {
+    if let Some(jobs) = matches.value_of("jobs") {
+        parallel::set_jobs(jobs.parse().unwrap());
+    }
+}
+
+/// The notifier selected by `--notify`, `None` without it.
+pub(crate) fn notifier(matches: &ArgMatches) -> Option<Notifier> {
+    matches
+        .value_of("notify")
+        .map(|notifier| notifier.parse().unwrap())
+}
+
+/// The tags solutions must carry, `None` without `--tags`.
+pub(crate) fn tag_filter(matches: &ArgMatches) -> Option<TagFilter> {
+    let mut filter: TagFilter = matches.value_of("tags")?.parse().unwrap();
+    filter.any = matches.is_present("tags-any");
+    Some(filter)
+}
+
+/// Arguments of the commands playing a game.
+fn play_args() -> Vec<Arg<'static>> {
+    vec![
+        Arg::new("any-word")
+            .short('a')
+            .long("any-word")
+            .takes_value(false)
+            .help("Allow any word to be guessed"),
+        Arg::new("hard")
+            .long("hard")
+            .takes_value(false)
+            .help("Revealed hints must be used in later guesses"),
+        Arg::new("no-hard")
+            .long("no-hard")
+            .takes_value(false)
+            .overrides_with("hard")
+            .help("Play without hard mode, even if enabled in the config file"),
+        Arg::new("max-tries")
+            .short('t')
+            .long("max-tries")
+            .takes_value(true)
+            .validator(|v| v.parse::<u16>())
+            .help("Number of guesses before the game is lost [default: 6]"),
+        Arg::new("prefill")
+            .long("prefill")
+            .takes_value(true)
+            .value_name("N")
+            .validator(|v| v.parse::<usize>())
+            .help("Reveal N random letters of the solution from the start"),
+        Arg::new("count-hints")
+            .long("count-hints")
+            .takes_value(false)
+            .help("Mark letters guessed more often than they are in the word"),
+        Arg::new("answers-only")
+            .long("answers-only")
+            .takes_value(false)
+            .conflicts_with("any-word")
+            .help("Only accept guesses which can be the solution"),
+        Arg::new("theme")
+            .long("theme")
+            .takes_value(true)
+            .possible_values(Theme::NAMES)
+            .help("Colors of the game board"),
+        Arg::new("align")
+            .long("align")
+            .takes_value(true)
+            .possible_values(Align::NAMES)
+            .help("Place the board in the top left corner, the center or by the margins"),
+        Arg::new("margin-left")
+            .long("margin-left")
+            .takes_value(true)
+            .value_name("N")
+            .validator(|v| v.parse::<u16>())
+            .help("Columns left of the board, selects --align custom if not given"),
+        Arg::new("margin-top")
+            .long("margin-top")
+            .takes_value(true)
+            .value_name("N")
+            .validator(|v| v.parse::<u16>())
+            .help("Rows above the board, selects --align custom if not given"),
+        Arg::new("keyboard-layout")
+            .long("keyboard-layout")
+            .takes_value(true)
+            .possible_values(KeyboardLayout::NAMES)
+            .help("Layout of the on-screen keyboard"),
+        Arg::new("animations")
+            .long("animations")
+            .takes_value(false)
+            .overrides_with("no-animations")
+            .help("Reveal the tiles of a guess one after another"),
+        Arg::new("no-animations")
+            .long("no-animations")
+            .takes_value(false)
+            .overrides_with("animations")
+            .help("Reveal all tiles of a guess at once"),
+        Arg::new("bell")
+            .long("bell")
+            .takes_value(false)
+            .overrides_with("no-bell")
+            .help("Ring the terminal bell when a guess is rejected"),
+        Arg::new("no-bell")
+            .long("no-bell")
+            .takes_value(false)
+            .overrides_with("bell")
+            .help("Never ring the terminal bell"),
+        Arg::new("no-pattern")
+            .long("no-pattern")
+            .takes_value(false)
+            .help("Do not show the known letters as a pattern below the keyboard"),
+        Arg::new("uppercase-tiles")
+            .long("uppercase-tiles")
+            .takes_value(false)
+            .overrides_with("no-uppercase-tiles")
+            .help("Show the letters on the board and the keyboard in uppercase (default)"),
+        Arg::new("no-uppercase-tiles")
+            .long("no-uppercase-tiles")
+            .takes_value(false)
+            .overrides_with("uppercase-tiles")
+            .help("Show the letters in lowercase, as they are typed"),
+        Arg::new("row-marker")
+            .long("row-marker")
+            .takes_value(false)
+            .overrides_with("no-row-marker")
+            .help("Mark the row taking the next guess with `>` [default: with the monochrome theme]"),
+        Arg::new("no-row-marker")
+            .long("no-row-marker")
+            .takes_value(false)
+            .overrides_with("row-marker")
+            .help("Do not mark the row taking the next guess"),
+        Arg::new("row-numbers")
+            .long("row-numbers")
+            .takes_value(false)
+            .overrides_with("no-row-numbers")
+            .help("Number the rows left of the board (default)"),
+        Arg::new("no-row-numbers")
+            .long("no-row-numbers")
+            .takes_value(false)
+            .overrides_with("row-numbers")
+            .help("Do not number the rows of the board"),
+        Arg::new("dim-future-rows")
+            .long("dim-future-rows")
+            .takes_value(false)
+            .help("Show the rows not reached yet faint"),
+        Arg::new("rtl")
+            .long("rtl")
+            .takes_value(false)
+            .overrides_with("ltr")
+            .help("Mirror the board and the keyboard for a script written from right to left [default: by the word list]"),
+        Arg::new("ltr")
+            .long("ltr")
+            .takes_value(false)
+            .overrides_with("rtl")
+            .help("Show the words from left to right, even for a Hebrew or Arabic word list"),
+        Arg::new("show-constraints")
+            .long("show-constraints")
+            .takes_value(false)
+            .help("Show the letters still possible at each position below the board"),
+        Arg::new("no-altscreen")
+            .long("no-altscreen")
+            .takes_value(false)
+            .help("Play on the normal screen, leaving the board in the scrollback"),
+        Arg::new("record")
+            .long("record")
+            .takes_value(true)
+            .value_name("FILE")
+            .conflicts_with_all(&["json", "stdin"])
+            .help("Record the session on the terminal as an asciinema cast in FILE"),
+        notify_arg(),
+        Arg::new("export-html")
+            .long("export-html")
+            .takes_value(true)
+            .value_name("FILE")
+            .help("Write the board of the game to FILE as a web page"),
+        Arg::new("no-letters")
+            .long("no-letters")
+            .takes_value(false)
+            .requires("export-html")
+            .help("Only show the colors in the exported board, not to spoil the word"),
+        Arg::new("define")
+            .long("define")
+            .takes_value(true)
+            .possible_values(Define::NAMES)
+            .default_value("glossary")
+            .help("Where the definition of the word shown after the game comes from"),
+        Arg::new("assist")
+            .long("assist")
+            .takes_value(false)
+            .help("Show the likeliest answers and the best guesses below the board"),
+        Arg::new("json")
+            .long("json")
+            .takes_value(false)
+            .conflicts_with("hint-budget")
+            .help("Play over newline delimited JSON on stdin and stdout instead of the terminal"),
+        Arg::new("stdin")
+            .long("stdin")
+            .takes_value(false)
+            .conflicts_with_all(&["json", "hint-budget"])
+            .help("Read one guess per line from stdin and print the hints as text"),
+        Arg::new("auto")
+            .long("auto")
+            .takes_value(false)
+            .conflicts_with_all(&["json", "stdin", "hint-budget"])
+            .help("Let the built-in solver play the game, any key aborts it"),
+        Arg::new("hint-budget")
+            .long("hint-budget")
+            .takes_value(true)
+            .value_name("POINTS")
+            .validator(|v| v.parse::<u32>())
+            .help("Points to spend on hints, opened with Tab: a letter costs 2, others 1"),
+        Arg::new("coop")
+            .long("coop")
+            .takes_value(true)
+            .value_name("NAMES")
+            .conflicts_with_all(&["json", "auto"])
+            .validator(validate_names)
+            .help("Take turns guessing on one board, e.g. `--coop Alice,Bob`"),
+        Arg::new("team")
+            .long("team")
+            .takes_value(true)
+            .value_name("NAMES")
+            .conflicts_with_all(&["json", "auto", "coop"])
+            .validator(validate_names)
+            .help("Guess as a team in turns, crediting what each found, e.g. `--team Mum,Dad,Kid`"),
+        Arg::new("lang")
+            .long("lang")
+            .takes_value(true)
+            .possible_values(Language::NAMES)
+            .help("Language of messages [default: from the locale]"),
+        Arg::new("post-game-hook")
+            .long("post-game-hook")
+            .takes_value(true)
+            .value_name("CMD")
+            .help("Run CMD after the game, with the result as JSON on stdin"),
+        Arg::new("hardcore")
+            .long("hardcore")
+            .takes_value(false)
+            .conflicts_with_all(&["auto", "coop", "team", "no-stats"])
+            .help("Count a hardcore run of games won in a row, any loss or leaving ends it"),
+        Arg::new("no-stats")
+            .long("no-stats")
+            .takes_value(false)
+            .help("Practice: record nothing of this run in the stats, history or packs"),
+        Arg::new("summary-format")
+            .long("summary-format")
+            .takes_value(true)
+            .possible_values(SummaryFormat::NAMES)
+            .default_value("kv")
+            .help("Format of the result line printed after a game on the terminal"),
+        Arg::new("quiet")
+            .short('q')
+            .long("quiet")
+            .takes_value(false)
+            .help("Do not print the result line after a game on the terminal"),
+    ]
+}
+
+/// Checks the comma separated names of `--coop` and `--team`.
+fn validate_names(v: &str) -> Result<(), String> {
+    let names: Vec<&str> = v.split(',').map(str::trim).collect();
+    if names.len() < 2 || names.iter().any(|name| name.is_empty()) {
+        Err("expected at least two comma separated names".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Arguments of `play_args` which set the rules of a game, as read by
+/// `game_options`.
+const GAME_ARGS: [&str; 7] = [
+    "any-word",
+    "hard",
+    "no-hard",
+    "max-tries",
+    "prefill",
+    "count-hints",
+    "answers-only",
+];
+
+/// Arguments of `play_args` which set up the terminal interface, as read
+/// by `interface`.
+const INTERFACE_ARGS: [&str; 23] = [
+    "theme",
+    "keyboard-layout",
+    "align",
+    "margin-left",
+    "margin-top",
+    "no-pattern",
+    "animations",
+    "no-animations",
+    "bell",
+    "no-bell",
+    "show-constraints",
+    "assist",
+    "define",
+    "no-altscreen",
+    "uppercase-tiles",
+    "no-uppercase-tiles",
+    "row-marker",
+    "no-row-marker",
+    "dim-future-rows",
+    "row-numbers",
+    "no-row-numbers",
+    "rtl",
+    "ltr",
+];
+
+/// Arguments selecting the solution, only for games with a random word.
+fn solution_args() -> Vec<Arg<'static>> {
+    vec![
+        Arg::new("word")
+            .long("word")
+            .takes_value(true)
+            .conflicts_with("hardcore")
+            .help("Play with this solution, the game is not recorded"),
+        Arg::new("challenge")
+            .long("challenge")
+            .takes_value(true)
+            .value_name("CODE")
+            .conflicts_with("word")
+            .validator(|v| v.parse::<Challenge>())
+            .help("Play the solution of a challenge code printed by `rordle pick --codes`"),
+        Arg::new("seed")
+            .long("seed")
+            .takes_value(true)
+            .conflicts_with_all(&["word", "challenge"])
+            .validator(|v| v.parse::<u64>())
+            .help("Seed for picking the solution"),
+        Arg::new("number")
+            .long("number")
+            .takes_value(true)
+            .value_name("N")
+            .conflicts_with_all(&["word", "challenge", "seed", "avoid-recent"])
+            .validator(|v| match v.parse::<usize>() {
+                Ok(0) => Err("must be at least 1".to_string()),
+                Ok(_) => Ok(()),
+                Err(error) => Err(error.to_string()),
+            })
+            .help("Play solution number N of the word list, the same for every player"),
+        Arg::new("avoid-recent")
+            .long("avoid-recent")
+            .takes_value(true)
+            .value_name("N")
+            .conflicts_with_all(&["word", "challenge"])
+            .validator(|v| v.parse::<usize>())
+            .help("Do not pick any solution of the last N recorded games"),
+        Arg::new("practice-weak")
+            .long("practice-weak")
+            .conflicts_with_all(&["word", "challenge", "number"])
+            .help("Prefer solutions with the letters you are slowest to place in your history"),
+        Arg::new("pattern")
+            .long("pattern")
+            .takes_value(true)
+            .value_name("REGEX")
+            .conflicts_with_all(&["word", "challenge"])
+            .validator(|v| v.parse::<Pattern>())
+            .help("Only pick solutions matching this regular expression, e.g. '^s.*e$'"),
+        Arg::new("tags")
+            .long("tags")
+            .takes_value(true)
+            .value_name("TAGS")
+            .conflicts_with_all(&["word", "challenge"])
+            .validator(|v| v.parse::<TagFilter>())
+            .help("Only pick solutions tagged with all of these, e.g. 'easy,science'"),
+        Arg::new("tags-any")
+            .long("tags-any")
+            .requires("tags")
+            .help("Only pick solutions tagged with any of the --tags"),
+        Arg::new("pack")
+            .long("pack")
+            .takes_value(true)
+            .value_name("FILE")
+            .conflicts_with_all(&[
+                "word",
+                "challenge",
+                "seed",
+                "number",
+                "avoid-recent",
+                "practice-weak",
+                "pattern",
+                "tags",
+                "json",
+                "auto",
+                "hardcore",
+            ])
+            .help("Play the puzzles of a pack file in order, resuming where you stopped"),
+    ]
+}
+
+/// Exit codes, shown in the help of the commands playing a game.
+const EXIT_CODES: &str = "EXIT CODES:
+    0    The word was found
+    1    The game was lost
+    2    Invalid arguments, word list or config file
+    3    The terminal or standard streams failed
+    4    The game was left before it was over";
+
+/// Definition of the command line interface. Without a subcommand a game
+/// is played, so the arguments of `play` are also accepted at the top level.
+pub(crate) fn app() -> App<'static> {
+    let app = App::new("Rordle")
+        .version("0.2.0")
+        .author("Niko Abeler <niko@rerere.org>")
+        .about("A Wordle clone for the terminal")
+        .after_help(EXIT_CODES)
+        .arg(profile_arg())
+        .args(play_args())
+        .args(solution_args())
+        .args(ListArgs::args())
+        .subcommand(
+            App::new("play")
+                .about("Play a game with a random word (default)")
+                .after_help(EXIT_CODES)
+                .args(play_args())
+                .args(solution_args())
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("daily")
+                .about("Play the word of the day")
+                .after_help(EXIT_CODES)
+                .arg(
+                    Arg::new("daily-epoch")
+                        .long("daily-epoch")
+                        .takes_value(true)
+                        .possible_values(DailyEpoch::NAMES)
+                        .default_value("rordle")
+                        .help("Number the puzzles like rordle or like the official Wordle"),
+                )
+                .arg(
+                    Arg::new("date")
+                        .long("date")
+                        .takes_value(true)
+                        .value_name("YYYY-MM-DD")
+                        .validator(|v| v.parse::<Date>())
+                        .help("Play the puzzle of a past day, it does not count for the streak"),
+                )
+                .arg(
+                    Arg::new("count-streak")
+                        .long("count-streak")
+                        .takes_value(false)
+                        .requires("date")
+                        .help("Let the puzzle of --date count for the streak"),
+                )
+                .args(play_args())
+                .args(leaderboard_args())
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("tutorial")
+                .about("Play a guided game explaining the hints, it is not recorded")
+                .args(play_args().into_iter().filter(|arg| {
+                    INTERFACE_ARGS.contains(&arg.get_name()) || arg.get_name() == "lang"
+                }))
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("demo")
+                .about("Play a scripted game with timed typing, for recording demos")
+                .arg(
+                    Arg::new("script")
+                        .long("script")
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("FILE")
+                        .help("Steps to play, e.g. 'word crane', 'type slate speed=120ms', 'pause 1s' and 'enter'"),
+                )
+                .arg(
+                    Arg::new("loop")
+                        .long("loop")
+                        .takes_value(false)
+                        .help("Play the script again and again until a key is pressed"),
+                )
+                .args(play_args().into_iter().filter(|arg| {
+                    INTERFACE_ARGS.contains(&arg.get_name()) || arg.get_name() == "lang"
+                }))
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("stats")
+                .about("Show statistics of played games")
+                .arg(
+                    Arg::new("openers")
+                        .long("openers")
+                        .takes_value(false)
+                        .help("Compare the games by their first guess"),
+                )
+                .arg(
+                    Arg::new("weekly")
+                        .long("weekly")
+                        .takes_value(false)
+                        .conflicts_with("openers")
+                        .help("Sum up the last seven days and compare them to the week before"),
+                )
+                .arg(
+                    Arg::new("letters")
+                        .long("letters")
+                        .takes_value(false)
+                        .conflicts_with_all(&["openers", "weekly"])
+                        .help("Show how often each letter of the solutions was missed by the third guess"),
+                )
+                .arg(
+                    Arg::new("import-shares")
+                        .long("import-shares")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .conflicts_with_all(&["openers", "weekly", "letters"])
+                        .help("Add the results of the official Wordle shared in FILE to the stats"),
+                )
+                .arg(format_arg()),
+        )
+        .subcommand(App::new("profiles").about("List the profiles, marking the one in use"))
+        .subcommand(
+            App::new("history")
+                .about("List played games")
+                .arg(
+                    Arg::new("limit")
+                        .short('n')
+                        .long("limit")
+                        .takes_value(true)
+                        .default_value("10")
+                        .validator(|v| v.parse::<usize>())
+                        .help("Number of games to list, starting with the latest"),
+                )
+                .arg(
+                    Arg::new("show-times")
+                        .long("show-times")
+                        .takes_value(false)
+                        .help("Add the time each guess took and the total"),
+                )
+                .arg(format_arg()),
+        )
+        .subcommand(
+            App::new("status")
+                .about("Print one line on the daily puzzle of today, for a shell prompt")
+                .arg(
+                    Arg::new("daily-epoch")
+                        .long("daily-epoch")
+                        .takes_value(true)
+                        .possible_values(DailyEpoch::NAMES)
+                        .default_value("rordle")
+                        .help("Number the puzzles like rordle or like the official Wordle"),
+                )
+                .arg(
+                    Arg::new("date")
+                        .long("date")
+                        .takes_value(true)
+                        .value_name("YYYY-MM-DD")
+                        .validator(|v| v.parse::<Date>())
+                        .help("Show the puzzle of a past day"),
+                )
+                .arg(
+                    Arg::new("quiet")
+                        .short('q')
+                        .long("quiet")
+                        .takes_value(false)
+                        .help("Print nothing and exit with 1 once the puzzle is played"),
+                ),
+        )
+        .subcommand(
+            App::new("solve")
+                .about("List the words matching the hints of guesses")
+                .arg(
+                    Arg::new("feedback")
+                        .takes_value(true)
+                        .multiple_values(true)
+                        .value_name("GUESS=HINTS")
+                        .validator(|v| v.parse::<Feedback>())
+                        .help("A guess and its hints: g for green, y for yellow, - for gray"),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .short('n')
+                        .long("limit")
+                        .takes_value(true)
+                        .default_value("20")
+                        .validator(|v| v.parse::<usize>())
+                        .help("Number of words to print"),
+                )
+                .arg(
+                    weighting_arg()
+                        .help("Suggest informative words, common words of weighted lists or both"),
+                )
+                .arg(jobs_arg())
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("bench")
+                .about("Measure how well the built-in solver plays")
+                .arg(
+                    Arg::new("games")
+                        .short('g')
+                        .long("games")
+                        .takes_value(true)
+                        .default_value("100")
+                        .validator(|v| v.parse::<usize>())
+                        .help("Number of games to play"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .validator(|v| v.parse::<u64>())
+                        .help("Seed for picking the solutions"),
+                )
+                .arg(
+                    Arg::new("max-tries")
+                        .short('t')
+                        .long("max-tries")
+                        .takes_value(true)
+                        .default_value("6")
+                        .validator(|v| v.parse::<u16>())
+                        .help("Number of guesses before a game is lost"),
+                )
+                .arg(weighting_arg().help("How the opener of every game is ranked"))
+                .arg(jobs_arg())
+                .arg(
+                    Arg::new("rebuild-cache")
+                        .long("rebuild-cache")
+                        .takes_value(false)
+                        .help("Rank the opener again, even if it is in the opener cache"),
+                )
+                .arg(notify_arg())
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("serve")
+                .about("Serve games over TCP, one per connection, to play with nc or telnet")
+                .arg(
+                    Arg::new("port")
+                        .short('p')
+                        .long("port")
+                        .takes_value(true)
+                        .default_value("7777")
+                        .validator(|v| v.parse::<u16>())
+                        .help("Port to listen on"),
+                )
+                .arg(
+                    Arg::new("bind")
+                        .long("bind")
+                        .takes_value(true)
+                        .default_value("0.0.0.0")
+                        .help("Address to listen on"),
+                )
+                .arg(
+                    Arg::new("max-connections")
+                        .long("max-connections")
+                        .takes_value(true)
+                        .default_value("16")
+                        .validator(|v| match v.parse::<usize>() {
+                            Ok(0) => Err("must be at least 1".to_string()),
+                            Ok(_) => Ok(()),
+                            Err(error) => Err(error.to_string()),
+                        })
+                        .help("Number of players connected at the same time"),
+                )
+                .arg(
+                    Arg::new("daily")
+                        .long("daily")
+                        .takes_value(false)
+                        .help("Every connection plays the word of the day"),
+                )
+                .args(
+                    play_args().into_iter().filter(|arg| {
+                        GAME_ARGS.contains(&arg.get_name()) || arg.get_name() == "lang"
+                    }),
+                )
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("duel")
+                .about("Race a friend to the same word over the network")
+                .after_help(EXIT_CODES)
+                .arg(
+                    Arg::new("listen")
+                        .long("listen")
+                        .takes_value(true)
+                        .value_name("PORT")
+                        .validator(|v| v.parse::<u16>())
+                        .help("Host a duel and wait for the opponent on PORT"),
+                )
+                .arg(
+                    Arg::new("connect")
+                        .long("connect")
+                        .takes_value(true)
+                        .value_name("HOST:PORT")
+                        .help("Join the duel hosted at HOST:PORT"),
+                )
+                .group(
+                    ArgGroup::new("peer")
+                        .args(&["listen", "connect"])
+                        .required(true),
+                )
+                .args(play_args().into_iter().filter(|arg| {
+                    ![
+                        "json",
+                        "stdin",
+                        "auto",
+                        "coop",
+                        "team",
+                        "hint-budget",
+                        "assist",
+                        "post-game-hook",
+                        "no-stats",
+                        "summary-format",
+                        "quiet",
+                        "export-html",
+                        "no-letters",
+                        "record",
+                        "notify",
+                        "hardcore",
+                    ]
+                    .contains(&arg.get_name())
+                }))
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("battle")
+                .about("Take turns setting words for each other at one keyboard")
+                .arg(
+                    Arg::new("rounds")
+                        .long("rounds")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("6")
+                        .validator(|v| match v.parse::<usize>() {
+                            Ok(0) => Err("must be at least 1".to_string()),
+                            Ok(_) => Ok(()),
+                            Err(error) => Err(error.to_string()),
+                        })
+                        .help("Number of rounds, the players take turns setting the word"),
+                )
+                .args(play_args().into_iter().filter(|arg| {
+                    ![
+                        "json",
+                        "stdin",
+                        "auto",
+                        "coop",
+                        "team",
+                        "post-game-hook",
+                        "no-stats",
+                        "summary-format",
+                        "quiet",
+                        "export-html",
+                        "no-letters",
+                        "record",
+                        "notify",
+                        "hardcore",
+                    ]
+                    .contains(&arg.get_name())
+                }))
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("pick")
+                .about("Print solutions for puzzle setters, spoiling them")
+                .arg(
+                    Arg::new("count")
+                        .short('n')
+                        .long("count")
+                        .takes_value(true)
+                        .default_value("10")
+                        .validator(|v| v.parse::<usize>())
+                        .help("Number of solutions to print"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .validator(|v| v.parse::<u64>())
+                        .help("Seed for picking the solutions"),
+                )
+                .arg(
+                    Arg::new("codes").long("codes").takes_value(false).help(
+                        "Print the challenge code of each solution, to play with --challenge",
+                    ),
+                )
+                .arg(
+                    Arg::new("quiet")
+                        .short('q')
+                        .long("quiet")
+                        .takes_value(false)
+                        .help("Do not warn about spoilers"),
+                )
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("encode-pack")
+                .about("Print a pack file with its words as challenge codes, to share it")
+                .arg(
+                    Arg::new("file")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("The pack file"),
+                ),
+        )
+        .subcommand(
+            App::new("list-words")
+                .about("Print the valid words of the word list")
+                .args(ListArgs::args()),
+        )
+        .subcommand(
+            App::new("validate-wordlist")
+                .about("Check a word file before sharing it, reporting problems by line")
+                .arg(
+                    Arg::new("file")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("The word file"),
+                ),
+        )
+        .subcommand(
+            App::new("config")
+                .about("Manage the config file")
+                .arg(
+                    Arg::new("path")
+                        .long("path")
+                        .takes_value(false)
+                        .help("Print the location of the config file"),
+                )
+                .arg(
+                    Arg::new("init")
+                        .long("init")
+                        .takes_value(false)
+                        .help("Write a commented config file if none exists"),
+                )
+                .group(
+                    ArgGroup::new("action")
+                        .args(&["path", "init"])
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new("completions")
+                .about("Print a shell completion script")
+                .arg(
+                    Arg::new("shell")
+                        .required(true)
+                        .possible_values(SHELLS)
+                        .help("Shell the script is written for"),
+                ),
+        )
+        .subcommand(
+            App::new("__complete")
+                .setting(AppSettings::Hidden)
+                .about("Print candidates for the completion scripts")
+                .arg(Arg::new("kind").required(true).possible_values(["lists"])),
+        );
+    #[cfg(feature = "network")]
+    let app = app.subcommand(
+        App::new("serve-leaderboard")
+            .about("Serve an office leaderboard for --leaderboard, keeping the results in a file")
+            .arg(
+                Arg::new("port")
+                    .long("port")
+                    .takes_value(true)
+                    .default_value("8080")
+                    .validator(|v| v.parse::<u16>())
+                    .help("Port to listen on"),
+            )
+            .arg(
+                Arg::new("bind")
+                    .long("bind")
+                    .takes_value(true)
+                    .default_value("0.0.0.0")
+                    .value_name("ADDRESS")
+                    .help("Address to listen on"),
+            )
+            .arg(
+                Arg::new("file")
+                    .long("file")
+                    .takes_value(true)
+                    .value_name("FILE")
+                    .help("JSON file of the results [default: leaderboard.json next to the stats]"),
+            ),
+    );
+    // every subcommand takes `--profile` as well, see `profile`
+    let mut app = app;
+    for subcommand in app.get_subcommands_mut() {
+        *subcommand = std::mem::take(subcommand).arg(profile_arg());
+    }
+    app
+}
+
+/// `--profile NAME`, before or after the subcommand.
+fn profile_arg() -> Arg<'static> {
+    Arg::new("profile")
+        .long("profile")
+        .takes_value(true)
+        .value_name("NAME")
+        .validator(validate_profile)
+        .help("Keep the stats, packs and results of this player apart [default: from the config file]")
+}
+
+/// The profile selected by `--profile`, given before or after the
+/// subcommand. Different profiles before and after it are an error, rather
+/// than recording a game in one of them by surprise.
+pub(crate) fn profile(matches: &ArgMatches) -> Result<Option<String>, RordleError> {
+    let before = matches.value_of("profile");
+    let after = matches
+        .subcommand()
+        .and_then(|(_, subcommand)| subcommand.value_of("profile"));
+    match (before, after) {
+        (Some(before), Some(after)) if before != after => Err(RordleError::ProfileConflict(
+            before.to_string(),
+            after.to_string(),
+        )),
+        _ => Ok(before.or(after).map(str::to_string)),
+    }
+}
+
+/// `--leaderboard` of `rordle daily`, only built with the `network`
+/// feature.
+fn leaderboard_args() -> Vec<Arg<'static>> {
+    #[cfg(feature = "network")]
+    return vec![Arg::new("leaderboard")
+        .long("leaderboard")
+        .takes_value(true)
+        .value_name("URL")
+        .validator(|v| {
+            if v.starts_with("http://") {
+                Ok(())
+            } else {
+                Err("expected an http:// URL")
+            }
+        })
+        .help("Send the result to an office leaderboard and show the ranking of the day")];
+    #[cfg(not(feature = "network"))]
+    Vec::new()
+}
+
+/// Settings of the terminal interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Interface {
+    pub(crate) render: RenderOptions,
+    pub(crate) animations: bool,
+    pub(crate) bell: bool,
+    pub(crate) keybindings: Keybindings,
+    /// Show the letters still possible at each position below the board.
+    pub(crate) show_constraints: bool,
+    /// Show the likeliest answers and the best guesses below the board.
+    pub(crate) assist: bool,
+    /// Show the captions of `rordle tutorial` below the board.
+    pub(crate) tutorial: bool,
+    /// Where the definition shown after the game comes from.
+    pub(crate) define: Define,
+    /// Play on the alternate screen, which is left at the end.
+    pub(crate) altscreen: bool,
+    /// A hardcore game, which is only left once confirmed.
+    pub(crate) hardcore: bool,
+    /// The opener cache of the profile, read by `assist`.
+    pub(crate) opener_cache: Option<PathBuf>,
+}
+
+/// Value of a pair of `--flag` and `--no-flag`, `default` if neither is given.
+fn switch(matches: &ArgMatches, on: &str, off: &str, default: bool) -> bool {
+    if matches.is_present(on) {
+        true
+    } else if matches.is_present(off) {
+        false
+    } else {
+        default
+    }
+}
+
+/// Translates the command line arguments into the options of a game.
+/// Arguments take precedence over the config file.
+pub(crate) fn game_options(matches: &ArgMatches, config: &Config) -> GameOptions {
+    let mut options = GameOptions::default()
+        .any_word(matches.is_present("any-word"))
+        .hard(switch(matches, "hard", "no-hard", config.hard))
+        .count_hints(matches.is_present("count-hints"))
+        .answers_only(matches.is_present("answers-only"));
+    if let Some(max_tries) = matches.value_of("max-tries") {
+        options = options.max_tries(max_tries.parse().unwrap());
+    }
+    if let Some(prefill) = matches.value_of("prefill") {
+        options = options.prefill(prefill.parse().unwrap());
+    }
+    options
+}
+
+/// Points of `--hint-budget`, 0 without hints. Not read by `game_options`,
+/// as hints can only be bought on the terminal.
+pub(crate) fn hint_budget(matches: &ArgMatches) -> u32 {
+    matches
+        .value_of("hint-budget")
+        .map_or(0, |points| points.parse().unwrap())
+}
+
+/// Players of a `--coop` or `--team` game in the order they take turns,
+/// empty for games played alone.
+pub(crate) fn players(matches: &ArgMatches) -> Vec<String> {
+    let names = matches
+        .value_of("coop")
+        .or_else(|| matches.value_of("team"));
+    names.map_or_else(Vec::new, |names| {
+        names
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .collect()
+    })
+}
+
+/// Language of messages from the command line arguments, the config file or
+/// the locale, in this order.
+pub(crate) fn language(matches: &ArgMatches, config: &Config) -> Language {
+    match matches.value_of("lang") {
+        Some(name) => name.parse().unwrap(),
+        None => config.language.unwrap_or_else(Language::detect),
+    }
+}
+
+/// Placement of the board from the command line arguments and the config
+/// file. Margins given on the command line replace both margins of the
+/// config file.
+pub(crate) fn layout(matches: &ArgMatches, config: &Config) -> Layout {
+    let margin = |name| matches.value_of(name).map(|v| v.parse().unwrap());
+    let (left, top) = (margin("margin-left"), margin("margin-top"));
+    if left.is_none() && top.is_none() {
+        return Layout {
+            align: matches
+                .value_of("align")
+                .map_or(config.align, |align| align.parse().unwrap()),
+            ..Layout::from_config(config)
+        };
+    }
+    Layout {
+        align: matches
+            .value_of("align")
+            .map_or(Align::Custom, |align| align.parse().unwrap()),
+        margin: (left.unwrap_or(0), top.unwrap_or(0)),
+    }
+}
+
+/// Settings of the interface from the command line arguments and the config
+/// file. Arguments take precedence over the config file.
+pub(crate) fn interface(matches: &ArgMatches, config: &Config) -> Interface {
+    let theme = matches
+        .value_of("theme")
+        .map_or(config.theme, |theme| theme.parse().unwrap());
+    Interface {
+        render: RenderOptions {
+            theme,
+            keyboard_layout: matches
+                .value_of("keyboard-layout")
+                .map_or(config.keyboard_layout, |layout| layout.parse().unwrap()),
+            show_pattern: !matches.is_present("no-pattern"),
+            exploring: false,
+            uppercase: switch(matches, "uppercase-tiles", "no-uppercase-tiles", true),
+            layout: layout(matches, config),
+            rtl: if matches.is_present("rtl") {
+                Some(true)
+            } else if matches.is_present("ltr") {
+                Some(false)
+            } else {
+                None
+            },
+            row_marker: switch(
+                matches,
+                "row-marker",
+                "no-row-marker",
+                theme == Theme::Monochrome,
+            ),
+            dim_future_rows: matches.is_present("dim-future-rows"),
+            row_numbers: switch(matches, "row-numbers", "no-row-numbers", true),
+            // read by `on_terminal` once a terminal is opened
+            terminal: DEFAULT_SIZE,
+        },
+        animations: switch(matches, "animations", "no-animations", config.animations),
+        bell: switch(matches, "bell", "no-bell", config.bell),
+        show_constraints: matches.is_present("show-constraints"),
+        assist: matches.is_present("assist"),
+        tutorial: false,
+        define: matches.value_of("define").unwrap().parse().unwrap(),
+        altscreen: !matches.is_present("no-altscreen"),
+        // not an argument of the tutorial, set by `play_game`
+        hardcore: false,
+        keybindings: config.keybindings,
+        opener_cache: paths::path(config, DataFile::Openers),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(args: &[&str]) -> ArgMatches {
+        app().get_matches_from(std::iter::once("rordle").chain(args.iter().cloned()))
+    }
+
+    fn config(contents: &str) -> Config {
+        Config::parse(contents, "config.toml").unwrap()
+    }
+
+    #[test]
+    fn test_app_is_valid() {
+        // checks every subcommand, e.g. for conflicts with arguments it lacks
+        app().debug_assert();
+    }
+
+    #[test]
+    fn test_defaults() {
+        let options = game_options(&matches(&[]), &Config::default());
+        assert_eq!(options, GameOptions::default());
+        let interface = interface(&matches(&[]), &Config::default());
+        assert_eq!(interface.render, RenderOptions::default());
+        assert!(!interface.animations);
+        assert!(!interface.bell);
+        assert!(interface.altscreen);
+        assert_eq!(interface.keybindings, Keybindings::default());
+    }
+
+    #[test]
+    fn test_language() {
+        let config = config("language = \"de\"");
+        assert_eq!(language(&matches(&[]), &config), Language::German);
+        let daily_matches = matches(&["daily", "--lang", "en"]);
+        let (_, daily) = daily_matches.subcommand().unwrap();
+        assert_eq!(language(daily, &config), Language::English);
+    }
+
+    #[test]
+    fn test_config_overrides_defaults() {
+        let config = config("hard = true\ntheme = \"high-contrast\"\nbell = true");
+        assert_eq!(
+            game_options(&matches(&[]), &config),
+            GameOptions::default().hard(true)
+        );
+        let interface = interface(&matches(&[]), &config);
+        assert_eq!(interface.render.theme, Theme::HighContrast);
+        assert_eq!(interface.render.keyboard_layout, KeyboardLayout::Qwerty);
+        assert!(interface.bell);
+        assert!(game_options(&matches(&["--count-hints"]), &config).count_hints);
+        assert!(game_options(&matches(&["--answers-only"]), &config).answers_only);
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        let config = config(
+            "hard = true\ntheme = \"high-contrast\"\nkeyboard_layout = \"azerty\"\nanimations = true",
+        );
+        let args = matches(&[
+            "--no-hard",
+            "--theme",
+            "classic",
+            "--no-animations",
+            "--bell",
+            "--no-altscreen",
+        ]);
+        assert_eq!(game_options(&args, &config), GameOptions::default());
+        let interface = interface(&args, &config);
+        assert_eq!(interface.render.theme, Theme::Classic);
+        // keys without a flag keep the value of the config file
+        assert_eq!(interface.render.keyboard_layout, KeyboardLayout::Azerty);
+        assert!(!interface.animations);
+        assert!(interface.bell);
+        assert!(!interface.altscreen);
+    }
+
+    #[test]
+    fn test_direction() {
+        let rtl = |args: &[&str]| interface(&matches(args), &Config::default()).render.rtl;
+        assert_eq!(rtl(&[]), None);
+        assert_eq!(rtl(&["--rtl"]), Some(true));
+        assert_eq!(rtl(&["--ltr"]), Some(false));
+        // the last one counts
+        assert_eq!(rtl(&["--ltr", "--rtl"]), Some(true));
+    }
+
+    #[test]
+    fn test_layout() {
+        let config = config("align = \"custom\"\nmargin_left = 20\nmargin_top = 2");
+        let layout = |args: &[&str]| super::layout(&matches(args), &config);
+        assert_eq!(
+            layout(&[]),
+            Layout {
+                align: Align::Custom,
+                margin: (20, 2)
+            }
+        );
+        assert_eq!(layout(&["--align", "center"]).align, Align::Center);
+        // margins on the command line replace both of the config file
+        assert_eq!(layout(&["--margin-top", "5"]).margin, (0, 5));
+        let layout = super::layout(&matches(&["--margin-left", "3"]), &Config::default());
+        assert_eq!(
+            layout,
+            Layout {
+                align: Align::Custom,
+                margin: (3, 0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_precedence_of_hard_mode() {
+        let env = |value: &'static str| {
+            move |name: &str| (name == "RORDLE_HARD").then(|| value.to_string())
+        };
+        // default < config file < environment < flag
+        let mut config = config("hard = true");
+        assert!(game_options(&matches(&[]), &config).hard);
+        config.apply_vars(env("off")).unwrap();
+        assert!(!game_options(&matches(&[]), &config).hard);
+        assert!(game_options(&matches(&["--hard"]), &config).hard);
+        config.apply_vars(env("on")).unwrap();
+        assert!(!game_options(&matches(&["--no-hard"]), &config).hard);
+    }
+
+    #[test]
+    fn test_play_args_at_top_level_and_in_play() {
+        let config = Config::default();
+        for args in [&["--hard", "-t", "3"][..], &["play", "--hard", "-t", "3"]] {
+            let matches = matches(args);
+            let play = match matches.subcommand() {
+                Some(("play", play)) => play,
+                _ => &matches,
+            };
+            assert_eq!(
+                game_options(play, &config),
+                GameOptions::default().hard(true).max_tries(3)
+            );
+        }
+    }
+
+    #[test]
+    fn test_tutorial_args() {
+        let matches = matches(&["tutorial", "--theme", "monochrome", "--lang", "de"]);
+        let tutorial = matches.subcommand_matches("tutorial").unwrap();
+        let interface = interface(tutorial, &Config::default());
+        assert_eq!(interface.render.theme, Theme::Monochrome);
+        assert_eq!(language(tutorial, &Config::default()), Language::German);
+    }
+
+    #[test]
+    fn test_notifier() {
+        assert_eq!(notifier(&matches(&[])), None);
+        assert_eq!(notifier(&matches(&["--notify"])), Some(Notifier::Terminal));
+        let bench = matches(&["bench", "--notify=send", "--games", "1"]);
+        let (_, bench) = bench.subcommand().unwrap();
+        assert_eq!(notifier(bench), Some(Notifier::Send));
+        // the value has to be attached, it is not taken from the next word
+        let bench = matches(&["bench", "--notify", "--games", "1"]);
+        let (_, bench) = bench.subcommand().unwrap();
+        assert_eq!(notifier(bench), Some(Notifier::Terminal));
+        assert!(app()
+            .try_get_matches_from(["rordle", "--notify=mail"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_list_args() {
+        let solve_matches = matches(&["solve", "-w", "a.txt", "-w", "b.txt", "--length", "4"]);
+        let (_, solve) = solve_matches.subcommand().unwrap();
+        assert_eq!(
+            ListArgs::from_matches(solve),
+            ListArgs {
+                list: None,
+                word_files: vec!["a.txt", "b.txt"],
+                words: None,
+                digraphs: None,
+                solutions_from_all: false,
+                stream: false,
+                length: Some(4),
+                system_dict: false,
+                no_plurals: false,
+                clean: false,
+            }
+        );
+        let daily_matches = matches(&["daily", "--language", "english"]);
+        let (_, daily) = daily_matches.subcommand().unwrap();
+        assert_eq!(ListArgs::from_matches(daily).list, Some("english"));
+        let digraph_matches = matches(&["--digraphs", "ch,ll,rr"]);
+        assert_eq!(
+            ListArgs::from_matches(&digraph_matches).digraphs,
+            Some("ch,ll,rr")
+        );
+        assert!(app()
+            .try_get_matches_from(["rordle", "--digraphs", "l"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_list_sources() {
+        let config = Config::default();
+        let args = |argv: &[&str]| -> Vec<String> {
+            let matches = matches(argv);
+            ListArgs::from_matches(&matches)
+                .sources(&config)
+                .unwrap()
+                .iter()
+                .map(|source| source.name())
+                .collect()
+        };
+        // only a build with the english list has it
+        if cfg!(feature = "bundled-words") {
+            assert_eq!(args(&[]), vec!["english"]);
+            assert_eq!(args(&["--list", "english"]), vec!["english"]);
+        }
+        assert_eq!(
+            args(&[
+                "-w",
+                "a.txt",
+                "-w",
+                "http://example.com/b.txt",
+                "--system-dict"
+            ]),
+            vec!["a.txt", "http://example.com/b.txt", "/usr/share/dict/words"]
+        );
+        assert_eq!(args(&["--words", "apple,crane"]), vec!["--words"]);
+        assert!(app()
+            .try_get_matches_from(["rordle", "--words", "apple", "-w", "a.txt"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_last_switch_wins() {
+        let config = Config::default();
+        let options = game_options(&matches(&["--no-hard", "--hard"]), &config);
+        assert_eq!(options, GameOptions::default().hard(true));
+        let options = game_options(&matches(&["--hard", "--no-hard"]), &config);
+        assert_eq!(options, GameOptions::default());
+    }
+}
@@ -0,0 +1,814 @@
+//! Subcommands which do not play a game.
+
+use crate::cli;
+use crate::error::RordleError;
+use crate::notify::notify;
+use crate::output::{OutputFormat, Records};
+use crate::summary::pacing;
+use crate::{check_daily_date, daily_date, local_utc_offset, report_unsaved_openers};
+use clap::ArgMatches;
+use rand::prelude::*;
+use rordle_core::challenge::Challenge;
+use rordle_core::config::{config_path, Config};
+use rordle_core::date::{unix_time, DailyEpoch, Date, Puzzle};
+use rordle_core::game::GameOptions;
+use rordle_core::json::Json;
+use rordle_core::opener::{openers, OpenerKey, Source, KEPT};
+use rordle_core::parallel;
+use rordle_core::paths::{self, DataFile};
+use rordle_core::picker::{AvoidRecent, RandomPicker, WordPicker};
+use rordle_core::share::parse_shares;
+use rordle_core::solver::{candidates, rank, solve_from, Feedback, Weighting};
+use rordle_core::stats::{
+    letter_accuracy, merge_records, opener_stats, read_records, write_records, GameRecord,
+    LetterAccuracy, OpenerStats, Summary, WeekSummary, CONFIDENT_SAMPLES, PLACED_BY,
+};
+use rordle_core::words::{lint_word_list, MergedWordList, Severity, WordFileError};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// `rordle config`: prints the location of the config file or writes a
+/// template to it.
+pub(crate) fn config(matches: &ArgMatches) -> Result<(), RordleError> {
+    let path = config_path().ok_or(RordleError::NoConfigDir)?;
+    if matches.is_present("init") {
+        Config::write_template(&path)?;
+        println!("Wrote {}", path.display());
+    } else {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// Location of the stats file of the profile, set in the config file or
+/// the default one.
+pub(crate) fn stats_path(config: &Config) -> Result<PathBuf, RordleError> {
+    data_path(config, DataFile::Stats)
+}
+
+/// Location of `file` of the profile, see `paths::path`.
+pub(crate) fn data_path(config: &Config, file: DataFile) -> Result<PathBuf, RordleError> {
+    paths::path(config, file).ok_or(RordleError::NoStatsPath)
+}
+
+/// `rordle stats`: summarizes all recorded games, with `--openers` by their
+/// first guess and with `--letters` by the letters of the solutions.
+pub(crate) fn stats(matches: &ArgMatches, config: &Config) -> Result<(), RordleError> {
+    let format: OutputFormat = matches.value_of("format").unwrap().parse().unwrap();
+    if let Some(file) = matches.value_of("import-shares") {
+        return import_shares(file, config);
+    }
+    let records = read_records(&stats_path(config)?)?;
+    if matches.is_present("weekly") {
+        let now = unix_time();
+        let today = Date::from_timestamp_with_offset(now, local_utc_offset(now));
+        let local_date =
+            |timestamp| Date::from_timestamp_with_offset(timestamp, local_utc_offset(timestamp));
+        let week = WeekSummary::new(&records, today, local_date);
+        let before = WeekSummary::new(&records, Date::from_days(today.days() - 7), local_date);
+        match format {
+            OutputFormat::Table => print_lines(week_lines(&week, &before)),
+            OutputFormat::Json => println!("{}", week_records(&week, &before).object(0)),
+            OutputFormat::Csv => print!("{}", week_records(&week, &before).to_csv()),
+        }
+        return Ok(());
+    }
+    if matches.is_present("openers") {
+        let openers = opener_stats(&records);
+        match format {
+            OutputFormat::Table => print_lines(opener_lines(&openers)),
+            OutputFormat::Json => println!("{}", opener_records(&openers).to_json()),
+            OutputFormat::Csv => print!("{}", opener_records(&openers).to_csv()),
+        }
+        return Ok(());
+    }
+    if matches.is_present("letters") {
+        let letters = letter_accuracy(&records);
+        match format {
+            OutputFormat::Table => print_lines(letter_lines(&letters)),
+            OutputFormat::Json => println!("{}", letter_records(&letters).to_json()),
+            OutputFormat::Csv => print!("{}", letter_records(&letters).to_csv()),
+        }
+        return Ok(());
+    }
+    let summary = Summary::new(&records);
+    match format {
+        OutputFormat::Table => print_lines(summary_lines(&summary)),
+        OutputFormat::Json => println!("{}", summary_records(&summary).object(0)),
+        OutputFormat::Csv => print!("{}", summary_records(&summary).to_csv()),
+    }
+    Ok(())
+}
+
+/// `rordle stats --import-shares`: adds the games of the official Wordle
+/// shared in `file` to the stats. Puzzles recorded already are skipped, so
+/// the same notes can be imported again when they grew.
+fn import_shares(file: &str, config: &Config) -> Result<(), RordleError> {
+    let text = std::fs::read_to_string(file)
+        .map_err(|error| RordleError::ShareFile(file.to_string(), error))?;
+    let mut imported = Vec::new();
+    for share in parse_shares(&text) {
+        match share {
+            Ok(share) => imported.push(share.record()),
+            Err(error) => eprintln!("warning: {}: {}", file, error),
+        }
+    }
+    let path = stats_path(config)?;
+    let mut records = read_records(&path)?;
+    let found = imported.len();
+    let added = merge_records(&mut records, imported);
+    if added > 0 {
+        write_records(&path, &records)?;
+    }
+    println!(
+        "Imported {} games, {} were recorded already",
+        added,
+        found - added
+    );
+    Ok(())
+}
+
+fn print_lines(lines: Vec<String>) {
+    for line in lines {
+        println!("{}", line);
+    }
+}
+
+/// The lines printed by `rordle stats`, also shown in the menu.
+pub(crate) fn stats_lines(config: &Config) -> Result<Vec<String>, RordleError> {
+    Ok(summary_lines(&Summary::new(&read_records(&stats_path(
+        config,
+    )?)?)))
+}
+
+fn summary_lines(summary: &Summary) -> Vec<String> {
+    let mut lines = vec![
+        format!("Played:         {}", summary.played),
+        format!("Win %:          {}", summary.win_percentage()),
+        format!("Current streak: {}", summary.current_streak),
+        format!("Max streak:     {}", summary.max_streak),
+    ];
+    if summary.count_hints > 0 {
+        lines.push(format!("Count hints:    {}", summary.count_hints));
+    }
+    if summary.answers_only > 0 {
+        lines.push(format!("Answers only:   {}", summary.answers_only));
+    }
+    if summary.distribution.is_empty() {
+        return lines;
+    }
+    lines.push("Guess distribution:".to_string());
+    let most = summary.distribution.iter().max().cloned().unwrap_or(1);
+    for (i, count) in summary.distribution.iter().enumerate() {
+        // bars are scaled to at most 30 characters
+        let bar = "#".repeat((count * 30).div_ceil(most));
+        lines.push(format!("{:>2} {} {}", i + 1, bar, count));
+    }
+    lines
+}
+
+/// The summary as a single record. `distribution` lists the numbers of
+/// games won with 1, 2, ... guesses, `count_hints` and `answers_only` count
+/// the games played with count hints and with only solutions as guesses.
+fn summary_records(summary: &Summary) -> Records {
+    let mut records = Records::new(&[
+        "played",
+        "won",
+        "win_percentage",
+        "current_streak",
+        "max_streak",
+        "distribution",
+        "count_hints",
+        "answers_only",
+    ]);
+    records.push(vec![
+        Json::from(summary.played),
+        Json::from(summary.won),
+        Json::from(summary.win_percentage()),
+        Json::from(summary.current_streak),
+        Json::from(summary.max_streak),
+        Json::Array(
+            summary
+                .distribution
+                .iter()
+                .copied()
+                .map(Json::from)
+                .collect(),
+        ),
+        Json::from(summary.count_hints),
+        Json::from(summary.answers_only),
+    ]);
+    records
+}
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// The report of `rordle stats --weekly`, comparing `week` to the week
+/// `before`.
+fn week_lines(week: &WeekSummary, before: &WeekSummary) -> Vec<String> {
+    let last_day = Date::from_days(week.first_day.days() + 6);
+    let mut lines = vec![format!(
+        "Week {} to {}, changes to the week before in brackets",
+        week.first_day, last_day
+    )];
+    for (i, games) in week.games_per_day.iter().enumerate() {
+        let day = Date::from_days(week.first_day.days() + i as i64);
+        lines.push(format!("{} {}  {}", WEEKDAYS[day.weekday()], day, games));
+    }
+    let change = |now: usize, then: usize| format!("({:+})", now as i64 - then as i64);
+    lines.push(format!(
+        "Games:        {} {}",
+        week.played(),
+        change(week.played(), before.played())
+    ));
+    lines.push(format!(
+        "Won:          {} {}",
+        week.won,
+        change(week.won, before.won)
+    ));
+    lines.push(format!("Dailies:      {}", week.dailies));
+    if let Some(average) = week.average_guesses {
+        let mut line = format!("Avg. guesses: {:.1}", average);
+        if let Some(before) = before.average_guesses {
+            line = format!("{} ({:+.1})", line, average - before);
+        }
+        lines.push(line);
+    }
+    if let Some((word, guesses)) = &week.best {
+        lines.push(format!("Best:         {} in {}", word, guesses));
+    }
+    match &week.worst {
+        Some((word, _)) if week.played() > week.won => {
+            lines.push(format!("Worst:        {}, lost", word))
+        }
+        Some((word, guesses)) => lines.push(format!("Worst:        {} in {}", word, guesses)),
+        None => {}
+    }
+    lines
+}
+
+fn week_records(week: &WeekSummary, before: &WeekSummary) -> Records {
+    let word = |word: &Option<(String, usize)>| {
+        word.as_ref()
+            .map_or(Json::Null, |(word, _)| Json::from(word.as_str()))
+    };
+    let average = |week: &WeekSummary| week.average_guesses.map_or(Json::Null, Json::Number);
+    let mut records = Records::new(&[
+        "from",
+        "to",
+        "games_per_day",
+        "played",
+        "won",
+        "dailies",
+        "average_guesses",
+        "best",
+        "worst",
+        "previous_played",
+        "previous_won",
+        "previous_average_guesses",
+    ]);
+    records.push(vec![
+        Json::from(week.first_day.to_string()),
+        Json::from(Date::from_days(week.first_day.days() + 6).to_string()),
+        Json::Array(week.games_per_day.iter().copied().map(Json::from).collect()),
+        Json::from(week.played()),
+        Json::from(week.won),
+        Json::from(week.dailies),
+        average(week),
+        word(&week.best),
+        word(&week.worst),
+        Json::from(before.played()),
+        Json::from(before.won),
+        average(before),
+    ]);
+    records
+}
+
+/// Whether the opener `i` is played too few times to tell, or does just as
+/// well as the one before or after.
+fn low_confidence(openers: &[OpenerStats], i: usize) -> bool {
+    openers[i].low_confidence()
+        || [i.checked_sub(1), Some(i + 1)]
+            .into_iter()
+            .flatten()
+            .filter_map(|j| openers.get(j))
+            .any(|other| openers[i].ties(other))
+}
+
+/// The table of `rordle stats --openers`, low confidence openers are marked
+/// `?`.
+fn opener_lines(openers: &[OpenerStats]) -> Vec<String> {
+    let width = openers
+        .iter()
+        .map(|stats| stats.opener.chars().count() + 1)
+        .max()
+        .unwrap_or(0)
+        .max("Opener".len());
+    let mut lines = vec![format!(
+        "{:<width$}  Games  Win %  Tries after",
+        "Opener",
+        width = width
+    )];
+    for (i, stats) in openers.iter().enumerate() {
+        let mark = if low_confidence(openers, i) { "?" } else { "" };
+        let tries = match stats.average_tries_after() {
+            Some(tries) => format!("{:.1}", tries),
+            None => "-".to_string(),
+        };
+        lines.push(format!(
+            "{:<width$}  {:>5}  {:>5}  {:>11}",
+            format!("{}{}", stats.opener, mark),
+            stats.played,
+            stats.win_percentage(),
+            tries,
+            width = width
+        ));
+    }
+    if lines.len() > 1 {
+        lines.push(format!(
+            "? fewer than {} games, or tied with a neighbour",
+            CONFIDENT_SAMPLES
+        ));
+    }
+    lines
+}
+
+fn opener_records(openers: &[OpenerStats]) -> Records {
+    let mut records = Records::new(&[
+        "opener",
+        "played",
+        "won",
+        "win_percentage",
+        "tries_after",
+        "low_confidence",
+    ]);
+    for (i, stats) in openers.iter().enumerate() {
+        records.push(vec![
+            Json::from(stats.opener.as_str()),
+            Json::from(stats.played),
+            Json::from(stats.won),
+            Json::from(stats.win_percentage()),
+            stats.average_tries_after().map_or(Json::Null, Json::Number),
+            Json::from(low_confidence(openers, i)),
+        ]);
+    }
+    records
+}
+
+/// The table of `rordle stats --letters`, letters in too few solutions to
+/// tell are marked `?`.
+fn letter_lines(letters: &[LetterAccuracy]) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Letter  Seen  Found by guess {}  Miss %",
+        PLACED_BY
+    )];
+    for stats in letters {
+        let mark = if stats.low_confidence() { "?" } else { "" };
+        lines.push(format!(
+            "{:<6}  {:>4}  {:>16}  {:>6}",
+            format!("{}{}", stats.letter, mark),
+            stats.seen,
+            stats.found,
+            stats.missed() * 100 / stats.seen
+        ));
+    }
+    if lines.len() > 1 {
+        lines.push(format!("? in fewer than {} solutions", CONFIDENT_SAMPLES));
+    }
+    lines
+}
+
+fn letter_records(letters: &[LetterAccuracy]) -> Records {
+    let mut records = Records::new(&["letter", "seen", "found", "miss_rate", "low_confidence"]);
+    for stats in letters {
+        records.push(vec![
+            Json::from(stats.letter.to_string()),
+            Json::from(stats.seen),
+            Json::from(stats.found),
+            Json::Number(stats.miss_rate()),
+            Json::from(stats.low_confidence()),
+        ]);
+    }
+    records
+}
+
+/// `rordle profiles`: lists the profiles which saved anything, the one in
+/// use marked with `*` even if it did not save anything yet.
+pub(crate) fn profiles(config: &Config) -> Result<(), RordleError> {
+    let mut names = paths::profiles(config);
+    if let Some(profile) = config.profile.as_ref().filter(|p| !names.contains(p)) {
+        names.push(profile.clone());
+        names.sort();
+    }
+    for name in names {
+        let mark = if config.profile.as_ref() == Some(&name) {
+            '*'
+        } else {
+            ' '
+        };
+        println!("{} {}", mark, name);
+    }
+    Ok(())
+}
+
+/// `rordle history`: lists the latest recorded games, newest first.
+pub(crate) fn history(matches: &ArgMatches, config: &Config) -> Result<(), RordleError> {
+    let limit: usize = matches.value_of("limit").unwrap().parse().unwrap();
+    let format: OutputFormat = matches.value_of("format").unwrap().parse().unwrap();
+    let show_times = matches.is_present("show-times");
+    let records = read_records(&stats_path(config)?)?;
+    let latest: Vec<&GameRecord> = records.iter().rev().take(limit).collect();
+    match format {
+        OutputFormat::Table => print_lines(
+            latest
+                .iter()
+                .map(|record| history_line(record, show_times))
+                .collect(),
+        ),
+        OutputFormat::Json => println!("{}", history_records(&latest, show_times).to_json()),
+        OutputFormat::Csv => print!("{}", history_records(&latest, show_times).to_csv()),
+    }
+    Ok(())
+}
+
+/// The times of a record, e.g. `12.3s 0.9s - 1m05s = 1m18s`, with `-` for
+/// gaps. `None` for games recorded without times.
+fn times_column(record: &GameRecord) -> Option<String> {
+    if record.times.is_empty() {
+        return None;
+    }
+    let (spans, total) = pacing(&record.times);
+    let spans: Vec<String> = spans
+        .into_iter()
+        .map(|span| span.unwrap_or_else(|| "-".to_string()))
+        .collect();
+    Some(format!("{} = {}", spans.join(" "), total))
+}
+
+fn history_line(record: &GameRecord, show_times: bool) -> String {
+    let result = if record.won {
+        format!("won in {}", record.guesses.len())
+    } else {
+        "lost".to_string()
+    };
+    let line = format!(
+        "{}  {:<6} {}  {}",
+        record.day(),
+        record.mode,
+        record.word,
+        result
+    );
+    let line = match record.puzzle {
+        Some(puzzle) => format!("{}  {}", line, puzzle),
+        None => line,
+    };
+    let line = if record.count_hints {
+        format!("{}  count hints", line)
+    } else {
+        line
+    };
+    let line = if record.answers_only {
+        format!("{}  answers only", line)
+    } else {
+        line
+    };
+    match times_column(record).filter(|_| show_times) {
+        Some(times) => format!("{}  {}", line, times),
+        None => line,
+    }
+}
+
+/// The records with the fields of the stats file. `puzzle` is written as
+/// there, e.g. `wordle:250`, and `null` for games without one. With
+/// `show_times` the milliseconds each guess took are added as `times`,
+/// `null` for gaps.
+fn history_records(latest: &[&GameRecord], show_times: bool) -> Records {
+    let mut records = Records::new(if show_times {
+        &[
+            "timestamp",
+            "mode",
+            "word",
+            "guesses",
+            "won",
+            "puzzle",
+            "times",
+        ]
+    } else {
+        &["timestamp", "mode", "word", "guesses", "won", "puzzle"]
+    });
+    for record in latest {
+        let mut row = vec![
+            Json::Number(record.timestamp as f64),
+            Json::from(record.mode.as_str()),
+            Json::from(record.word.as_str()),
+            Json::Array(
+                record
+                    .guesses
+                    .iter()
+                    .map(|guess| Json::from(guess.as_str()))
+                    .collect(),
+            ),
+            Json::from(record.won),
+            record.puzzle.map_or(Json::Null, |puzzle| {
+                Json::from(format!("{}:{}", puzzle.epoch.name(), puzzle.number))
+            }),
+        ];
+        if show_times {
+            row.push(Json::Array(
+                record
+                    .times
+                    .iter()
+                    .map(|time| {
+                        time.map_or(Json::Null, |time| Json::from(time.as_millis() as usize))
+                    })
+                    .collect(),
+            ));
+        }
+        records.push(row);
+    }
+    records
+}
+
+/// `rordle status`: one line on the daily puzzle of today for a shell
+/// prompt, e.g. `Rordle #812: ✓ 4/6`, read from the stats file alone so
+/// that it is fast. With `--quiet` nothing is printed once the puzzle is
+/// played and the exit code is 1, so a prompt can remind until then.
+pub(crate) fn status(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    let epoch: DailyEpoch = matches.value_of("daily-epoch").unwrap().parse().unwrap();
+    let today = daily_date(epoch, unix_time());
+    let date = match matches.value_of("date") {
+        Some(date) => check_daily_date(epoch, date.parse().unwrap(), today)?,
+        None => today,
+    };
+    let puzzle = Puzzle::of(epoch, date);
+    let records = read_records(&stats_path(config)?)?;
+    let record = records
+        .iter()
+        .rev()
+        .find(|record| record.puzzle == Some(puzzle) && record.mode == "daily");
+    if matches.is_present("quiet") && record.is_some() {
+        return Ok(1);
+    }
+    println!("{}", status_line(puzzle, record));
+    Ok(0)
+}
+
+fn status_line(puzzle: Puzzle, record: Option<&GameRecord>) -> String {
+    let status = match record {
+        None => "not played".to_string(),
+        // the number of tries allowed is not recorded, dailies have the
+        // default unless played with --max-tries
+        Some(record) if record.won => format!(
+            "✓ {}/{}",
+            record.guesses.len(),
+            GameOptions::default().max_tries
+        ),
+        Some(_) => "✗".to_string(),
+    };
+    format!("{}: {}", puzzle, status)
+}
+
+/// `rordle validate-wordlist`: lints a word file, printing its problems
+/// like a compiler, `FILE:LINE: error: ...`, and how many words of each
+/// length it has. Exits with 2 if there are errors.
+pub(crate) fn validate_wordlist(matches: &ArgMatches) -> Result<i32, RordleError> {
+    let file = matches.value_of("file").unwrap();
+    let contents = std::fs::read_to_string(file)
+        .map_err(|error| RordleError::WordList(WordFileError::Io(file.to_string(), error)))?;
+    let report = lint_word_list(&contents);
+    for finding in &report.findings {
+        let severity = match finding.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        println!(
+            "{}:{}: {}: {}",
+            file, finding.line, severity, finding.message
+        );
+    }
+    for (length, count) in &report.lengths {
+        println!("{} letters: {}", length, count);
+    }
+    let errors = report
+        .findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .count();
+    let warnings = report.findings.len() - errors;
+    println!("{} errors, {} warnings", errors, warnings);
+    Ok(if report.has_errors() { 2 } else { 0 })
+}
+
+/// `rordle solve`: prints the solutions matching the given hints, best
+/// suggestions first.
+pub(crate) fn solve(
+    matches: &ArgMatches,
+    word_list: &MergedWordList,
+    config: &Config,
+) -> Result<(), RordleError> {
+    cli::set_jobs(matches);
+    let limit: usize = matches.value_of("limit").unwrap().parse().unwrap();
+    let feedback: Vec<Feedback> = matches
+        .values_of("feedback")
+        .map(|values| values.map(|v| v.parse().unwrap()).collect())
+        .unwrap_or_default();
+    let weighting: Weighting = matches
+        .value_of("solver-weighting")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let words = candidates(word_list.solutions.iter().map(|w| w.as_str()), &feedback);
+    // without hints the ranking is that of the openers, which is cached
+    let ranked = if feedback.is_empty() && limit <= KEPT {
+        ranked_openers(word_list, weighting, false, config).0
+    } else {
+        let ranked = rank(&words, &word_list.frequencies, weighting);
+        ranked
+            .iter()
+            .take(limit)
+            .map(|word| word.to_string())
+            .collect()
+    };
+    for word in ranked.iter().take(limit) {
+        println!("{}", word);
+    }
+    if words.len() > limit {
+        println!("... and {} more", words.len() - limit);
+    }
+    Ok(())
+}
+
+/// The best openers of the solutions of `word_list`, from the opener cache
+/// of the profile unless `rebuild`, and how long getting them took.
+fn ranked_openers(
+    word_list: &MergedWordList,
+    weighting: Weighting,
+    rebuild: bool,
+    config: &Config,
+) -> (Vec<String>, Source, Duration) {
+    let start = Instant::now();
+    let solutions: Vec<&str> = word_list.solutions.iter().map(|w| w.as_str()).collect();
+    let key = OpenerKey::new(&solutions, &word_list.frequencies, weighting);
+    let cache = paths::path(config, DataFile::Openers);
+    let ranking = openers(cache.as_deref(), key, rebuild, || {
+        rank(&solutions, &word_list.frequencies, weighting)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    });
+    report_unsaved_openers(cache.as_deref(), ranking.unsaved.as_ref());
+    (ranking.openers, ranking.source, start.elapsed())
+}
+
+/// `rordle bench`: lets the solver play random solutions and reports how
+/// well and how fast it did.
+pub(crate) fn bench(
+    matches: &ArgMatches,
+    word_list: &MergedWordList,
+    config: &Config,
+) -> Result<(), RordleError> {
+    if word_list.solutions.is_empty() {
+        return Err(RordleError::EmptyWordList);
+    }
+    cli::set_jobs(matches);
+    let games: usize = matches.value_of("games").unwrap().parse().unwrap();
+    let max_tries: u16 = matches.value_of("max-tries").unwrap().parse().unwrap();
+    let mut rng = match matches.value_of("seed") {
+        Some(seed) => StdRng::seed_from_u64(seed.parse().unwrap()),
+        None => StdRng::from_entropy(),
+    };
+    let words: Vec<&str> = word_list.words.iter().map(|w| w.as_str()).collect();
+    let weighting: Weighting = matches
+        .value_of("solver-weighting")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let (openers, source, ranking) = ranked_openers(
+        word_list,
+        weighting,
+        matches.is_present("rebuild-cache"),
+        config,
+    );
+
+    // drawn before playing, so the games are the same with any --jobs
+    let solutions: Vec<&str> = (0..games)
+        .map(|_| word_list.solutions.choose(&mut rng).unwrap().as_str())
+        .collect();
+    let start = Instant::now();
+    let played = parallel::map(&solutions, |solution| {
+        let start = thread_time();
+        let length = solution.chars().count();
+        // the best opener of the length of the solution, if it was kept
+        let opener = openers
+            .iter()
+            .find(|opener| opener.chars().count() == length)
+            .map(|opener| opener.as_str());
+        let guesses = solve_from(solution, &words, opener, max_tries);
+        let won = guesses.last() == Some(solution);
+        (won, guesses.len(), thread_time() - start)
+    });
+    let elapsed = start.elapsed();
+    let won = played.iter().filter(|(won, _, _)| *won).count();
+    let total_guesses: usize = played
+        .iter()
+        .filter(|(won, _, _)| *won)
+        .map(|(_, guesses, _)| guesses)
+        .sum();
+    // the time the games would take one after another, to the time they took
+    let speedup = played
+        .iter()
+        .map(|(_, _, time)| *time)
+        .sum::<Duration>()
+        .as_secs_f64()
+        / elapsed.as_secs_f64().max(1e-9);
+
+    println!("Games:           {}", games);
+    println!("Won:             {} ({}%)", won, won * 100 / games.max(1));
+    if won > 0 {
+        println!("Average guesses: {:.2}", total_guesses as f64 / won as f64);
+    }
+    println!("Time:            {:.3}s", elapsed.as_secs_f64());
+    println!(
+        "Jobs:            {} ({:.2}x the speed of one)",
+        parallel::jobs(),
+        speedup
+    );
+    if let Some(opener) = openers.first() {
+        let source = match source {
+            Source::Cached => "cached",
+            Source::Ranked => "ranked",
+        };
+        println!(
+            "Opener:          {} ({} in {:.3}s)",
+            opener,
+            source,
+            ranking.as_secs_f64()
+        );
+    }
+    if let Some(notifier) = cli::notifier(matches) {
+        let body = format!(
+            "won {} of {} games in {:.1}s",
+            won,
+            games,
+            elapsed.as_secs_f64()
+        );
+        notify(notifier, "rordle bench", &body);
+    }
+    Ok(())
+}
+
+/// CPU time used by the calling thread, the time a game took without the
+/// time its thread waited for a core.
+fn thread_time() -> Duration {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: clock_gettime only writes to the zeroed timespec it is given
+    // and the clock id is a constant, the clock of the calling thread, for
+    // which it can not fail
+    unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+    Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+}
+
+/// `rordle pick`: prints distinct random solutions, picked like the ones of
+/// `rordle play --avoid-recent`, optionally with their challenge codes.
+pub(crate) fn pick(matches: &ArgMatches, word_list: &MergedWordList) -> Result<(), RordleError> {
+    if word_list.solutions.is_empty() {
+        return Err(RordleError::EmptyWordList);
+    }
+    let count: usize = matches.value_of("count").unwrap().parse().unwrap();
+    let random = match matches.value_of("seed") {
+        Some(seed) => RandomPicker::seeded(seed.parse().unwrap()),
+        None => RandomPicker::new(),
+    };
+    // avoiding the picked words makes them distinct
+    let mut picker = AvoidRecent::new(random, Vec::new());
+    let count = if count > word_list.solutions.len() {
+        eprintln!(
+            "warning: the word list has only {} solutions",
+            word_list.solutions.len()
+        );
+        word_list.solutions.len()
+    } else {
+        count
+    };
+    let words: Vec<String> = (0..count)
+        .map(|_| picker.pick(&word_list.solutions))
+        .collect();
+
+    if !matches.is_present("quiet") {
+        eprintln!(
+            "warning: the following output contains the solutions, do not show it to players"
+        );
+    }
+    for word in words {
+        if matches.is_present("codes") {
+            let code = Challenge { word: word.clone() };
+            println!("{}\t{}", word, code);
+        } else {
+            println!("{}", word);
+        }
+    }
+    Ok(())
+}
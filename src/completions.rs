@@ -0,0 +1,395 @@
+//! Shell completion scripts generated from the definition of the command
+//! line interface. Word list names are completed dynamically by calling
+//! the hidden `rordle __complete lists`.
+
+use clap::{App, AppSettings, ArgSettings};
+use rordle_core::config::Config;
+use rordle_core::words::BUILTIN_LISTS;
+use std::fmt::Write;
+
+/// Shells completion scripts can be generated for.
+pub(crate) const SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+/// Values completed for an option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Values {
+    /// The option takes no value.
+    None,
+    /// The value can not be completed.
+    Free,
+    /// A file name.
+    File,
+    /// Names printed by `rordle __complete lists`.
+    Lists,
+    /// One of a fixed set of words.
+    Choices(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+struct Opt {
+    long: Option<String>,
+    short: Option<char>,
+    help: String,
+    values: Values,
+}
+
+impl Opt {
+    /// Spellings of the option, e.g. `--list`, `-l` and `--language`.
+    fn flags(&self) -> Vec<String> {
+        let mut flags: Vec<String> = self.long.iter().map(|l| format!("--{}", l)).collect();
+        flags.extend(self.short.map(|s| format!("-{}", s)));
+        flags
+    }
+}
+
+/// A command and what can be completed after it.
+#[derive(Debug, Clone)]
+struct Command {
+    /// Name of the subcommand, empty for the top level.
+    name: String,
+    options: Vec<Opt>,
+    /// Possible values of positional arguments.
+    positional: Vec<String>,
+    /// Names and descriptions of visible subcommands.
+    subcommands: Vec<(String, String)>,
+}
+
+fn command(app: &App, name: &str) -> Command {
+    let mut options = Vec::new();
+    let mut positional = Vec::new();
+    for arg in app.get_arguments() {
+        if arg.is_set(ArgSettings::Hidden) {
+            continue;
+        }
+        let choices: Vec<String> = arg
+            .get_possible_values()
+            .unwrap_or_default()
+            .iter()
+            .filter(|value| !value.is_hidden())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        if arg.is_positional() {
+            positional.extend(choices);
+            continue;
+        }
+        let values = if !arg.is_set(ArgSettings::TakesValue) {
+            Values::None
+        } else if !choices.is_empty() {
+            Values::Choices(choices)
+        } else {
+            match arg.get_name() {
+                "word-file" => Values::File,
+                "list" => Values::Lists,
+                _ => Values::Free,
+            }
+        };
+        let help = arg.get_help().unwrap_or_default().to_string();
+        options.push(Opt {
+            long: arg.get_long().map(|long| long.to_string()),
+            short: arg.get_short(),
+            help: help.clone(),
+            values: values.clone(),
+        });
+        for alias in arg.get_visible_aliases().unwrap_or_default() {
+            options.push(Opt {
+                long: Some(alias.to_string()),
+                short: None,
+                help: help.clone(),
+                values: values.clone(),
+            });
+        }
+    }
+    options.push(Opt {
+        long: Some("help".to_string()),
+        short: Some('h'),
+        help: "Print help information".to_string(),
+        values: Values::None,
+    });
+    let subcommands = app
+        .get_subcommands()
+        .filter(|sub| !sub.is_set(AppSettings::Hidden))
+        .map(|sub| {
+            (
+                sub.get_name().to_string(),
+                sub.get_about().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+    Command {
+        name: name.to_string(),
+        options,
+        positional,
+        subcommands,
+    }
+}
+
+/// The top level command followed by all visible subcommands.
+fn commands(app: &App) -> Vec<Command> {
+    let mut commands = vec![command(app, "")];
+    commands.extend(
+        app.get_subcommands()
+            .filter(|sub| !sub.is_set(AppSettings::Hidden))
+            .map(|sub| command(sub, sub.get_name())),
+    );
+    commands
+}
+
+/// Options taking a value, deduplicated over all commands.
+fn value_options(commands: &[Command]) -> Vec<Opt> {
+    let mut options: Vec<Opt> = Vec::new();
+    for opt in commands.iter().flat_map(|command| &command.options) {
+        if opt.values != Values::None && !options.iter().any(|o| o.flags() == opt.flags()) {
+            options.push(opt.clone());
+        }
+    }
+    options
+}
+
+/// Words completed after a command: its options, subcommands and values of
+/// positional arguments.
+fn words(command: &Command) -> Vec<String> {
+    let mut words: Vec<String> = command.options.iter().flat_map(Opt::flags).collect();
+    words.extend(command.subcommands.iter().map(|(name, _)| name.clone()));
+    words.extend(command.positional.iter().cloned());
+    words
+}
+
+fn bash(commands: &[Command]) -> String {
+    let mut script = String::new();
+    let names: Vec<&str> = commands[1..].iter().map(|c| c.name.as_str()).collect();
+    writeln!(
+        script,
+        r#"_rordle() {{
+    local cur prev cmd word
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    cmd=""
+    for word in "${{COMP_WORDS[@]:1:COMP_CWORD-1}}"; do
+        case "$word" in
+            {}) cmd="$word"; break ;;
+        esac
+    done
+    case "$prev" in"#,
+        names.join("|")
+    )
+    .unwrap();
+    for opt in value_options(commands) {
+        let reply = match &opt.values {
+            Values::File => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+            Values::Lists => {
+                "COMPREPLY=($(compgen -W \"$(rordle __complete lists 2>/dev/null)\" -- \"$cur\"))"
+                    .to_string()
+            }
+            Values::Choices(choices) => format!(
+                "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                choices.join(" ")
+            ),
+            _ => "COMPREPLY=()".to_string(),
+        };
+        writeln!(
+            script,
+            "        {}) {}; return ;;",
+            opt.flags().join("|"),
+            reply
+        )
+        .unwrap();
+    }
+    script.push_str("    esac\n    case \"$cmd\" in\n");
+    for command in commands {
+        let pattern = if command.name.is_empty() {
+            "\"\""
+        } else {
+            &command.name
+        };
+        writeln!(
+            script,
+            "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;",
+            pattern,
+            words(command).join(" ")
+        )
+        .unwrap();
+    }
+    script.push_str("    esac\n}\ncomplete -F _rordle rordle\n");
+    script
+}
+
+fn zsh(commands: &[Command]) -> String {
+    let mut script = String::new();
+    let names: Vec<&str> = commands[1..].iter().map(|c| c.name.as_str()).collect();
+    writeln!(
+        script,
+        r#"#compdef rordle
+
+_rordle() {{
+    local cmd="" word
+    for word in ${{words[2,CURRENT-1]}}; do
+        case "$word" in
+            {}) cmd="$word"; break ;;
+        esac
+    done
+    case "${{words[CURRENT-1]}}" in"#,
+        names.join("|")
+    )
+    .unwrap();
+    for opt in value_options(commands) {
+        let reply = match &opt.values {
+            Values::File => "_files".to_string(),
+            Values::Lists => {
+                "compadd -- ${(f)\"$(rordle __complete lists 2>/dev/null)\"}".to_string()
+            }
+            Values::Choices(choices) => format!("compadd -- {}", choices.join(" ")),
+            _ => ":".to_string(),
+        };
+        writeln!(
+            script,
+            "        {}) {}; return ;;",
+            opt.flags().join("|"),
+            reply
+        )
+        .unwrap();
+    }
+    script.push_str("    esac\n    case \"$cmd\" in\n");
+    for command in commands {
+        let pattern = if command.name.is_empty() {
+            "\"\""
+        } else {
+            &command.name
+        };
+        writeln!(
+            script,
+            "        {}) compadd -- {} ;;",
+            pattern,
+            words(command).join(" ")
+        )
+        .unwrap();
+    }
+    script.push_str("    esac\n}\n\ncompdef _rordle rordle\n");
+    script
+}
+
+/// Quotes `text` for fish.
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn fish(commands: &[Command]) -> String {
+    let mut script = String::from("complete -c rordle -f\n");
+    for command in commands {
+        let condition = if command.name.is_empty() {
+            "__fish_use_subcommand".to_string()
+        } else {
+            format!("__fish_seen_subcommand_from {}", command.name)
+        };
+        for (name, about) in &command.subcommands {
+            writeln!(
+                script,
+                "complete -c rordle -n '{}' -a {} -d {}",
+                condition,
+                name,
+                fish_quote(about)
+            )
+            .unwrap();
+        }
+        if !command.positional.is_empty() {
+            writeln!(
+                script,
+                "complete -c rordle -n '{}' -a '{}'",
+                condition,
+                command.positional.join(" ")
+            )
+            .unwrap();
+        }
+        for opt in &command.options {
+            let mut line = format!("complete -c rordle -n '{}'", condition);
+            if let Some(long) = &opt.long {
+                write!(line, " -l {}", long).unwrap();
+            }
+            if let Some(short) = opt.short {
+                write!(line, " -s {}", short).unwrap();
+            }
+            match &opt.values {
+                Values::None => (),
+                Values::Free => line.push_str(" -x"),
+                Values::File => line.push_str(" -r -F"),
+                Values::Lists => line.push_str(" -x -a '(rordle __complete lists 2>/dev/null)'"),
+                Values::Choices(choices) => write!(line, " -x -a '{}'", choices.join(" ")).unwrap(),
+            }
+            if !opt.help.is_empty() {
+                write!(line, " -d {}", fish_quote(&opt.help)).unwrap();
+            }
+            script.push_str(&line);
+            script.push('\n');
+        }
+    }
+    script
+}
+
+/// Completion script of `app` for `shell`, one of `SHELLS`.
+pub(crate) fn script(app: &App, shell: &str) -> String {
+    let commands = commands(app);
+    match shell {
+        "bash" => bash(&commands),
+        "zsh" => zsh(&commands),
+        "fish" => fish(&commands),
+        _ => unreachable!("unknown shell {}", shell),
+    }
+}
+
+/// Names of the built-in lists and the lists registered in `config`.
+pub(crate) fn list_names(config: &Config) -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN_LISTS.iter().map(|name| name.to_string()).collect();
+    for name in config.lists.keys() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::app;
+
+    #[test]
+    fn test_commands() {
+        let commands = commands(&app());
+        assert_eq!(commands[0].name, "");
+        assert!(commands.iter().any(|c| c.name == "play"));
+        assert!(!commands.iter().any(|c| c.name == "__complete"));
+        let completions = commands.iter().find(|c| c.name == "completions").unwrap();
+        assert_eq!(completions.positional, SHELLS);
+        let list = commands[0]
+            .options
+            .iter()
+            .find(|o| o.long.as_deref() == Some("list"))
+            .unwrap();
+        assert_eq!(list.values, Values::Lists);
+        assert_eq!(list.flags(), vec!["--list", "-l"]);
+    }
+
+    #[test]
+    fn test_scripts_complete_lists_dynamically() {
+        for shell in SHELLS {
+            let script = script(&app(), shell);
+            assert!(script.contains("rordle __complete lists"), "{}", shell);
+            assert!(script.contains("high-contrast"), "{}", shell);
+            assert!(script.contains("daily"), "{}", shell);
+            assert!(!script.contains("__complete\""), "{}", shell);
+        }
+        let script = script(&app(), "bash");
+        assert!(script.contains("        --list|-l) COMPREPLY="));
+        assert!(script.contains("        --language) COMPREPLY="));
+        assert!(script.contains("        --word-file|-w) COMPREPLY=($(compgen -f"));
+    }
+
+    #[test]
+    fn test_list_names() {
+        let config = Config::parse(
+            "[lists]\ngerman = \"de.txt\"\nenglish = \"en.txt\"",
+            "config.toml",
+        )
+        .unwrap();
+        assert_eq!(list_names(&config), vec!["english", "german"]);
+    }
+}
@@ -0,0 +1,1097 @@
+//! The config file, `~/.config/rordle/config.toml`.
+
+use crate::i18n::Language;
+use crate::paths::validate_profile;
+use crate::words::KeyboardLayout;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConfigValue {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Table(BTreeMap<String, ConfigValue>),
+}
+
+impl ConfigValue {
+    fn type_name(&self) -> &'static str {
+        match self {
+            ConfigValue::String(_) => "a string",
+            ConfigValue::Integer(_) => "an integer",
+            ConfigValue::Boolean(_) => "a boolean",
+            ConfigValue::Table(_) => "a table",
+        }
+    }
+}
+
+/// A `key = value` pair of the config file and the line it was found on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ConfigEntry {
+    line: usize,
+    key: String,
+    value: ConfigValue,
+}
+
+/// Entries of the config file grouped by `[section]`. Entries before the
+/// first section header belong to the section `""`.
+type ConfigSections = BTreeMap<String, Vec<ConfigEntry>>;
+
+/// An error in the config file, with the line it was found on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    path: String,
+    line: usize,
+    message: String,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // errors not caused by a line have no line number
+        if self.line == 0 {
+            write!(f, "{}: {}", self.path, self.message)
+        } else {
+            write!(f, "{}:{}: {}", self.path, self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Parser for the small subset of TOML used by the config file.
+struct TomlParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl TomlParser {
+    fn new(text: &str) -> TomlParser {
+        TomlParser {
+            chars: text.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ') | Some('\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('#') => Ok(()),
+            Some(c) => Err(format!("unexpected `{}`", c)),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        if self.peek() == Some('"') {
+            return self.string();
+        }
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !(c.is_alphanumeric() || c == '-' || c == '_') {
+                break;
+            }
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err("expected a key".to_string());
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        // opening quote
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err("unterminated string".to_string()),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        _ => return Err("invalid escape sequence".to_string()),
+                    };
+                    value.push(escaped);
+                    self.pos += 1;
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<ConfigValue, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => Ok(ConfigValue::String(self.string()?)),
+            Some('{') => {
+                self.pos += 1;
+                let mut table = BTreeMap::new();
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                    return Ok(ConfigValue::Table(table));
+                }
+                loop {
+                    let (key, value) = self.key_value()?;
+                    table.insert(key, value);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.pos += 1,
+                        Some('}') => {
+                            self.pos += 1;
+                            return Ok(ConfigValue::Table(table));
+                        }
+                        _ => return Err("expected `,` or `}` in inline table".to_string()),
+                    }
+                }
+            }
+            _ => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if !(c.is_alphanumeric() || c == '-' || c == '+' || c == '_') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                let word: String = self.chars[start..self.pos].iter().collect();
+                match word.as_str() {
+                    "true" => Ok(ConfigValue::Boolean(true)),
+                    "false" => Ok(ConfigValue::Boolean(false)),
+                    "" => Err("expected a value".to_string()),
+                    _ => word
+                        .replace('_', "")
+                        .parse()
+                        .map(ConfigValue::Integer)
+                        .map_err(|_| format!("invalid value `{}`", word)),
+                }
+            }
+        }
+    }
+
+    fn key_value(&mut self) -> Result<(String, ConfigValue), String> {
+        let key = self.key()?;
+        self.skip_whitespace();
+        if self.peek() != Some('=') {
+            return Err(format!("expected `=` after `{}`", key));
+        }
+        self.pos += 1;
+        Ok((key, self.value()?))
+    }
+}
+
+/// Parses `contents` into sections, reporting errors with their line number.
+fn parse_toml(contents: &str) -> Result<ConfigSections, (usize, String)> {
+    let mut sections = ConfigSections::new();
+    let mut section = String::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line_no = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let mut parser = TomlParser::new(trimmed);
+        if trimmed.starts_with('[') {
+            parser.pos += 1;
+            section = parser.key().map_err(|e| (line_no, e))?;
+            parser.skip_whitespace();
+            if parser.peek() != Some(']') {
+                return Err((line_no, "expected `]` after section name".to_string()));
+            }
+            parser.pos += 1;
+            parser.expect_end().map_err(|e| (line_no, e))?;
+            sections.entry(section.clone()).or_default();
+            continue;
+        }
+        let (key, value) = parser.key_value().map_err(|e| (line_no, e))?;
+        parser.expect_end().map_err(|e| (line_no, e))?;
+        sections
+            .entry(section.clone())
+            .or_default()
+            .push(ConfigEntry {
+                line: line_no,
+                key,
+                value,
+            });
+    }
+    Ok(sections)
+}
+
+/// A word list registered in the `[lists]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListEntry {
+    /// Path of the word file.
+    pub file: PathBuf,
+    /// Only words of this length are used.
+    pub length: Option<usize>,
+    line: usize,
+}
+
+/// Colors used for the tiles and the keyboard.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    /// Green, yellow and black like the original game.
+    #[default]
+    Classic,
+    /// Blue and orange, distinguishable with color blindness.
+    HighContrast,
+    /// No colors, hints are shown with bold, underlined and dim letters.
+    Monochrome,
+}
+
+impl Theme {
+    /// Names accepted by `from_str`.
+    pub const NAMES: [&'static str; 3] = ["classic", "high-contrast", "monochrome"];
+}
+
+impl std::str::FromStr for Theme {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Theme, String> {
+        match name {
+            "classic" => Ok(Theme::Classic),
+            "high-contrast" => Ok(Theme::HighContrast),
+            "monochrome" => Ok(Theme::Monochrome),
+            _ => Err(format!(
+                "unknown theme `{}`, expected one of {}",
+                name,
+                Theme::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Where the board is placed on the terminal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Align {
+    /// In the top left corner, leaving the rest of the terminal free.
+    #[default]
+    TopLeft,
+    /// In the middle of the terminal.
+    Center,
+    /// Moved away from the top left corner by the margins.
+    Custom,
+}
+
+impl Align {
+    /// Names accepted by `from_str`.
+    pub const NAMES: [&'static str; 3] = ["top-left", "center", "custom"];
+}
+
+impl std::str::FromStr for Align {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Align, String> {
+        match name {
+            "top-left" => Ok(Align::TopLeft),
+            "center" => Ok(Align::Center),
+            "custom" => Ok(Align::Custom),
+            _ => Err(format!(
+                "unknown alignment `{}`, expected one of {}",
+                name,
+                Align::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// A key that can be bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyName {
+    /// A printable character.
+    Char(char),
+    /// A character pressed together with control.
+    Ctrl(char),
+    /// The escape key.
+    Esc,
+    /// The enter key.
+    Enter,
+    /// The backspace key.
+    Backspace,
+    /// The delete key.
+    Delete,
+    /// The tab key.
+    Tab,
+}
+
+impl std::fmt::Display for KeyName {
+    /// Writes the key as it is parsed by `FromStr`.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KeyName::Char(c) => write!(f, "{}", c),
+            KeyName::Ctrl(c) => write!(f, "ctrl-{}", c),
+            KeyName::Esc => f.write_str("esc"),
+            KeyName::Enter => f.write_str("enter"),
+            KeyName::Backspace => f.write_str("backspace"),
+            KeyName::Delete => f.write_str("delete"),
+            KeyName::Tab => f.write_str("tab"),
+        }
+    }
+}
+
+impl std::str::FromStr for KeyName {
+    type Err = String;
+
+    /// Parses `esc`, `enter`, `backspace`, `delete`, `tab`, `ctrl-<char>`
+    /// or a single character.
+    fn from_str(name: &str) -> Result<KeyName, String> {
+        let mut chars = name.chars();
+        match (name, chars.next(), chars.next()) {
+            ("esc", _, _) => Ok(KeyName::Esc),
+            ("enter", _, _) => Ok(KeyName::Enter),
+            ("backspace", _, _) => Ok(KeyName::Backspace),
+            ("delete", _, _) => Ok(KeyName::Delete),
+            ("tab", _, _) => Ok(KeyName::Tab),
+            (_, Some(c), None) => Ok(KeyName::Char(c)),
+            _ => match name.strip_prefix("ctrl-").map(|rest| rest.chars()) {
+                Some(mut rest) => match (rest.next(), rest.next()) {
+                    (Some(c), None) => Ok(KeyName::Ctrl(c)),
+                    _ => Err(format!("invalid key `{}`", name)),
+                },
+                None => Err(format!("invalid key `{}`", name)),
+            },
+        }
+    }
+}
+
+/// Keys bound to the actions of the game, set in the `[keybindings]`
+/// section of the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keybindings {
+    /// Ends the game.
+    pub quit: KeyName,
+    /// Removes the last letter of the current guess.
+    pub delete: KeyName,
+    /// Submits the current guess.
+    pub submit: KeyName,
+    /// Opens the menu of hints, with a hint budget.
+    pub hint: KeyName,
+}
+
+impl Default for Keybindings {
+    fn default() -> Keybindings {
+        Keybindings {
+            quit: KeyName::Esc,
+            delete: KeyName::Backspace,
+            submit: KeyName::Enter,
+            hint: KeyName::Tab,
+        }
+    }
+}
+
+/// Settings read from the config file and the `RORDLE_*` environment
+/// variables.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    path: String,
+    /// Colors of the game board.
+    pub theme: Theme,
+    /// Layout of the on-screen keyboard.
+    pub keyboard_layout: KeyboardLayout,
+    /// Where the board is placed on the terminal.
+    pub align: Align,
+    /// Columns left of the board with `Align::Custom`.
+    pub margin_left: u16,
+    /// Rows above the board with `Align::Custom`.
+    pub margin_top: u16,
+    /// Language of messages, detected from the locale if not set.
+    pub language: Option<Language>,
+    /// List used if neither a list nor a word file is selected.
+    pub default_list: Option<String>,
+    /// Word files used if neither a list nor a word file is selected, only
+    /// set by `RORDLE_WORD_FILE`. They take precedence over `default_list`.
+    pub word_files: Vec<String>,
+    /// Play in hard mode.
+    pub hard: bool,
+    /// Reveal the tiles of a guess one after another.
+    pub animations: bool,
+    /// Ring the terminal bell when a guess is rejected.
+    pub bell: bool,
+    /// File the statistics of played games are stored in.
+    pub stats_path: Option<PathBuf>,
+    /// Command run after each game, see `rordle --help`.
+    pub post_game_hook: Option<String>,
+    /// Name of the player on leaderboards, `$USER` if not set.
+    pub name: Option<String>,
+    /// Profile whose stats, packs and results are used, see
+    /// [`crate::paths`]. The files outside of any profile if not set.
+    pub profile: Option<String>,
+    /// Keys bound to the actions of the game.
+    pub keybindings: Keybindings,
+    /// Word lists registered by name.
+    pub lists: BTreeMap<String, ListEntry>,
+}
+
+/// Commented template written by `rordle config --init`. All settings are
+/// commented out, so it results in the default config.
+pub const CONFIG_TEMPLATE: &str = r#"# Configuration of rordle.
+#
+# RORDLE_* environment variables take precedence over the settings in this
+# file, command line flags take precedence over both.
+
+# Colors of the tiles: "classic", "high-contrast" or "monochrome".
+# theme = "classic"
+
+# Layout of the on-screen keyboard: "qwerty", "qwertz" or "azerty".
+# keyboard_layout = "qwerty"
+
+# Where the board is placed: "top-left", "center" or "custom", which moves it
+# away from the top left corner by the margins.
+# align = "top-left"
+# margin_left = 0
+# margin_top = 0
+
+# Language of messages: "en" or "de". Detected from the locale if not set.
+# language = "en"
+
+# Word list used if neither --list nor --word-file is given.
+# default_list = "english"
+
+# Revealed hints must be used in later guesses.
+# hard = false
+
+# Reveal the tiles of a guess one after another.
+# animations = false
+
+# Ring the terminal bell when a guess is rejected.
+# bell = false
+
+# File the statistics of played games are stored in.
+# stats_path = "~/.local/share/rordle/stats"
+
+# Command run with `sh -c` after each game, it gets the result as JSON on
+# stdin and in RORDLE_RESULT, RORDLE_GUESSES and RORDLE_DAY.
+# post_game_hook = "cat >> ~/rordle-results.jsonl"
+
+# Name of the player on --leaderboard, $USER if not set.
+# name = "alex"
+
+# Profile whose stats are kept apart from other players on this account, like
+# --profile NAME. Listed by `rordle profiles`.
+# profile = "alex"
+
+# Keys are a single character, "ctrl-<char>", "esc", "enter", "backspace",
+# "delete" or "tab".
+[keybindings]
+# quit = "esc"
+# delete = "backspace"
+# submit = "enter"
+# hint = "tab"
+
+# Word lists selectable with --list NAME.
+[lists]
+# german = "~/words/german.txt"
+# scrabble = { file = "~/words/scrabble.txt", length = 5 }
+"#;
+
+/// Location of the config file, `$XDG_CONFIG_HOME/rordle/config.toml`.
+/// `None` if neither `XDG_CONFIG_HOME` nor `HOME` is set.
+pub fn config_path() -> Option<PathBuf> {
+    let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join("rordle").join("config.toml"))
+}
+
+/// Parses a boolean from the environment: `1`, `true`, `yes` and `on` or
+/// `0`, `false`, `no` and `off`, ignoring case.
+pub fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!(
+            "expected one of 1, true, yes, on, 0, false, no, off, not `{}`",
+            value
+        )),
+    }
+}
+
+/// Expands a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+impl Config {
+    /// Loads the config file, a missing file results in the default config.
+    /// The lists dropped by [`Config::validate`] are returned next to it.
+    pub fn load() -> Result<(Config, Vec<ConfigError>), ConfigError> {
+        let path = match config_path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok((Config::default(), Vec::new())),
+        };
+        let path_str = path.to_string_lossy().to_string();
+        let contents = std::fs::read_to_string(&path).map_err(|error| ConfigError {
+            path: path_str.clone(),
+            line: 0,
+            message: error.to_string(),
+        })?;
+        let mut config = Config::parse(&contents, &path_str)?;
+        let dropped = config.validate();
+        Ok((config, dropped))
+    }
+
+    /// Applies the environment variables `RORDLE_WORD_FILE`, `RORDLE_LANG`,
+    /// `RORDLE_HARD`, `RORDLE_NO_COLOR` and `RORDLE_DATA_DIR`, which take
+    /// precedence over the config file.
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        self.apply_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Applies the variables returned by `var`, see `apply_env`. Empty
+    /// variables count as unset.
+    pub fn apply_vars<F: Fn(&str) -> Option<String>>(&mut self, var: F) -> Result<(), ConfigError> {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let error = |name: &str, message: String| ConfigError {
+            path: name.to_string(),
+            line: 0,
+            message,
+        };
+        if let Some(files) = var("RORDLE_WORD_FILE") {
+            self.word_files = std::env::split_paths(&files)
+                .map(|file| file.to_string_lossy().to_string())
+                .collect();
+        }
+        if let Some(list) = var("RORDLE_LANG") {
+            self.default_list = Some(list);
+        }
+        if let Some(hard) = var("RORDLE_HARD") {
+            self.hard = parse_bool(&hard).map_err(|e| error("RORDLE_HARD", e))?;
+        }
+        if let Some(no_color) = var("RORDLE_NO_COLOR") {
+            if parse_bool(&no_color).map_err(|e| error("RORDLE_NO_COLOR", e))? {
+                self.theme = Theme::Monochrome;
+            }
+        }
+        if let Some(dir) = var("RORDLE_DATA_DIR") {
+            self.stats_path = Some(PathBuf::from(dir).join("stats"));
+        }
+        Ok(())
+    }
+
+    /// Parses the contents of the config file found at `path`.
+    pub fn parse(contents: &str, path: &str) -> Result<Config, ConfigError> {
+        let error = |line: usize, message: String| ConfigError {
+            path: path.to_string(),
+            line,
+            message,
+        };
+        let sections = parse_toml(contents).map_err(|(line, message)| error(line, message))?;
+        let mut config = Config {
+            path: path.to_string(),
+            ..Config::default()
+        };
+        for entry in sections.get("").into_iter().flatten() {
+            let key = entry.key.as_str();
+            match (key, &entry.value) {
+                ("theme", ConfigValue::String(name)) => {
+                    config.theme = name.parse().map_err(|e| error(entry.line, e))?
+                }
+                ("keyboard_layout", ConfigValue::String(name)) => {
+                    config.keyboard_layout = name.parse().map_err(|e| error(entry.line, e))?
+                }
+                ("align", ConfigValue::String(name)) => {
+                    config.align = name.parse().map_err(|e| error(entry.line, e))?
+                }
+                ("margin_left" | "margin_top", ConfigValue::Integer(margin)) => {
+                    let margin = u16::try_from(*margin).map_err(|_| {
+                        error(
+                            entry.line,
+                            format!("`{}` must be between 0 and {}", key, u16::MAX),
+                        )
+                    })?;
+                    if key == "margin_left" {
+                        config.margin_left = margin;
+                    } else {
+                        config.margin_top = margin;
+                    }
+                }
+                ("language", ConfigValue::String(name)) => {
+                    config.language = Some(name.parse().map_err(|e| error(entry.line, e))?)
+                }
+                ("default_list", ConfigValue::String(name)) => {
+                    config.default_list = Some(name.clone())
+                }
+                ("stats_path", ConfigValue::String(file)) => {
+                    config.stats_path = Some(expand_home(file))
+                }
+                ("post_game_hook", ConfigValue::String(command)) => {
+                    config.post_game_hook = Some(command.clone())
+                }
+                ("name", ConfigValue::String(name)) => config.name = Some(name.clone()),
+                ("profile", ConfigValue::String(name)) => {
+                    validate_profile(name).map_err(|e| error(entry.line, e))?;
+                    config.profile = Some(name.clone())
+                }
+                ("hard", ConfigValue::Boolean(hard)) => config.hard = *hard,
+                ("animations", ConfigValue::Boolean(animations)) => config.animations = *animations,
+                ("bell", ConfigValue::Boolean(bell)) => config.bell = *bell,
+                (
+                    "theme" | "keyboard_layout" | "align" | "language" | "default_list"
+                    | "stats_path" | "post_game_hook" | "name" | "profile",
+                    value,
+                ) => {
+                    return Err(error(
+                        entry.line,
+                        format!("`{}` must be a string, not {}", key, value.type_name()),
+                    ))
+                }
+                ("hard" | "animations" | "bell", value) => {
+                    return Err(error(
+                        entry.line,
+                        format!("`{}` must be a boolean, not {}", key, value.type_name()),
+                    ))
+                }
+                ("margin_left" | "margin_top", value) => {
+                    return Err(error(
+                        entry.line,
+                        format!("`{}` must be an integer, not {}", key, value.type_name()),
+                    ))
+                }
+                _ => return Err(error(entry.line, format!("unknown setting `{}`", key))),
+            }
+        }
+        for entry in sections.get("keybindings").into_iter().flatten() {
+            let key = match &entry.value {
+                ConfigValue::String(name) => name.parse().map_err(|e| error(entry.line, e))?,
+                value => {
+                    return Err(error(
+                        entry.line,
+                        format!(
+                            "key of `{}` must be a string, not {}",
+                            entry.key,
+                            value.type_name()
+                        ),
+                    ))
+                }
+            };
+            match entry.key.as_str() {
+                "quit" => config.keybindings.quit = key,
+                "delete" => config.keybindings.delete = key,
+                "submit" => config.keybindings.submit = key,
+                "hint" => config.keybindings.hint = key,
+                action => return Err(error(entry.line, format!("unknown action `{}`", action))),
+            }
+        }
+        for entry in sections.get("lists").into_iter().flatten() {
+            let list = match &entry.value {
+                ConfigValue::String(file) => ListEntry {
+                    file: expand_home(file),
+                    length: None,
+                    line: entry.line,
+                },
+                ConfigValue::Table(table) => {
+                    let mut list = ListEntry {
+                        file: PathBuf::new(),
+                        length: None,
+                        line: entry.line,
+                    };
+                    for (key, value) in table {
+                        match (key.as_str(), value) {
+                            ("file", ConfigValue::String(file)) => list.file = expand_home(file),
+                            ("length", ConfigValue::Integer(length)) if *length > 0 => {
+                                list.length = Some(*length as usize)
+                            }
+                            ("file", _) | ("length", _) => {
+                                return Err(error(
+                                    entry.line,
+                                    format!("list `{}` has an invalid `{}`", entry.key, key),
+                                ))
+                            }
+                            _ => {
+                                return Err(error(
+                                    entry.line,
+                                    format!("list `{}` has unknown key `{}`", entry.key, key),
+                                ))
+                            }
+                        }
+                    }
+                    if list.file.as_os_str().is_empty() {
+                        return Err(error(
+                            entry.line,
+                            format!("list `{}` is missing `file`", entry.key),
+                        ));
+                    }
+                    list
+                }
+                value => {
+                    return Err(error(
+                        entry.line,
+                        format!(
+                            "list `{}` must be a file name or a table, not {}",
+                            entry.key,
+                            value.type_name()
+                        ),
+                    ))
+                }
+            };
+            config.lists.insert(entry.key.clone(), list);
+        }
+        Ok(config)
+    }
+
+    /// Writes `CONFIG_TEMPLATE` to `path`, creating missing directories.
+    /// An existing file is not overwritten.
+    pub fn write_template(path: &Path) -> Result<(), ConfigError> {
+        let error = |error: std::io::Error| ConfigError {
+            path: path.to_string_lossy().to_string(),
+            line: 0,
+            message: error.to_string(),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(error)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(error)?;
+        std::io::Write::write_all(&mut file, CONFIG_TEMPLATE.as_bytes()).map_err(error)
+    }
+
+    /// Sets the top level setting `key` to the string `value` in the
+    /// config file at `path`, keeping the rest of the file. An existing
+    /// setting is replaced, else it is added below its commented out
+    /// example of the template or before the first section. A missing file
+    /// is created.
+    pub fn write_setting(path: &Path, key: &str, value: &str) -> Result<(), ConfigError> {
+        let error = |error: std::io::Error| ConfigError {
+            path: path.to_string_lossy().to_string(),
+            line: 0,
+            message: error.to_string(),
+        };
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(error(e)),
+        };
+        let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+        let setting = format!("{} = \"{}\"", key, value);
+        let sets = |line: &str, key: &str| {
+            line.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        };
+        let first_section = lines
+            .iter()
+            .position(|line| line.trim_start().starts_with('['))
+            .unwrap_or(lines.len());
+        let top = &lines[..first_section];
+        if let Some(i) = top.iter().position(|line| sets(line.trim(), key)) {
+            lines[i] = setting;
+        } else if let Some(i) = top.iter().position(|line| {
+            line.trim()
+                .strip_prefix('#')
+                .is_some_and(|example| sets(example.trim_start(), key))
+        }) {
+            lines.insert(i + 1, setting);
+        } else {
+            lines.insert(first_section, setting);
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(error)?;
+        }
+        std::fs::write(path, lines.join("\n") + "\n").map_err(error)
+    }
+
+    /// Drops the registered lists which refer to missing files, so the
+    /// rest of the config is still used, and returns why.
+    pub fn validate(&mut self) -> Vec<ConfigError> {
+        let mut dropped = Vec::new();
+        let path = &self.path;
+        self.lists.retain(|name, list| {
+            if list.file.is_file() {
+                return true;
+            }
+            dropped.push(ConfigError {
+                path: path.clone(),
+                line: list.line,
+                message: format!(
+                    "list `{}` refers to missing file {}",
+                    name,
+                    list.file.display()
+                ),
+            });
+            false
+        });
+        dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toml() {
+        let sections = parse_toml(
+            "top = 1\n\n# comment\n[lists]\ngerman = \"/de.txt\" # comment\nscrabble = { file = \"a \\\" b\", length = 5 }\nflag = false",
+        )
+        .unwrap();
+        assert_eq!(sections[""][0].value, ConfigValue::Integer(1));
+        let lists = &sections["lists"];
+        assert_eq!(lists[0].key, "german");
+        assert_eq!(lists[0].line, 5);
+        assert_eq!(lists[0].value, ConfigValue::String("/de.txt".to_string()));
+        let mut table = BTreeMap::new();
+        table.insert(
+            "file".to_string(),
+            ConfigValue::String("a \" b".to_string()),
+        );
+        table.insert("length".to_string(), ConfigValue::Integer(5));
+        assert_eq!(lists[1].value, ConfigValue::Table(table));
+        assert_eq!(lists[2].value, ConfigValue::Boolean(false));
+    }
+
+    #[test]
+    fn test_parse_toml_errors() {
+        assert_eq!(parse_toml("[lists]\nfoo").unwrap_err().0, 2);
+        assert_eq!(parse_toml("foo = \"bar").unwrap_err().0, 1);
+        assert_eq!(parse_toml("foo = bar").unwrap_err().0, 1);
+        assert_eq!(parse_toml("foo = 1 2").unwrap_err().0, 1);
+        assert_eq!(parse_toml("[lists").unwrap_err().0, 1);
+        assert_eq!(parse_toml("a = { b = 1").unwrap_err().0, 1);
+    }
+
+    #[test]
+    fn test_config_lists() {
+        let config = Config::parse(
+            "[lists]\ngerman = \"/home/me/de.txt\"\nscrabble = { file = \"s.txt\", length = 5 }",
+            "config.toml",
+        )
+        .unwrap();
+        assert_eq!(
+            config.lists["german"],
+            ListEntry {
+                file: PathBuf::from("/home/me/de.txt"),
+                length: None,
+                line: 2
+            }
+        );
+        assert_eq!(config.lists["scrabble"].length, Some(5));
+    }
+
+    #[test]
+    fn test_config_list_errors() {
+        let error = Config::parse("[lists]\nfoo = 5", "config.toml").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "config.toml:2: list `foo` must be a file name or a table, not an integer"
+        );
+        let error = Config::parse("[lists]\nfoo = { length = 5 }", "c").unwrap_err();
+        assert_eq!(error.to_string(), "c:2: list `foo` is missing `file`");
+        let error = Config::parse("[lists]\nfoo = { file = \"x\", len = 5 }", "c").unwrap_err();
+        assert_eq!(error.to_string(), "c:2: list `foo` has unknown key `len`");
+        let mut config = Config::parse(
+            "hard = true\n[lists]\n\nfoo = \"does/not/exist\"\nexample = \"example_word_list.txt\"",
+            "c",
+        )
+        .unwrap();
+        let dropped: Vec<String> = config.validate().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            dropped,
+            ["c:4: list `foo` refers to missing file does/not/exist"]
+        );
+        // only the list is dropped
+        assert!(config.hard);
+        assert_eq!(config.lists.keys().collect::<Vec<_>>(), ["example"]);
+    }
+
+    #[test]
+    fn test_config_settings() {
+        let config = Config::parse(
+            "theme = \"high-contrast\"\nkeyboard_layout = \"qwertz\"\nalign = \"custom\"\nmargin_left = 20\nmargin_top = 2\nlanguage = \"de\"\ndefault_list = \"german\"\nhard = true\nanimations = true\nbell = true\nstats_path = \"/tmp/stats\"\npost_game_hook = \"logger -t rordle\"\nname = \"alex\"\nprofile = \"alex\"\n[keybindings]\nquit = \"ctrl-q\"\nsubmit = \"tab\"\nhint = \"?\"",
+            "c",
+        )
+        .unwrap();
+        assert_eq!(config.theme, Theme::HighContrast);
+        assert_eq!(config.keyboard_layout, KeyboardLayout::Qwertz);
+        assert_eq!(
+            (config.align, config.margin_left, config.margin_top),
+            (Align::Custom, 20, 2)
+        );
+        assert_eq!(config.language, Some(Language::German));
+        assert_eq!(config.default_list, Some("german".to_string()));
+        assert!(config.hard && config.animations && config.bell);
+        assert_eq!(config.stats_path, Some(PathBuf::from("/tmp/stats")));
+        assert_eq!(config.post_game_hook, Some("logger -t rordle".to_string()));
+        assert_eq!(config.name.as_deref(), Some("alex"));
+        assert_eq!(config.profile.as_deref(), Some("alex"));
+        assert!(Config::parse("profile = \"../alex\"", "c").is_err());
+        assert_eq!(
+            config.keybindings,
+            Keybindings {
+                quit: KeyName::Ctrl('q'),
+                delete: KeyName::Backspace,
+                submit: KeyName::Tab,
+                hint: KeyName::Char('?'),
+            }
+        );
+    }
+
+    #[test]
+    fn test_config_setting_errors() {
+        let error = Config::parse("\ntheme = \"pink\"", "c").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "c:2: unknown theme `pink`, expected one of classic, high-contrast, monochrome"
+        );
+        let error = Config::parse("hard = \"yes\"", "c").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "c:1: `hard` must be a boolean, not a string"
+        );
+        let error = Config::parse("margin_top = -1", "c").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "c:1: `margin_top` must be between 0 and 65535"
+        );
+        let error = Config::parse("margin_left = \"10\"", "c").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "c:1: `margin_left` must be an integer, not a string"
+        );
+        let error = Config::parse("colour = 1", "c").unwrap_err();
+        assert_eq!(error.to_string(), "c:1: unknown setting `colour`");
+        let error = Config::parse("[keybindings]\nquit = \"ctrl-\"", "c").unwrap_err();
+        assert_eq!(error.to_string(), "c:2: invalid key `ctrl-`");
+        let error = Config::parse("[keybindings]\njump = \"j\"", "c").unwrap_err();
+        assert_eq!(error.to_string(), "c:2: unknown action `jump`");
+    }
+
+    #[test]
+    fn test_key_names_round_trip() {
+        for name in ["esc", "enter", "backspace", "delete", "tab", "ctrl-q", "?"] {
+            assert_eq!(name.parse::<KeyName>().unwrap().to_string(), name);
+        }
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        for value in ["1", "true", "Yes", "ON"] {
+            assert_eq!(parse_bool(value), Ok(true));
+        }
+        for value in ["0", "false", "No", "OFF"] {
+            assert_eq!(parse_bool(value), Ok(false));
+        }
+        assert!(parse_bool("maybe").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides_config() {
+        let vars = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let mut config = Config::parse(
+            "theme = \"high-contrast\"\ndefault_list = \"german\"\nhard = true\nstats_path = \"/tmp/stats\"",
+            "c",
+        )
+        .unwrap();
+        config.apply_vars(vars(&[("RORDLE_HARD", "")])).unwrap();
+        assert!(config.hard);
+        config
+            .apply_vars(vars(&[
+                ("RORDLE_WORD_FILE", "a.txt:b.txt"),
+                ("RORDLE_LANG", "english"),
+                ("RORDLE_HARD", "no"),
+                ("RORDLE_NO_COLOR", "1"),
+                ("RORDLE_DATA_DIR", "/data"),
+            ]))
+            .unwrap();
+        assert_eq!(config.word_files, vec!["a.txt", "b.txt"]);
+        assert_eq!(config.default_list, Some("english".to_string()));
+        assert!(!config.hard);
+        assert_eq!(config.theme, Theme::Monochrome);
+        assert_eq!(config.stats_path, Some(PathBuf::from("/data/stats")));
+
+        let error = config
+            .apply_vars(vars(&[("RORDLE_HARD", "maybe")]))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "RORDLE_HARD: expected one of 1, true, yes, on, 0, false, no, off, not `maybe`"
+        );
+    }
+
+    #[test]
+    fn test_template_is_default_config() {
+        let config = Config::parse(CONFIG_TEMPLATE, "c").unwrap();
+        assert_eq!(
+            config,
+            Config {
+                path: "c".to_string(),
+                ..Config::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_write_template() {
+        let dir = std::env::temp_dir().join(format!("rordle-template-{}", std::process::id()));
+        let path = dir.join("rordle").join("config.toml");
+        let _ = std::fs::remove_dir_all(&dir);
+        Config::write_template(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), CONFIG_TEMPLATE);
+        // an existing config is kept
+        assert!(Config::write_template(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_setting() {
+        let dir = std::env::temp_dir().join(format!("rordle-setting-{}", std::process::id()));
+        let path = dir.join("rordle").join("config.toml");
+        let _ = std::fs::remove_dir_all(&dir);
+        Config::write_setting(&path, "theme", "monochrome").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "theme = \"monochrome\"\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        Config::write_template(&path).unwrap();
+        Config::write_setting(&path, "theme", "monochrome").unwrap();
+        Config::write_setting(&path, "theme", "high-contrast").unwrap();
+        Config::write_setting(&path, "keyboard_layout", "azerty").unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("# theme = \"classic\"\ntheme = \"high-contrast\"\n"));
+        assert_eq!(contents.matches("\ntheme =").count(), 1);
+        let config = Config::parse(&contents, "config.toml").unwrap();
+        assert_eq!(config.theme, Theme::HighContrast);
+        assert_eq!(config.keyboard_layout, KeyboardLayout::Azerty);
+
+        // settings of sections are left alone
+        std::fs::write(&path, "[keybindings]\nquit = \"q\"\n").unwrap();
+        Config::write_setting(&path, "quit", "x").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "quit = \"x\"\n[keybindings]\nquit = \"q\"\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,275 @@
+//! Calendar dates, used for the daily word and the game history.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A day of the proleptic gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    /// The year, e.g. `2022`.
+    pub year: i32,
+    /// The month, from 1 to 12.
+    pub month: u32,
+    /// The day of the month, from 1 to 31.
+    pub day: u32,
+}
+
+/// Seconds since the unix epoch.
+pub fn unix_time() -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(error) => -(error.duration().as_secs() as i64),
+    }
+}
+
+impl Date {
+    /// The current day in UTC.
+    pub fn today() -> Date {
+        Date::from_timestamp(unix_time())
+    }
+
+    /// The day in UTC of a unix timestamp.
+    pub fn from_timestamp(timestamp: i64) -> Date {
+        Date::from_days(timestamp.div_euclid(86400))
+    }
+
+    /// The day of a unix timestamp in a timezone `utc_offset` seconds ahead
+    /// of UTC, e.g. `-18000` for New York in winter.
+    pub fn from_timestamp_with_offset(timestamp: i64, utc_offset: i64) -> Date {
+        Date::from_timestamp(timestamp + utc_offset)
+    }
+
+    /// The day `days` after 1970-01-01.
+    pub fn from_days(days: i64) -> Date {
+        // civil_from_days of http://howardhinnant.github.io/date_algorithms.html
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+
+    /// Number of days since 1970-01-01.
+    pub fn days(&self) -> i64 {
+        // days_from_civil of http://howardhinnant.github.io/date_algorithms.html
+        let year = if self.month <= 2 {
+            self.year as i64 - 1
+        } else {
+            self.year as i64
+        };
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = self.month as i64;
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    /// Day of the week, from 0 for Monday to 6 for Sunday.
+    pub fn weekday(&self) -> usize {
+        // 1970-01-01 was a Thursday
+        (self.days() + 3).rem_euclid(7) as usize
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl std::str::FromStr for Date {
+    type Err = String;
+
+    /// Parses a date of the form `YYYY-MM-DD`.
+    fn from_str(text: &str) -> Result<Date, String> {
+        let invalid = || format!("invalid date `{}`, expected YYYY-MM-DD", text);
+        let parts: Vec<&str> = text.split('-').collect();
+        let (year, month, day) = match parts[..] {
+            [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => (
+                year.parse().map_err(|_| invalid())?,
+                month.parse().map_err(|_| invalid())?,
+                day.parse().map_err(|_| invalid())?,
+            ),
+            _ => return Err(invalid()),
+        };
+        let date = Date { year, month, day };
+        // days which do not exist are moved into the next month
+        if Date::from_days(date.days()) != date {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+}
+
+/// How daily puzzles are numbered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DailyEpoch {
+    /// Days since 1970-01-01 in UTC, the numbering of rordle's own words.
+    #[default]
+    Rordle,
+    /// The numbering of the official Wordle, which started with puzzle 0
+    /// on 2021-06-19 and moves on at local midnight.
+    Wordle,
+}
+
+impl DailyEpoch {
+    /// Names accepted by `FromStr`.
+    pub const NAMES: [&'static str; 2] = ["rordle", "wordle"];
+
+    /// The day of puzzle 0.
+    pub fn first_day(self) -> Date {
+        match self {
+            DailyEpoch::Rordle => Date::from_days(0),
+            DailyEpoch::Wordle => Date {
+                year: 2021,
+                month: 6,
+                day: 19,
+            },
+        }
+    }
+
+    /// Number of the puzzle of `date`.
+    pub fn number(self, date: Date) -> i64 {
+        date.days() - self.first_day().days()
+    }
+
+    /// Day of puzzle `number`.
+    pub fn date(self, number: i64) -> Date {
+        Date::from_days(self.first_day().days() + number)
+    }
+
+    /// Name as accepted by `FromStr`.
+    pub fn name(self) -> &'static str {
+        match self {
+            DailyEpoch::Rordle => "rordle",
+            DailyEpoch::Wordle => "wordle",
+        }
+    }
+}
+
+impl std::str::FromStr for DailyEpoch {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<DailyEpoch, String> {
+        match name {
+            "rordle" => Ok(DailyEpoch::Rordle),
+            "wordle" => Ok(DailyEpoch::Wordle),
+            _ => Err(format!(
+                "unknown epoch `{}`, expected one of {}",
+                name,
+                DailyEpoch::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// A numbered daily puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    /// How `number` counts.
+    pub epoch: DailyEpoch,
+    /// Number of the puzzle, counted from the first day of the epoch.
+    pub number: i64,
+}
+
+impl Puzzle {
+    /// The puzzle of `date`.
+    pub fn of(epoch: DailyEpoch, date: Date) -> Puzzle {
+        Puzzle {
+            epoch,
+            number: epoch.number(date),
+        }
+    }
+
+    /// The day of the puzzle.
+    pub fn date(&self) -> Date {
+        self.epoch.date(self.number)
+    }
+}
+
+impl std::fmt::Display for Puzzle {
+    /// Writes the label shown to players, e.g. `Wordle #1000`.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self.epoch {
+            DailyEpoch::Rordle => "Rordle",
+            DailyEpoch::Wordle => "Wordle",
+        };
+        write!(f, "{} #{}", name, self.number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weekday() {
+        assert_eq!(Date::from_days(0).weekday(), 3);
+        let date: Date = "2024-11-03".parse().unwrap();
+        assert_eq!(date.weekday(), 6);
+        assert_eq!(Date::from_days(date.days() + 1).weekday(), 0);
+        assert_eq!(Date::from_days(-1).weekday(), 2);
+    }
+
+    #[test]
+    fn test_days_round_trip() {
+        for days in -1000..100000 {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+        assert_eq!(
+            Date::from_days(0),
+            Date {
+                year: 1970,
+                month: 1,
+                day: 1
+            }
+        );
+        assert_eq!(Date::from_timestamp(1645747200).to_string(), "2022-02-25");
+        assert_eq!(Date::from_timestamp(-1).to_string(), "1969-12-31");
+    }
+
+    #[test]
+    fn test_wordle_numbers() {
+        for (date, number) in [
+            ("2021-06-19", 0),
+            ("2021-06-20", 1),
+            ("2022-01-01", 196),
+            ("2022-02-24", 250),
+            ("2024-03-15", 1000),
+        ] {
+            let date: Date = date.parse().unwrap();
+            assert_eq!(DailyEpoch::Wordle.number(date), number, "{}", date);
+            assert_eq!(DailyEpoch::Wordle.date(number), date);
+        }
+        let puzzle = Puzzle::of(DailyEpoch::Rordle, "2022-02-25".parse().unwrap());
+        assert_eq!(puzzle.to_string(), "Rordle #19048");
+        assert_eq!(puzzle.date().to_string(), "2022-02-25");
+    }
+
+    #[test]
+    fn test_timezones() {
+        // 2024-03-15 02:00 UTC is still the evening before in New York
+        let timestamp = 1710468000;
+        let utc = Date::from_timestamp_with_offset(timestamp, 0);
+        let new_york = Date::from_timestamp_with_offset(timestamp, -5 * 3600);
+        let tokyo = Date::from_timestamp_with_offset(timestamp, 9 * 3600);
+        assert_eq!(DailyEpoch::Wordle.number(utc), 1000);
+        assert_eq!(DailyEpoch::Wordle.number(new_york), 999);
+        assert_eq!(DailyEpoch::Wordle.number(tokyo), 1000);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let date: Date = "2024-02-29".parse().unwrap();
+        assert_eq!(date.to_string(), "2024-02-29");
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2023-2-1".parse::<Date>().is_err());
+        assert!("today".parse::<Date>().is_err());
+    }
+}
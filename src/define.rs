@@ -0,0 +1,150 @@
+//! The definition of the solution, shown once a game is won or lost: from
+//! the glossary of the built-in list, or with the `define-online` feature
+//! looked up at dictionaryapi.dev.
+
+use rordle_core::words::embedded;
+use std::str::FromStr;
+
+/// Where definitions come from, set by `--define`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Define {
+    /// No definitions.
+    Off,
+    /// Only the glossary of the built-in list.
+    Glossary,
+    /// The glossary, then the online dictionary.
+    #[cfg(feature = "define-online")]
+    Online,
+}
+
+impl Define {
+    /// Names accepted by `FromStr`.
+    #[cfg(not(feature = "define-online"))]
+    pub(crate) const NAMES: [&'static str; 2] = ["off", "glossary"];
+    /// Names accepted by `FromStr`.
+    #[cfg(feature = "define-online")]
+    pub(crate) const NAMES: [&'static str; 3] = ["off", "glossary", "online"];
+}
+
+impl FromStr for Define {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Define::Off),
+            "glossary" => Ok(Define::Glossary),
+            #[cfg(feature = "define-online")]
+            "online" => Ok(Define::Online),
+            _ => Err(format!("expected one of {}", Define::NAMES.join(", "))),
+        }
+    }
+}
+
+/// The definition of `word`, `None` if there is none or the lookup failed.
+/// Only to be called once the game is over, it may spoil the solution and
+/// may take a few seconds online.
+pub(crate) fn definition(word: &str, define: Define) -> Option<String> {
+    match define {
+        Define::Off => None,
+        Define::Glossary => embedded::gloss(word).map(str::to_string),
+        #[cfg(feature = "define-online")]
+        Define::Online => embedded::gloss(word)
+            .map(str::to_string)
+            .or_else(|| online::lookup(word)),
+    }
+}
+
+#[cfg(feature = "define-online")]
+mod online {
+    use rordle_core::json::Json;
+    use std::process::Command;
+
+    /// Seconds a lookup may take before it is given up.
+    const TIMEOUT_SECS: u32 = 5;
+
+    /// Looks `word` up with curl, which also speaks https. Any failure, from
+    /// a missing curl to an unknown word, gives `None`.
+    pub(super) fn lookup(word: &str) -> Option<String> {
+        let url = format!(
+            "https://api.dictionaryapi.dev/api/v2/entries/en/{}",
+            percent_encode(word)
+        );
+        let output = Command::new("curl")
+            .args(["-fsS", "--max-time", &TIMEOUT_SECS.to_string(), &url])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_definition(&String::from_utf8(output.stdout).ok()?)
+    }
+
+    fn percent_encode(word: &str) -> String {
+        word.bytes()
+            .map(|byte| match byte {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' => (byte as char).to_string(),
+                _ => format!("%{:02X}", byte),
+            })
+            .collect()
+    }
+
+    fn first(json: &Json) -> Option<&Json> {
+        match json {
+            Json::Array(items) => items.first(),
+            _ => None,
+        }
+    }
+
+    /// The first definition of the first meaning in an answer of the API.
+    fn parse_definition(body: &str) -> Option<String> {
+        let json = Json::parse(body).ok()?;
+        let entry = first(&json)?;
+        let meaning = first(entry.get("meanings")?)?;
+        let definition = first(meaning.get("definitions")?)?;
+        Some(definition.get("definition")?.as_str()?.to_string())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_definition() {
+            let body = r#"[{"word":"crane","meanings":[{"partOfSpeech":"noun",
+                "definitions":[{"definition":"A large, long-necked bird."},
+                {"definition":"A lifting machine."}]}]}]"#;
+            assert_eq!(
+                parse_definition(body).as_deref(),
+                Some("A large, long-necked bird.")
+            );
+            // unknown words are answered with an object
+            assert_eq!(
+                parse_definition(r#"{"title":"No Definitions Found"}"#),
+                None
+            );
+            assert_eq!(parse_definition("<html>"), None);
+            assert_eq!(percent_encode("bär"), "b%C3%A4r");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition() {
+        // the glossary is bundled with the english list
+        let cigar = "a roll of dried tobacco leaves for smoking";
+        assert_eq!(
+            definition("cigar", Define::Glossary).as_deref(),
+            cfg!(feature = "bundled-words").then_some(cigar)
+        );
+        assert_eq!(definition("cigar", Define::Off), None);
+        assert_eq!(definition("zzzzz", Define::Glossary), None);
+        assert_eq!(
+            "online".parse::<Define>().is_ok(),
+            cfg!(feature = "define-online")
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! `rordle demo --script FILE`: plays a scripted game in the terminal
+//! interface, for recording demos. The keys are typed with the small
+//! irregular delays of a person typing, guesses are revealed with their
+//! animation and the demo ends with the script, or with any key pressed.
+//! A script has one step per line:
+//!
+//! ```text
+//! # the solution, before anything is typed
+//! word crane
+//! type slate speed=120ms
+//! pause 1s
+//! backspace 2
+//! type nt
+//! enter
+//! ```
+//!
+//! `type` types letters, `speed=` sets the time per key, 150ms by
+//! default. `enter` submits the guess and `backspace N` deletes N letters,
+//! one by default. `pause` waits, e.g. `500ms` or `1.5s`.
+
+use crate::cli::{self, Interface};
+use crate::error::RordleError;
+use crate::terminal::Terminal;
+use crate::{key_loop, load_word_list, new_game, on_terminal};
+use clap::ArgMatches;
+use rand::Rng;
+use rordle_core::config::{Config, Keybindings};
+use rordle_core::game::GameOptions;
+use rordle_core::i18n;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use termion::event::Key;
+use termion::input::TermRead;
+
+/// Time per key of `type` without `speed=`.
+const DEFAULT_SPEED: Duration = Duration::from_millis(150);
+
+/// How often waiting checks whether a key was pressed to stop the demo.
+const POLL: Duration = Duration::from_millis(20);
+
+/// A step of a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Type { text: String, speed: Duration },
+    Enter,
+    Backspace(usize),
+    Pause(Duration),
+}
+
+/// A parsed script: the solution and the steps playing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Script {
+    word: String,
+    steps: Vec<Step>,
+}
+
+/// A line of a script which can not be played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ScriptError {
+    /// Line of the script, counted from 1.
+    pub(crate) line: usize,
+    pub(crate) message: String,
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Parses a duration like `120ms`, `1s` or `1.5s`.
+fn parse_duration(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("`{}` is not a duration like 120ms or 1.5s", text);
+    let (number, unit) = match text.strip_suffix("ms") {
+        Some(number) => (number, 0.001),
+        None => (text.strip_suffix('s').ok_or_else(invalid)?, 1.0),
+    };
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    if !number.is_finite() || number < 0.0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs_f64(number * unit))
+}
+
+/// A step of a script other than `word`.
+fn parse_step(command: &str, args: &[&str]) -> Result<Step, String> {
+    let no_args = || match args {
+        [] => Ok(()),
+        _ => Err(format!("`{}` takes no arguments", command)),
+    };
+    match command {
+        "type" => {
+            let (text, options) = args
+                .split_first()
+                .ok_or("`type` needs the letters to type")?;
+            if let Some(c) = text.chars().find(|c| !c.is_alphabetic()) {
+                return Err(format!("`{}` is not a letter", c));
+            }
+            let mut speed = DEFAULT_SPEED;
+            for option in options {
+                match option.split_once('=') {
+                    Some(("speed", value)) => speed = parse_duration(value)?,
+                    _ => return Err(format!("unknown option `{}`, expected speed=", option)),
+                }
+            }
+            Ok(Step::Type {
+                text: text.to_lowercase(),
+                speed,
+            })
+        }
+        "enter" => no_args().map(|_| Step::Enter),
+        "backspace" => match args {
+            [] => Ok(Step::Backspace(1)),
+            [count] => count
+                .parse()
+                .map(Step::Backspace)
+                .map_err(|_| format!("`{}` is not a number of letters", count)),
+            _ => Err("`backspace` takes a number of letters".to_string()),
+        },
+        "pause" => match args {
+            [duration] => parse_duration(duration).map(Step::Pause),
+            _ => Err("`pause` takes a duration like 1s".to_string()),
+        },
+        _ => Err(format!(
+            "unknown step `{}`, expected word, type, enter, backspace or pause",
+            command
+        )),
+    }
+}
+
+impl FromStr for Script {
+    type Err = ScriptError;
+
+    /// Parses a script, checking that the guesses it types fit the word.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut word: Option<String> = None;
+        let mut steps = Vec::new();
+        // letters of the guess being typed, to catch guesses too long
+        let mut typed = 0;
+        for (i, line) in text.lines().enumerate() {
+            let error = |message: String| ScriptError {
+                line: i + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let command = words.next().unwrap();
+            let args: Vec<&str> = words.collect();
+            if command == "word" {
+                match (&word, args.as_slice()) {
+                    (Some(_), _) => return Err(error("the word is given twice".to_string())),
+                    (None, [solution]) => word = Some(solution.to_lowercase()),
+                    (None, _) => return Err(error("`word` takes the solution".to_string())),
+                }
+                continue;
+            }
+            let step = parse_step(command, &args).map_err(error)?;
+            let length = match &word {
+                Some(word) => word.chars().count(),
+                None => return Err(error("the script must start with `word`".to_string())),
+            };
+            match &step {
+                Step::Type { text, .. } => {
+                    typed += text.chars().count();
+                    if typed > length {
+                        return Err(error(format!(
+                            "the guess is longer than the {} letters of the word",
+                            length
+                        )));
+                    }
+                }
+                Step::Backspace(count) => typed = typed.saturating_sub(*count),
+                // an incomplete guess stays, like in the game
+                Step::Enter if typed == length => typed = 0,
+                _ => {}
+            }
+            steps.push(step);
+        }
+        let word = word.ok_or(ScriptError {
+            line: text.lines().count().max(1),
+            message: "the script has no `word`".to_string(),
+        })?;
+        Ok(Script { word, steps })
+    }
+}
+
+impl Script {
+    /// The keys of the script, each with the time to wait before it. Keys
+    /// take between half and one and a half times their speed, like typed
+    /// by hand. A pause at the end is waited for without a key.
+    fn timeline(&self, rng: &mut impl Rng) -> Vec<(Duration, Option<Key>)> {
+        let mut timeline = Vec::new();
+        let mut wait = Duration::ZERO;
+        let mut speed = DEFAULT_SPEED;
+        let mut press = |wait: &mut Duration, speed: Duration, key: Key| {
+            let delay = speed.mul_f64(rng.gen_range(0.5..1.5));
+            timeline.push((*wait + delay, Some(key)));
+            *wait = Duration::ZERO;
+        };
+        for step in &self.steps {
+            match step {
+                Step::Type {
+                    text,
+                    speed: typing,
+                } => {
+                    speed = *typing;
+                    for c in text.chars() {
+                        press(&mut wait, speed, Key::Char(c));
+                    }
+                }
+                Step::Enter => press(&mut wait, speed, Key::Char('\n')),
+                Step::Backspace(count) => {
+                    for _ in 0..*count {
+                        press(&mut wait, speed, Key::Backspace);
+                    }
+                }
+                Step::Pause(pause) => wait += *pause,
+            }
+        }
+        if wait > Duration::ZERO {
+            timeline.push((wait, None));
+        }
+        timeline
+    }
+}
+
+/// Waits for `delay`, `true` if a key was pressed meanwhile.
+fn wait(delay: Duration, pressed: &mut impl FnMut() -> bool) -> bool {
+    let end = Instant::now() + delay;
+    loop {
+        if pressed() {
+            return true;
+        }
+        let now = Instant::now();
+        if now >= end {
+            return false;
+        }
+        std::thread::sleep(POLL.min(end - now));
+    }
+}
+
+/// `rordle demo`: plays the script of `--script`, again and again with
+/// `--loop` until a key is pressed. Nothing is recorded.
+pub(crate) fn demo(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    let file = matches.value_of("script").unwrap();
+    let script: Script = std::fs::read_to_string(file)
+        .map_err(|error| RordleError::Script(file.to_string(), error.to_string()))?
+        .parse()
+        .map_err(|error: ScriptError| RordleError::Script(file.to_string(), error.to_string()))?;
+    i18n::set_language(cli::language(matches, config));
+    let word_list = load_word_list(&cli::ListArgs::from_matches(matches), config, false)?;
+    // the scripted guesses need not be in the word list
+    let options = GameOptions::default().any_word(true);
+    let game_state = new_game(options, word_list, script.word.clone())?;
+    // the script types the keys of the default bindings
+    let interface = on_terminal(&Interface {
+        keybindings: Keybindings::default(),
+        ..cli::interface(matches, config)
+    });
+    let mut terminal = Terminal::open(interface.altscreen).map_err(RordleError::Terminal)?;
+    let mut keys = termion::async_stdin().keys();
+    let mut pressed = || keys.next().is_some();
+    let mut rng = rand::thread_rng();
+    loop {
+        let mut game = game_state.sandbox();
+        let mut stopped = false;
+        let timeline = script
+            .timeline(&mut rng)
+            .into_iter()
+            .map_while(|(delay, key)| {
+                stopped = wait(delay, &mut pressed);
+                if stopped {
+                    None
+                } else {
+                    key.map(Ok)
+                }
+            });
+        key_loop(&mut game, timeline, &mut terminal, &interface).map_err(RordleError::Terminal)?;
+        if stopped || !matches.is_present("loop") {
+            break;
+        }
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_parse_script() {
+        let script: Script = "\
+# a demo
+word Crane
+
+type slate speed=100ms
+pause 1.5s
+backspace 2
+type nt
+enter
+pause 500ms
+"
+        .parse()
+        .unwrap();
+        assert_eq!(script.word, "crane");
+        assert_eq!(
+            script.steps,
+            [
+                Step::Type {
+                    text: "slate".to_string(),
+                    speed: Duration::from_millis(100)
+                },
+                Step::Pause(Duration::from_millis(1500)),
+                Step::Backspace(2),
+                Step::Type {
+                    text: "nt".to_string(),
+                    speed: DEFAULT_SPEED
+                },
+                Step::Enter,
+                Step::Pause(Duration::from_millis(500)),
+            ]
+        );
+        for (text, error) in [
+            ("type crane", "line 1: the script must start with `word`"),
+            (
+                "word crane\ntype cranes",
+                "line 2: the guess is longer than the 5 letters of the word",
+            ),
+            (
+                "word crane\ntype cr\nenter\ntype anes",
+                "line 4: the guess is longer than the 5 letters of the word",
+            ),
+            (
+                "word crane\n\npause 1 s",
+                "line 3: `pause` takes a duration like 1s",
+            ),
+            (
+                "word crane\ntype crane speed=fast",
+                "line 2: `fast` is not a duration like 120ms or 1.5s",
+            ),
+            (
+                "word crane\ntype crane slowly",
+                "line 2: unknown option `slowly`, expected speed=",
+            ),
+            (
+                "word crane\nenter now",
+                "line 2: `enter` takes no arguments",
+            ),
+            (
+                "word crane\nclick",
+                "line 2: unknown step `click`, expected word, type, enter, backspace or pause",
+            ),
+            ("word crane\nword slate", "line 2: the word is given twice"),
+            ("pause 1s", "line 1: the script must start with `word`"),
+            ("", "line 1: the script has no `word`"),
+        ] {
+            let parsed = text.parse::<Script>().map_err(|error| error.to_string());
+            assert_eq!(parsed, Err(error.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_timeline() {
+        let script: Script = "word crane\npause 1s\ntype ab speed=100ms\nenter\npause 2s"
+            .parse()
+            .unwrap();
+        let timeline = script.timeline(&mut StdRng::seed_from_u64(1));
+        let keys: Vec<Option<Key>> = timeline.iter().map(|(_, key)| *key).collect();
+        assert_eq!(
+            keys,
+            [
+                Some(Key::Char('a')),
+                Some(Key::Char('b')),
+                Some(Key::Char('\n')),
+                None
+            ]
+        );
+        let delays: Vec<Duration> = timeline.iter().map(|(delay, _)| *delay).collect();
+        // the pause comes before the first key, each key takes 50 to 150ms
+        assert!(
+            delays[0] >= Duration::from_millis(1050) && delays[0] < Duration::from_millis(1150)
+        );
+        for delay in &delays[1..3] {
+            assert!(*delay >= Duration::from_millis(50) && *delay < Duration::from_millis(150));
+        }
+        assert_eq!(delays[3], Duration::from_secs(2));
+        assert_eq!(parse_duration("0.5s"), Ok(Duration::from_millis(500)));
+        assert!(parse_duration("-1s").is_err());
+    }
+}
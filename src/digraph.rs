@@ -0,0 +1,195 @@
+//! Digraphs played as single letters, like the `ch` and `ll` of traditional
+//! Spanish: `llave` is four tiles, `ll`, `a`, `v` and `e`.
+//!
+//! Words are tokenized by replacing each digraph with a character of the
+//! private use area of Unicode, so everything working on letters, from
+//! scoring to the keyboard, treats it as one. The characters are handed out
+//! once per process, so tokenized words can always be turned back into text
+//! with [`untokenize`].
+
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// First character of the private use area, standing for the first digraph
+/// ever used.
+const FIRST_TOKEN: u32 = 0xe000;
+
+/// Number of characters of the private use area.
+const TOKENS: usize = 6400;
+
+/// The digraphs handed out a character, that of `FIRST_TOKEN` first.
+static REGISTRY: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// The character standing for `digraph`, handing out the next one if it
+/// has none yet.
+fn intern(digraph: &str) -> Option<char> {
+    let mut registry = REGISTRY.lock().unwrap();
+    let i = match registry.iter().position(|known| known == digraph) {
+        Some(i) => i,
+        None if registry.len() < TOKENS => {
+            registry.push(digraph.to_string());
+            registry.len() - 1
+        }
+        None => return None,
+    };
+    char::from_u32(FIRST_TOKEN + i as u32)
+}
+
+/// The digraph `c` stands for, `None` if it stands for itself.
+pub fn text(c: char) -> Option<String> {
+    let i = (c as u32).checked_sub(FIRST_TOKEN)? as usize;
+    REGISTRY.lock().unwrap().get(i).cloned()
+}
+
+/// `word` with its digraphs written out again, e.g. `llave` for the four
+/// tiles of `llave`.
+pub fn untokenize(word: &str) -> String {
+    word.chars()
+        .map(|c| text(c).unwrap_or_else(|| c.to_string()))
+        .collect()
+}
+
+/// A set of digraphs, e.g. `ch,ll,rr`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Digraphs {
+    /// The digraphs in lowercase and the characters standing for them,
+    /// longest first, so they are matched greedily.
+    units: Vec<(String, char)>,
+}
+
+impl Digraphs {
+    /// Digraphs of at least two letters each, lowercased.
+    pub fn new<'a>(digraphs: impl IntoIterator<Item = &'a str>) -> Result<Digraphs, String> {
+        let mut units: Vec<(String, char)> = Vec::new();
+        for digraph in digraphs {
+            let digraph = digraph.to_lowercase();
+            if digraph.chars().count() < 2 {
+                return Err(format!(
+                    "`{}` is not a digraph of two or more letters",
+                    digraph
+                ));
+            }
+            if let Some(c) = digraph.chars().find(|c| !c.is_alphabetic()) {
+                return Err(format!("`{}` of `{}` is not a letter", c, digraph));
+            }
+            if units.iter().any(|(known, _)| *known == digraph) {
+                continue;
+            }
+            let token = intern(&digraph).ok_or("too many digraphs")?;
+            units.push((digraph, token));
+        }
+        units.sort_by_key(|(digraph, _)| std::cmp::Reverse(digraph.chars().count()));
+        Ok(Digraphs { units })
+    }
+
+    /// Adds the digraphs of `other` missing from these.
+    pub fn extend(&mut self, other: &Digraphs) {
+        for unit in &other.units {
+            if !self.units.contains(unit) {
+                self.units.push(unit.clone());
+            }
+        }
+        self.units
+            .sort_by_key(|(digraph, _)| std::cmp::Reverse(digraph.chars().count()));
+    }
+
+    /// Whether there are no digraphs, words are then left as they are.
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    /// The characters standing for the digraphs.
+    pub fn tokens(&self) -> impl Iterator<Item = char> + '_ {
+        self.units.iter().map(|(_, token)| *token)
+    }
+
+    /// `word` with each digraph replaced by its character, matching the
+    /// longest digraph first from left to right. Tokenizing a tokenized
+    /// word leaves it as it is.
+    pub fn tokenize(&self, word: &str) -> String {
+        if self.is_empty() {
+            return word.to_string();
+        }
+        let mut tokenized = String::with_capacity(word.len());
+        let mut rest = word;
+        while let Some(c) = rest.chars().next() {
+            match self
+                .units
+                .iter()
+                .find(|(digraph, _)| rest.starts_with(digraph.as_str()))
+            {
+                Some((digraph, token)) => {
+                    tokenized.push(*token);
+                    rest = &rest[digraph.len()..];
+                }
+                None => {
+                    tokenized.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        tokenized
+    }
+
+    /// The digraph typing `c` after `last` makes, e.g. `ll` for `l` after
+    /// `l`, `None` if they stay two letters.
+    pub fn compose(&self, last: char, c: char) -> Option<char> {
+        let mut typed = text(last).unwrap_or_else(|| last.to_string());
+        typed.push(c);
+        self.units
+            .iter()
+            .find(|(digraph, _)| *digraph == typed)
+            .map(|(_, token)| *token)
+    }
+}
+
+impl std::fmt::Display for Digraphs {
+    /// Writes the digraphs like they are given, e.g. `ch,ll,rr`.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let digraphs: Vec<&str> = self.units.iter().map(|(d, _)| d.as_str()).collect();
+        write!(f, "{}", digraphs.join(","))
+    }
+}
+
+impl FromStr for Digraphs {
+    type Err = String;
+
+    /// Parses digraphs separated by commas or whitespace, e.g. `ch,ll,rr`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Digraphs::new(
+            s.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|digraph| !digraph.is_empty()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let digraphs: Digraphs = "ch, LL rr".parse().unwrap();
+        let llave = digraphs.tokenize("llave");
+        assert_eq!(llave.chars().count(), 4);
+        assert_eq!(untokenize(&llave), "llave");
+        assert_eq!(digraphs.tokenize(&llave), llave);
+        // `lll` is `ll` followed by `l`
+        assert_eq!(digraphs.tokenize("calle").chars().count(), 4);
+        assert_eq!(digraphs.tokenize("perro").chars().count(), 4);
+        assert_eq!(digraphs.tokenize("lll").chars().nth(1), Some('l'));
+        assert_eq!(digraphs.tokenize("hola"), "hola");
+        let ll = llave.chars().next().unwrap();
+        assert_eq!(text(ll).as_deref(), Some("ll"));
+        assert_eq!(text('l'), None);
+        assert_eq!(digraphs.compose('l', 'l'), Some(ll));
+        assert_eq!(digraphs.compose('l', 'a'), None);
+        assert_eq!(digraphs.tokens().count(), 3);
+        // the same digraph always gets the same character
+        let again: Digraphs = "ll".parse().unwrap();
+        assert_eq!(again.tokenize("ll"), ll.to_string());
+        assert!("l".parse::<Digraphs>().is_err());
+        assert!("c-h".parse::<Digraphs>().is_err());
+        assert!(Digraphs::default().is_empty());
+    }
+}
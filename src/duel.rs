@@ -0,0 +1,598 @@
+//! `rordle duel`: two players race to find the same word over TCP, each
+//! seeing the hints of the other one's guesses but never their letters.
+//!
+//! Both sides send newline delimited JSON:
+//!
+//! - `{"type":"start","seed":123,"solutions":2314,"max_tries":6,"prefill":0}`
+//!   is sent by the host once the guest connected. Both pick the word with
+//!   the seed from their own word list, so both need the same list.
+//! - `{"type":"guess","hits":"gy--g"}` is sent for every accepted guess.
+//! - `{"type":"done","won":true,"guesses":3,"millis":41250}` is sent once
+//!   the game of a player is over or was left.
+//!
+//! A closed connection means the opponent left the duel.
+
+use crate::cli::{game_options, interface, language, Interface, ListArgs};
+use crate::error::RordleError;
+use crate::render::{
+    animate_last_guess, hit_char, render_below, render_game_state, render_opponent, tile_word,
+    Constraints,
+};
+use crate::terminal::Terminal;
+use crate::{init_game, key_name, load_word_list, on_terminal, outcome_exit_code};
+use clap::ArgMatches;
+use rordle_core::config::{Config, KeyName};
+use rordle_core::game::{GameOptions, GameState, HitInfo, Outcome};
+use rordle_core::i18n::{self, tr};
+use rordle_core::json::Json;
+use rordle_core::picker::RandomPicker;
+use rordle_core::words::MergedWordList;
+use std::cmp::Ordering;
+use std::io::{stdin, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant};
+use termion::event::Key;
+use termion::input::TermRead;
+
+/// How a player's game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Finish {
+    won: bool,
+    guesses: usize,
+    millis: u64,
+}
+
+impl Finish {
+    fn of(game_state: &GameState, elapsed: Duration) -> Finish {
+        Finish {
+            won: game_state.won(),
+            guesses: game_state.guesses.len(),
+            millis: elapsed.as_millis() as u64,
+        }
+    }
+
+    /// `Less` if this is the better result: the word was found with fewer
+    /// guesses, then faster. Two players who did not find it are equal.
+    fn compare(&self, other: &Finish) -> Ordering {
+        if !self.won && !other.won {
+            return Ordering::Equal;
+        }
+        (!self.won, self.guesses, self.millis).cmp(&(!other.won, other.guesses, other.millis))
+    }
+}
+
+/// A line sent between the players.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Message {
+    Start {
+        seed: u32,
+        solutions: usize,
+        max_tries: u16,
+        prefill: usize,
+    },
+    Guess(Vec<HitInfo>),
+    Done(Finish),
+}
+
+fn number(json: &Json, key: &str) -> Result<u64, String> {
+    match json.get(key) {
+        Some(Json::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as u64),
+        _ => Err(format!("a message needs a `{}` number", key)),
+    }
+}
+
+impl Message {
+    fn to_json(&self) -> Json {
+        match self {
+            Message::Start {
+                seed,
+                solutions,
+                max_tries,
+                prefill,
+            } => Json::object([
+                ("type", Json::from("start")),
+                ("seed", Json::from(*seed as usize)),
+                ("solutions", Json::from(*solutions)),
+                ("max_tries", Json::from(*max_tries as usize)),
+                ("prefill", Json::from(*prefill)),
+            ]),
+            Message::Guess(hits) => Json::object([
+                ("type", Json::from("guess")),
+                (
+                    "hits",
+                    Json::from(hits.iter().map(hit_char).collect::<String>()),
+                ),
+            ]),
+            Message::Done(finish) => Json::object([
+                ("type", Json::from("done")),
+                ("won", Json::from(finish.won)),
+                ("guesses", Json::from(finish.guesses)),
+                ("millis", Json::Number(finish.millis as f64)),
+            ]),
+        }
+    }
+
+    fn parse(line: &str) -> Result<Message, String> {
+        let json = Json::parse(line)?;
+        match json.get("type").and_then(Json::as_str) {
+            Some("start") => Ok(Message::Start {
+                seed: number(&json, "seed")? as u32,
+                solutions: number(&json, "solutions")? as usize,
+                max_tries: number(&json, "max_tries")? as u16,
+                prefill: number(&json, "prefill")? as usize,
+            }),
+            Some("guess") => {
+                let hits = json.get("hits").and_then(Json::as_str).unwrap_or_default();
+                hits.chars()
+                    .map(|c| match c {
+                        'g' => Ok(HitInfo::Hit),
+                        'y' => Ok(HitInfo::Contains),
+                        '-' => Ok(HitInfo::Miss),
+                        c => Err(format!("invalid hint `{}`", c)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Message::Guess)
+            }
+            Some("done") => Ok(Message::Done(Finish {
+                won: matches!(json.get("won"), Some(Json::Bool(true))),
+                guesses: number(&json, "guesses")? as usize,
+                millis: number(&json, "millis")?,
+            })),
+            _ => Err(format!("unknown message `{}`", line)),
+        }
+    }
+}
+
+fn send<W: Write>(peer: &mut W, message: &Message) -> std::io::Result<()> {
+    writeln!(peer, "{}", message.to_json())?;
+    peer.flush()
+}
+
+fn protocol_error(message: String) -> RordleError {
+    RordleError::Protocol(std::io::Error::new(ErrorKind::InvalidData, message))
+}
+
+/// Longest line read from the opponent, messages are far shorter.
+const MAX_LINE: usize = 4096;
+
+/// Reads a line of the opponent without its newline, `None` once the
+/// connection is closed. A line longer than `MAX_LINE` is not read to its
+/// end, the opponent is not following the protocol.
+fn read_message<R: BufRead>(peer: &mut R) -> Result<Option<String>, RordleError> {
+    let mut line = String::new();
+    // termion's `TermRead` has a `read_line` too
+    let mut limited = Read::take(&mut *peer, MAX_LINE as u64 + 1);
+    let read = BufRead::read_line(&mut limited, &mut line).map_err(RordleError::Network)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.len() > MAX_LINE {
+        return Err(protocol_error(format!(
+            "the opponent sent a line longer than {} bytes",
+            MAX_LINE
+        )));
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+/// Both players pick the word and the prefilled letters with the seed.
+fn start_game(
+    seed: u32,
+    options: GameOptions,
+    word_list: MergedWordList,
+) -> Result<GameState, RordleError> {
+    let seed = seed as u64;
+    init_game(
+        options.seed(Some(seed)),
+        word_list,
+        &mut RandomPicker::seeded(seed),
+    )
+}
+
+/// Starts the game of the host, telling the guest how to start the same.
+fn host_game<W: Write>(
+    peer: &mut W,
+    seed: u32,
+    options: GameOptions,
+    word_list: MergedWordList,
+) -> Result<GameState, RordleError> {
+    let start = Message::Start {
+        seed,
+        solutions: word_list.solutions.len(),
+        max_tries: options.max_tries,
+        prefill: options.prefill,
+    };
+    send(peer, &start).map_err(RordleError::Network)?;
+    start_game(seed, options, word_list)
+}
+
+/// Starts the game of the guest as told by the host.
+fn join_game<R: BufRead>(
+    peer: &mut R,
+    options: GameOptions,
+    word_list: MergedWordList,
+) -> Result<GameState, RordleError> {
+    let line = read_message(peer)?.unwrap_or_default();
+    match Message::parse(line.trim()).map_err(protocol_error)? {
+        Message::Start {
+            seed,
+            solutions,
+            max_tries,
+            prefill,
+        } => {
+            if solutions != word_list.solutions.len() {
+                return Err(protocol_error(format!(
+                    "the opponent plays with {} solutions, this word list has {}",
+                    solutions,
+                    word_list.solutions.len()
+                )));
+            }
+            let options = options.max_tries(max_tries).prefill(prefill);
+            start_game(seed, options, word_list)
+        }
+        _ => Err(protocol_error("expected the start of the duel".to_string())),
+    }
+}
+
+/// Something the duel has to react to.
+enum Event {
+    Key(Key),
+    Opponent(Message),
+    Left,
+}
+
+/// Forwards the messages of the opponent, malformed lines are skipped. A
+/// line too long to be a message ends the duel like a closed connection.
+fn spawn_reader(mut reader: BufReader<TcpStream>, events: Sender<Event>) {
+    std::thread::spawn(move || {
+        while let Ok(Some(line)) = read_message(&mut reader) {
+            if let Ok(message) = Message::parse(&line) {
+                if events.send(Event::Opponent(message)).is_err() {
+                    return;
+                }
+            }
+        }
+        let _ = events.send(Event::Left);
+    });
+}
+
+fn spawn_keys(events: Sender<Event>) {
+    std::thread::spawn(move || {
+        for key in stdin().keys() {
+            match key {
+                Ok(key) if events.send(Event::Key(key)).is_ok() => {}
+                _ => return,
+            }
+        }
+    });
+}
+
+/// Plays the duel until both games are over or the opponent left. Returns
+/// the results of both players, the opponent's is `None` if they left.
+fn duel_loop<P: Write, W: Write>(
+    game_state: &mut GameState,
+    events: &Receiver<Event>,
+    peer: &mut P,
+    output: &mut W,
+    interface: &Interface,
+) -> std::io::Result<(Finish, Option<Finish>)> {
+    let bindings = &interface.keybindings;
+    let start = Instant::now();
+    let mut rows = Vec::new();
+    let mut mine: Option<Finish> = None;
+    let mut theirs: Option<Finish> = None;
+    let mut left = false;
+    let mut constraints = Constraints::default();
+    // the opponent may be gone, which only matters once both are done
+    let finish = |game_state: &GameState, peer: &mut P| {
+        let finish = Finish::of(game_state, start.elapsed());
+        let _ = send(peer, &Message::Done(finish));
+        finish
+    };
+    loop {
+        let status = if left {
+            tr("duel-opponent-left", &[])
+        } else if theirs.is_some() {
+            tr("duel-opponent-finished", &[])
+        } else if mine.is_some() {
+            tr("duel-waiting-opponent", &[])
+        } else {
+            tr("duel-opponent", &[])
+        };
+        render_game_state(output, game_state, &interface.render);
+        render_opponent(output, game_state, &rows, &status, &interface.render);
+        if interface.show_constraints {
+            let lines = [constraints.line(game_state, &interface.render).to_string()];
+            render_below(output, game_state, &lines, &interface.render);
+        }
+        output.flush()?;
+        if mine.is_some() && (theirs.is_some() || left) {
+            break;
+        }
+        let key = match events.recv() {
+            Ok(Event::Key(key)) => key,
+            Ok(Event::Opponent(Message::Guess(hits))) => {
+                rows.push(hits);
+                continue;
+            }
+            Ok(Event::Opponent(Message::Done(finish))) => {
+                theirs = Some(finish);
+                continue;
+            }
+            Ok(Event::Opponent(Message::Start { .. })) => continue,
+            Ok(Event::Left) | Err(_) => {
+                left = true;
+                continue;
+            }
+        };
+        let key = key_name(key);
+        if mine.is_some() {
+            // waiting for the opponent can be given up on
+            if key == Some(bindings.quit) {
+                break;
+            }
+            continue;
+        }
+        match key {
+            Some(key) if key == bindings.quit => mine = Some(finish(game_state, peer)),
+            Some(key) if key == bindings.delete => game_state.back(),
+            Some(key) if key == bindings.submit => {
+                let tries = game_state.guesses.len();
+                game_state.confirm();
+                if game_state.last_error.is_some() && interface.bell {
+                    write!(output, "\x07")?;
+                }
+                if game_state.guesses.len() > tries {
+                    let hits = game_state.get_guess_hits(tries);
+                    let _ = send(peer, &Message::Guess(hits));
+                    if interface.animations {
+                        animate_last_guess(output, game_state, &interface.render);
+                    }
+                    if game_state.over() {
+                        mine = Some(finish(game_state, peer));
+                    }
+                }
+            }
+            Some(KeyName::Char(c)) => game_state.add_char(c),
+            _ => {}
+        }
+    }
+    let mine = mine.unwrap_or_else(|| finish(game_state, peer));
+    Ok((mine, theirs))
+}
+
+/// Id of the message announcing the winner.
+fn verdict(mine: &Finish, theirs: Option<&Finish>) -> &'static str {
+    match theirs.map(|theirs| mine.compare(theirs)) {
+        // leaving the duel gives it up
+        None | Some(Ordering::Less) => "duel-you-won",
+        Some(Ordering::Greater) => "duel-you-lost",
+        Some(Ordering::Equal) => "duel-draw",
+    }
+}
+
+fn result_line(player: &str, finish: &Finish) -> String {
+    let player: &dyn std::fmt::Display = &player;
+    if finish.won {
+        let seconds = format!("{:.1}", finish.millis as f64 / 1000.0);
+        tr(
+            "duel-solved",
+            &[
+                ("player", player),
+                ("guesses", &finish.guesses),
+                ("seconds", &seconds),
+            ],
+        )
+    } else {
+        tr("duel-failed", &[("player", player)])
+    }
+}
+
+/// `rordle duel`: hosts a duel with `--listen` or joins one with
+/// `--connect`. Exits like a game with the outcome of the own game.
+pub(crate) fn duel(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    i18n::set_language(language(matches, config));
+    let word_list = load_word_list(&ListArgs::from_matches(matches), config, false)?;
+    if word_list.solutions.is_empty() {
+        return Err(RordleError::EmptyWordList);
+    }
+    let options = game_options(matches, config);
+    let (stream, reader, mut game_state) = if let Some(port) = matches.value_of("listen") {
+        let port: u16 = port.parse().unwrap();
+        let listener = TcpListener::bind(("0.0.0.0", port)).map_err(RordleError::Network)?;
+        let port = listener.local_addr().map_err(RordleError::Network)?.port();
+        eprintln!("{}", tr("duel-waiting", &[("port", &port)]));
+        let (stream, _) = listener.accept().map_err(RordleError::Network)?;
+        let reader = BufReader::new(stream.try_clone().map_err(RordleError::Network)?);
+        let game_state = host_game(&mut &stream, rand::random(), options, word_list)?;
+        (stream, reader, game_state)
+    } else {
+        let address = matches.value_of("connect").unwrap();
+        let stream = TcpStream::connect(address).map_err(RordleError::Network)?;
+        let mut reader = BufReader::new(stream.try_clone().map_err(RordleError::Network)?);
+        let game_state = join_game(&mut reader, options, word_list)?;
+        (stream, reader, game_state)
+    };
+
+    let (sender, events) = channel();
+    spawn_reader(reader, sender.clone());
+    spawn_keys(sender);
+    let interface = on_terminal(&interface(matches, config));
+    let mut output = Terminal::open(interface.altscreen).map_err(RordleError::Terminal)?;
+    let (mine, theirs) = duel_loop(
+        &mut game_state,
+        &events,
+        &mut &stream,
+        &mut output,
+        &interface,
+    )
+    .map_err(RordleError::Terminal)?;
+    drop(output);
+    let _ = stream.shutdown(Shutdown::Both);
+
+    println!();
+    let word: &dyn std::fmt::Display = &tile_word(&game_state.word, interface.render.uppercase);
+    match game_state.outcome() {
+        Outcome::Won => println!("{}", tr("won", &[])),
+        Outcome::Lost => println!("{}", tr("lost", &[("word", word)])),
+        Outcome::Abandoned => println!("{}", tr("abandoned", &[("word", word)])),
+    }
+    println!("{}", result_line(&tr("duel-you", &[]), &mine));
+    match &theirs {
+        Some(theirs) => println!("{}", result_line(&tr("duel-opponent", &[]), theirs)),
+        None => println!("{}", tr("duel-opponent-left", &[])),
+    }
+    println!("{}", tr(verdict(&mine, theirs.as_ref()), &[]));
+    Ok(outcome_exit_code(game_state.outcome()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rordle_core::words::ListHeader;
+
+    fn word_list() -> MergedWordList {
+        let words: Vec<String> = ["hello", "jolly", "holly", "world"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        MergedWordList {
+            header: ListHeader::default(),
+            solutions: words.clone(),
+            words,
+            ..MergedWordList::default()
+        }
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+        let messages = [
+            Message::Start {
+                seed: 4_000_000_000,
+                solutions: 2314,
+                max_tries: 6,
+                prefill: 1,
+            },
+            Message::Guess(vec![HitInfo::Hit, HitInfo::Contains, HitInfo::Miss]),
+            Message::Done(Finish {
+                won: true,
+                guesses: 3,
+                millis: 41250,
+            }),
+        ];
+        for message in messages {
+            assert_eq!(Message::parse(&message.to_json().to_string()), Ok(message));
+        }
+        assert_eq!(
+            Message::Guess(vec![HitInfo::Hit, HitInfo::Miss])
+                .to_json()
+                .to_string(),
+            "{\"type\":\"guess\",\"hits\":\"g-\"}"
+        );
+        assert!(Message::parse("{\"type\":\"guess\",\"hits\":\"gx\"}").is_err());
+        assert!(Message::parse("{\"type\":\"start\"}").is_err());
+        assert!(Message::parse("hello").is_err());
+    }
+
+    #[test]
+    fn test_both_players_get_the_same_word() {
+        let options = GameOptions::default().max_tries(4).prefill(2);
+        let mut peer = Vec::new();
+        let host = host_game(&mut peer, 99, options, word_list()).unwrap();
+        // the guest takes the rules of the host
+        let guest = join_game(&mut &peer[..], GameOptions::default(), word_list()).unwrap();
+        assert_eq!(guest.word, host.word);
+        assert_eq!(guest.max_tries, 4);
+        assert_eq!(guest.current_guess, host.current_guess);
+
+        let mut other_list = word_list();
+        other_list.solutions.pop();
+        let result = join_game(&mut &peer[..], GameOptions::default(), other_list);
+        assert!(matches!(result, Err(RordleError::Protocol(_))));
+    }
+
+    #[test]
+    fn test_read_message() {
+        let mut peer = &b"{\"type\":\"guess\",\"hits\":\"g-\"}\r\nlast"[..];
+        assert_eq!(
+            read_message(&mut peer).unwrap().as_deref(),
+            Some("{\"type\":\"guess\",\"hits\":\"g-\"}")
+        );
+        assert_eq!(read_message(&mut peer).unwrap().as_deref(), Some("last"));
+        assert_eq!(read_message(&mut peer).unwrap(), None);
+        // a peer sending one endless line can not exhaust the memory
+        let endless = "[".repeat(MAX_LINE * 10);
+        let result = read_message(&mut endless.as_bytes());
+        assert!(matches!(result, Err(RordleError::Protocol(_))));
+        let result = join_game(&mut endless.as_bytes(), GameOptions::default(), word_list());
+        assert!(matches!(result, Err(RordleError::Protocol(_))));
+    }
+
+    #[test]
+    fn test_duel_loop_sends_only_hints() {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            ["hello", "jolly"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let (sender, events) = channel();
+        let opponent = Finish {
+            won: true,
+            guesses: 2,
+            millis: 5000,
+        };
+        sender
+            .send(Event::Opponent(Message::Guess(vec![HitInfo::Miss; 5])))
+            .unwrap();
+        for c in "jolly\nhello\n".chars() {
+            sender.send(Event::Key(Key::Char(c))).unwrap();
+        }
+        sender
+            .send(Event::Opponent(Message::Done(opponent)))
+            .unwrap();
+        let interface = crate::cli::interface(
+            &crate::cli::app().get_matches_from(["rordle"]),
+            &Config::default(),
+        );
+        let mut peer = Vec::new();
+        let (mine, theirs) = duel_loop(
+            &mut game_state,
+            &events,
+            &mut peer,
+            &mut Vec::new(),
+            &interface,
+        )
+        .unwrap();
+        assert!(mine.won);
+        assert_eq!(mine.guesses, 2);
+        assert_eq!(theirs, Some(opponent));
+        let peer = String::from_utf8(peer).unwrap();
+        let lines: Vec<&str> = peer.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "{\"type\":\"guess\",\"hits\":\"-ygg-\"}");
+        assert!(!peer.contains("jolly") && !peer.contains("hello"));
+        assert!(lines[2].starts_with("{\"type\":\"done\",\"won\":true,\"guesses\":2,"));
+    }
+
+    #[test]
+    fn test_verdict() {
+        let finish = |won, guesses, millis| Finish {
+            won,
+            guesses,
+            millis,
+        };
+        let mine = finish(true, 3, 9000);
+        assert_eq!(verdict(&mine, Some(&finish(true, 4, 1000))), "duel-you-won");
+        assert_eq!(
+            verdict(&mine, Some(&finish(true, 3, 8000))),
+            "duel-you-lost"
+        );
+        assert_eq!(verdict(&mine, Some(&finish(false, 1, 10))), "duel-you-won");
+        assert_eq!(verdict(&mine, None), "duel-you-won");
+        let lost = finish(false, 6, 9000);
+        assert_eq!(verdict(&lost, Some(&finish(false, 6, 10))), "duel-draw");
+        assert_eq!(verdict(&lost, Some(&mine)), "duel-you-lost");
+    }
+}
@@ -0,0 +1,124 @@
+use rordle_core::config::ConfigError;
+use rordle_core::date::Date;
+use rordle_core::game::OptionsError;
+use rordle_core::pack::PackError;
+use rordle_core::stats::StatsError;
+use rordle_core::words::WordFileError;
+
+/// Errors ending the program, reported to the user by `main`.
+#[derive(Debug)]
+pub(crate) enum RordleError {
+    WordList(WordFileError),
+    EmptyWordList,
+    NoMatchingSolutions(String),
+    NoTaggedSolutions(String),
+    Pack(String, PackError),
+    FutureDaily {
+        date: Date,
+        today: Date,
+    },
+    BeforeFirstDaily {
+        date: Date,
+        first: Date,
+    },
+    Config(ConfigError),
+    NoConfigDir,
+    Options(OptionsError),
+    Stats(StatsError),
+    NoStatsPath,
+    ProfileConflict(String, String),
+    ShareFile(String, std::io::Error),
+    Script(String, String),
+    Terminal(std::io::Error),
+    Protocol(std::io::Error),
+    Network(std::io::Error),
+    #[cfg(feature = "network")]
+    Leaderboard(std::path::PathBuf, String),
+}
+
+impl RordleError {
+    /// Exit code of the program: 2 for usage and data errors, 3 if the
+    /// terminal or the standard streams can not be used. Played games
+    /// exit with 0, 1 or 4 for their outcome, see `outcome_exit_code`.
+    pub(crate) fn exit_code(&self) -> i32 {
+        match self {
+            RordleError::Terminal(_) | RordleError::Protocol(_) => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for RordleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RordleError::WordList(error) => write!(f, "{}", error),
+            RordleError::EmptyWordList => write!(f, "the word list contains no words"),
+            RordleError::NoMatchingSolutions(pattern) => {
+                write!(f, "no solution of the word list matches `{}`", pattern)
+            }
+            RordleError::NoTaggedSolutions(tags) => {
+                write!(f, "no solution of the word list has the tags `{}`", tags)
+            }
+            RordleError::Pack(file, error) => write!(f, "{}: {}", file, error),
+            RordleError::FutureDaily { date, today } => write!(
+                f,
+                "there is no puzzle for {} yet, the latest one is for {}",
+                date, today
+            ),
+            RordleError::BeforeFirstDaily { date, first } => write!(
+                f,
+                "there is no puzzle for {}, the first one is for {}",
+                date, first
+            ),
+            RordleError::Config(error) => write!(f, "{}", error),
+            RordleError::NoConfigDir => write!(
+                f,
+                "can not locate the config file, neither XDG_CONFIG_HOME nor HOME is set"
+            ),
+            RordleError::Options(error) => write!(f, "{}", error),
+            RordleError::Stats(error) => write!(f, "{}", error),
+            RordleError::NoStatsPath => write!(
+                f,
+                "can not locate the stats file, neither XDG_DATA_HOME nor HOME is set"
+            ),
+            RordleError::ProfileConflict(before, after) => write!(
+                f,
+                "--profile {} and --profile {} select different profiles, give only one",
+                before, after
+            ),
+            RordleError::ShareFile(file, error) => write!(f, "{}: {}", file, error),
+            RordleError::Script(file, error) => write!(f, "{}: {}", file, error),
+            RordleError::Terminal(error) => write!(f, "terminal error: {}", error),
+            RordleError::Protocol(error) => write!(f, "protocol error: {}", error),
+            RordleError::Network(error) => write!(f, "network error: {}", error),
+            #[cfg(feature = "network")]
+            RordleError::Leaderboard(file, error) => write!(f, "{}: {}", file.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for RordleError {}
+
+impl From<WordFileError> for RordleError {
+    fn from(error: WordFileError) -> RordleError {
+        RordleError::WordList(error)
+    }
+}
+
+impl From<ConfigError> for RordleError {
+    fn from(error: ConfigError) -> RordleError {
+        RordleError::Config(error)
+    }
+}
+
+impl From<OptionsError> for RordleError {
+    fn from(error: OptionsError) -> RordleError {
+        RordleError::Options(error)
+    }
+}
+
+impl From<StatsError> for RordleError {
+    fn from(error: StatsError) -> RordleError {
+        RordleError::Stats(error)
+    }
+}
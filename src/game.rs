@@ -0,0 +1,2072 @@
+//! Rules of the game: scoring guesses and tracking the state of a game.
+
+use crate::digraph::{self, Digraphs};
+use crate::i18n::tr;
+use crate::words::{Alphabet, Rarity, WordStore};
+use rand::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+/// Feedback for a single letter of a guess.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum HitInfo {
+    /// The letter is in the word at this position.
+    Hit,
+    /// The letter is in the word at another position.
+    Contains,
+    /// The letter is not in the word.
+    Miss,
+    /// The letter is in the word, but the guess has more of it than the
+    /// word, shown only with [`GameOptions::count_hints`].
+    TooMany,
+    /// Not guessed yet.
+    None,
+}
+
+impl HitInfo {
+    fn rank(&self) -> u8 {
+        match self {
+            HitInfo::None => 0,
+            HitInfo::Miss => 1,
+            HitInfo::TooMany => 2,
+            HitInfo::Contains => 3,
+            HitInfo::Hit => 4,
+        }
+    }
+}
+
+/// Reasons for a guess or a hint to be rejected. Positions are counted
+/// from 0.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GameError {
+    /// The guess does not have the length of the solution.
+    WrongLength {
+        /// Length of the solution.
+        expected: usize,
+        /// Length of the guess.
+        actual: usize,
+    },
+    /// The guess contains a letter outside of the declared alphabet.
+    InvalidCharacter {
+        /// The offending letter.
+        letter: char,
+        /// Position of the letter in the guess.
+        position: usize,
+    },
+    /// The guess is not in the list of valid words.
+    InvalidWord,
+    /// The guess is a valid word, but none of the solutions of the list,
+    /// with [`GameOptions::answers_only`].
+    NotAnAnswer,
+    /// The word was guessed before.
+    RepeatedGuess,
+    /// Hard mode: a letter known to be in the word is not used.
+    MissingLetter {
+        /// The letter which must be used.
+        letter: char,
+    },
+    /// Hard mode: a letter known to be at a position is not used there.
+    MisplacedLetter {
+        /// The letter which must be used.
+        letter: char,
+        /// Position the letter must be at.
+        position: usize,
+    },
+    /// The game is already won or lost.
+    GameOver,
+    /// The hint costs more points than are left of the hint budget.
+    NotEnoughHintPoints {
+        /// Points the hint costs.
+        cost: u32,
+        /// Points left.
+        left: u32,
+    },
+    /// The hint has nothing left to reveal.
+    NothingToReveal,
+}
+
+// all messages of guess errors, translated in `crate::i18n`
+impl std::fmt::Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // a digraph is shown as the letters it stands for
+        let text = |letter: &char| digraph::text(*letter).unwrap_or_else(|| letter.to_string());
+        let message = match self {
+            GameError::WrongLength { expected, actual } => tr(
+                "wrong-length",
+                &[("expected", expected), ("actual", actual)],
+            ),
+            GameError::InvalidCharacter { letter, position } => tr(
+                "invalid-character",
+                &[("letter", &text(letter)), ("position", &(position + 1))],
+            ),
+            GameError::InvalidWord => tr("invalid-word", &[]),
+            GameError::NotAnAnswer => tr("not-an-answer", &[]),
+            GameError::RepeatedGuess => tr("repeated-guess", &[]),
+            GameError::MissingLetter { letter } => {
+                tr("missing-letter", &[("letter", &text(letter))])
+            }
+            GameError::MisplacedLetter { letter, position } => tr(
+                "misplaced-letter",
+                &[("letter", &text(letter)), ("position", &(position + 1))],
+            ),
+            GameError::GameOver => tr("game-over", &[]),
+            GameError::NotEnoughHintPoints { cost, left } => {
+                tr("hint-too-expensive", &[("cost", cost), ("left", left)])
+            }
+            GameError::NothingToReveal => tr("nothing-to-reveal", &[]),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for GameError {}
+
+/// Settings of a game, built with chained setters:
+/// `GameOptions::default().any_word(true).max_tries(8)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameOptions {
+    /// Accept any guess of the right length, not only valid words.
+    pub any_word: bool,
+    /// Number of guesses before the game is lost.
+    pub max_tries: u16,
+    /// Revealed hints must be used in later guesses.
+    pub hard: bool,
+    /// Number of letters of the solution revealed from the start.
+    pub prefill: usize,
+    /// Seed of the random choices of the game, like the prefilled letters.
+    /// `None` seeds from the operating system.
+    pub seed: Option<u64>,
+    /// Points to spend on hints during the game.
+    pub hint_budget: u32,
+    /// Letters guessed more often than they are in the word are marked
+    /// [`HitInfo::TooMany`], so the hints tell how often each letter is in
+    /// the word.
+    pub count_hints: bool,
+    /// Only words which can be the solution are accepted as guesses, see
+    /// [`GameState::solutions`].
+    pub answers_only: bool,
+}
+
+impl Default for GameOptions {
+    fn default() -> GameOptions {
+        GameOptions {
+            any_word: false,
+            max_tries: 6,
+            hard: false,
+            prefill: 0,
+            seed: None,
+            hint_budget: 0,
+            count_hints: false,
+            answers_only: false,
+        }
+    }
+}
+
+impl GameOptions {
+    /// Sets whether any guess of the right length is accepted.
+    pub fn any_word(mut self, any_word: bool) -> GameOptions {
+        self.any_word = any_word;
+        self
+    }
+
+    /// Sets the number of guesses before the game is lost.
+    pub fn max_tries(mut self, max_tries: u16) -> GameOptions {
+        self.max_tries = max_tries;
+        self
+    }
+
+    /// Sets whether revealed hints must be used in later guesses.
+    pub fn hard(mut self, hard: bool) -> GameOptions {
+        self.hard = hard;
+        self
+    }
+
+    /// Sets the number of letters revealed from the start.
+    pub fn prefill(mut self, prefill: usize) -> GameOptions {
+        self.prefill = prefill;
+        self
+    }
+
+    /// Sets the seed of the random choices of the game.
+    pub fn seed(mut self, seed: Option<u64>) -> GameOptions {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the points to spend on hints.
+    pub fn hint_budget(mut self, hint_budget: u32) -> GameOptions {
+        self.hint_budget = hint_budget;
+        self
+    }
+
+    /// Sets whether the hints tell how often each letter is in the word.
+    pub fn count_hints(mut self, count_hints: bool) -> GameOptions {
+        self.count_hints = count_hints;
+        self
+    }
+
+    /// Sets whether only words which can be the solution are guessed.
+    pub fn answers_only(mut self, answers_only: bool) -> GameOptions {
+        self.answers_only = answers_only;
+        self
+    }
+
+    /// Checks that the options can be used for a game.
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        if self.max_tries == 0 {
+            return Err(OptionsError::ZeroMaxTries);
+        }
+        Ok(())
+    }
+}
+
+/// Reasons a game can not be created.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OptionsError {
+    /// `max_tries` is 0.
+    ZeroMaxTries,
+    /// The solution is empty.
+    EmptyWord,
+    /// `prefill` leaves no letter of the solution to guess.
+    TooManyPrefilled {
+        /// Letters to prefill.
+        prefill: usize,
+        /// Letters of the solution.
+        length: usize,
+    },
+}
+
+impl std::fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OptionsError::ZeroMaxTries => write!(f, "At least one try is needed"),
+            OptionsError::EmptyWord => write!(f, "The word to guess is empty"),
+            OptionsError::TooManyPrefilled { prefill, length } => write!(
+                f,
+                "Can not prefill {} letters, the word to guess has only {}",
+                prefill, length
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OptionsError {}
+
+/// Number of times each letter occurs in a word. Counted once per word, so
+/// scoring and filtering do not search the word for every letter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LetterCounts {
+    /// Counts of `a` to `z`, the letters of most word lists.
+    ascii: [u8; 26],
+    /// Counts of all other letters.
+    other: HashMap<char, u8>,
+}
+
+impl LetterCounts {
+    /// Counts the letters of `word`.
+    pub fn new(word: &[char]) -> LetterCounts {
+        let mut counts = LetterCounts::default();
+        for &c in word {
+            match c {
+                'a'..='z' => counts.ascii[c as usize - 'a' as usize] += 1,
+                _ => *counts.other.entry(c).or_insert(0) += 1,
+            }
+        }
+        counts
+    }
+
+    /// How often `c` occurs.
+    pub fn get(&self, c: char) -> u8 {
+        match c {
+            'a'..='z' => self.ascii[c as usize - 'a' as usize],
+            _ => self.other.get(&c).copied().unwrap_or(0),
+        }
+    }
+
+    /// Takes one `c` away, whether there was one left.
+    fn take(&mut self, c: char) -> bool {
+        let count = match c {
+            'a'..='z' => &mut self.ascii[c as usize - 'a' as usize],
+            _ => match self.other.get_mut(&c) {
+                Some(count) => count,
+                None => return false,
+            },
+        };
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+}
+
+/// Scores a guess against the solution `word`, one `HitInfo` per letter,
+/// with the plain scorer [`score_letters`]. The guess must have the same
+/// length as the word.
+pub fn score(guess: &str, word: &str) -> Vec<HitInfo> {
+    let word: Vec<char> = word.chars().collect();
+    score_letters(guess.chars(), &word, &LetterCounts::new(&word))
+}
+
+/// Scores the letters of a guess against the letters of the solution and
+/// their counts.
+///
+/// This plain scorer ignores how often a letter is in the word: every
+/// letter of the guess in the word but not at its position is
+/// [`HitInfo::Contains`], e.g. both `e` of `eerie` besides the hit against
+/// the one of `hello`. Games score with it unless [`GameOptions::count_hints`] is set,
+/// and [`score`] and the solver always use it, also to rank guesses of a
+/// game with count hints. The counts only save searching the word for each
+/// letter.
+pub fn score_letters(
+    guess: impl IntoIterator<Item = char>,
+    word: &[char],
+    counts: &LetterCounts,
+) -> Vec<HitInfo> {
+    guess
+        .into_iter()
+        .zip(word)
+        .map(|(c, w)| {
+            if c == *w {
+                HitInfo::Hit
+            } else if counts.get(c) > 0 {
+                HitInfo::Contains
+            } else {
+                HitInfo::Miss
+            }
+        })
+        .collect()
+}
+
+/// Scores like [`score_letters`], but for each letter only as many of the
+/// guess as the word has are hits or contained, hits first. The others of
+/// a letter in the word are [`HitInfo::TooMany`], so the hints tell how
+/// often each letter is in the word.
+///
+/// This is the only scorer aware of letters guessed more often than they
+/// are in the word. Games score with it only with
+/// [`GameOptions::count_hints`].
+pub fn score_letters_counted(
+    guess: impl IntoIterator<Item = char>,
+    word: &[char],
+    counts: &LetterCounts,
+) -> Vec<HitInfo> {
+    let guess: Vec<char> = guess.into_iter().collect();
+    let mut left = counts.clone();
+    for (&c, w) in guess.iter().zip(word) {
+        if c == *w {
+            left.take(c);
+        }
+    }
+    guess
+        .iter()
+        .zip(word)
+        .map(|(&c, w)| {
+            if c == *w {
+                HitInfo::Hit
+            } else if left.take(c) {
+                HitInfo::Contains
+            } else if counts.get(c) > 0 {
+                HitInfo::TooMany
+            } else {
+                HitInfo::Miss
+            }
+        })
+        .collect()
+}
+
+/// A hint bought with points of the hint budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    /// Reveals the letter at a position not found yet.
+    Position,
+    /// Tells whether the word contains the letter.
+    Letter(char),
+    /// Reveals three letters which are not in the word.
+    Eliminate,
+}
+
+impl Hint {
+    /// Points the hint costs.
+    pub fn cost(&self) -> u32 {
+        match self {
+            Hint::Position => 2,
+            Hint::Letter(_) | Hint::Eliminate => 1,
+        }
+    }
+}
+
+/// Letters a hint eliminates at once.
+const ELIMINATED_LETTERS: usize = 3;
+
+/// What a hint revealed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Revealed {
+    /// The letter of the word at a position, counted from 0.
+    Position {
+        /// The position.
+        position: usize,
+        /// The letter at the position.
+        letter: char,
+    },
+    /// Whether the word contains a letter.
+    Letter {
+        /// The letter asked for.
+        letter: char,
+        /// Whether it is in the word.
+        contained: bool,
+    },
+    /// Letters not in the word, sorted.
+    Eliminated(Vec<char>),
+}
+
+impl std::fmt::Display for Revealed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message = match self {
+            Revealed::Position { position, letter } => tr(
+                "hint-position",
+                &[("letter", letter), ("position", &(position + 1))],
+            ),
+            Revealed::Letter {
+                letter,
+                contained: true,
+            } => tr("hint-contains", &[("letter", letter)]),
+            Revealed::Letter {
+                letter,
+                contained: false,
+            } => tr("hint-not-contains", &[("letter", letter)]),
+            Revealed::Eliminated(letters) => {
+                let letters: Vec<String> = letters.iter().map(char::to_string).collect();
+                tr("hint-eliminated", &[("letters", &letters.join(", "))])
+            }
+        };
+        f.write_str(&message)
+    }
+}
+
+/// What is known about the solution, see `GameState::knowledge`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Knowledge {
+    /// The letter found at each position.
+    pub placed: Vec<Option<char>>,
+    /// Letters known to be in the word but not found at any position,
+    /// sorted.
+    pub unplaced: Vec<char>,
+    /// Letters known not to be in the word, sorted.
+    pub excluded: Vec<char>,
+}
+
+/// How a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The solution was found.
+    Won,
+    /// All tries were used without finding the solution.
+    Lost,
+    /// The game was left before it was over.
+    Abandoned,
+}
+
+/// State of a single game: the solution, the guesses made so far and the
+/// guess currently being typed.
+pub struct GameState {
+    /// Words accepted as guesses.
+    pub valid_words: WordStore,
+    /// The valid words which can be the solution, the only ones accepted
+    /// with [`GameOptions::answers_only`]. `None` if every valid word can.
+    pub solutions: Option<WordStore>,
+    /// Confirmed guesses, in order.
+    pub guesses: Vec<String>,
+    /// Time each guess of `guesses` took, since the guess before or the
+    /// start of the game. `None` marks a gap, see `mark_gap`.
+    pub guess_times: Vec<Option<Duration>>,
+    /// The guess being typed.
+    pub current_guess: String,
+    /// The solution.
+    pub word: String,
+    /// Number of guesses before the game is lost.
+    pub max_tries: u16,
+    /// Error of the last confirmed guess, if it was rejected.
+    pub last_error: Option<GameError>,
+    options: GameOptions,
+    /// Letters which can be typed.
+    pub alphabet: Alphabet,
+    /// Digraphs played as single letters, the words and guesses are
+    /// tokenized with them, see `crate::digraph`.
+    pub digraphs: Digraphs,
+    /// Display name of the word list.
+    pub list_name: Option<String>,
+    /// How rare `word` is among the solutions of the list.
+    pub rarity: Rarity,
+    /// Players taking turns in guessing, empty for a single player.
+    pub players: Vec<String>,
+    /// The players are a team, credited with what each of them discovered
+    /// rather than with the last two guesses.
+    pub team: bool,
+    /// Letters of `word`, so they can be indexed.
+    letters: Vec<char>,
+    /// Counts of `letters`.
+    counts: LetterCounts,
+    /// Letters of each guess in `guesses`.
+    guess_letters: Vec<Vec<char>>,
+    /// Hints of each guess in `guesses`, scored once when it was made.
+    guess_hits: Vec<Vec<HitInfo>>,
+    /// Whether each letter of `word` was revealed from the start or by a
+    /// hint.
+    prefilled: Vec<bool>,
+    /// Points left of the hint budget.
+    hint_points: u32,
+    /// What the hints bought so far revealed, in order.
+    hints: Vec<Revealed>,
+    /// Picks the prefilled letters and what hints reveal.
+    rng: StdRng,
+    /// When the time of the next guess started, `None` after a gap.
+    clock: Option<Instant>,
+}
+
+impl GameState {
+    /// Creates a game for the solution `word`.
+    pub fn new(
+        word: String,
+        valid_words: WordStore,
+        options: GameOptions,
+    ) -> Result<GameState, OptionsError> {
+        options.validate()?;
+        if word.is_empty() {
+            return Err(OptionsError::EmptyWord);
+        }
+        let letters: Vec<char> = word.chars().collect();
+        if options.prefill > 0 && options.prefill >= letters.len() {
+            return Err(OptionsError::TooManyPrefilled {
+                prefill: options.prefill,
+                length: letters.len(),
+            });
+        }
+        let mut rng = match options.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let mut prefilled = vec![false; letters.len()];
+        if options.prefill > 0 {
+            for position in (0..letters.len()).choose_multiple(&mut rng, options.prefill) {
+                prefilled[position] = true;
+            }
+        }
+        let mut game_state = GameState {
+            alphabet: Alphabet::from_words(valid_words.iter().chain([word.as_str()])),
+            digraphs: Digraphs::default(),
+            list_name: None,
+            rarity: Rarity::Unknown,
+            players: Vec::new(),
+            team: false,
+            valid_words,
+            solutions: None,
+            guesses: Vec::new(),
+            guess_times: Vec::new(),
+            current_guess: String::new(),
+            counts: LetterCounts::new(&letters),
+            letters,
+            guess_letters: Vec::new(),
+            guess_hits: Vec::new(),
+            prefilled,
+            hint_points: options.hint_budget,
+            hints: Vec::new(),
+            rng,
+            clock: Some(Instant::now()),
+            word,
+            max_tries: options.max_tries,
+            last_error: None,
+            options,
+        };
+        game_state.fill_prefilled();
+        Ok(game_state)
+    }
+
+    /// A game with the same solution, words and options as this one, in
+    /// the state it started in, to try other guesses without changing this
+    /// game. Letters revealed by hints are not revealed again.
+    pub fn sandbox(&self) -> GameState {
+        let mut prefilled = self.prefilled.clone();
+        for hint in &self.hints {
+            if let Revealed::Position { position, .. } = hint {
+                prefilled[*position] = false;
+            }
+        }
+        let mut game_state = GameState {
+            valid_words: self.valid_words.clone(),
+            solutions: self.solutions.clone(),
+            guesses: Vec::new(),
+            guess_times: Vec::new(),
+            current_guess: String::new(),
+            word: self.word.clone(),
+            max_tries: self.max_tries,
+            last_error: None,
+            options: self.options.clone(),
+            alphabet: self.alphabet.clone(),
+            digraphs: self.digraphs.clone(),
+            list_name: self.list_name.clone(),
+            rarity: self.rarity,
+            players: self.players.clone(),
+            team: self.team,
+            letters: self.letters.clone(),
+            counts: self.counts.clone(),
+            guess_letters: Vec::new(),
+            guess_hits: Vec::new(),
+            prefilled,
+            hint_points: self.options.hint_budget,
+            hints: Vec::new(),
+            rng: self.rng.clone(),
+            clock: Some(Instant::now()),
+        };
+        game_state.fill_prefilled();
+        game_state
+    }
+
+    /// Marks a gap in the times of the guesses, e.g. while the game is
+    /// suspended: the next guess gets no time instead of one counting the
+    /// time away.
+    pub fn mark_gap(&mut self) {
+        self.clock = None;
+    }
+
+    /// Submits a guess, returning whether it is the solution.
+    pub fn guess(&mut self, guess: String) -> Result<bool, GameError> {
+        if self.over() {
+            return Err(GameError::GameOver);
+        }
+        // guesses given as text, e.g. on the standard input, spell digraphs
+        // out
+        let guess = self.digraphs.tokenize(&guess);
+        let letters: Vec<char> = guess.chars().collect();
+        let expected = self.letters.len();
+        let actual = letters.len();
+        if actual != expected {
+            return Err(GameError::WrongLength { expected, actual });
+        }
+        // prefilled letters are part of the word, but can not be changed
+        if let Some(position) =
+            (0..expected).find(|i| self.prefilled[*i] && letters[*i] != self.letters[*i])
+        {
+            return Err(GameError::MisplacedLetter {
+                letter: self.letters[position],
+                position,
+            });
+        }
+        // also with any_word, a guess is made of letters only
+        if let Some((position, letter)) = letters
+            .iter()
+            .enumerate()
+            .find(|(i, c)| !self.prefilled[*i] && !self.alphabet.accepts(**c))
+        {
+            return Err(GameError::InvalidCharacter {
+                letter: *letter,
+                position,
+            });
+        }
+        if self.guesses.contains(&guess) {
+            return Err(GameError::RepeatedGuess);
+        }
+        if !self.options.any_word && !self.valid_words.contains(&guess) {
+            return Err(GameError::InvalidWord);
+        }
+        if self.options.answers_only && !self.guessable_words().contains(&guess) {
+            return Err(GameError::NotAnAnswer);
+        }
+        if self.options.hard {
+            self.check_hard_mode(&letters)?;
+        }
+        self.guesses.push(guess);
+        let now = Instant::now();
+        self.guess_times
+            .push(self.clock.map(|start| now.duration_since(start)));
+        self.clock = Some(now);
+        let hits = if self.options.count_hints {
+            score_letters_counted(letters.iter().copied(), &self.letters, &self.counts)
+        } else {
+            score_letters(letters.iter().copied(), &self.letters, &self.counts)
+        };
+        self.guess_hits.push(hits);
+        self.guess_letters.push(letters);
+        Ok(self.won())
+    }
+
+    /// Checks that the guess keeps all letters revealed by earlier guesses:
+    /// hits at their position, contained letters anywhere.
+    fn check_hard_mode(&self, letters: &[char]) -> Result<(), GameError> {
+        for (i, previous) in self.guess_letters.iter().enumerate() {
+            let hits = self.get_guess_hits(i);
+            for (position, (&letter, hit)) in previous.iter().zip(&hits).enumerate() {
+                if *hit == HitInfo::Hit && letters[position] != letter {
+                    return Err(GameError::MisplacedLetter { letter, position });
+                }
+            }
+            for (&letter, hit) in previous.iter().zip(&hits) {
+                if *hit == HitInfo::Contains && !letters.contains(&letter) {
+                    return Err(GameError::MissingLetter { letter });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn set_last_error(&mut self, error: GameError) {
+        self.last_error = Some(error);
+    }
+
+    fn reset_error(&mut self) {
+        self.last_error = None;
+    }
+
+    /// Whether the last guess is the solution.
+    pub fn won(&self) -> bool {
+        match self.guesses.last() {
+            Some(last_guess) => last_guess == &self.word,
+            None => false,
+        }
+    }
+
+    /// Whether revealed hints must be used in later guesses.
+    pub fn hard(&self) -> bool {
+        self.options.hard
+    }
+
+    /// Whether the hints tell how often each letter is in the word.
+    pub fn count_hints(&self) -> bool {
+        self.options.count_hints
+    }
+
+    /// Whether only words which can be the solution are accepted.
+    pub fn answers_only(&self) -> bool {
+        self.options.answers_only
+    }
+
+    /// The words accepted as guesses: the solutions with
+    /// [`GameOptions::answers_only`], else all valid words.
+    pub fn guessable_words(&self) -> &WordStore {
+        match &self.solutions {
+            Some(solutions) if self.options.answers_only => solutions,
+            _ => &self.valid_words,
+        }
+    }
+
+    /// Whether the game has ended, either won or out of guesses.
+    pub fn over(&self) -> bool {
+        self.won() || self.guesses.len() >= self.max_tries as usize
+    }
+
+    /// How the game ended, `Abandoned` while it is not over.
+    pub fn outcome(&self) -> Outcome {
+        if self.won() {
+            Outcome::Won
+        } else if self.over() {
+            Outcome::Lost
+        } else {
+            Outcome::Abandoned
+        }
+    }
+
+    /// Letters of the solution.
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// Player of the guess at `guess_position`, `None` without players. Only
+    /// accepted guesses pass the turn on.
+    pub fn player(&self, guess_position: usize) -> Option<&str> {
+        if self.players.is_empty() {
+            return None;
+        }
+        Some(&self.players[guess_position % self.players.len()])
+    }
+
+    /// Player whose turn it is.
+    pub fn current_player(&self) -> Option<&str> {
+        self.player(self.guesses.len())
+    }
+
+    /// The hits each player discovered, in the order of `players`: the
+    /// positions their guess was the first to hit, not counting revealed
+    /// ones.
+    pub fn discoveries(&self) -> Vec<(&str, usize)> {
+        let mut counts = vec![0; self.players.len()];
+        let mut found = self.prefilled.clone();
+        for (y, hits) in self.guess_hits.iter().enumerate() {
+            for (x, hit) in hits.iter().enumerate() {
+                if *hit == HitInfo::Hit && !found[x] {
+                    found[x] = true;
+                    counts[y % self.players.len()] += 1;
+                }
+            }
+        }
+        self.players
+            .iter()
+            .map(String::as_str)
+            .zip(counts)
+            .collect()
+    }
+
+    /// Whether the letter at `position` was revealed from the start or by
+    /// a hint.
+    pub fn prefilled(&self, position: usize) -> bool {
+        self.prefilled[position]
+    }
+
+    /// Points the game started with to spend on hints.
+    pub fn hint_budget(&self) -> u32 {
+        self.options.hint_budget
+    }
+
+    /// Points left to spend on hints.
+    pub fn hint_points(&self) -> u32 {
+        self.hint_points
+    }
+
+    /// What the hints bought so far revealed, in order.
+    pub fn hints(&self) -> &[Revealed] {
+        &self.hints
+    }
+
+    /// Buys `hint` with points of the hint budget. Revealed positions are
+    /// filled in like prefilled letters, so the guess being typed starts
+    /// over.
+    pub fn hint(&mut self, hint: Hint) -> Result<&Revealed, GameError> {
+        if self.over() {
+            return Err(GameError::GameOver);
+        }
+        let cost = hint.cost();
+        if cost > self.hint_points {
+            return Err(GameError::NotEnoughHintPoints {
+                cost,
+                left: self.hint_points,
+            });
+        }
+        let revealed = match hint {
+            Hint::Position => {
+                // positions not revealed and not found by a guess
+                let hidden: Vec<usize> = (0..self.letters.len())
+                    .filter(|&i| {
+                        !self.prefilled[i]
+                            && !self.guess_letters.iter().any(|g| g[i] == self.letters[i])
+                    })
+                    .collect();
+                let position = *hidden
+                    .choose(&mut self.rng)
+                    .ok_or(GameError::NothingToReveal)?;
+                self.prefilled[position] = true;
+                self.current_guess = String::new();
+                self.fill_prefilled();
+                Revealed::Position {
+                    position,
+                    letter: self.letters[position],
+                }
+            }
+            Hint::Letter(letter) => {
+                let letter = letter.to_lowercase().next().unwrap();
+                Revealed::Letter {
+                    letter,
+                    contained: self.counts.get(letter) > 0,
+                }
+            }
+            Hint::Eliminate => {
+                // letters whose hit info is not known yet
+                let known = self.letter_hits();
+                let candidates: Vec<char> = self
+                    .alphabet
+                    .letters
+                    .iter()
+                    .copied()
+                    .filter(|&c| self.counts.get(c) == 0 && !known.contains_key(&c))
+                    .collect();
+                if candidates.is_empty() {
+                    return Err(GameError::NothingToReveal);
+                }
+                let mut letters: Vec<char> = candidates
+                    .choose_multiple(&mut self.rng, ELIMINATED_LETTERS)
+                    .copied()
+                    .collect();
+                letters.sort_unstable();
+                Revealed::Eliminated(letters)
+            }
+        };
+        self.hint_points -= cost;
+        self.hints.push(revealed);
+        Ok(self.hints.last().unwrap())
+    }
+
+    /// Letters of the guess at `guess_position`.
+    pub fn guess_letters(&self, guess_position: usize) -> &[char] {
+        &self.guess_letters[guess_position]
+    }
+
+    /// Hints of the guess at `guess_position`.
+    pub fn get_guess_hits(&self, guess_position: usize) -> Vec<HitInfo> {
+        self.guess_hits[guess_position].clone()
+    }
+
+    /// What the hints of all guesses and the revealed letters tell about
+    /// the solution.
+    pub fn knowledge(&self) -> Knowledge {
+        let mut placed: Vec<Option<char>> = (0..self.letters.len())
+            .map(|i| self.prefilled[i].then(|| self.letters[i]))
+            .collect();
+        let mut contained = BTreeSet::new();
+        let mut missed = BTreeSet::new();
+        for (letters, hits) in self.guess_letters.iter().zip(&self.guess_hits) {
+            for (i, (&c, hit)) in letters.iter().zip(hits).enumerate() {
+                match hit {
+                    HitInfo::Hit => {
+                        placed[i] = Some(c);
+                        contained.insert(c);
+                    }
+                    HitInfo::Contains | HitInfo::TooMany => {
+                        contained.insert(c);
+                    }
+                    HitInfo::Miss => {
+                        missed.insert(c);
+                    }
+                    HitInfo::None => {}
+                }
+            }
+        }
+        for revealed in &self.hints {
+            match revealed {
+                Revealed::Letter {
+                    letter,
+                    contained: true,
+                } => {
+                    contained.insert(*letter);
+                }
+                Revealed::Letter {
+                    letter,
+                    contained: false,
+                } => {
+                    missed.insert(*letter);
+                }
+                Revealed::Eliminated(letters) => missed.extend(letters),
+                Revealed::Position { .. } => {}
+            }
+        }
+        // every occurrence of a contained letter is hinted as such, so a
+        // placed letter may or may not occur again and is not owed
+        let unplaced = contained
+            .iter()
+            .copied()
+            .filter(|c| !placed.contains(&Some(*c)))
+            .collect();
+        let excluded = missed.difference(&contained).copied().collect();
+        Knowledge {
+            placed,
+            unplaced,
+            excluded,
+        }
+    }
+
+    /// Best known hit info of every guessed letter and every letter
+    /// revealed by a hint, for the keyboard.
+    pub fn letter_hits(&self) -> HashMap<char, HitInfo> {
+        let mut letters = HashMap::new();
+        let mut learn = |c: char, hit: HitInfo| {
+            let known = letters.entry(c).or_insert(HitInfo::None);
+            if hit.rank() > known.rank() {
+                *known = hit;
+            }
+        };
+        for (i, guess) in self.guess_letters.iter().enumerate() {
+            for (&c, hit) in guess.iter().zip(self.get_guess_hits(i)) {
+                learn(c, hit);
+            }
+        }
+        for revealed in &self.hints {
+            match revealed {
+                Revealed::Position { letter, .. } => learn(*letter, HitInfo::Hit),
+                Revealed::Letter { letter, contained } => learn(
+                    *letter,
+                    if *contained {
+                        HitInfo::Contains
+                    } else {
+                        HitInfo::Miss
+                    },
+                ),
+                Revealed::Eliminated(eliminated) => {
+                    for &c in eliminated {
+                        learn(c, HitInfo::Miss);
+                    }
+                }
+            }
+        }
+        letters
+    }
+
+    /// Appends the prefilled letters following the typed ones, so the
+    /// cursor jumps over them.
+    fn fill_prefilled(&mut self) {
+        let mut typed = self.current_guess.chars().count();
+        while typed < self.letters.len() && self.prefilled[typed] {
+            self.current_guess.push(self.letters[typed]);
+            typed += 1;
+        }
+    }
+
+    /// Removes the last typed letter, keeping prefilled ones.
+    pub fn back(&mut self) {
+        let mut letters: Vec<char> = self.current_guess.chars().collect();
+        while !letters.is_empty() && self.prefilled[letters.len() - 1] {
+            letters.pop();
+        }
+        if letters.pop().is_some() {
+            self.current_guess = letters.into_iter().collect();
+        }
+    }
+
+    /// Submits the typed guess, remembering the error if it is rejected.
+    pub fn confirm(&mut self) {
+        let result = self.guess(self.current_guess.clone());
+        match result {
+            Ok(_) => {
+                self.reset_error();
+            }
+            Err(error) => {
+                self.set_last_error(error);
+            }
+        };
+        self.current_guess = String::new();
+        self.fill_prefilled();
+    }
+
+    /// Types a letter, ignoring it if the guess is complete or the letter
+    /// is not part of a declared alphabet. A letter making a digraph with
+    /// the letter typed before, like a second `l` after an `l`, turns both
+    /// into the digraph.
+    ///
+    /// A letter may lowercase to several characters, all of which are typed,
+    /// or none if they do not fit. The mapping is the one of Unicode, like
+    /// for the words of the lists, which are lowercased the same way: `İ`
+    /// becomes `i` followed by a combining dot, and `I` becomes `i`, not the
+    /// dotless `ı` of Turkish, which is typed as itself.
+    pub fn add_char(&mut self, c: char) {
+        let lowercase: Vec<char> = c.to_lowercase().collect();
+        // only declared alphabets filter input, inferred ones may be incomplete
+        if self.alphabet.declared && !lowercase.iter().all(|&c| self.alphabet.contains(c)) {
+            return;
+        }
+        let typed = self.current_guess.clone();
+        for c in lowercase {
+            let mut letters: Vec<char> = self.current_guess.chars().collect();
+            let last = letters.len().checked_sub(1);
+            let composed = last
+                .filter(|&last| !self.prefilled[last])
+                .and_then(|last| self.digraphs.compose(letters[last], c));
+            if let Some(composed) = composed {
+                letters.pop();
+                letters.push(composed);
+                self.current_guess = letters.into_iter().collect();
+                self.fill_prefilled();
+                continue;
+            }
+            if self.current_guess.chars().count() >= self.letters.len() {
+                self.current_guess = typed;
+                return;
+            }
+            self.current_guess.push(c);
+            self.fill_prefilled();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word_store(words: &[&str]) -> WordStore {
+        words.iter().collect()
+    }
+
+    #[test]
+    fn test_letter_counts() {
+        let letters: Vec<char> = "schüssel".chars().collect();
+        let counts = LetterCounts::new(&letters);
+        assert_eq!(counts.get('s'), 3);
+        assert_eq!(counts.get('ü'), 1);
+        assert_eq!(counts.get('a'), 0);
+        assert_eq!(counts.get('ä'), 0);
+    }
+
+    #[test]
+    fn test_score_letters_counted() {
+        use HitInfo::*;
+        let score = |guess: &str, word: &str| {
+            let word: Vec<char> = word.chars().collect();
+            score_letters_counted(guess.chars(), &word, &LetterCounts::new(&word))
+        };
+        // both l are hit, so the first one is one too many
+        assert_eq!(score("lolly", "hello"), [TooMany, Contains, Hit, Hit, Miss]);
+        assert_eq!(score("eerie", "hello"), [TooMany, Hit, Miss, Miss, TooMany]);
+        // the plain scorer ignores that `hello` has only one `e`
+        assert_eq!(
+            super::score("eerie", "hello"),
+            [Contains, Hit, Miss, Miss, Contains]
+        );
+        // the hit e comes first, the l are contained from left to right
+        assert_eq!(
+            score("level", "hello"),
+            [Contains, Hit, Miss, TooMany, Contains]
+        );
+        // like the plain scoring if there are enough of every letter
+        assert_eq!(score("world", "hello"), super::score("world", "hello"));
+
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            word_store(&["eerie"]),
+            GameOptions::default().count_hints(true),
+        )
+        .unwrap();
+        assert!(game_state.count_hints());
+        game_state.guess("eerie".to_string()).unwrap();
+        assert_eq!(game_state.get_guess_hits(0)[0], TooMany);
+        assert_eq!(game_state.knowledge().unplaced, Vec::<char>::new());
+    }
+
+    #[test]
+    fn test_new_guess() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let result = game_state.guess("hello".to_string());
+        assert!(result.unwrap());
+        assert_eq!(game_state.guesses.len(), 1);
+        assert_eq!(game_state.guesses[0], "hello".to_string());
+    }
+
+    #[test]
+    fn test_new_guess_umlaut() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hällö"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let result = game_state.guess("hällö".to_string());
+        assert!(!result.unwrap());
+        assert_eq!(game_state.guesses.len(), 1);
+        assert_eq!(game_state.guesses[0], "hällö".to_string());
+    }
+
+    #[test]
+    fn test_new_guess_miss() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "world"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let result = game_state.guess("world".to_string());
+        assert!(!result.unwrap());
+        assert_eq!(game_state.guesses.len(), 1);
+        assert_eq!(game_state.guesses[0], "world".to_string());
+    }
+
+    #[test]
+    fn test_guess_rejects_word_of_wrong_length() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let result = game_state.guess("hell".to_string());
+        assert!(
+            matches!(result, Err(GameError::WrongLength { .. })),
+            "No error raised for wrong length"
+        );
+        assert_eq!(game_state.guesses.len(), 0);
+    }
+
+    #[test]
+    fn test_guess_rejects_invalid_words() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let result = game_state.guess("jello".to_string());
+        assert!(
+            matches!(result, Err(GameError::InvalidWord)),
+            "No error raised for invalid word"
+        );
+        assert_eq!(game_state.guesses.len(), 0);
+    }
+
+    #[test]
+    fn test_get_guess_hits() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let result = game_state.guess("jolly".to_string());
+        assert!(!result.unwrap());
+        let hits = game_state.get_guess_hits(0);
+        assert_eq!(hits.len(), 5);
+        assert_eq!(hits[0], HitInfo::Miss);
+        assert_eq!(hits[1], HitInfo::Contains);
+        assert_eq!(hits[2], HitInfo::Hit);
+        assert_eq!(hits[3], HitInfo::Hit);
+        assert_eq!(hits[4], HitInfo::Miss);
+    }
+
+    #[test]
+    fn test_add_char() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('h');
+        assert_eq!(game_state.current_guess, "h".to_string());
+        game_state.add_char('e');
+        assert_eq!(game_state.current_guess, "he".to_string());
+        game_state.add_char('l');
+        assert_eq!(game_state.current_guess, "hel".to_string());
+        game_state.add_char('l');
+        assert_eq!(game_state.current_guess, "hell".to_string());
+        game_state.add_char('o');
+        assert_eq!(game_state.current_guess, "hello".to_string());
+        game_state.add_char('o');
+        assert_eq!(game_state.current_guess, "hello".to_string());
+    }
+
+    #[test]
+    fn test_digraphs() {
+        let digraphs: Digraphs = "ch,ll".parse().unwrap();
+        let llave = digraphs.tokenize("llave");
+        let lleva = digraphs.tokenize("lleva");
+        let mut game_state = super::GameState::new(
+            llave.clone(),
+            word_store(&[&llave, &lleva]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.digraphs = digraphs;
+        assert_eq!(game_state.letters().len(), 4);
+        // typing `l` twice makes one tile
+        for c in "lleva".chars() {
+            game_state.add_char(c);
+        }
+        assert_eq!(game_state.current_guess, lleva);
+        game_state.confirm();
+        assert_eq!(
+            game_state.get_guess_hits(0),
+            vec![
+                HitInfo::Hit,
+                HitInfo::Contains,
+                HitInfo::Hit,
+                HitInfo::Contains
+            ]
+        );
+        // guesses given as text are tokenized
+        game_state.guess("llave".to_string()).unwrap();
+        assert!(game_state.won());
+    }
+
+    #[test]
+    fn test_add_char_test_umlaut() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('Ü');
+        assert_eq!(game_state.current_guess, "ü".to_string());
+    }
+
+    #[test]
+    fn test_add_char_test_umlaut_length() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('Ü');
+        game_state.add_char('Ü');
+        game_state.add_char('Ü');
+        game_state.add_char('Ü');
+        game_state.add_char('Ü');
+        assert_eq!(game_state.current_guess, "üüüüü".to_string());
+    }
+
+    #[test]
+    fn test_add_char_lowercase_expansion() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        // a dot above is combined with the i
+        game_state.add_char('\u{130}');
+        assert_eq!(game_state.current_guess, "i\u{307}");
+        game_state.add_char('\u{1e9e}');
+        assert_eq!(game_state.current_guess, "i\u{307}ß");
+        game_state.add_char('ı');
+        game_state.add_char('ß');
+        assert_eq!(game_state.current_guess, "i\u{307}ßıß");
+        // both characters are typed or none
+        game_state.back();
+        game_state.add_char('\u{130}');
+        assert_eq!(game_state.current_guess, "i\u{307}ßı");
+        game_state.add_char('I');
+        assert_eq!(game_state.current_guess, "i\u{307}ßıi");
+    }
+
+    #[test]
+    fn test_add_char_converts_to_lowercase() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('H');
+        assert_eq!(game_state.current_guess, "h".to_string());
+        game_state.add_char('E');
+        assert_eq!(game_state.current_guess, "he".to_string());
+    }
+
+    #[test]
+    fn test_back() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('h');
+        assert_eq!(game_state.current_guess, "h".to_string());
+        game_state.add_char('e');
+        assert_eq!(game_state.current_guess, "he".to_string());
+        game_state.add_char('l');
+        assert_eq!(game_state.current_guess, "hel".to_string());
+        game_state.back();
+        assert_eq!(game_state.current_guess, "he".to_string());
+    }
+
+    #[test]
+    fn test_cofirm_with_too_few_chars() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('h');
+        game_state.add_char('e');
+        game_state.confirm();
+        assert_eq!(
+            game_state.last_error.unwrap(),
+            GameError::WrongLength {
+                expected: 5,
+                actual: 2
+            }
+        );
+        assert_eq!(game_state.current_guess.len(), 0);
+        assert_eq!(game_state.guesses.len(), 0);
+    }
+
+    #[test]
+    fn test_cofirm_with_invalid_word() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('j');
+        game_state.add_char('e');
+        game_state.add_char('l');
+        game_state.add_char('l');
+        game_state.add_char('o');
+        game_state.confirm();
+        assert_eq!(game_state.last_error.unwrap(), GameError::InvalidWord);
+        assert_eq!(game_state.current_guess.len(), 0);
+        assert_eq!(game_state.guesses.len(), 0);
+    }
+
+    #[test]
+    fn test_cofirm() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('h');
+        // produce error
+        game_state.confirm();
+        game_state.add_char('h');
+        game_state.add_char('e');
+        game_state.add_char('l');
+        game_state.add_char('l');
+        game_state.add_char('o');
+        game_state.confirm();
+        assert_eq!(game_state.last_error, None);
+        assert_eq!(game_state.current_guess.len(), 0);
+        assert_eq!(game_state.guesses.len(), 1);
+    }
+
+    #[test]
+    fn test_accepts_any_word() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default().any_word(true),
+        )
+        .unwrap();
+        let result = game_state.guess("milli".to_string()).unwrap();
+        assert!(!result);
+        assert_eq!(
+            game_state.guess("ab\tcd".to_string()),
+            Err(GameError::InvalidCharacter {
+                letter: '\t',
+                position: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_add_char_filtered_by_declared_alphabet() {
+        let mut game_state = super::GameState::new(
+            "hallo".to_string(),
+            word_store(&["hallo"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.alphabet = Alphabet::parse("a-z", 1).unwrap();
+        game_state.add_char('Ü');
+        game_state.add_char('1');
+        game_state.add_char('H');
+        assert_eq!(game_state.current_guess, "h".to_string());
+    }
+
+    #[test]
+    fn test_add_char_not_filtered_by_inferred_alphabet() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('z');
+        assert_eq!(game_state.current_guess, "z".to_string());
+    }
+
+    #[test]
+    fn test_letter_hits() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly", "holly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("jolly".to_string()).unwrap();
+        let letters = game_state.letter_hits();
+        assert_eq!(letters[&'o'], HitInfo::Contains);
+        game_state.guess("holly".to_string()).unwrap();
+        let letters = game_state.letter_hits();
+        assert_eq!(letters[&'h'], HitInfo::Hit);
+        assert_eq!(letters[&'o'], HitInfo::Contains);
+        assert_eq!(letters[&'l'], HitInfo::Hit);
+        assert_eq!(letters[&'j'], HitInfo::Miss);
+        assert_eq!(letters.get(&'e'), None);
+    }
+
+    #[test]
+    fn test_options_builder() {
+        let options = GameOptions::default().any_word(true).max_tries(8);
+        assert!(options.any_word);
+        assert_eq!(options.max_tries, 8);
+        assert_eq!(
+            GameOptions::default(),
+            GameOptions {
+                any_word: false,
+                max_tries: 6,
+                hard: false,
+                prefill: 0,
+                seed: None,
+                hint_budget: 0,
+                count_hints: false,
+                answers_only: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_options_validate() {
+        assert_eq!(GameOptions::default().validate(), Ok(()));
+        assert_eq!(
+            GameOptions::default().max_tries(0).validate(),
+            Err(OptionsError::ZeroMaxTries)
+        );
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_options() {
+        let result = GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default().max_tries(0),
+        );
+        assert_eq!(result.err(), Some(OptionsError::ZeroMaxTries));
+        let result = GameState::new(String::new(), word_store(&[]), GameOptions::default());
+        assert_eq!(result.err(), Some(OptionsError::EmptyWord));
+        let result = GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default().prefill(5),
+        );
+        assert_eq!(
+            result.err(),
+            Some(OptionsError::TooManyPrefilled {
+                prefill: 5,
+                length: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_uses_max_tries() {
+        let game_state = GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default().max_tries(8),
+        )
+        .unwrap();
+        assert_eq!(game_state.max_tries, 8);
+    }
+
+    #[test]
+    fn test_game_error_display() {
+        let messages = [
+            (
+                GameError::WrongLength {
+                    expected: 5,
+                    actual: 3,
+                },
+                "Word is not the correct length, 3 letters instead of 5",
+            ),
+            (
+                GameError::InvalidCharacter {
+                    letter: '1',
+                    position: 2,
+                },
+                "Letter 3 is not allowed, '1' is not in the alphabet",
+            ),
+            (GameError::InvalidWord, "Word is not valid"),
+            (GameError::NotAnAnswer, "Word must be a possible answer"),
+            (GameError::RepeatedGuess, "Word was already guessed"),
+            (
+                GameError::MissingLetter { letter: 'e' },
+                "Guess must contain 'e'",
+            ),
+            (
+                GameError::MisplacedLetter {
+                    letter: 'l',
+                    position: 0,
+                },
+                "Letter 1 must be 'l'",
+            ),
+            (GameError::GameOver, "The game is over"),
+            (
+                GameError::NotEnoughHintPoints { cost: 2, left: 1 },
+                "The hint costs 2 points, 1 left",
+            ),
+            (
+                GameError::NothingToReveal,
+                "This hint has nothing left to reveal",
+            ),
+        ];
+        for (error, message) in messages {
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn test_guess_rejects_invalid_characters() {
+        let mut game_state = super::GameState::new(
+            "hallo".to_string(),
+            word_store(&["hallo", "hällo"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.alphabet = Alphabet::parse("a-z", 1).unwrap();
+        assert_eq!(
+            game_state.guess("hällo".to_string()),
+            Err(GameError::InvalidCharacter {
+                letter: 'ä',
+                position: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_guess_rejects_repeated_guess() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(game_state.guess("jolly".to_string()), Ok(false));
+        assert_eq!(
+            game_state.guess("jolly".to_string()),
+            Err(GameError::RepeatedGuess)
+        );
+        assert_eq!(game_state.guesses.len(), 1);
+    }
+
+    #[test]
+    fn test_guess_rejects_guess_after_game_over() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert!(!game_state.over());
+        assert_eq!(game_state.outcome(), Outcome::Abandoned);
+        assert_eq!(game_state.guess("hello".to_string()), Ok(true));
+        assert!(game_state.over());
+        assert_eq!(game_state.outcome(), Outcome::Won);
+        assert_eq!(
+            game_state.guess("jolly".to_string()),
+            Err(GameError::GameOver)
+        );
+
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default().max_tries(1),
+        )
+        .unwrap();
+        assert_eq!(game_state.guess("jolly".to_string()), Ok(false));
+        assert!(game_state.over());
+        assert_eq!(game_state.outcome(), Outcome::Lost);
+        assert_eq!(
+            game_state.guess("hello".to_string()),
+            Err(GameError::GameOver)
+        );
+    }
+
+    #[test]
+    fn test_hard_mode() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly", "world", "helps", "below"]),
+            GameOptions::default().hard(true),
+        )
+        .unwrap();
+        // j miss, o contains, l hit, l hit, y miss
+        assert_eq!(game_state.guess("jolly".to_string()), Ok(false));
+        assert_eq!(
+            game_state.guess("world".to_string()),
+            Err(GameError::MisplacedLetter {
+                letter: 'l',
+                position: 2
+            })
+        );
+        assert_eq!(
+            game_state.guess("helps".to_string()),
+            Err(GameError::MisplacedLetter {
+                letter: 'l',
+                position: 3
+            })
+        );
+        assert_eq!(game_state.guesses.len(), 1);
+        assert_eq!(game_state.guess("hello".to_string()), Ok(true));
+    }
+
+    #[test]
+    fn test_hard_mode_missing_letter() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "world", "fiels"]),
+            GameOptions::default().hard(true),
+        )
+        .unwrap();
+        // w miss, o contains, r miss, l hit, d miss
+        assert_eq!(game_state.guess("world".to_string()), Ok(false));
+        assert_eq!(
+            game_state.guess("fiels".to_string()),
+            Err(GameError::MissingLetter { letter: 'o' })
+        );
+    }
+
+    #[test]
+    fn test_answers_only() {
+        let new = |options: GameOptions| {
+            let mut game_state = GameState::new(
+                "hello".to_string(),
+                word_store(&["hello", "ouija", "jolly"]),
+                options,
+            )
+            .unwrap();
+            game_state.solutions = Some(word_store(&["hello", "jolly"]));
+            game_state
+        };
+        let mut game_state = new(GameOptions::default().answers_only(true));
+        assert!(game_state.answers_only());
+        // ouija is a valid guess, but never the solution
+        assert_eq!(
+            game_state.guess("ouija".to_string()),
+            Err(GameError::NotAnAnswer)
+        );
+        assert_eq!(
+            game_state.guess("xxxxx".to_string()),
+            Err(GameError::InvalidWord)
+        );
+        assert_eq!(game_state.guess("jolly".to_string()), Ok(false));
+        assert_eq!(game_state.guessable_words().len(), 2);
+        let mut game_state = new(GameOptions::default());
+        assert_eq!(game_state.guess("ouija".to_string()), Ok(false));
+        assert_eq!(game_state.guessable_words().len(), 3);
+    }
+
+    #[test]
+    fn test_not_hard_mode_accepts_any_valid_word() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly", "world"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(game_state.guess("jolly".to_string()), Ok(false));
+        assert_eq!(game_state.guess("world".to_string()), Ok(false));
+    }
+
+    #[test]
+    fn test_players_take_turns() {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly", "world"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(game_state.current_player(), None);
+        game_state.players = vec!["Alice".to_string(), "Bob".to_string()];
+        assert_eq!(game_state.current_player(), Some("Alice"));
+        game_state.guess("jolly".to_string()).unwrap();
+        assert_eq!(game_state.current_player(), Some("Bob"));
+        // a rejected guess keeps the turn
+        assert!(game_state.guess("hellp".to_string()).is_err());
+        assert_eq!(game_state.current_player(), Some("Bob"));
+        game_state.guess("world".to_string()).unwrap();
+        assert_eq!(game_state.player(0), Some("Alice"));
+        assert_eq!(game_state.player(1), Some("Bob"));
+        assert_eq!(game_state.current_player(), Some("Alice"));
+    }
+
+    #[test]
+    fn test_discoveries() {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly", "holly", "cello", "world"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(game_state.discoveries(), []);
+        game_state.players = ["Mum", "Dad", "Kid"].map(String::from).to_vec();
+        game_state.guess("world".to_string()).unwrap();
+        // the rejected guess keeps the turn with Dad
+        assert!(game_state.guess("hellp".to_string()).is_err());
+        // the second l is new, the first was found by Mum
+        game_state.guess("jolly".to_string()).unwrap();
+        game_state.guess("holly".to_string()).unwrap();
+        game_state.guess("cello".to_string()).unwrap();
+        game_state.guess("hello".to_string()).unwrap();
+        assert_eq!(game_state.player(4), Some("Dad"));
+        assert_eq!(
+            game_state.discoveries(),
+            [("Mum", 3), ("Dad", 1), ("Kid", 1)]
+        );
+    }
+
+    fn prefilled_game(prefill: usize, seed: u64) -> GameState {
+        GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly", "hills", "cello"]),
+            GameOptions::default().prefill(prefill).seed(Some(seed)),
+        )
+        .unwrap()
+    }
+
+    fn prefilled_positions(game_state: &GameState) -> Vec<usize> {
+        (0..5).filter(|i| game_state.prefilled(*i)).collect()
+    }
+
+    #[test]
+    fn test_prefill_is_seeded() {
+        let positions = prefilled_positions(&prefilled_game(2, 7));
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions, prefilled_positions(&prefilled_game(2, 7)));
+        let seeds_differ =
+            (0..20).any(|seed| prefilled_positions(&prefilled_game(2, seed)) != positions);
+        assert!(seeds_differ);
+        assert!(prefilled_positions(&prefilled_game(0, 7)).is_empty());
+    }
+
+    #[test]
+    fn test_prefill_input_skips_revealed_letters() {
+        // find a seed revealing the h and the first l of hello
+        let mut game_state = (0..)
+            .map(|seed| prefilled_game(2, seed))
+            .find(|game_state| prefilled_positions(game_state) == [0, 2])
+            .unwrap();
+        assert_eq!(game_state.current_guess, "h");
+        game_state.add_char('x');
+        assert_eq!(game_state.current_guess, "hxl");
+        game_state.back();
+        assert_eq!(game_state.current_guess, "h");
+        // revealed letters can not be deleted
+        game_state.back();
+        assert_eq!(game_state.current_guess, "h");
+        for c in "elo".chars() {
+            game_state.add_char(c);
+        }
+        assert_eq!(game_state.current_guess, "hello");
+        game_state.back();
+        game_state.add_char('x');
+        assert_eq!(game_state.current_guess, "hellx");
+    }
+
+    #[test]
+    fn test_prefill_guesses() {
+        let mut game_state = (0..)
+            .map(|seed| prefilled_game(2, seed))
+            .find(|game_state| prefilled_positions(game_state) == [0, 2])
+            .unwrap();
+        // guesses typed in full must keep the revealed letters
+        assert_eq!(
+            game_state.guess("jolly".to_string()),
+            Err(GameError::MisplacedLetter {
+                letter: 'h',
+                position: 0
+            })
+        );
+        assert_eq!(
+            game_state.guess("hills".to_string()),
+            Ok(false),
+            "still checked against the word list"
+        );
+        let hits = game_state.get_guess_hits(0);
+        assert_eq!(hits[0], HitInfo::Hit);
+        assert_eq!(hits[1], HitInfo::Miss);
+        // the next guess starts with the revealed letters again
+        game_state.add_char('x');
+        game_state.confirm();
+        assert_eq!(game_state.current_guess, "h");
+        assert_eq!(game_state.guess("hello".to_string()), Ok(true));
+    }
+
+    fn hint_game(hint_budget: u32) -> GameState {
+        GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly", "hills", "cello"]),
+            GameOptions::default()
+                .hint_budget(hint_budget)
+                .seed(Some(3)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_hint_reveals_position() {
+        let mut game_state = hint_game(5);
+        game_state.guess("cello".to_string()).unwrap();
+        game_state.add_char('x');
+        // only the h is not found yet
+        let revealed = game_state.hint(Hint::Position).unwrap().clone();
+        assert_eq!(
+            revealed,
+            Revealed::Position {
+                position: 0,
+                letter: 'h'
+            }
+        );
+        assert_eq!(revealed.to_string(), "Letter 1 is 'h'");
+        assert!(game_state.prefilled(0));
+        assert_eq!(game_state.current_guess, "h", "typing starts over");
+        assert_eq!(game_state.hint_points(), 3);
+        assert_eq!(game_state.letter_hits()[&'h'], HitInfo::Hit);
+        assert_eq!(
+            game_state.hint(Hint::Position),
+            Err(GameError::NothingToReveal)
+        );
+        assert_eq!(game_state.hint_points(), 3, "failed hints are free");
+    }
+
+    #[test]
+    fn test_hint_asks_for_letter() {
+        let mut game_state = hint_game(2);
+        let revealed = game_state.hint(Hint::Letter('E')).unwrap();
+        assert_eq!(
+            *revealed,
+            Revealed::Letter {
+                letter: 'e',
+                contained: true
+            }
+        );
+        assert_eq!(revealed.to_string(), "The word contains 'e'");
+        let revealed = game_state.hint(Hint::Letter('j')).unwrap();
+        assert_eq!(revealed.to_string(), "The word does not contain 'j'");
+        assert_eq!(game_state.letter_hits()[&'e'], HitInfo::Contains);
+        assert_eq!(game_state.letter_hits()[&'j'], HitInfo::Miss);
+        assert_eq!(game_state.hints().len(), 2);
+        assert_eq!(game_state.hint_points(), 0);
+    }
+
+    #[test]
+    fn test_hint_eliminates_letters() {
+        let mut game_state = hint_game(3);
+        let eliminated = match game_state.hint(Hint::Eliminate).unwrap() {
+            Revealed::Eliminated(letters) => letters.clone(),
+            revealed => panic!("unexpected {:?}", revealed),
+        };
+        assert_eq!(eliminated.len(), 3);
+        assert!(eliminated.iter().all(|c| "jyisc".contains(*c)));
+        assert!(eliminated.windows(2).all(|pair| pair[0] < pair[1]));
+        let letters = game_state.letter_hits();
+        assert!(eliminated.iter().all(|c| letters[c] == HitInfo::Miss));
+        // the two letters left, eliminated ones are not repeated
+        match game_state.hint(Hint::Eliminate).unwrap() {
+            Revealed::Eliminated(letters) => {
+                assert_eq!(letters.len(), 2);
+                assert!(letters.iter().all(|c| !eliminated.contains(c)));
+            }
+            revealed => panic!("unexpected {:?}", revealed),
+        }
+        assert_eq!(
+            game_state.hint(Hint::Eliminate),
+            Err(GameError::NothingToReveal)
+        );
+    }
+
+    #[test]
+    fn test_hint_budget() {
+        let mut game_state = hint_game(1);
+        assert_eq!(game_state.hint_budget(), 1);
+        assert_eq!(
+            game_state.hint(Hint::Position),
+            Err(GameError::NotEnoughHintPoints { cost: 2, left: 1 })
+        );
+        assert!(game_state.hint(Hint::Eliminate).is_ok());
+        assert_eq!(
+            game_state.hint(Hint::Letter('a')),
+            Err(GameError::NotEnoughHintPoints { cost: 1, left: 0 })
+        );
+        game_state.guess("hello".to_string()).unwrap();
+        assert_eq!(game_state.hint(Hint::Eliminate), Err(GameError::GameOver));
+    }
+
+    fn knowledge_after(word: &str, guesses: &[&str]) -> Knowledge {
+        let mut words = guesses.to_vec();
+        words.push(word);
+        let mut game_state =
+            GameState::new(word.to_string(), word_store(&words), GameOptions::default()).unwrap();
+        for guess in guesses {
+            game_state.guess(guess.to_string()).unwrap();
+        }
+        game_state.knowledge()
+    }
+
+    #[test]
+    fn test_guess_times() {
+        let words = ["crane", "slate", "pilot", "hello"];
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            word_store(&words),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("crane".to_string()).unwrap();
+        // a rejected guess takes no time of its own
+        assert!(game_state.guess("xxxxx".to_string()).is_err());
+        game_state.mark_gap();
+        game_state.guess("slate".to_string()).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        game_state.guess("pilot".to_string()).unwrap();
+        let times = &game_state.guess_times;
+        assert_eq!(times.len(), 3);
+        assert!(times[0].is_some());
+        assert_eq!(times[1], None);
+        assert!(times[2].unwrap() >= Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_sandbox() {
+        let options = GameOptions::default()
+            .prefill(1)
+            .hint_budget(2)
+            .seed(Some(3));
+        let mut game_state =
+            GameState::new("hello".to_string(), word_store(&["hello"]), options).unwrap();
+        let typed = game_state.current_guess.clone();
+        game_state.hint(Hint::Position).unwrap();
+        game_state.guess("hello".to_string()).unwrap();
+        let sandbox = game_state.sandbox();
+        assert!(sandbox.guesses.is_empty());
+        assert_eq!(sandbox.current_guess, typed);
+        assert_eq!(sandbox.word, "hello");
+        assert_eq!(sandbox.hint_points(), 2);
+        assert!(sandbox.hints().is_empty());
+        assert_eq!((0..5).filter(|i| sandbox.prefilled(*i)).count(), 1);
+        // the game itself is left as it was
+        assert_eq!(game_state.guesses, ["hello"]);
+        assert_eq!((0..5).filter(|i| game_state.prefilled(*i)).count(), 2);
+    }
+
+    #[test]
+    fn test_knowledge() {
+        // crane against grace: c y, r g, a g, n -, e g
+        let knowledge = knowledge_after("grace", &["crane"]);
+        assert_eq!(
+            knowledge.placed,
+            [None, Some('r'), Some('a'), None, Some('e')]
+        );
+        assert_eq!(knowledge.unplaced, ['c']);
+        assert_eq!(knowledge.excluded, ['n']);
+        assert_eq!(
+            knowledge_after("grace", &[]),
+            Knowledge {
+                placed: vec![None; 5],
+                ..Knowledge::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_knowledge_of_repeated_letters() {
+        // lolly against hello: l y, o y, l g, l g, y -
+        let knowledge = knowledge_after("hello", &["lolly"]);
+        assert_eq!(knowledge.placed, [None, None, Some('l'), Some('l'), None]);
+        assert_eq!(knowledge.unplaced, ['o']);
+        assert_eq!(knowledge.excluded, ['y']);
+        // lilac against hello: the l is placed once and misplaced once, but
+        // every l is hinted, so no second l is owed
+        let knowledge = knowledge_after("hello", &["lilac"]);
+        assert_eq!(knowledge.placed, [None, None, Some('l'), None, None]);
+        assert_eq!(knowledge.unplaced, Vec::<char>::new());
+        assert_eq!(knowledge.excluded, ['a', 'c', 'i']);
+        // the o found later moves from unplaced to placed
+        let knowledge = knowledge_after("hello", &["lolly", "jello"]);
+        assert_eq!(knowledge.unplaced, Vec::<char>::new());
+        assert_eq!(knowledge.placed[4], Some('o'));
+        assert_eq!(knowledge.excluded, ['j', 'y']);
+    }
+}
@@ -0,0 +1,156 @@
+//! The post-game hook: a command run after each game, e.g. to log results
+//! to a personal database or update a status bar.
+//!
+//! The command is run with `sh -c` and gets the result as a JSON object on
+//! stdin and in the variables `RORDLE_RESULT` (`won`, `lost` or
+//! `abandoned`), `RORDLE_GUESSES` (number of guesses) and `RORDLE_DAY`
+//! (`YYYY-MM-DD`).
+
+use rordle_core::date::{unix_time, Date};
+use rordle_core::digraph;
+use rordle_core::game::{GameState, Outcome};
+use rordle_core::json::Json;
+use std::io::Write;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Hooks still running after this long are killed.
+pub(crate) const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a running hook is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+pub(crate) fn result_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Won => "won",
+        Outcome::Lost => "lost",
+        Outcome::Abandoned => "abandoned",
+    }
+}
+
+/// The result passed to the hook on stdin. `mode` is `None` for games with
+/// a solution given by `--word`.
+fn result_json(game_state: &GameState, mode: Option<&str>, day: Date) -> Json {
+    Json::object([
+        ("result", Json::from(result_name(game_state.outcome()))),
+        ("word", Json::from(digraph::untokenize(&game_state.word))),
+        (
+            "guesses",
+            Json::Array(
+                game_state
+                    .guesses
+                    .iter()
+                    .map(|guess| Json::from(digraph::untokenize(guess)))
+                    .collect(),
+            ),
+        ),
+        ("max_tries", Json::from(game_state.max_tries as usize)),
+        ("mode", mode.map_or(Json::Null, Json::from)),
+        ("day", Json::from(day.to_string())),
+    ])
+}
+
+/// Runs `command` for a finished game, killing it after `timeout`. The hook
+/// runs in a process group of its own, so that all it started is killed
+/// with it, e.g. a pipeline or a command put in the background.
+fn run(
+    command: &str,
+    game_state: &GameState,
+    mode: Option<&str>,
+    timeout: Duration,
+) -> Result<(), String> {
+    let day = Date::from_timestamp(unix_time());
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .env("RORDLE_RESULT", result_name(game_state.outcome()))
+        .env("RORDLE_GUESSES", game_state.guesses.len().to_string())
+        .env("RORDLE_DAY", day.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map_err(|error| error.to_string())?;
+    // a hook which does not read its stdin may exit before it is written
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = writeln!(stdin, "{}", result_json(game_state, mode, day));
+    }
+    let start = Instant::now();
+    loop {
+        match child.try_wait().map_err(|error| error.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => return Err(format!("it exited with {}", status)),
+            None if start.elapsed() >= timeout => {
+                // SAFETY: kill only sends a signal, to the group of the
+                // child, which can not be reused before the child is waited for
+                unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+                let _ = child.wait();
+                return Err(format!("killed after {:?}", timeout));
+            }
+            None => std::thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+/// Runs the post-game hook, a failing hook only results in a warning.
+pub(crate) fn post_game(command: &str, game_state: &GameState, mode: Option<&str>) {
+    if let Err(error) = run(command, game_state, mode, HOOK_TIMEOUT) {
+        eprintln!("warning: post-game hook failed: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rordle_core::game::GameOptions;
+    use rordle_core::words::WordStore;
+
+    fn won_game() -> GameState {
+        let words: WordStore = ["hello", "jolly"].iter().collect();
+        let mut game_state =
+            GameState::new("hello".to_string(), words, GameOptions::default()).unwrap();
+        game_state.guess("jolly".to_string()).unwrap();
+        game_state.guess("hello".to_string()).unwrap();
+        game_state
+    }
+
+    #[test]
+    fn test_result_json() {
+        let json = result_json(&won_game(), Some("daily"), Date::from_days(0));
+        assert_eq!(
+            json.to_string(),
+            "{\"result\":\"won\",\"word\":\"hello\",\"guesses\":[\"jolly\",\"hello\"],\
+             \"max_tries\":6,\"mode\":\"daily\",\"day\":\"1970-01-01\"}"
+        );
+    }
+
+    #[test]
+    fn test_failing_hooks() {
+        let game_state = won_game();
+        assert_eq!(run("exit 0", &game_state, None, HOOK_TIMEOUT), Ok(()));
+        assert_eq!(
+            run("exit 3", &game_state, None, HOOK_TIMEOUT),
+            Err("it exited with exit status: 3".to_string())
+        );
+        let start = Instant::now();
+        assert_eq!(
+            run("sleep 5", &game_state, None, Duration::from_millis(100)),
+            Err("killed after 100ms".to_string())
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_timeout_kills_all_the_hook_started() {
+        let marker =
+            std::env::temp_dir().join(format!("rordle-hook-marker-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        let command = format!("(sleep 0.3; touch '{}') & sleep 5", marker.display());
+        assert_eq!(
+            run(&command, &won_game(), None, Duration::from_millis(100)),
+            Err("killed after 100ms".to_string())
+        );
+        std::thread::sleep(Duration::from_millis(600));
+        assert!(!marker.exists());
+    }
+}
@@ -0,0 +1,330 @@
+//! `--export-html`: the board of a game as a standalone HTML page, with the
+//! colors of the theme it was played with, to be shared or kept.
+
+use crate::hook::result_name;
+use crate::render::{
+    board_row, right_to_left, tile_look, tile_text, Paint, RenderOptions, TileLook,
+};
+use rordle_core::date::Puzzle;
+use rordle_core::game::{GameState, HitInfo, Outcome};
+
+/// Columns the keyboard may take on the page, as on a terminal of 80.
+const KEYBOARD_WIDTH: usize = 70;
+
+const STYLE: &str = "\
+body { font-family: sans-serif; text-align: center; }
+table { border-spacing: 4px; margin: 1em auto; }
+td { width: 2em; height: 2em; border: 1px solid #888888; font-weight: bold; }
+th { color: #888888; font-weight: normal; }
+.keys span { display: inline-block; min-width: 1.5em; margin: 2px; padding: 0.3em 0; \
+border: 1px solid #888888; }";
+
+/// Escapes the characters with a meaning in HTML text and attributes.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The inline style of a tile.
+fn tile_css(look: TileLook) -> String {
+    let (mut background, mut foreground) = (look.background, look.foreground);
+    if look.invert {
+        // the terminal is taken to be light on dark
+        background = Some(foreground.unwrap_or(Paint::White));
+        foreground = Some(Paint::Black);
+    }
+    let mut css = Vec::new();
+    if let Some(background) = background {
+        css.push(format!("background:{}", background.css()));
+    }
+    if let Some(foreground) = foreground {
+        css.push(format!("color:{}", foreground.css()));
+    }
+    if look.underline {
+        css.push("text-decoration:underline".to_string());
+    }
+    if look.faint {
+        css.push("opacity:0.5".to_string());
+    }
+    css.join(";")
+}
+
+fn tile(hit: &HitInfo, letter: Option<char>, options: &RenderOptions) -> String {
+    let css = tile_css(tile_look(hit, options.theme));
+    let letter = letter.map_or(String::new(), |letter| {
+        escape(&tile_text(letter, options.uppercase))
+    });
+    if css.is_empty() {
+        format!("<td>{}</td>", letter)
+    } else {
+        format!("<td style=\"{}\">{}</td>", css, letter)
+    }
+}
+
+/// The heading of the page, e.g. `Rordle #812 4/6`: the puzzle or the list
+/// and the number of guesses, `X` for a lost game. A `+` marks a game
+/// played with count hints, which tell more, e.g. `Rordle #812 3/6+`, and
+/// a `!` one played with only possible solutions as guesses.
+pub(crate) fn title(game_state: &GameState, puzzle: Option<Puzzle>) -> String {
+    let label = match (puzzle, &game_state.list_name) {
+        (Some(puzzle), _) => puzzle.to_string(),
+        (None, Some(name)) => name.clone(),
+        (None, None) => "Rordle".to_string(),
+    };
+    let tries = match game_state.outcome() {
+        Outcome::Won => game_state.guesses.len().to_string(),
+        Outcome::Lost => "X".to_string(),
+        outcome => return format!("{} {}", label, result_name(outcome)),
+    };
+    let counted = if game_state.count_hints() { "+" } else { "" };
+    let answers_only = if game_state.answers_only() { "!" } else { "" };
+    format!(
+        "{} {}/{}{}{}",
+        label, tries, game_state.max_tries, counted, answers_only
+    )
+}
+
+/// The page showing the board and the keyboard like the terminal does.
+/// Without `letters` only the colors are shown, and no keyboard, which
+/// would give the letters away. Tiles are written first letter first, for
+/// a script written from right to left the browser mirrors them.
+pub(crate) fn board_html(
+    game_state: &GameState,
+    options: &RenderOptions,
+    title: &str,
+    letters: bool,
+) -> String {
+    let title = escape(title);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n",
+        title, STYLE
+    );
+    let dir = if right_to_left(game_state, options) {
+        " dir=\"rtl\""
+    } else {
+        ""
+    };
+    html.push_str(&format!(
+        "<h1>{}</h1>\n<table class=\"board\"{}>\n",
+        title, dir
+    ));
+    let width = game_state.letters().len();
+    for y in 0..game_state.max_tries as usize {
+        let (row_letters, hits) = board_row(game_state, y, width);
+        html.push_str("<tr>");
+        if options.row_numbers {
+            html.push_str(&format!("<th>{}</th>", y + 1));
+        }
+        for (letter, hit) in row_letters.iter().zip(&hits) {
+            let shown = (letters && *letter != '_').then_some(*letter);
+            html.push_str(&tile(hit, shown, options));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    if letters {
+        let letter_hits = game_state.letter_hits();
+        html.push_str(&format!("<div class=\"keys\"{}>\n", dir));
+        for row in game_state
+            .alphabet
+            .keyboard_rows(options.keyboard_layout, KEYBOARD_WIDTH)
+        {
+            html.push_str("<div>");
+            for c in row {
+                let look = tile_look(letter_hits.get(&c).unwrap_or(&HitInfo::None), options.theme);
+                html.push_str(&format!(
+                    "<span style=\"{}\">{}</span>",
+                    tile_css(look),
+                    escape(&tile_text(c, options.uppercase))
+                ));
+            }
+            html.push_str("</div>\n");
+        }
+        html.push_str("</div>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rordle_core::config::Theme;
+    use rordle_core::game::GameOptions;
+
+    /// Elements which have no closing tag.
+    const VOID: [&str; 1] = ["meta"];
+
+    /// Checks that `html` is a document of properly nested elements with
+    /// quoted attributes, returning the names of the opening tags in
+    /// document order and the text of the `td` elements.
+    fn parse(html: &str) -> (Vec<String>, Vec<String>) {
+        let rest = html
+            .strip_prefix("<!DOCTYPE html>")
+            .expect("missing doctype");
+        let mut open: Vec<String> = Vec::new();
+        let mut tags = Vec::new();
+        let mut cells = Vec::new();
+        let mut text = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            if c != '<' {
+                assert!(c != '>', "stray >");
+                text.push(c);
+                continue;
+            }
+            let tag: String = chars.by_ref().take_while(|c| *c != '>').collect();
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(open.pop().as_deref(), Some(name), "misnested </{}>", name);
+                if name == "td" {
+                    cells.push(std::mem::take(&mut text));
+                }
+                continue;
+            }
+            let (name, attributes) = tag.split_once(' ').unwrap_or((&tag, ""));
+            assert!(
+                name.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()),
+                "bad tag <{}>",
+                tag
+            );
+            assert_eq!(attributes.matches('"').count() % 2, 0, "unquoted <{}>", tag);
+            for attribute in attributes.split('"').step_by(2) {
+                let attribute = attribute.trim();
+                assert!(
+                    attribute.is_empty() || attribute.ends_with('='),
+                    "<{}>",
+                    tag
+                );
+            }
+            tags.push(name.to_string());
+            text.clear();
+            if !VOID.contains(&name) {
+                open.push(name.to_string());
+            }
+        }
+        assert!(open.is_empty(), "unclosed {:?}", open);
+        assert_eq!(tags[..3], ["html", "head", "meta"]);
+        (tags, cells)
+    }
+
+    fn game() -> GameState {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            ["hello", "jolly"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("jolly".to_string()).unwrap();
+        game_state.guess("hello".to_string()).unwrap();
+        game_state
+    }
+
+    #[test]
+    fn test_board_html() {
+        let game_state = game();
+        let title = title(&game_state, None);
+        assert_eq!(title, "Rordle 2/6");
+        let html = board_html(&game_state, &RenderOptions::default(), &title, true);
+        let (tags, cells) = parse(&html);
+        assert_eq!(tags.iter().filter(|tag| *tag == "tr").count(), 6);
+        assert_eq!(cells.len(), 30);
+        assert_eq!(cells[..10].concat(), "JOLLYHELLO");
+        assert!(cells[10..].iter().all(String::is_empty));
+        assert!(tags.contains(&"span".to_string()));
+        assert!(html.contains("<tr><th>1</th><td"));
+        assert_eq!(tags.iter().filter(|tag| *tag == "th").count(), 6);
+        let options = RenderOptions {
+            row_numbers: false,
+            ..RenderOptions::default()
+        };
+        assert!(!board_html(&game_state, &options, &title, true).contains("<th>"));
+        let mut counted = GameState::new(
+            "hello".to_string(),
+            ["hello"].iter().collect(),
+            GameOptions::default().count_hints(true),
+        )
+        .unwrap();
+        counted.guess("hello".to_string()).unwrap();
+        assert_eq!(super::title(&counted, None), "Rordle 1/6+");
+        let mut answers_only = GameState::new(
+            "hello".to_string(),
+            ["hello"].iter().collect(),
+            GameOptions::default().answers_only(true),
+        )
+        .unwrap();
+        answers_only.guess("hello".to_string()).unwrap();
+        assert_eq!(super::title(&answers_only, None), "Rordle 1/6!");
+        // the colors are the ones of the terminal
+        assert!(html.contains("<td style=\"background:#00cd00;color:#000000\">H</td>"));
+    }
+
+    #[test]
+    fn test_board_html_letter_case() {
+        let mut game_state = GameState::new(
+            "maße".to_string(),
+            ["maße"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("maße".to_string()).unwrap();
+        let html = |uppercase| {
+            let options = RenderOptions {
+                uppercase,
+                ..RenderOptions::default()
+            };
+            parse(&board_html(&game_state, &options, "", true)).1[..4].concat()
+        };
+        // there is no single uppercase letter of ß
+        assert_eq!(html(true), "MAßE");
+        assert_eq!(html(false), "maße");
+    }
+
+    #[test]
+    fn test_board_html_right_to_left() {
+        let mut game_state = GameState::new(
+            "שלום".to_string(),
+            ["שלום", "מלון"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("מלון".to_string()).unwrap();
+        let html = board_html(&game_state, &RenderOptions::default(), "", true);
+        let (_, cells) = parse(&html);
+        // in logical order, mirrored by the browser
+        assert_eq!(cells[..4].concat(), "מלון");
+        assert!(html.contains("<table class=\"board\" dir=\"rtl\">"));
+        assert!(html.contains("<div class=\"keys\" dir=\"rtl\">"));
+        let ltr = RenderOptions {
+            rtl: Some(false),
+            ..RenderOptions::default()
+        };
+        assert!(!board_html(&game_state, &ltr, "", true).contains("dir="));
+    }
+
+    #[test]
+    fn test_board_html_without_letters() {
+        let mut game_state = game();
+        game_state.list_name = Some("<b>".to_string());
+        let options = RenderOptions {
+            theme: Theme::HighContrast,
+            ..RenderOptions::default()
+        };
+        let html = board_html(&game_state, &options, &title(&game_state, None), false);
+        let (tags, cells) = parse(&html);
+        assert!(cells.iter().all(String::is_empty));
+        assert!(!tags.contains(&"span".to_string()));
+        assert!(html.contains("<h1>&lt;b&gt; 2/6</h1>"));
+        assert!(html.contains("background:#f5793a"));
+    }
+}
@@ -0,0 +1,571 @@
+//! Translations of the messages shown to players.
+//!
+//! Each language has a catalog mapping message ids to texts with
+//! `{name}` placeholders. Messages missing from a catalog fall back to
+//! English. The language is set once for the whole program with
+//! [`set_language`], as messages are also produced by `Display`
+//! implementations like the one of [`crate::game::GameError`].
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// A language messages are shown in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    /// English, the default.
+    #[default]
+    English,
+    /// German.
+    German,
+}
+
+impl Language {
+    /// Codes accepted by `FromStr`, in the order of the enum.
+    pub const NAMES: [&'static str; 2] = ["en", "de"];
+
+    const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// Language of a locale like `de_DE.UTF-8`, `None` for locales without
+    /// a catalog, including `C` and `POSIX`.
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        let code = locale.split(['_', '.', '@']).next().unwrap_or_default();
+        Language::NAMES
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(code))
+            .map(|i| Language::ALL[i])
+    }
+
+    /// Language of the locale `LC_ALL`, `LC_MESSAGES` or `LANG`, English if
+    /// none is set or there is no catalog for it.
+    pub fn detect() -> Language {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Language::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => ENGLISH,
+            Language::German => GERMAN,
+        }
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Language, String> {
+        match name {
+            "en" | "english" => Ok(Language::English),
+            "de" | "german" | "deutsch" => Ok(Language::German),
+            _ => Err(format!(
+                "unknown language `{}`, expected one of {}",
+                name,
+                Language::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    (
+        "wrong-length",
+        "Word is not the correct length, {actual} letters instead of {expected}",
+    ),
+    (
+        "invalid-character",
+        "Letter {position} is not allowed, '{letter}' is not in the alphabet",
+    ),
+    ("invalid-word", "Word is not valid"),
+    ("not-an-answer", "Word must be a possible answer"),
+    ("repeated-guess", "Word was already guessed"),
+    ("missing-letter", "Guess must contain '{letter}'"),
+    ("misplaced-letter", "Letter {position} must be '{letter}'"),
+    ("game-over", "The game is over"),
+    ("guess-error", "error: {message}"),
+    ("won", "You won!"),
+    ("lost", "You lost! The word was: {word}"),
+    ("abandoned", "Game abandoned. The word was: {word}"),
+    (
+        "welcome",
+        "Welcome to rordle! Guess the {length}-letter word in {tries} tries.",
+    ),
+    (
+        "hints",
+        "Hints: g = right letter, right spot; y = in the word, wrong spot; - = not in the word",
+    ),
+    ("server-full", "The server is full, try again later."),
+    ("server-shutdown", "The server is shutting down."),
+    (
+        "idle",
+        "Closing the connection after being idle for too long.",
+    ),
+    ("duel-waiting", "Waiting for an opponent on port {port}..."),
+    ("duel-you", "You"),
+    ("duel-opponent", "Opponent"),
+    ("duel-opponent-finished", "Opponent finished"),
+    ("duel-opponent-left", "Opponent left the duel"),
+    (
+        "duel-waiting-opponent",
+        "Waiting for the opponent to finish...",
+    ),
+    (
+        "duel-solved",
+        "{player}: found the word in {guesses} guesses, {seconds}s",
+    ),
+    ("duel-failed", "{player}: did not find the word"),
+    ("duel-you-won", "You win the duel!"),
+    ("duel-you-lost", "Your opponent wins the duel."),
+    ("duel-draw", "The duel is a draw."),
+    ("coop-found", "{player} found it on {setup}'s setup"),
+    ("coop-found-alone", "{player} found it"),
+    ("team-greens", "{player}: greens discovered: {greens}"),
+    ("team-turn", "{player}'s turn"),
+    (
+        "terminal-too-small",
+        "Make the terminal {width}x{height} or larger to play",
+    ),
+    ("battle-player", "Player {number}"),
+    ("battle-round", "Round {round} of {rounds}"),
+    (
+        "battle-setter",
+        "{setter}, type a secret word for {guesser}. No peeking!",
+    ),
+    ("battle-guesses", "{guesser} guesses"),
+    (
+        "battle-round-won",
+        "{player} found {word} in {guesses} guesses and scores {points}.",
+    ),
+    ("battle-round-lost", "{player} did not find {word}."),
+    (
+        "battle-next",
+        "Press Enter for the next round or Esc to stop.",
+    ),
+    ("battle-winner", "{player} wins the battle!"),
+    ("battle-draw", "The battle is a draw."),
+    (
+        "battle-stopped",
+        "Battle stopped after {played} of {rounds} rounds.",
+    ),
+    (
+        "hint-too-expensive",
+        "The hint costs {cost} points, {left} left",
+    ),
+    ("nothing-to-reveal", "This hint has nothing left to reveal"),
+    ("hint-position", "Letter {position} is '{letter}'"),
+    ("hint-contains", "The word contains '{letter}'"),
+    ("hint-not-contains", "The word does not contain '{letter}'"),
+    ("hint-eliminated", "Not in the word: {letters}"),
+    (
+        "hint-points",
+        "Hint points: {points}, {key} opens the hints",
+    ),
+    (
+        "hint-menu",
+        "Spend hint points, {points} left ({key} closes):",
+    ),
+    ("hint-reveal-position", "reveal a letter"),
+    ("hint-ask-letter", "ask whether the word contains a letter"),
+    ("hint-eliminate", "remove three letters not in the word"),
+    ("hint-ask", "Type the letter to ask for"),
+    ("hints-used", "Hints used: {hints}, for {points} points"),
+    ("pattern-unplaced", "+{letters} somewhere"),
+    ("pattern-excluded", "no {letters}"),
+    ("pattern-excluded-count", "{count} letters ruled out"),
+    ("assist-answers", "Answers ({remaining} left): {answers}"),
+    ("assist-guesses", "Best guesses: {guesses}"),
+    ("assist-heatmap", "Brighter keys are in more of the answers"),
+    ("pattern-label", "pattern {pattern}"),
+    ("practice-label", "practice"),
+    ("replay-label", "replay, not recorded"),
+    ("weak-label", "weak letters {letters}"),
+    ("hardcore-label", "hardcore run: {run}"),
+    (
+        "hardcore-quit",
+        "Leaving counts as a loss and ends your hardcore run, press {key} again to leave",
+    ),
+    (
+        "hardcore-broken",
+        "☠ Your hardcore run of {run} ends here. Back to zero.",
+    ),
+    ("definition", "{word}: {definition}"),
+    ("pacing", "Time per guess: {times}, {total} in all"),
+    ("pacing-away", "away"),
+    (
+        "rarity-rarer",
+        "‘{word}’ is rarer than {percent}% of solutions",
+    ),
+    (
+        "rarity-commoner",
+        "‘{word}’ is more common than {percent}% of solutions",
+    ),
+    ("rarity-very-common", "‘{word}’ is a very common word"),
+    (
+        "rarity-unlisted",
+        "‘{word}’ is not in the frequencies of the list, a rare word",
+    ),
+    ("menu-tutorial", "Tutorial, new here?"),
+    ("menu-play", "Play"),
+    ("menu-daily", "Daily puzzle"),
+    ("menu-hard", "Hard mode"),
+    ("menu-statistics", "Statistics"),
+    ("menu-settings", "Settings"),
+    ("menu-quit", "Quit"),
+    ("menu-theme", "Theme: {theme}"),
+    ("menu-keyboard-layout", "Keyboard: {layout}"),
+    ("menu-back", "Back"),
+    (
+        "menu-help",
+        "Up and down select, Enter chooses, {quit} quits",
+    ),
+    (
+        "menu-settings-help",
+        "Left and right change a setting, it is saved at once, {quit} goes back",
+    ),
+    ("menu-any-key", "Any key goes back"),
+    ("menu-not-saved", "Not saved: {error}"),
+    (
+        "tutorial-welcome",
+        "Find the hidden word in {tries} guesses, {quit} skips the tutorial",
+    ),
+    (
+        "tutorial-type",
+        "Type {guess} and press {submit} to guess it, {delete} deletes a letter",
+    ),
+    (
+        "tutorial-hit",
+        "{letter}, letter {position}, is {style}: it is in the word at this spot",
+    ),
+    (
+        "tutorial-contains",
+        "{letter}, letter {position}, is {style}: it is in the word, but at another spot",
+    ),
+    (
+        "tutorial-miss",
+        "{letter}, letter {position}, is {style}: it is not in the word",
+    ),
+    (
+        "tutorial-go-on",
+        "Now find the word, every guess gets hints like these",
+    ),
+    ("tutorial-done", "That is all there is to it, have fun!"),
+    ("style-green", "green"),
+    ("style-yellow", "yellow"),
+    ("style-gray", "gray"),
+    ("style-orange", "orange"),
+    ("style-blue", "blue"),
+    ("style-inverted", "inverted"),
+    ("style-underlined", "underlined"),
+    ("style-dim", "dim"),
+    ("style-yellow-on-black", "yellow on black"),
+    ("style-blue-on-black", "blue on black"),
+    ("style-dim-underlined", "dim underlined"),
+    (
+        "explore-prompt",
+        "Press e to try other guesses, any other key to leave",
+    ),
+    (
+        "explore-title",
+        "Exploring, nothing is recorded ({quit} goes back)",
+    ),
+    ("explore-back", "Press any key to go back to your game"),
+    ("explore-remaining", "{count} left"),
+    ("pack-puzzle", "Puzzle {number}/{count}: {title}"),
+    ("pack-hint", "hint: {hint}"),
+    ("pack-complete", "Puzzle {number}/{count} complete"),
+    (
+        "pack-next",
+        "Press Enter for the next puzzle, q and Enter to stop",
+    ),
+    (
+        "pack-stopped",
+        "Progress saved after {played} of {count} puzzles",
+    ),
+    (
+        "pack-restart",
+        "You finished this pack before, starting it again",
+    ),
+    (
+        "pack-summary",
+        "Pack complete: {solved} of {count} puzzles solved",
+    ),
+    ("pack-average", "{average} guesses on average"),
+    ("leaderboard-title", "Ranking of puzzle {day}:"),
+];
+
+const GERMAN: &[(&str, &str)] = &[
+    (
+        "wrong-length",
+        "Das Wort hat die falsche Länge, {actual} statt {expected} Buchstaben",
+    ),
+    (
+        "invalid-character",
+        "Buchstabe {position} ist nicht erlaubt, '{letter}' ist nicht im Alphabet",
+    ),
+    ("invalid-word", "Das Wort ist nicht gültig"),
+    ("not-an-answer", "Das Wort muss eine mögliche Lösung sein"),
+    ("repeated-guess", "Das Wort wurde schon geraten"),
+    ("missing-letter", "Der Versuch muss '{letter}' enthalten"),
+    ("misplaced-letter", "Buchstabe {position} muss '{letter}' sein"),
+    ("game-over", "Das Spiel ist vorbei"),
+    ("guess-error", "Fehler: {message}"),
+    ("won", "Gewonnen!"),
+    ("lost", "Verloren! Das Wort war: {word}"),
+    ("abandoned", "Spiel abgebrochen. Das Wort war: {word}"),
+    (
+        "welcome",
+        "Willkommen bei rordle! Errate das Wort mit {length} Buchstaben in {tries} Versuchen.",
+    ),
+    (
+        "hints",
+        "Hinweise: g = richtiger Buchstabe an richtiger Stelle; y = im Wort an anderer Stelle; - = nicht im Wort",
+    ),
+    (
+        "server-full",
+        "Der Server ist voll, versuche es später noch einmal.",
+    ),
+    ("server-shutdown", "Der Server wird beendet."),
+    (
+        "idle",
+        "Die Verbindung wird nach zu langer Inaktivität geschlossen.",
+    ),
+    ("duel-waiting", "Warte auf einen Gegner an Port {port}..."),
+    ("duel-you", "Du"),
+    ("duel-opponent", "Gegner"),
+    ("duel-opponent-finished", "Gegner ist fertig"),
+    ("duel-opponent-left", "Gegner hat das Duell verlassen"),
+    ("duel-waiting-opponent", "Warte, bis der Gegner fertig ist..."),
+    (
+        "duel-solved",
+        "{player}: Wort in {guesses} Versuchen gefunden, {seconds} s",
+    ),
+    ("duel-failed", "{player}: Wort nicht gefunden"),
+    ("duel-you-won", "Du gewinnst das Duell!"),
+    ("duel-you-lost", "Dein Gegner gewinnt das Duell."),
+    ("duel-draw", "Das Duell endet unentschieden."),
+    ("coop-found", "{player} hat es nach der Vorarbeit von {setup} gefunden"),
+    ("coop-found-alone", "{player} hat es gefunden"),
+    ("team-greens", "{player}: entdeckte Grüne: {greens}"),
+    ("team-turn", "{player} ist dran"),
+    ("terminal-too-small", "Vergrößere das Terminal auf {width}x{height}, um zu spielen"),
+    ("battle-player", "Spieler {number}"),
+    ("battle-round", "Runde {round} von {rounds}"),
+    (
+        "battle-setter",
+        "{setter}, gib ein geheimes Wort für {guesser} ein. Nicht hinsehen!",
+    ),
+    ("battle-guesses", "{guesser} rät"),
+    (
+        "battle-round-won",
+        "{player} hat {word} in {guesses} Versuchen gefunden und bekommt {points} Punkte.",
+    ),
+    ("battle-round-lost", "{player} hat {word} nicht gefunden."),
+    (
+        "battle-next",
+        "Enter startet die nächste Runde, Esc beendet das Match.",
+    ),
+    ("battle-winner", "{player} gewinnt das Match!"),
+    ("battle-draw", "Das Match endet unentschieden."),
+    (
+        "battle-stopped",
+        "Match nach {played} von {rounds} Runden abgebrochen.",
+    ),    ("hint-too-expensive", "Der Hinweis kostet {cost} Punkte, {left} übrig"),
+    ("nothing-to-reveal", "Dieser Hinweis kann nichts mehr aufdecken"),
+    ("hint-position", "Buchstabe {position} ist '{letter}'"),
+    ("hint-contains", "Das Wort enthält '{letter}'"),
+    ("hint-not-contains", "Das Wort enthält kein '{letter}'"),
+    ("hint-eliminated", "Nicht im Wort: {letters}"),
+    ("hint-points", "Hinweispunkte: {points}, {key} öffnet die Hinweise"),
+    (
+        "hint-menu",
+        "Hinweispunkte ausgeben, {points} übrig ({key} schließt):",
+    ),
+    ("hint-reveal-position", "einen Buchstaben aufdecken"),
+    ("hint-ask-letter", "fragen, ob das Wort einen Buchstaben enthält"),
+    ("hint-eliminate", "drei Buchstaben entfernen, die nicht im Wort sind"),
+    ("hint-ask", "Tippe den Buchstaben, nach dem du fragst"),
+    ("hints-used", "Benutzte Hinweise: {hints}, für {points} Punkte"),
+    ("pattern-unplaced", "+{letters} irgendwo"),
+    ("pattern-excluded", "kein {letters}"),
+    ("pattern-excluded-count", "{count} Buchstaben ausgeschlossen"),
+    ("assist-answers", "Lösungen ({remaining} übrig): {answers}"),
+    ("assist-guesses", "Beste Rateversuche: {guesses}"),
+    ("assist-heatmap", "Hellere Tasten sind in mehr Lösungen"),
+    ("pattern-label", "Muster {pattern}"),
+    ("practice-label", "Übung"),
+    ("replay-label", "Wiederholung, nicht gespeichert"),
+    ("weak-label", "schwache Buchstaben {letters}"),
+    ("hardcore-label", "Hardcore-Serie: {run}"),
+    ("hardcore-quit", "Aufhören zählt als verloren und beendet die Hardcore-Serie, zum Aufhören nochmal {key}"),
+    ("hardcore-broken", "☠ Deine Hardcore-Serie von {run} endet hier. Zurück auf null."),
+    ("definition", "{word}: {definition}"),
+    ("pacing", "Zeit je Versuch: {times}, insgesamt {total}"),
+    ("pacing-away", "unterbrochen"),
+    ("rarity-rarer", "‘{word}’ ist seltener als {percent}% der Lösungen"),
+    ("rarity-commoner", "‘{word}’ ist häufiger als {percent}% der Lösungen"),
+    ("rarity-very-common", "‘{word}’ ist ein sehr häufiges Wort"),
+    ("rarity-unlisted", "‘{word}’ fehlt in den Häufigkeiten der Liste, ein seltenes Wort"),
+    ("menu-tutorial", "Einführung, zum ersten Mal hier?"),
+    ("menu-play", "Spielen"),
+    ("menu-daily", "Tagesrätsel"),
+    ("menu-hard", "Schwerer Modus"),
+    ("menu-statistics", "Statistik"),
+    ("menu-settings", "Einstellungen"),
+    ("menu-quit", "Beenden"),
+    ("menu-theme", "Farben: {theme}"),
+    ("menu-keyboard-layout", "Tastatur: {layout}"),
+    ("menu-back", "Zurück"),
+    ("menu-help", "Hoch und runter wählen, Enter bestätigt, {quit} beendet"),
+    (
+        "menu-settings-help",
+        "Links und rechts ändern eine Einstellung, sie wird sofort gespeichert, {quit} führt zurück",
+    ),
+    ("menu-any-key", "Eine beliebige Taste führt zurück"),
+    ("menu-not-saved", "Nicht gespeichert: {error}"),
+    (
+        "tutorial-welcome",
+        "Finde das versteckte Wort mit {tries} Versuchen, {quit} überspringt die Einführung",
+    ),
+    (
+        "tutorial-type",
+        "Tippe {guess} und drücke {submit}, um es zu raten, {delete} löscht einen Buchstaben",
+    ),
+    (
+        "tutorial-hit",
+        "{letter}, Buchstabe {position}, ist {style}: er steht an dieser Stelle im Wort",
+    ),
+    (
+        "tutorial-contains",
+        "{letter}, Buchstabe {position}, ist {style}: er ist im Wort, aber an einer anderen Stelle",
+    ),
+    (
+        "tutorial-miss",
+        "{letter}, Buchstabe {position}, ist {style}: er ist nicht im Wort",
+    ),
+    (
+        "tutorial-go-on",
+        "Finde jetzt das Wort, jeder Versuch bekommt solche Hinweise",
+    ),
+    ("tutorial-done", "Mehr gibt es nicht zu wissen, viel Spaß!"),
+    ("style-green", "grün"),
+    ("style-yellow", "gelb"),
+    ("style-gray", "grau"),
+    ("style-orange", "orange"),
+    ("style-blue", "blau"),
+    ("style-inverted", "invertiert"),
+    ("style-underlined", "unterstrichen"),
+    ("style-dim", "blass"),
+    ("style-yellow-on-black", "gelb auf schwarz"),
+    ("style-blue-on-black", "blau auf schwarz"),
+    ("style-dim-underlined", "blass unterstrichen"),
+    ("explore-prompt", "e probiert andere Rateversuche, jede andere Taste beendet"),
+    ("explore-title", "Erkunden, nichts wird gespeichert ({quit} führt zurück)"),
+    ("explore-back", "Eine beliebige Taste führt zurück zum Spiel"),
+    ("explore-remaining", "{count} übrig"),
+    ("pack-puzzle", "Rätsel {number}/{count}: {title}"),
+    ("pack-hint", "Tipp: {hint}"),
+    ("pack-complete", "Rätsel {number}/{count} geschafft"),
+    ("pack-next", "Enter für das nächste Rätsel, q und Enter zum Aufhören"),
+    ("pack-stopped", "Fortschritt nach {played} von {count} Rätseln gespeichert"),
+    ("pack-restart", "Dieses Paket ist schon geschafft, es beginnt von vorn"),
+    ("pack-summary", "Paket geschafft: {solved} von {count} Rätseln gelöst"),
+    ("pack-average", "{average} Rateversuche im Schnitt"),
+    ("leaderboard-title", "Rangliste von Rätsel {day}:"),
+];
+
+/// Index of the language in `Language::ALL`.
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the language of all messages.
+pub fn set_language(language: Language) {
+    let index = Language::ALL.iter().position(|l| *l == language).unwrap();
+    LANGUAGE.store(index as u8, Ordering::Relaxed);
+}
+
+/// The language set by `set_language`, English by default.
+pub fn language() -> Language {
+    Language::ALL[LANGUAGE.load(Ordering::Relaxed) as usize]
+}
+
+/// Message `id` in `language` with its placeholders replaced by `args`.
+/// Falls back to English, and to the id itself for unknown ids.
+pub fn translate(language: Language, id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let lookup = |language: Language| {
+        language
+            .catalog()
+            .iter()
+            .find(|(key, _)| *key == id)
+            .map(|(_, text)| *text)
+    };
+    let text = lookup(language)
+        .or_else(|| lookup(Language::English))
+        .unwrap_or(id);
+    let mut message = text.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+/// Message `id` in the language set by `set_language`, see `translate`.
+pub fn tr(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    translate(language(), id, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Placeholders of a message, e.g. `["actual", "expected"]`.
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split('}').next())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_every_message_in_every_catalog() {
+        for language in Language::ALL {
+            let catalog = language.catalog();
+            for (id, english) in ENGLISH {
+                let text = catalog.iter().find(|(key, _)| key == id);
+                let (_, text) = text.unwrap_or_else(|| panic!("{:?} lacks `{}`", language, id));
+                assert_eq!(placeholders(text), placeholders(english), "{}", id);
+            }
+            for (id, _) in catalog {
+                assert!(ENGLISH.iter().any(|(key, _)| key == id), "unknown `{}`", id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate() {
+        let word: &dyn Display = &"hello";
+        assert_eq!(
+            translate(Language::German, "lost", &[("word", word)]),
+            "Verloren! Das Wort war: hello"
+        );
+        assert_eq!(
+            translate(Language::English, "lost", &[("word", word)]),
+            "You lost! The word was: hello"
+        );
+        assert_eq!(translate(Language::German, "no-such-id", &[]), "no-such-id");
+    }
+
+    #[test]
+    fn test_languages() {
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), Some(Language::German));
+        assert_eq!(Language::from_locale("en_US"), Some(Language::English));
+        assert_eq!(Language::from_locale("C"), None);
+        assert_eq!(Language::from_locale(""), None);
+        assert_eq!("deutsch".parse(), Ok(Language::German));
+        assert!("fr".parse::<Language>().is_err());
+    }
+}
@@ -0,0 +1,335 @@
+//! Office leaderboards of the daily puzzle: `rordle --leaderboard URL`
+//! submits the result of a daily game and shows the ranking of the day,
+//! `rordle serve-leaderboard` is the server. The API is plain HTTP with
+//! JSON bodies:
+//!
+//! - `POST /results` with a result like
+//!   `{"name":"alex","day":1234,"guesses":4,"hard":true}` records it.
+//!   `day` is the number of the puzzle and `guesses` is `null` for a lost
+//!   game. The word is never sent, others may still be playing. The server
+//!   answers `200` with `{"ok":true}`, or `400` with `{"error":"..."}` for
+//!   a malformed result. Only the first result of a player and day counts,
+//!   later ones are accepted but ignored, so a result may be sent again.
+//! - `GET /ranking?day=1234` answers `200` with the ranking of the day,
+//!   `{"day":1234,"ranking":[{"rank":1,"name":"alex","guesses":4,"hard":true}]}`.
+//!   Fewer guesses rank higher, then hard mode, then whoever was first.
+//!   Lost games come last. Results with the same guesses and mode share a
+//!   rank.
+//!
+//! URLs of a leaderboard may have a path, e.g. `http://host:8080/team`,
+//! which the paths of the API are appended to.
+
+use crate::json::Json;
+
+/// The result of a daily game as submitted to a leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Name of the player.
+    pub name: String,
+    /// Number of the puzzle.
+    pub day: i64,
+    /// Number of guesses of a won game, `None` for a lost one.
+    pub guesses: Option<usize>,
+    /// Whether the game was played in hard mode.
+    pub hard: bool,
+}
+
+/// Longest name accepted, in characters.
+pub const MAX_NAME: usize = 32;
+
+/// The integer of `value`, if it is a whole number.
+fn integer(value: Option<&Json>) -> Option<i64> {
+    match value {
+        Some(Json::Number(n)) if n.fract() == 0.0 && n.abs() < 1e15 => Some(*n as i64),
+        _ => None,
+    }
+}
+
+impl Submission {
+    /// The body of `POST /results`.
+    pub fn to_json(&self) -> Json {
+        Json::object([
+            ("name", Json::from(self.name.as_str())),
+            ("day", Json::Number(self.day as f64)),
+            ("guesses", self.guesses.map_or(Json::Null, Json::from)),
+            ("hard", Json::from(self.hard)),
+        ])
+    }
+
+    /// Reads a submission, checking that the name is not empty or too long
+    /// and that a won game took at least one guess.
+    pub fn from_json(json: &Json) -> Result<Submission, String> {
+        let name = json
+            .get("name")
+            .and_then(Json::as_str)
+            .map(str::trim)
+            .ok_or("a result needs a `name` string")?;
+        if name.is_empty() || name.chars().count() > MAX_NAME {
+            return Err(format!("the name must have 1 to {} characters", MAX_NAME));
+        }
+        if name.chars().any(char::is_control) {
+            return Err("the name must not contain control characters".to_string());
+        }
+        let day = integer(json.get("day")).ok_or("a result needs a `day` number")?;
+        let guesses = match json.get("guesses") {
+            Some(Json::Null) => None,
+            guesses => Some(
+                integer(guesses)
+                    .filter(|guesses| *guesses > 0)
+                    .ok_or("`guesses` must be a positive number or null")? as usize,
+            ),
+        };
+        let hard = match json.get("hard") {
+            None => false,
+            Some(Json::Bool(hard)) => *hard,
+            Some(_) => return Err("`hard` must be a boolean".to_string()),
+        };
+        Ok(Submission {
+            name: name.to_string(),
+            day,
+            guesses,
+            hard,
+        })
+    }
+
+    /// The result like in a share, e.g. `4` or `X`, followed by a `*` in
+    /// hard mode.
+    pub fn result(&self) -> String {
+        let tries = self
+            .guesses
+            .map_or("X".to_string(), |guesses| guesses.to_string());
+        if self.hard {
+            format!("{}*", tries)
+        } else {
+            tries
+        }
+    }
+
+    /// Orders results from best to worst, equal ones share a rank.
+    fn key(&self) -> (usize, bool) {
+        (self.guesses.unwrap_or(usize::MAX), !self.hard)
+    }
+}
+
+/// A result in a ranking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    /// Rank, counted from 1.
+    pub rank: usize,
+    /// The result.
+    pub submission: Submission,
+}
+
+/// The ranking of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranking {
+    /// Number of the puzzle.
+    pub day: i64,
+    /// The results from best to worst.
+    pub standings: Vec<Standing>,
+}
+
+impl Ranking {
+    /// The body of the answer to `GET /ranking`.
+    pub fn to_json(&self) -> Json {
+        let standings = self.standings.iter().map(|standing| {
+            let submission = &standing.submission;
+            Json::object([
+                ("rank", Json::from(standing.rank)),
+                ("name", Json::from(submission.name.as_str())),
+                ("guesses", submission.guesses.map_or(Json::Null, Json::from)),
+                ("hard", Json::from(submission.hard)),
+            ])
+        });
+        Json::object([
+            ("day", Json::Number(self.day as f64)),
+            ("ranking", Json::Array(standings.collect())),
+        ])
+    }
+
+    /// Reads a ranking as answered by a server.
+    pub fn from_json(json: &Json) -> Result<Ranking, String> {
+        let day = integer(json.get("day")).ok_or("a ranking needs a `day` number")?;
+        let standings = match json.get("ranking") {
+            Some(Json::Array(standings)) => standings,
+            _ => return Err("a ranking needs a `ranking` array".to_string()),
+        };
+        let standings = standings
+            .iter()
+            .map(|standing| {
+                let rank = integer(standing.get("rank"))
+                    .filter(|rank| *rank > 0)
+                    .ok_or("a standing needs a positive `rank`")?;
+                // the standings carry no day, it is the one of the ranking
+                let mut member = standing.clone();
+                if let Json::Object(members) = &mut member {
+                    members.push(("day".to_string(), Json::Number(day as f64)));
+                }
+                Ok(Standing {
+                    rank: rank as usize,
+                    submission: Submission::from_json(&member)?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Ranking { day, standings })
+    }
+}
+
+/// The results stored by a leaderboard server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Leaderboard {
+    /// The results in the order they were submitted.
+    pub submissions: Vec<Submission>,
+}
+
+impl Leaderboard {
+    /// Records `submission`, unless the player already has a result of the
+    /// day. Returns whether it was recorded.
+    pub fn submit(&mut self, submission: Submission) -> bool {
+        let known = self
+            .submissions
+            .iter()
+            .any(|known| known.day == submission.day && known.name == submission.name);
+        if !known {
+            self.submissions.push(submission);
+        }
+        !known
+    }
+
+    /// The ranking of `day`.
+    pub fn ranking(&self, day: i64) -> Ranking {
+        let mut submissions: Vec<&Submission> = self
+            .submissions
+            .iter()
+            .filter(|submission| submission.day == day)
+            .collect();
+        // stable, so the first of equal results stays first
+        submissions.sort_by_key(|submission| submission.key());
+        let mut standings: Vec<Standing> = Vec::with_capacity(submissions.len());
+        for (i, submission) in submissions.into_iter().enumerate() {
+            let rank = match standings.last() {
+                Some(last) if last.submission.key() == submission.key() => last.rank,
+                _ => i + 1,
+            };
+            standings.push(Standing {
+                rank,
+                submission: submission.clone(),
+            });
+        }
+        Ranking { day, standings }
+    }
+
+    /// The contents of the file of a server, `{"results":[...]}`.
+    pub fn to_json(&self) -> Json {
+        Json::object([(
+            "results",
+            Json::Array(self.submissions.iter().map(Submission::to_json).collect()),
+        )])
+    }
+
+    /// Reads the file of a server.
+    pub fn from_json(json: &Json) -> Result<Leaderboard, String> {
+        match json.get("results") {
+            Some(Json::Array(results)) => Ok(Leaderboard {
+                submissions: results
+                    .iter()
+                    .map(Submission::from_json)
+                    .collect::<Result<_, String>>()?,
+            }),
+            _ => Err("expected an object with a `results` array".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(name: &str, guesses: Option<usize>, hard: bool) -> Submission {
+        Submission {
+            name: name.to_string(),
+            day: 1234,
+            guesses,
+            hard,
+        }
+    }
+
+    #[test]
+    fn test_submission_json() {
+        let alex = submission("alex", Some(4), true);
+        assert_eq!(
+            alex.to_json().to_string(),
+            r#"{"name":"alex","day":1234,"guesses":4,"hard":true}"#
+        );
+        assert_eq!(Submission::from_json(&alex.to_json()), Ok(alex));
+        let lost = Json::parse(r#"{"name":" sam ","day":1234,"guesses":null}"#).unwrap();
+        assert_eq!(
+            Submission::from_json(&lost),
+            Ok(submission("sam", None, false))
+        );
+        for (json, error) in [
+            (r#"{"day":1}"#, "a result needs a `name` string"),
+            (
+                r#"{"name":"","day":1}"#,
+                "the name must have 1 to 32 characters",
+            ),
+            (r#"{"name":"a","day":1.5}"#, "a result needs a `day` number"),
+            (
+                r#"{"name":"a","day":1,"guesses":0}"#,
+                "`guesses` must be a positive number or null",
+            ),
+            (
+                r#"{"name":"a","day":1,"guesses":2,"hard":"yes"}"#,
+                "`hard` must be a boolean",
+            ),
+        ] {
+            let json = Json::parse(json).unwrap();
+            assert_eq!(Submission::from_json(&json), Err(error.to_string()));
+        }
+        assert_eq!(submission("a", Some(3), true).result(), "3*");
+        assert_eq!(submission("a", None, false).result(), "X");
+    }
+
+    #[test]
+    fn test_ranking() {
+        let mut leaderboard = Leaderboard::default();
+        for (name, guesses, hard) in [
+            ("lost", None, true),
+            ("easy", Some(3), false),
+            ("hard", Some(3), true),
+            ("kim", Some(5), false),
+            ("also", Some(3), false),
+        ] {
+            assert!(leaderboard.submit(submission(name, guesses, hard)));
+        }
+        // only the first result of a day counts
+        assert!(!leaderboard.submit(submission("kim", Some(1), false)));
+        assert!(leaderboard.submit(Submission {
+            day: 1235,
+            ..submission("kim", Some(1), false)
+        }));
+        let ranking = leaderboard.ranking(1234);
+        let standings: Vec<(usize, &str)> = ranking
+            .standings
+            .iter()
+            .map(|standing| (standing.rank, standing.submission.name.as_str()))
+            .collect();
+        assert_eq!(
+            standings,
+            [
+                (1, "hard"),
+                (2, "easy"),
+                (2, "also"),
+                (4, "kim"),
+                (5, "lost")
+            ]
+        );
+        assert_eq!(Ranking::from_json(&ranking.to_json()), Ok(ranking));
+        assert_eq!(leaderboard.ranking(1).standings, []);
+        assert_eq!(
+            Leaderboard::from_json(&leaderboard.to_json()),
+            Ok(leaderboard)
+        );
+        assert!(Leaderboard::from_json(&Json::Null).is_err());
+    }
+}
@@ -0,0 +1,492 @@
+//! `--leaderboard URL`, sending the result of a daily game to an office
+//! leaderboard and showing the ranking of the day, and `rordle
+//! serve-leaderboard`, the server keeping the results in a JSON file. The
+//! API is described in `rordle_core::leaderboard`.
+//!
+//! A leaderboard which can not be reached does not get in the way: the
+//! result is queued in the data directory and sent with the next one.
+
+use crate::error::RordleError;
+use clap::ArgMatches;
+use rordle_core::config::Config;
+use rordle_core::i18n::tr;
+use rordle_core::json::Json;
+use rordle_core::leaderboard::{Leaderboard, Ranking, Submission};
+use rordle_core::paths::{self, DataFile};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long connecting, sending or receiving may take, a leaderboard is
+/// never worth waiting for.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest request body the server reads.
+const MAX_BODY: usize = 4096;
+
+/// The file results which could not be sent are queued in, one per line
+/// as the URL and the JSON of the result separated by a tab.
+pub(crate) fn queue_path(config: &Config) -> Option<PathBuf> {
+    paths::path(config, DataFile::LeaderboardQueue)
+}
+
+/// The default file of `rordle serve-leaderboard`.
+fn default_file(config: &Config) -> Option<PathBuf> {
+    paths::path(config, DataFile::Leaderboard)
+}
+
+/// Sends a request to the leaderboard at `url`, which must be an
+/// `http://` URL, and gives the status and the body of the answer.
+fn request(
+    url: &str,
+    method: &str,
+    path: &str,
+    body: Option<&str>,
+) -> Result<(u16, String), String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or("the leaderboard URL must start with http://")?;
+    let (host, base) = match rest.find('/') {
+        Some(slash) => (&rest[..slash], rest[slash..].trim_end_matches('/')),
+        None => (rest, ""),
+    };
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+    let address = address
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("can not resolve `{}`", host))?;
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+        .map_err(|e| e.to_string())?;
+    let body = body.unwrap_or_default();
+    write!(
+        stream,
+        "{} {}{} HTTP/1.0\r\nHost: {}\r\nUser-Agent: rordle\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        base,
+        path,
+        host,
+        body.len(),
+        body
+    )
+    .map_err(|e| e.to_string())?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|e| e.to_string())?;
+    let response = String::from_utf8(response).map_err(|_| "the answer is not UTF-8")?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("malformed HTTP response")?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or("malformed HTTP response")?;
+    Ok((status, body.to_string()))
+}
+
+/// Sends one result, `Ok(false)` if the leaderboard rejected it as
+/// malformed, which sending it again would not change.
+fn send(url: &str, submission: &Json) -> Result<bool, String> {
+    match request(url, "POST", "/results", Some(&submission.to_string()))? {
+        (200, _) => Ok(true),
+        (400, body) => {
+            let error = Json::parse(&body).ok();
+            let message = error
+                .as_ref()
+                .and_then(|error| error.get("error"))
+                .and_then(Json::as_str)
+                .unwrap_or("rejected");
+            eprintln!("warning: the leaderboard rejected a result: {}", message);
+            Ok(false)
+        }
+        (status, _) => Err(format!("the leaderboard answered {}", status)),
+    }
+}
+
+/// Sends `submission` to `url`, after the results queued for it, and
+/// writes the results which could not be sent back to `queue`. A
+/// leaderboard which can not be reached gives an error once everything
+/// is queued.
+pub(crate) fn submit(url: &str, submission: &Submission, queue: &Path) -> Result<(), String> {
+    let queued = std::fs::read_to_string(queue).unwrap_or_default();
+    let mut pending: Vec<(String, String)> = queued
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(url, json)| (url.to_string(), json.to_string()))
+        .collect();
+    pending.push((url.to_string(), submission.to_json().to_string()));
+    let mut error = None;
+    let mut kept = Vec::new();
+    for (target, json) in pending {
+        // results of other leaderboards wait for a run with their URL, and
+        // once one is offline the rest is not tried
+        if target != url || error.is_some() {
+            kept.push((target, json));
+            continue;
+        }
+        let sent = Json::parse(&json)
+            .map_err(|e| e.to_string())
+            .and_then(|json| send(url, &json));
+        if let Err(message) = sent {
+            error = Some(message);
+            kept.push((target, json));
+        }
+    }
+    let lines: String = kept
+        .iter()
+        .map(|(url, json)| format!("{}\t{}\n", url, json))
+        .collect();
+    let written = if lines.is_empty() {
+        match std::fs::remove_file(queue) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    } else {
+        queue
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(queue, lines))
+    };
+    if let Err(write_error) = written {
+        eprintln!(
+            "warning: leaderboard queue not saved: {}: {}",
+            queue.display(),
+            write_error
+        );
+    }
+    error.map_or(Ok(()), Err)
+}
+
+/// The ranking of `day` at the leaderboard `url`.
+pub(crate) fn fetch_ranking(url: &str, day: i64) -> Result<Ranking, String> {
+    match request(url, "GET", &format!("/ranking?day={}", day), None)? {
+        (200, body) => Json::parse(&body).and_then(|json| Ranking::from_json(&json)),
+        (status, _) => Err(format!("the leaderboard answered {}", status)),
+    }
+}
+
+/// The ranking as a table below the end screen, with the row of `name`
+/// marked.
+pub(crate) fn ranking_lines(ranking: &Ranking, name: &str) -> Vec<String> {
+    let width = ranking
+        .standings
+        .iter()
+        .map(|standing| standing.submission.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut lines = vec![tr("leaderboard-title", &[("day", &ranking.day)])];
+    lines.extend(ranking.standings.iter().map(|standing| {
+        let submission = &standing.submission;
+        let marker = if submission.name == name { '>' } else { ' ' };
+        format!(
+            "{}{:>3}. {:<width$}  {}",
+            marker,
+            standing.rank,
+            submission.name,
+            submission.result(),
+            width = width
+        )
+    }));
+    lines
+}
+
+/// The name the player submits results as: the one of the config file,
+/// else the profile, else the login name.
+pub(crate) fn player_name(config: &Config) -> String {
+    config
+        .name
+        .clone()
+        .or_else(|| config.profile.clone())
+        .or_else(|| std::env::var("USER").ok())
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "player".to_string())
+}
+
+/// Submits the result of a finished daily game and prints the ranking of
+/// the day if played on the `terminal`. Anything going wrong is only a
+/// warning, the game was played.
+pub(crate) fn after_daily_game(
+    url: &str,
+    submission: &Submission,
+    terminal: bool,
+    config: &Config,
+) {
+    let queue = match queue_path(config) {
+        Some(queue) => queue,
+        None => {
+            eprintln!(
+                "warning: can not locate the data directory, neither XDG_DATA_HOME nor HOME is set"
+            );
+            return;
+        }
+    };
+    if let Err(error) = submit(url, submission, &queue) {
+        eprintln!(
+            "warning: leaderboard not reached, the result is sent next time: {}",
+            error
+        );
+        return;
+    }
+    if !terminal {
+        return;
+    }
+    match fetch_ranking(url, submission.day) {
+        Ok(ranking) => {
+            println!();
+            for line in ranking_lines(&ranking, &submission.name) {
+                println!("{}", line);
+            }
+        }
+        Err(error) => eprintln!("warning: ranking not fetched: {}", error),
+    }
+}
+
+/// An HTTP answer with a JSON body.
+fn respond(stream: &mut TcpStream, status: u16, body: &Json) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.0 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+fn error_json(message: &str) -> Json {
+    Json::object([("error", Json::from(message))])
+}
+
+/// Answers one request, saving `leaderboard` to `file` when a result was
+/// recorded.
+fn handle_request(
+    stream: &mut TcpStream,
+    leaderboard: &mut Leaderboard,
+    file: &Path,
+) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return respond(stream, 400, &error_json("the result is too long"));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let mut words = request_line.split_whitespace();
+    let (method, target) = (words.next().unwrap_or(""), words.next().unwrap_or(""));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    // a leaderboard may be served below a path, see the API
+    let endpoint = path.rsplit('/').next().unwrap_or("");
+    match (method, endpoint) {
+        ("POST", "results") => {
+            let submission = String::from_utf8(body)
+                .map_err(|_| "the result is not UTF-8".to_string())
+                .and_then(|body| Json::parse(&body))
+                .and_then(|json| Submission::from_json(&json));
+            match submission {
+                Ok(submission) => {
+                    if leaderboard.submit(submission) {
+                        std::fs::write(file, format!("{}\n", leaderboard.to_json()))?;
+                    }
+                    respond(stream, 200, &Json::object([("ok", Json::from(true))]))
+                }
+                Err(message) => respond(stream, 400, &error_json(&message)),
+            }
+        }
+        ("GET", "ranking") => {
+            let day = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("day="))
+                .and_then(|day| day.parse().ok());
+            match day {
+                Some(day) => respond(stream, 200, &leaderboard.ranking(day).to_json()),
+                None => respond(stream, 400, &error_json("a ranking needs a `day` number")),
+            }
+        }
+        _ => respond(stream, 404, &error_json("unknown endpoint")),
+    }
+}
+
+/// Answers requests on `listener` one after another until the program is
+/// stopped. Each request is short, and bounded by the timeout.
+fn serve_on(listener: TcpListener, mut leaderboard: Leaderboard, file: &Path) {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("can not accept a connection: {}", error);
+                continue;
+            }
+        };
+        if let Err(error) = handle_request(&mut stream, &mut leaderboard, file) {
+            eprintln!("request failed: {}", error);
+        }
+    }
+}
+
+/// Reads the results kept in `file`, a missing file has none.
+fn load_leaderboard(file: &Path) -> Result<Leaderboard, RordleError> {
+    let error = |message: String| RordleError::Leaderboard(file.to_path_buf(), message);
+    match std::fs::read_to_string(file) {
+        Ok(contents) => Json::parse(&contents)
+            .and_then(|json| Leaderboard::from_json(&json))
+            .map_err(error),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Leaderboard::default()),
+        Err(e) => Err(error(e.to_string())),
+    }
+}
+
+/// `rordle serve-leaderboard`: keeps the results of a team in a JSON file
+/// and serves the rankings, until Ctrl+C.
+pub(crate) fn serve(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    let file = match matches.value_of("file") {
+        Some(file) => PathBuf::from(file),
+        None => default_file(config).ok_or(RordleError::NoStatsPath)?,
+    };
+    let leaderboard = load_leaderboard(&file)?;
+    if let Some(dir) = file.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| RordleError::Leaderboard(file.clone(), e.to_string()))?;
+    }
+    let port: u16 = matches.value_of("port").unwrap().parse().unwrap();
+    let address = (matches.value_of("bind").unwrap(), port);
+    let listener = TcpListener::bind(address).map_err(RordleError::Network)?;
+    let address = listener.local_addr().map_err(RordleError::Network)?;
+    eprintln!(
+        "serving the leaderboard on http://{}, results are kept in {}",
+        address,
+        file.display()
+    );
+    serve_on(listener, leaderboard, &file);
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rordle-{}-{}", name, std::process::id()))
+    }
+
+    fn submission(name: &str, guesses: Option<usize>) -> Submission {
+        Submission {
+            name: name.to_string(),
+            day: 812,
+            guesses,
+            hard: false,
+        }
+    }
+
+    #[test]
+    fn test_submit_and_fetch() {
+        let file = temp_path("leaderboard.json");
+        let queue = temp_path("leaderboard-queue");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/team", listener.local_addr().unwrap());
+        let server_file = file.clone();
+        std::thread::spawn(move || serve_on(listener, Leaderboard::default(), &server_file));
+
+        submit(&url, &submission("sam", None), &queue).unwrap();
+        submit(&url, &submission("alex", Some(3)), &queue).unwrap();
+        let ranking = fetch_ranking(&url, 812).unwrap();
+        assert_eq!(
+            ranking_lines(&ranking, "alex"),
+            ["Ranking of puzzle 812:", ">  1. alex  3", "   2. sam   X"]
+        );
+        assert!(fetch_ranking(&url, 813).unwrap().standings.is_empty());
+        // the results are kept, the word is not
+        let kept = load_leaderboard(&file).unwrap();
+        assert_eq!(kept.submissions.len(), 2);
+        assert!(!queue.exists());
+        // malformed results are rejected, but not queued
+        let rejected = submit(&url, &submission(" ", Some(3)), &queue);
+        assert_eq!(rejected, Ok(()));
+        assert!(!queue.exists());
+        assert_eq!(request(&url, "GET", "/ranking", None).unwrap().0, 400);
+        assert_eq!(request(&url, "GET", "/other", None).unwrap().0, 404);
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_offline_queue() {
+        let queue = temp_path("offline-queue");
+        // a port nobody listens on
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        std::fs::write(&queue, "http://elsewhere\t{}\n").unwrap();
+        assert!(submit(&url, &submission("sam", Some(4)), &queue).is_err());
+        assert!(submit(&url, &submission("sam", Some(2)), &queue).is_err());
+        let queued = std::fs::read_to_string(&queue).unwrap();
+        assert_eq!(queued.lines().count(), 3);
+        assert!(queued.starts_with("http://elsewhere\t"));
+
+        // once the leaderboard is up, the queue is sent first
+        let listener = TcpListener::bind(url.trim_start_matches("http://")).unwrap();
+        let file = temp_path("offline-leaderboard.json");
+        let server_file = file.clone();
+        std::thread::spawn(move || serve_on(listener, Leaderboard::default(), &server_file));
+        submit(&url, &submission("kim", Some(5)), &queue).unwrap();
+        let ranking = fetch_ranking(&url, 812).unwrap();
+        let names: Vec<(&str, String)> = ranking
+            .standings
+            .iter()
+            .map(|standing| {
+                (
+                    standing.submission.name.as_str(),
+                    standing.submission.result(),
+                )
+            })
+            .collect();
+        // only the first result of sam counts
+        assert_eq!(names, [("sam", "4".to_string()), ("kim", "5".to_string())]);
+        assert_eq!(
+            std::fs::read_to_string(&queue).unwrap(),
+            "http://elsewhere\t{}\n"
+        );
+        assert!(submit("https://example.com", &submission("kim", None), &queue).is_err());
+        std::fs::remove_file(&queue).unwrap();
+        std::fs::remove_file(&file).unwrap();
+        let mut config = Config::default();
+        config.name = Some("alex".to_string());
+        config.profile = Some("kim".to_string());
+        assert_eq!(player_name(&config), "alex");
+        config.name = None;
+        assert_eq!(player_name(&config), "kim");
+    }
+}
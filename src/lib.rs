@@ -0,0 +1,41 @@
+//! Rules and word lists of rordle, a Wordle clone.
+//!
+//! This crate contains everything needed to play a game without a
+//! terminal: [`game::GameState`] tracks a game and [`game::score`] scores
+//! guesses, while [`words`] loads the word lists a game is played with.
+//! [`picker`] picks the solution of a game.
+//! [`stats`] records finished games and [`solver`] narrows down solutions,
+//! [`opener`] keeps the rankings of its first guesses, [`parallel`] spreads
+//! its work over threads.
+//! [`protocol`] lets other programs play a game over newline delimited JSON.
+//! [`challenge`] shares a solution as a code, [`pack`] a series of them.
+//! [`share`] reads the results shared from the official Wordle.
+//! [`pattern`] restricts solutions to words matching a regular expression.
+//! [`i18n`] translates the messages shown to players.
+//! [`digraph`] plays digraphs like the `ll` of Spanish as single letters.
+//! [`leaderboard`] ranks the results of daily puzzles within a team.
+//! [`paths`] locates the files saved, apart for each profile, [`hash`]
+//! recognizes what they were saved for.
+
+#![warn(missing_docs)]
+
+pub mod challenge;
+pub mod config;
+pub mod date;
+pub mod digraph;
+pub mod game;
+pub mod hash;
+pub mod i18n;
+pub mod json;
+pub mod leaderboard;
+pub mod opener;
+pub mod pack;
+pub mod parallel;
+pub mod paths;
+pub mod pattern;
+pub mod picker;
+pub mod protocol;
+pub mod share;
+pub mod solver;
+pub mod stats;
+pub mod words;
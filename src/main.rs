@@ -1,559 +1,1781 @@
 extern crate termion;
 
-use clap::{App, Arg};
-use rand::prelude::*;
-use std::fs::File;
-use std::io::Read;
-use std::io::{stdin, stdout, Write};
-use termion::color;
+mod battle;
+mod cast;
+mod cli;
+mod commands;
+mod completions;
+mod define;
+mod demo;
+mod duel;
+mod error;
+mod hook;
+mod html;
+#[cfg(feature = "network")]
+mod leaderboards;
+mod menu;
+mod messages;
+mod notify;
+mod output;
+mod packs;
+mod recorder;
+mod render;
+mod server;
+mod summary;
+mod terminal;
+mod tutorial;
+
+use cast::{Recording, Tee};
+use clap::ArgMatches;
+use cli::{Interface, ListArgs};
+use error::RordleError;
+use messages::{error_ttl, Message, Messages};
+use recorder::Recorder;
+use render::{
+    animate_last_guess, plain_row, render_below, render_counts, render_game_state, render_heatmap,
+    AssistPanel, Constraints, Heatmap, RemainingCounts, RenderOptions, DEFAULT_SIZE,
+};
+use rordle_core::challenge::Challenge;
+use rordle_core::config::{Config, KeyName, Keybindings};
+use rordle_core::date::{unix_time, DailyEpoch, Date, Puzzle};
+use rordle_core::digraph::{self, Digraphs};
+use rordle_core::game::{GameOptions, GameState, Hint, Outcome};
+use rordle_core::i18n::{self, tr};
+use rordle_core::pack::Pack;
+use rordle_core::pattern::Pattern;
+use rordle_core::picker::{
+    AvoidRecent, DailyPicker, LetterBiasPicker, RandomPicker, SequentialPicker, WordPicker,
+};
+use rordle_core::protocol::serve;
+use rordle_core::solver::suggest;
+use rordle_core::stats::{letter_weakness, read_records, GameRecord, BACKFILL};
+use rordle_core::words::{
+    exceeds_stream_threshold, load_sources, stream_word_files, MergedWordList, Rarity, TagFilter,
+};
+use std::io::{stdin, stdout, BufRead, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use summary::{pacing, summary_line};
+use terminal::{Input, Terminal, TickingKeys};
 use termion::event::Key;
 use termion::input::TermRead;
-use termion::raw::IntoRawMode;
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-enum HitInfo {
-    Hit,
-    Contains,
-    Miss,
-    None,
+/// Name of a key pressed on the terminal, to compare it to the keybindings.
+fn key_name(key: Key) -> Option<KeyName> {
+    match key {
+        Key::Esc => Some(KeyName::Esc),
+        Key::Backspace => Some(KeyName::Backspace),
+        Key::Delete => Some(KeyName::Delete),
+        Key::Char('\n') => Some(KeyName::Enter),
+        Key::Char('\t') => Some(KeyName::Tab),
+        Key::Char(c) => Some(KeyName::Char(c)),
+        Key::Ctrl(c) => Some(KeyName::Ctrl(c)),
+        _ => None,
+    }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-enum GameError {
-    WrongLength,
-    InvalidWord,
+/// State of the menu of hints, opened with the hint key in games with a
+/// hint budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HintMenu {
+    Closed,
+    /// Choosing a hint by its number.
+    Open,
+    /// Choosing the letter to ask for.
+    Letter,
 }
 
-impl std::fmt::Display for GameError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            GameError::WrongLength => write!(f, "Word is not the correct length"),
-            GameError::InvalidWord => write!(f, "Word is not valid"),
+/// Hints of the menu with the ids of their labels, chosen by number. The
+/// letter to ask for is typed next and does not change the cost.
+const HINT_MENU: [(&str, Hint); 3] = [
+    ("hint-reveal-position", Hint::Position),
+    ("hint-ask-letter", Hint::Letter('?')),
+    ("hint-eliminate", Hint::Eliminate),
+];
+
+/// The state of the menu after `key`, with the hint to buy if one was
+/// chosen. `None` if the key does nothing in the menu.
+fn menu_key(
+    menu: HintMenu,
+    key: Option<KeyName>,
+    bindings: &Keybindings,
+) -> Option<(HintMenu, Option<Hint>)> {
+    match (menu, key?) {
+        (_, key) if key == bindings.quit => Some((HintMenu::Closed, None)),
+        (HintMenu::Open, KeyName::Char(c)) => {
+            let index = c.to_digit(10)?.checked_sub(1)?;
+            match HINT_MENU.get(index as usize)? {
+                (_, Hint::Letter(_)) => Some((HintMenu::Letter, None)),
+                (_, hint) => Some((HintMenu::Closed, Some(*hint))),
+            }
         }
+        (HintMenu::Letter, KeyName::Char(c)) if c.is_alphabetic() => {
+            Some((HintMenu::Closed, Some(Hint::Letter(c))))
+        }
+        _ => None,
     }
 }
 
-struct GameState {
-    valid_words: Vec<String>,
-    guesses: Vec<String>,
-    current_guess: String,
-    word: String,
-    max_tries: u16,
-    last_error: Option<GameError>,
-    any_word: bool,
+/// `guess` in the current row, with the letters of the locked cells of
+/// prefill mode and hints in their place. It stops at the first open cell
+/// past its end, like a row typed up to there.
+fn locked_row(game_state: &GameState, guess: &[char]) -> String {
+    let mut row = String::new();
+    for (i, &letter) in game_state.letters().iter().enumerate() {
+        match guess.get(i) {
+            _ if game_state.prefilled(i) => row.push(letter),
+            Some(&c) => row.push(c),
+            None => break,
+        }
+    }
+    row
 }
 
-impl GameState {
-    pub fn new(word: String, valid_words: Vec<String>, any_word: bool) -> GameState {
-        GameState {
-            valid_words,
-            guesses: Vec::new(),
-            current_guess: String::new(),
-            word,
-            max_tries: 6,
-            last_error: None,
-            any_word,
-        }
+/// Recalls an earlier guess into the current row: Up the one before the
+/// guess recalled last, starting with the most recent, and Down the one
+/// after, back to an empty row. `recall` counts the guesses back from the
+/// most recent one and is `None` while the row is typed by hand. Gives the
+/// new index and row, `None` if the key does nothing.
+fn recall_key(
+    game_state: &GameState,
+    recall: Option<usize>,
+    key: Key,
+) -> Option<(Option<usize>, String)> {
+    let recall = match (key, recall) {
+        (Key::Up, None) => Some(0),
+        (Key::Up, Some(back)) => Some(back + 1),
+        (Key::Down, Some(0)) => None,
+        (Key::Down, Some(back)) => Some(back - 1),
+        _ => return None,
+    };
+    let guessed = game_state.guesses.len();
+    let guess = match recall {
+        Some(back) if back >= guessed => return None,
+        Some(back) => game_state.guess_letters(guessed - 1 - back),
+        None => &[],
+    };
+    Some((recall, locked_row(game_state, guess)))
+}
+
+/// Lines below the keyboard: in games with a hint budget the points left
+/// or the open menu, then the message, what the last hint revealed or a
+/// warning.
+fn hint_lines(
+    game_state: &GameState,
+    menu: HintMenu,
+    message: Option<&str>,
+    bindings: &Keybindings,
+) -> Vec<String> {
+    if game_state.hint_budget() == 0 {
+        return message.map(str::to_string).into_iter().collect();
     }
+    let points = game_state.hint_points();
+    let mut lines = match menu {
+        HintMenu::Closed => vec![tr(
+            "hint-points",
+            &[("points", &points), ("key", &bindings.hint)],
+        )],
+        HintMenu::Open => {
+            let title = tr("hint-menu", &[("points", &points), ("key", &bindings.quit)]);
+            let entries = HINT_MENU
+                .iter()
+                .enumerate()
+                .map(|(i, (id, hint))| format!("{}  {} [{}]", i + 1, tr(id, &[]), hint.cost()));
+            [title].into_iter().chain(entries).collect()
+        }
+        HintMenu::Letter => vec![tr("hint-ask", &[])],
+    };
+    lines.extend(message.map(str::to_string));
+    lines
+}
 
-    fn guess(&mut self, guess: String) -> Result<bool, GameError> {
-        if guess.chars().count() != self.word.chars().count() {
-            return Err(GameError::WrongLength);
+/// Plays a game with the keys read from `keys`, rendering to `output`.
+/// The board is rendered once per change of the game state, a tick only
+/// renders it if a message expired. Once the game is over, `e` explores
+/// other guesses in a sandbox of it, any other key ends the loop.
+fn key_loop<K, W, I>(
+    game_state: &mut GameState,
+    mut keys: K,
+    output: &mut W,
+    interface: &Interface,
+) -> std::io::Result<()>
+where
+    K: Iterator<Item = std::io::Result<I>>,
+    I: Into<Input>,
+    W: Write,
+{
+    let bindings = &interface.keybindings;
+    let mut menu = HintMenu::Closed;
+    // what the last hint revealed, until the next guess
+    let mut messages = Messages::default();
+    let mut constraints = Constraints::default();
+    let mut assist = AssistPanel::default();
+    let mut heatmap = Heatmap::default();
+    let mut counts = RemainingCounts::default();
+    // the guess recalled into the current row, see `recall_key`
+    let mut recall = None;
+    // the quit key was pressed once in a hardcore game
+    let mut quitting = false;
+    let exploring = interface.render.exploring;
+    // `board` is false after an animation, which ends with the board
+    let mut render =
+        |output: &mut W, game_state: &GameState, menu, message: Option<&str>, board| {
+            if board {
+                render_game_state(output, game_state, &interface.render);
+            }
+            let mut lines = Vec::new();
+            if interface.tutorial {
+                lines.extend(tutorial::tutorial_lines(
+                    game_state,
+                    interface.render.theme,
+                    bindings,
+                ));
+            }
+            if interface.show_constraints {
+                lines.push(constraints.line(game_state, &interface.render).to_string());
+            }
+            if interface.assist {
+                let cache = interface.opener_cache.as_deref();
+                lines.extend_from_slice(assist.lines(game_state, cache));
+                report_unsaved_openers(cache, assist.take_unsaved().as_ref());
+            }
+            lines.extend(hint_lines(game_state, menu, message, bindings));
+            // the keys tell nothing without --assist
+            if interface.assist && !game_state.over() {
+                render_heatmap(
+                    output,
+                    game_state,
+                    heatmap.frequencies(game_state),
+                    &interface.render,
+                );
+            }
+            match (game_state.over(), exploring) {
+                (false, _) => {}
+                (true, false) => lines.push(tr("explore-prompt", &[])),
+                (true, true) => {
+                    lines.push(outcome_message(game_state, interface.render.uppercase));
+                    lines.push(tr("explore-back", &[]));
+                }
+            }
+            render_below(output, game_state, &lines, &interface.render);
+            if exploring {
+                render_counts(
+                    output,
+                    game_state,
+                    counts.counts(game_state),
+                    &interface.render,
+                );
+            }
+            output.flush()
+        };
+    render(output, game_state, menu, None, true)?;
+    while let Some(input) = keys.next() {
+        let pressed = match input?.into() {
+            Input::Key(pressed) => pressed,
+            Input::Tick => {
+                if messages.tick(game_state, Instant::now()) {
+                    render(output, game_state, menu, messages.text(), true)?;
+                }
+                continue;
+            }
+        };
+        let key = key_name(pressed);
+        if game_state.over() {
+            if exploring || !matches!(key, Some(KeyName::Char('e' | 'E'))) {
+                break;
+            }
+            let mut sandbox = game_state.sandbox();
+            sandbox.list_name = Some(tr("explore-title", &[("quit", &bindings.quit)]));
+            let sandbox_interface = Interface {
+                render: RenderOptions {
+                    exploring: true,
+                    ..interface.render
+                },
+                tutorial: false,
+                hardcore: false,
+                ..interface.clone()
+            };
+            // as a trait object, so exploring does not instantiate another
+            // key loop for each level of references
+            let keys: &mut dyn Iterator<Item = std::io::Result<I>> = &mut keys;
+            key_loop(&mut sandbox, keys, output, &sandbox_interface)?;
+            render(output, game_state, menu, None, true)?;
+            continue;
+        }
+        if menu != HintMenu::Closed {
+            let (next, hint) = match menu_key(menu, key, bindings) {
+                Some(change) => change,
+                None => continue,
+            };
+            menu = next;
+            if let Some(hint) = hint {
+                recall = None;
+                messages.show(match game_state.hint(hint) {
+                    Ok(revealed) => Message::sticky(revealed.to_string()),
+                    Err(error) => {
+                        Message::fading(error.to_string(), error_ttl(&error), Instant::now())
+                    }
+                });
+            }
+            render(output, game_state, menu, messages.text(), true)?;
+            continue;
+        }
+        if quitting && key != Some(bindings.quit) {
+            quitting = false;
+            messages.clear();
         }
-        if !self.any_word && !self.valid_words.contains(&guess) {
-            return Err(GameError::InvalidWord);
+        match key {
+            // leaving a hardcore game ends the run, so it is confirmed
+            Some(key) if key == bindings.quit && interface.hardcore && !quitting => {
+                quitting = true;
+                messages.show(Message::sticky(tr(
+                    "hardcore-quit",
+                    &[("key", &bindings.quit)],
+                )));
+            }
+            Some(key) if key == bindings.quit => break,
+            Some(key) if key == bindings.delete => {
+                recall = None;
+                game_state.back();
+            }
+            Some(key) if key == bindings.submit => {
+                recall = None;
+                let tries = game_state.guesses.len();
+                game_state.confirm();
+                messages.confirmed(game_state, Instant::now());
+                if game_state.last_error.is_some() && interface.bell {
+                    write!(output, "\x07")?;
+                }
+                if game_state.guesses.len() > tries {
+                    messages.clear();
+                    if interface.animations {
+                        animate_last_guess(output, game_state, &interface.render);
+                        render(output, game_state, menu, None, false)?;
+                        continue;
+                    }
+                }
+            }
+            Some(key) if key == bindings.hint && game_state.hint_budget() > 0 => {
+                menu = HintMenu::Open;
+            }
+            Some(KeyName::Char(c)) => {
+                recall = None;
+                game_state.add_char(c);
+            }
+            None => match recall_key(game_state, recall, pressed) {
+                Some((index, row)) => {
+                    recall = index;
+                    game_state.current_guess = row;
+                }
+                None => continue,
+            },
+            // raw mode passes Ctrl+Z on as a key
+            Some(KeyName::Ctrl('z')) if terminal::suspend()? => {
+                // the time away is not counted for the next guess
+                game_state.mark_gap();
+            }
+            // nothing changed, nothing to render
+            _ => continue,
         }
-        self.guesses.push(guess);
-        Ok(self.won())
+        render(output, game_state, menu, messages.text(), true)?;
     }
+    Ok(())
+}
+
+/// Time between two letters typed by `--auto`, the solver waits three
+/// times as long after each guess.
+const AUTO_TYPE_DELAY: Duration = Duration::from_millis(150);
 
-    fn set_last_error(&mut self, error: GameError) {
-        self.last_error = Some(error);
+/// Lets the solver play the game, typing its guesses letter by letter with
+/// `delay` in between. Stops early once `aborted` returns true.
+fn auto_loop<W: Write>(
+    game_state: &mut GameState,
+    mut aborted: impl FnMut() -> bool,
+    output: &mut W,
+    interface: &Interface,
+    delay: Duration,
+) -> std::io::Result<()> {
+    let mut pause = |delay| {
+        std::thread::sleep(delay);
+        aborted()
+    };
+    render_game_state(output, game_state, &interface.render);
+    output.flush()?;
+    while !game_state.over() {
+        let guess = match suggest(game_state) {
+            Some(guess) => guess.to_string(),
+            None => break,
+        };
+        for (i, c) in guess.chars().enumerate() {
+            // prefilled letters are filled in by the game
+            if game_state.prefilled(i) {
+                continue;
+            }
+            if pause(delay) {
+                return Ok(());
+            }
+            game_state.add_char(c);
+            render_game_state(output, game_state, &interface.render);
+            output.flush()?;
+        }
+        if pause(delay) {
+            return Ok(());
+        }
+        game_state.confirm();
+        if game_state.last_error.is_some() {
+            // the solver only suggests words the game accepts
+            break;
+        }
+        if interface.animations {
+            animate_last_guess(output, game_state, &interface.render);
+        } else {
+            render_game_state(output, game_state, &interface.render);
+            output.flush()?;
+        }
+        if !game_state.over() && pause(delay * 3) {
+            return Ok(());
+        }
     }
+    Ok(())
+}
 
-    fn reset_error(&mut self) {
-        self.last_error = None;
+/// Columns and rows of the terminal rordle is running in.
+pub(crate) fn terminal_size() -> (u16, u16) {
+    termion::terminal_size().unwrap_or(DEFAULT_SIZE)
+}
+
+/// `interface` fitted to the terminal rordle is running in, whose size is
+/// read once for the whole game.
+pub(crate) fn on_terminal(interface: &Interface) -> Interface {
+    Interface {
+        render: RenderOptions {
+            terminal: terminal_size(),
+            ..interface.render
+        },
+        ..interface.clone()
     }
+}
 
-    pub fn won(&self) -> bool {
-        match self.guesses.last() {
-            Some(last_guess) => last_guess == &self.word,
-            None => false,
+/// Plays a game on the terminal, by the player or with `auto` by the solver.
+/// With a `recording` the session is written to a cast file as well.
+fn game_loop(
+    mut game_state: GameState,
+    interface: &Interface,
+    auto: bool,
+    recording: Option<Recording>,
+) -> Result<GameState, RordleError> {
+    let timestamp = unix_time();
+    let interface = &on_terminal(interface);
+    // the terminal is set up once and restored when `terminal` is dropped
+    let terminal = Terminal::open(interface.altscreen).map_err(RordleError::Terminal)?;
+    let mut stdout = Tee::new(terminal, recording.is_some());
+    if auto {
+        // any key aborts the game
+        let mut keys = termion::async_stdin().keys();
+        let aborted = || keys.next().is_some();
+        auto_loop(
+            &mut game_state,
+            aborted,
+            &mut stdout,
+            interface,
+            AUTO_TYPE_DELAY,
+        )
+    } else {
+        let keys = TickingKeys::open().map_err(RordleError::Terminal)?;
+        key_loop(&mut game_state, keys, &mut stdout, interface)
+    }
+    .map_err(RordleError::Terminal)?;
+    // the lines are printed once the terminal is restored, on the normal
+    // screen and starting at the left again
+    let mut lines = vec![
+        String::new(),
+        end_message(&game_state, interface.render.uppercase),
+    ];
+    lines.extend(pacing_line(&game_state, interface.render.uppercase));
+    // looked up only now, it names the solution and may take a while
+    if game_state.over() {
+        lines.extend(rarity_note(&game_state));
+        let word = digraph::untokenize(&game_state.word);
+        if let Some(definition) = define::definition(&word, interface.define) {
+            lines.push(tr(
+                "definition",
+                &[("word", &word), ("definition", &definition)],
+            ));
         }
     }
+    // others may still play an unfinished daily puzzle, its end message
+    // names the solution
+    if recording.is_some_and(|recording| !recording.daily || game_state.over()) {
+        stdout.record(&format!("{}\r\n", lines.join("\r\n")));
+    }
+    let (terminal, events) = stdout.into_parts();
+    drop(terminal);
+    for line in &lines {
+        println!("{}", line);
+    }
+    if let (Some(recording), Some(events)) = (recording, events) {
+        if let Err(error) = cast::write_cast(
+            recording.path,
+            &events,
+            interface.render.terminal,
+            timestamp,
+        ) {
+            eprintln!(
+                "warning: session not recorded: {}: {}",
+                recording.path.display(),
+                error
+            );
+        }
+    }
+    Ok(game_state)
+}
 
-    pub fn get_guess_hits(&self, guess_position: usize) -> Vec<HitInfo> {
-        let mut hits = Vec::new();
-        let guess = self.guesses.get(guess_position).unwrap();
-        for (i, c) in guess.chars().enumerate() {
-            if c == self.word.chars().nth(i).unwrap() {
-                hits.push(HitInfo::Hit);
-            } else if self.word.contains(c) {
-                hits.push(HitInfo::Contains);
-            } else {
-                hits.push(HitInfo::Miss);
-            }
+/// The time each guess took and the total, shown after a game on the
+/// terminal. With `uppercase` the guesses are written as on the tiles.
+fn pacing_line(game_state: &GameState, uppercase: bool) -> Option<String> {
+    if game_state.guess_times.is_empty() {
+        return None;
+    }
+    let (spans, total) = pacing(&game_state.guess_times);
+    let times: Vec<String> = game_state
+        .guesses
+        .iter()
+        .zip(spans)
+        .map(|(guess, span)| {
+            let span = span.unwrap_or_else(|| tr("pacing-away", &[]));
+            format!("{} {}", render::tile_word(guess, uppercase), span)
+        })
+        .collect();
+    Some(tr(
+        "pacing",
+        &[("times", &times.join(", ")), ("total", &total)],
+    ))
+}
+
+/// Message shown at the end of a game, noting what each player of a team
+/// discovered and the hints used. Won co-op games credit the player of the
+/// last guess and the one before, who set it up, team games the player of
+/// the last guess. With `uppercase` the solution is named as on the tiles.
+fn end_message(game_state: &GameState, uppercase: bool) -> String {
+    let mut lines = vec![outcome_message(game_state, uppercase)];
+    if game_state.team {
+        for (player, greens) in game_state.discoveries() {
+            lines.push(tr(
+                "team-greens",
+                &[("player", &player), ("greens", &greens)],
+            ));
         }
-        hits
     }
+    if !game_state.hints().is_empty() {
+        let points = game_state.hint_budget() - game_state.hint_points();
+        lines.push(tr(
+            "hints-used",
+            &[("hints", &game_state.hints().len()), ("points", &points)],
+        ));
+    }
+    lines.join("\n")
+}
 
-    pub fn back(&mut self) {
-        if self.current_guess.chars().count() > 0 {
-            self.current_guess.pop();
+/// How common the solution is among the solutions of the list, `None` for
+/// lists without frequencies.
+fn rarity_note(game_state: &GameState) -> Option<String> {
+    let word: &dyn std::fmt::Display = &digraph::untokenize(&game_state.word);
+    let percent = |share: f64| (share * 100.0).round() as u32;
+    Some(match game_state.rarity {
+        Rarity::Unknown => return None,
+        Rarity::Unlisted => tr("rarity-unlisted", &[("word", word)]),
+        Rarity::Percentile { commoner, .. } if commoner < 0.1 => {
+            tr("rarity-very-common", &[("word", word)])
         }
+        Rarity::Percentile { commoner, .. } if commoner >= 0.5 => tr(
+            "rarity-rarer",
+            &[("word", word), ("percent", &percent(commoner))],
+        ),
+        Rarity::Percentile { rarer, .. } => tr(
+            "rarity-commoner",
+            &[("word", word), ("percent", &percent(rarer))],
+        ),
+    })
+}
+
+fn outcome_message(game_state: &GameState, uppercase: bool) -> String {
+    let word: &dyn std::fmt::Display = &render::tile_word(&game_state.word, uppercase);
+    match game_state.outcome() {
+        Outcome::Won => {
+            let last = game_state.guesses.len() - 1;
+            let credit = match (game_state.player(last), last.checked_sub(1)) {
+                (Some(player), _) if game_state.team => {
+                    tr("coop-found-alone", &[("player", &player)])
+                }
+                (Some(player), Some(setup)) => {
+                    let setup = game_state.player(setup).unwrap();
+                    tr("coop-found", &[("player", &player), ("setup", &setup)])
+                }
+                (Some(player), None) => tr("coop-found-alone", &[("player", &player)]),
+                (None, _) => return tr("won", &[]),
+            };
+            format!("{}\n{}", tr("won", &[]), credit)
+        }
+        Outcome::Lost => tr("lost", &[("word", word)]),
+        Outcome::Abandoned => tr("abandoned", &[("word", word)]),
     }
+}
 
-    pub fn confirm(&mut self) {
-        let result = self.guess(self.current_guess.clone());
-        match result {
-            Ok(_) => {
-                self.reset_error();
-            }
-            Err(error) => {
-                self.set_last_error(error);
-            }
-        };
-        self.current_guess = String::new();
+/// Seconds the local timezone is ahead of UTC at `timestamp`.
+fn local_utc_offset(timestamp: i64) -> i64 {
+    let time = timestamp as libc::time_t;
+    // SAFETY: localtime_r only writes to the tm it is given
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return 0;
     }
+    tm.tm_gmtoff
+}
 
-    pub fn add_char(&mut self, c: char) {
-        if self.current_guess.chars().count() < self.word.chars().count() {
-            self.current_guess.push(c.to_lowercase().next().unwrap());
+/// Day of the daily puzzle at `timestamp`. Rordle's own puzzles change at
+/// midnight UTC, the official Wordle ones at local midnight.
+fn daily_date(epoch: DailyEpoch, timestamp: i64) -> Date {
+    match epoch {
+        DailyEpoch::Rordle => Date::from_timestamp(timestamp),
+        DailyEpoch::Wordle => {
+            Date::from_timestamp_with_offset(timestamp, local_utc_offset(timestamp))
         }
     }
 }
 
-fn render_game_state(game_state: &GameState) {
-    let mut stdout = stdout().into_raw_mode().unwrap();
-    writeln!(stdout, "{}{}", termion::clear::All, termion::cursor::Hide).unwrap();
-    let width = game_state.word.chars().count() as u16;
-    let height = game_state.max_tries as u16;
-    let m_top = 4;
-    let m_left = 10;
-    for y in 0..height {
-        write!(
-            stdout,
-            "{}{}",
-            termion::cursor::Goto(m_left, m_top + y * 2 - 1),
-            (0..(width * 2 + 1)).map(|_| "-").collect::<String>()
-        )
-        .unwrap();
+/// `date` if a daily puzzle of `epoch` exists for it: it is not after
+/// `today` and not before the first puzzle.
+fn check_daily_date(epoch: DailyEpoch, date: Date, today: Date) -> Result<Date, RordleError> {
+    if date.days() > today.days() {
+        return Err(RordleError::FutureDaily { date, today });
+    }
+    let first = epoch.first_day();
+    if date.days() < first.days() {
+        return Err(RordleError::BeforeFirstDaily { date, first });
+    }
+    Ok(date)
+}
 
-        // get guess of line or a string of underscores
-        let line_guess: String;
-        if y < game_state.guesses.len() as u16 {
-            line_guess = game_state.guesses[y as usize].clone();
-        } else if y == game_state.guesses.len() as u16 {
-            let mut curr_guess = game_state.current_guess.clone();
-            while curr_guess.chars().count() < width as usize {
-                curr_guess.push('_');
-            }
-            line_guess = curr_guess;
-        } else {
-            line_guess = (0..width).map(|_| "_").collect::<String>();
+/// Where the solution of a game comes from.
+enum Pick {
+    /// A solution picked from the word list, recorded as `mode` in the
+    /// history.
+    List {
+        picker: Box<dyn WordPicker>,
+        mode: &'static str,
+        /// Only solutions matching it are picked, all words can be guessed.
+        pattern: Option<Pattern>,
+        /// Only solutions with these tags are picked, given by `--tags`.
+        tags: Option<TagFilter>,
+        /// The letters preferred by `--practice-weak`, shown next to the
+        /// name of the list.
+        weak: Vec<char>,
+    },
+    /// The word of the day of a puzzle, recorded as `daily`, or as
+    /// `BACKFILL` for a past puzzle not counting for the streak. A `replay`
+    /// of a puzzle recorded before is not recorded again.
+    Daily {
+        puzzle: Puzzle,
+        backfill: bool,
+        replay: bool,
+    },
+    /// A solution given by the player, which need not be in the word list.
+    Word(String),
+    /// A solution shared as a challenge code, unknown to the player.
+    Challenge(String),
+}
+
+impl Pick {
+    /// Name of the mode in the game history, `None` if the game is not
+    /// recorded because the solution was known or the daily puzzle was
+    /// played before.
+    fn mode(&self) -> Option<&'static str> {
+        match self {
+            Pick::List { mode, .. } => Some(mode),
+            Pick::Daily { replay: true, .. } => None,
+            Pick::Daily { backfill: true, .. } => Some(BACKFILL),
+            Pick::Daily { .. } => Some("daily"),
+            Pick::Word(_) => None,
+            Pick::Challenge(_) => Some("challenge"),
         }
+    }
+}
 
-        // get hits of line
-        let line_hits: Vec<HitInfo>;
-        if (y as usize) < game_state.guesses.len() {
-            line_hits = game_state.get_guess_hits(y as usize);
-        } else {
-            line_hits = vec![HitInfo::None; width as usize];
+/// Starts a game with a solution picked from the word list by `picker`.
+fn init_game(
+    options: GameOptions,
+    word_list: MergedWordList,
+    picker: &mut dyn WordPicker,
+) -> Result<GameState, RordleError> {
+    if word_list.solutions.is_empty() {
+        return Err(RordleError::EmptyWordList);
+    }
+    let word = picker.pick(&word_list.solutions);
+    new_game(options, word_list, word)
+}
+
+/// Starts a game with `word` as the solution, which need not be in the
+/// word list.
+fn new_game(
+    options: GameOptions,
+    word_list: MergedWordList,
+    word: String,
+) -> Result<GameState, RordleError> {
+    let mut word_list = word_list;
+    // digraphs are played as single letters from here on
+    word_list.tokenize(&Digraphs::default());
+    let digraphs = word_list.header.digraphs.clone().unwrap_or_default();
+    let word = digraphs.tokenize(&word.to_lowercase());
+    // only words of the solution's length can be valid guesses
+    let length = word.chars().count();
+    let valid_words = word_list
+        .words
+        .iter()
+        .filter(|w| w.chars().count() == length)
+        .chain([&word])
+        .collect();
+    let rarity = word_list.rarity(&word);
+    let solutions = options.answers_only.then(|| {
+        word_list
+            .solutions
+            .iter()
+            .filter(|w| w.chars().count() == length)
+            .chain([&word])
+            .collect()
+    });
+    let mut game_state = GameState::new(word, valid_words, options)?;
+    game_state.solutions = solutions;
+    game_state.rarity = rarity;
+    if let Some(alphabet) = word_list.header.alphabet {
+        game_state.alphabet = alphabet;
+    }
+    // the keyboard gets keys for the digraphs
+    for token in digraphs.tokens() {
+        if !game_state.alphabet.contains(token) {
+            game_state.alphabet.letters.push(token);
         }
+    }
+    game_state.digraphs = digraphs;
+    game_state.list_name = word_list.header.name;
+    Ok(game_state)
+}
 
-        for x in 0..width {
-            // print each letter into a cell
-            write!(
-                stdout,
-                "{}|",
-                termion::cursor::Goto(m_left + x * 2, m_top + y * 2),
-            )
-            .unwrap();
+/// Reports why ranked openers could not be added to the cache file at
+/// `path`, which only costs ranking them again next time.
+fn report_unsaved_openers(path: Option<&Path>, unsaved: Option<&std::io::Error>) {
+    if let (Some(path), Some(error)) = (path, unsaved) {
+        eprintln!(
+            "warning: opener cache not saved: {}: {}",
+            path.display(),
+            error
+        );
+    }
+}
 
-            // set color according to hit info
-            let hit_info = line_hits.get(x as usize).unwrap();
-            if hit_info == &HitInfo::Hit {
-                write!(
-                    stdout,
-                    "{}{}",
-                    color::Bg(color::Green),
-                    color::Fg(color::Black),
-                )
-                .unwrap();
-            }
-            if hit_info == &HitInfo::Contains {
-                write!(
-                    stdout,
-                    "{}{}",
-                    color::Bg(color::Yellow),
-                    color::Fg(color::Black),
-                )
-                .unwrap();
-            }
-            if hit_info == &HitInfo::Miss {
-                write!(
-                    stdout,
-                    "{}{}",
-                    color::Bg(color::Black),
-                    color::Fg(color::White),
-                )
-                .unwrap();
-            }
-            if hit_info == &HitInfo::None {
-                write!(
-                    stdout,
-                    "{}{}",
-                    color::Bg(color::Reset),
-                    color::Fg(color::Reset)
-                )
-                .unwrap();
-            }
-
-            write!(
-                stdout,
-                "{}{}{}",
-                line_guess.chars().nth(x as usize).unwrap(),
-                color::Bg(color::Reset),
-                color::Fg(color::Reset)
-            )
-            .unwrap();
+/// Shows `label` next to the name of the list, after the labels before it.
+fn append_label(game_state: &mut GameState, label: &str) {
+    game_state.list_name = Some(match game_state.list_name.take() {
+        Some(name) => format!("{}  {}", name, label),
+        None => label.to_string(),
+    });
+}
+
+/// Loads the word list selected by `args`, printing warnings found in it.
+/// If no list is selected, the word files of `RORDLE_WORD_FILE` or else the
+/// default list of the config are used.
+fn load_word_list(
+    args: &ListArgs,
+    config: &Config,
+    allow_stream: bool,
+) -> Result<MergedWordList, RordleError> {
+    let mut args = args.clone();
+    if args.list.is_none() && args.word_files.is_empty() && args.words.is_none() {
+        args.word_files = config.word_files.iter().map(String::as_str).collect();
+        if args.word_files.is_empty() {
+            args.list = config.default_list.as_deref();
         }
-        // close cell
-        writeln!(stdout, "|").unwrap();
-    }
-    // print error below game board
-    match game_state.last_error {
-        Some(error) => {
-            writeln!(
-                stdout,
-                "{}{}",
-                termion::cursor::Goto(m_left, m_top + height * 2 + 1),
-                format!("{}", error)
-            )
-            .unwrap();
+    }
+    // streaming only keeps the words of the solution's length, so all words
+    // can only be used if they are read into memory. Only local files can
+    // be streamed.
+    let stream = allow_stream
+        && args.length.is_none()
+        && args.list.is_none()
+        && !args.system_dict
+        && !args.no_plurals
+        && !args.clean
+        && args.digraphs.is_none()
+        && !args.word_files.is_empty()
+        && !args.word_files.iter().any(|file| file.contains("://"))
+        && (args.stream || exceeds_stream_threshold(&args.word_files));
+    let mut word_list = if stream {
+        stream_word_files(
+            &args.word_files,
+            args.solutions_from_all,
+            &mut rand::thread_rng(),
+        )?
+    } else {
+        load_sources(&args.sources(config)?, args.solutions_from_all)?
+    };
+    for warning in &word_list.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    if let Some(digraphs) = args.digraphs {
+        let digraphs: Digraphs = digraphs.parse().unwrap();
+        word_list
+            .header
+            .digraphs
+            .get_or_insert_with(Digraphs::default)
+            .extend(&digraphs);
+    }
+    // before filtering by length, which could drop the singular
+    if args.no_plurals {
+        word_list.remove_plural_solutions();
+    }
+    if let Some(length) = args.length {
+        word_list.retain_length(length);
+    }
+    Ok(word_list)
+}
+
+/// Plays a game read line by line from `input`, writing the hints of each
+/// guess to `output`, until the game is over or the input ends. `prompt` is
+/// written before each line is read.
+fn text_loop<R: BufRead, W: Write>(
+    game_state: &mut GameState,
+    input: R,
+    output: &mut W,
+    prompt: &str,
+) -> std::io::Result<()> {
+    let mut lines = input.lines();
+    // the prefilled letters, e.g. `h_l__`, as there is no board showing them
+    let length = game_state.letters().len();
+    if (0..length).any(|i| game_state.prefilled(i)) {
+        let pattern: String = (0..length)
+            .map(|i| {
+                if game_state.prefilled(i) {
+                    game_state.letters()[i]
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        writeln!(output, "{}", pattern)?;
+    }
+    while !game_state.over() {
+        write!(output, "{}", prompt)?;
+        output.flush()?;
+        let guess = match lines.next() {
+            Some(line) => line?.trim().to_lowercase(),
+            None => break,
+        };
+        if guess.is_empty() {
+            continue;
+        }
+        match game_state.guess(guess.clone()) {
+            Ok(_) => {
+                let last = game_state.guesses.len() - 1;
+                let row = plain_row(&guess, &game_state.get_guess_hits(last));
+                match game_state.player(last) {
+                    Some(player) => writeln!(output, "{}: {}", player, row)?,
+                    None => writeln!(output, "{}", row)?,
+                }
+            }
+            Err(error) => writeln!(output, "{}", tr("guess-error", &[("message", &error)]))?,
         }
-        None => (),
     }
+    // scripts compare the solution to their lowercase guesses
+    writeln!(output, "{}", end_message(game_state, false))
 }
 
-fn game_loop(mut game_state: GameState) {
-    let mut stdin = stdin().keys();
-    let mut stdout = stdout().into_raw_mode().unwrap();
-    'game_loop: while game_state.guesses.len() < 6 {
-        render_game_state(&game_state);
-        'input_loop: loop {
-            let b = stdin.next().unwrap().unwrap();
-            match b {
-                Key::Esc => break 'game_loop,
-                Key::Backspace => game_state.back(),
-                Key::Char('\n') => {
-                    game_state.confirm();
-                    break 'input_loop;
+/// Exit code of a played game, documented in the help of the command
+/// line interface.
+fn outcome_exit_code(outcome: Outcome) -> i32 {
+    match outcome {
+        Outcome::Won => 0,
+        Outcome::Lost => 1,
+        Outcome::Abandoned => 4,
+    }
+}
+
+/// Plays a game and records it in the stats file once it is over. Returns
+/// the exit code of the game.
+fn play(matches: &ArgMatches, config: &Config, pick: Pick) -> Result<i32, RordleError> {
+    i18n::set_language(cli::language(matches, config));
+    // matching the solutions needs all of them, a stream picks one at once
+    let stream = !matches!(
+        pick,
+        Pick::List {
+            pattern: Some(_),
+            ..
+        } | Pick::List { tags: Some(_), .. }
+    );
+    let mut word_list = load_word_list(&ListArgs::from_matches(matches), config, stream)?;
+    let mode = pick.mode();
+    let options = cli::game_options(matches, config).hint_budget(cli::hint_budget(matches));
+    let mut puzzle = None;
+    let mut game_state = match pick {
+        Pick::List {
+            mut picker,
+            pattern,
+            tags,
+            weak,
+            ..
+        } => {
+            if let Some(pattern) = &pattern {
+                word_list.solutions.retain(|word| pattern.is_match(word));
+                if word_list.solutions.is_empty() {
+                    return Err(RordleError::NoMatchingSolutions(pattern.to_string()));
+                }
+            }
+            if let Some(tags) = &tags {
+                word_list.retain_tagged(tags);
+                if word_list.solutions.is_empty() {
+                    return Err(RordleError::NoTaggedSolutions(tags.to_string()));
                 }
-                Key::Char(c) => game_state.add_char(c),
-                _ => (),
             }
-            stdout.flush().unwrap();
-            render_game_state(&game_state);
+            // the seed picking the solution also picks the prefilled letters
+            let seed = matches.value_of("seed").map(|seed| seed.parse().unwrap());
+            let mut game_state = init_game(options.seed(seed), word_list, picker.as_mut())?;
+            // the pattern is shown next to the name of the list, so it is
+            // clear why all solutions look alike
+            if let Some(pattern) = pattern {
+                let label = tr("pattern-label", &[("pattern", &pattern)]);
+                append_label(&mut game_state, &label);
+            }
+            if !weak.is_empty() {
+                let letters: Vec<String> =
+                    weak.iter().map(|c| c.to_uppercase().collect()).collect();
+                let label = tr("weak-label", &[("letters", &letters.join("/"))]);
+                append_label(&mut game_state, &label);
+            }
+            game_state
+        }
+        Pick::Daily {
+            puzzle: daily,
+            replay,
+            ..
+        } => {
+            puzzle = Some(daily);
+            let date = daily.date();
+            let mut picker = DailyPicker { date };
+            // everyone gets the same letters prefilled on the same day
+            let options = options.seed(Some(date.days() as u64));
+            let mut game_state = init_game(options, word_list, &mut picker)?;
+            // the number is shown next to the name of the list, so it can be
+            // compared to the results of others
+            append_label(&mut game_state, &daily.to_string());
+            if replay {
+                append_label(&mut game_state, &tr("replay-label", &[]));
+            }
+            game_state
         }
+        Pick::Word(word) | Pick::Challenge(word) => new_game(options, word_list, word)?,
+    };
+    game_state.players = cli::players(matches);
+    game_state.team = matches.is_present("team");
+    if matches.is_present("hardcore") {
+        let run = Recorder::new(matches, config).hardcore_run()?;
+        let label = tr("hardcore-label", &[("run", &run)]);
+        append_label(&mut game_state, &label);
+    }
+    let start = Instant::now();
+    let game_state = play_game(game_state, matches, config, puzzle.is_some())?;
+    finish_game(&game_state, matches, config, mode, puzzle, start.elapsed());
+    Ok(outcome_exit_code(game_state.outcome()))
+}
 
-        match game_state.last_error {
-            None => {
-                if game_state.won() {
-                    println!("You won!");
-                    break;
+/// Plays a game over JSON, plain text or the terminal, as selected by
+/// `matches`. `daily` games are recorded by `--record` without their
+/// solution until they are finished.
+fn play_game(
+    mut game_state: GameState,
+    matches: &ArgMatches,
+    config: &Config,
+    daily: bool,
+) -> Result<GameState, RordleError> {
+    if Recorder::new(matches, config).practice() {
+        let label = tr("practice-label", &[]);
+        append_label(&mut game_state, &label);
+    }
+    if matches.is_present("json") {
+        serve(&mut game_state, stdin().lock(), stdout().lock()).map_err(RordleError::Protocol)?;
+    } else if matches.is_present("stdin") {
+        text_loop(&mut game_state, stdin().lock(), &mut stdout().lock(), "")
+            .map_err(RordleError::Protocol)?;
+    } else {
+        // the terminal is restored when game_loop returns
+        let interface = Interface {
+            hardcore: matches.is_present("hardcore"),
+            ..cli::interface(matches, config)
+        };
+        let recording = matches.value_of("record").map(|path| Recording {
+            path: Path::new(path),
+            daily,
+        });
+        game_state = game_loop(
+            game_state,
+            &interface,
+            matches.is_present("auto"),
+            recording,
+        )?;
+    }
+    Ok(game_state)
+}
+
+/// Runs the post-game hook, records a played game in the stats file as
+/// `mode`, unless `mode` is `None`, and prints the summary line of games
+/// played on the terminal, which took `time`.
+fn finish_game(
+    game_state: &GameState,
+    matches: &ArgMatches,
+    config: &Config,
+    mode: Option<&str>,
+    puzzle: Option<Puzzle>,
+    time: Duration,
+) {
+    let terminal = !matches.is_present("json") && !matches.is_present("stdin");
+    let recorder = Recorder::new(matches, config);
+    let hardcore_run = if matches.is_present("hardcore") {
+        match update_hardcore_run(game_state, &recorder) {
+            Ok((before, after)) => {
+                if terminal && before > 0 && after == 0 {
+                    println!("{}", tr("hardcore-broken", &[("run", &before)]));
+                } else if terminal {
+                    println!("{}", tr("hardcore-label", &[("run", &after)]));
                 }
+                Some(after)
             }
-            _ => (),
+            Err(error) => {
+                eprintln!("warning: hardcore run not saved: {}", error);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if terminal && !matches.is_present("quiet") {
+        let interface = cli::interface(matches, config);
+        // the board is gone with the alternate screen, a copy is kept
+        if interface.altscreen {
+            for line in render::board_lines(game_state, &interface.render) {
+                println!("{}", line);
+            }
+        }
+        let format = matches.value_of("summary-format").unwrap().parse().unwrap();
+        println!(
+            "{}",
+            summary_line(game_state, mode, puzzle, hardcore_run, time, format)
+        );
+    }
+    if let Some(file) = matches.value_of("export-html") {
+        let html = html::board_html(
+            game_state,
+            &cli::interface(matches, config).render,
+            &html::title(game_state, puzzle),
+            !matches.is_present("no-letters"),
+        );
+        // like a game which can not be recorded, the game was still played
+        if let Err(error) = std::fs::write(file, html) {
+            eprintln!("warning: board not exported: {}: {}", file, error);
+        }
+    }
+    if let Some(notifier) = cli::notifier(matches) {
+        notify::notify(notifier, "rordle", &notification(game_state, puzzle));
+    }
+    let hook = matches
+        .value_of("post-game-hook")
+        .or(config.post_game_hook.as_deref());
+    if let Some(command) = hook {
+        hook::post_game(command, game_state, mode);
+    }
+    // games played by the solver or by several players are neither recorded
+    // nor sent to a leaderboard
+    let solo = !matches.is_present("auto") && game_state.players.is_empty();
+    // only `rordle daily` has `--leaderboard`, replays of a puzzle and
+    // practice runs, which are not recorded, are not sent either
+    #[cfg(feature = "network")]
+    if let Some((url, puzzle)) = puzzle
+        .filter(|_| mode.is_some() && !recorder.practice())
+        .and_then(|puzzle| Some((matches.value_of("leaderboard")?, puzzle)))
+    {
+        // count hints and answers only make the puzzle play differently
+        // than it does for the others
+        let rules = game_state.count_hints() || game_state.answers_only();
+        if game_state.over() && solo && !rules {
+            let submission = rordle_core::leaderboard::Submission {
+                name: leaderboards::player_name(config),
+                day: puzzle.number,
+                guesses: game_state.won().then_some(game_state.guesses.len()),
+                hard: game_state.hard(),
+            };
+            leaderboards::after_daily_game(url, &submission, terminal, config);
         }
     }
+    let mode = match mode {
+        Some(mode) if game_state.over() && solo => mode,
+        _ => return,
+    };
+    let record = GameRecord {
+        timestamp: unix_time(),
+        mode: mode.to_string(),
+        word: digraph::untokenize(&game_state.word),
+        guesses: game_state
+            .guesses
+            .iter()
+            .map(|guess| digraph::untokenize(guess))
+            .collect(),
+        won: game_state.won(),
+        puzzle,
+        times: game_state.guess_times.clone(),
+        count_hints: game_state.count_hints(),
+        answers_only: game_state.answers_only(),
+    };
+    // a game which can not be recorded was still played
+    if let Err(error) = recorder.record(&record) {
+        eprintln!("warning: game not recorded: {}", error);
+    }
+}
 
-    render_game_state(&game_state);
-    writeln!(stdout, "{}", termion::cursor::Show).unwrap();
-    if !game_state.won() {
-        println!("You lost! The word was: {}", game_state.word);
+/// The text of the `--notify` notification after a game: the result, as in
+/// the title of an exported board, and the solution unless the game is a
+/// daily puzzle, which others may still play.
+fn notification(game_state: &GameState, puzzle: Option<Puzzle>) -> String {
+    let result = html::title(game_state, puzzle);
+    if puzzle.is_some() {
+        result
+    } else {
+        format!("{}: {}", result, render::tile_word(&game_state.word, true))
     }
 }
 
-fn init_game(any_word: bool, word_file: Option<&str>) -> GameState {
-    // load valid word list from file
-    let mut words = Vec::new();
-    let word;
+/// Updates the run after a `--hardcore` game: a won game adds to it, a lost
+/// one or one left before it was over ends it. Gives the run before and
+/// after the game.
+fn update_hardcore_run(
+    game_state: &GameState,
+    recorder: &Recorder,
+) -> Result<(u32, u32), RordleError> {
+    let before = recorder.hardcore_run()?;
+    let after = if game_state.won() { before + 1 } else { 0 };
+    recorder.save_hardcore_run(after)?;
+    Ok((before, after))
+}
+
+/// `rordle play`: plays a pack if one is given, else a single game.
+fn play_command(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    match matches.value_of("pack") {
+        Some(file) => packs::play_pack(matches, config, file),
+        None => play(matches, config, pick(matches, config)?),
+    }
+}
 
-    match word_file {
-        Some(file) => {
-            let mut file = File::open(file).unwrap();
-            let mut contents = String::new();
-            file.read_to_string(&mut contents).unwrap();
-            words = contents
-                .split('\n')
-                .map(|s| s.trim().to_string().to_lowercase())
+/// `rordle daily`: plays the puzzle of today or of `--date`.
+fn daily_command(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    let epoch: DailyEpoch = matches.value_of("daily-epoch").unwrap().parse().unwrap();
+    let today = daily_date(epoch, unix_time());
+    let date = match matches.value_of("date") {
+        Some(date) => check_daily_date(epoch, date.parse().unwrap(), today)?,
+        None => today,
+    };
+    let backfill = date != today && !matches.is_present("count-streak");
+    let replay = Recorder::new(matches, config).played_daily(date)?;
+    let puzzle = Puzzle::of(epoch, date);
+    if replay {
+        eprintln!(
+            "warning: {} was played before, this replay is not recorded",
+            puzzle
+        );
+    }
+    play(
+        matches,
+        config,
+        Pick::Daily {
+            puzzle,
+            backfill,
+            replay,
+        },
+    )
+}
+
+/// Number of the weakest letters preferred by `--practice-weak`.
+const WEAK_LETTERS: usize = 3;
+
+/// Selects where the solution of `rordle play` comes from.
+fn pick(matches: &ArgMatches, config: &Config) -> Result<Pick, RordleError> {
+    if let Some(word) = matches.value_of("word") {
+        return Ok(Pick::Word(word.to_string()));
+    }
+    if let Some(code) = matches.value_of("challenge") {
+        return Ok(Pick::Challenge(code.parse::<Challenge>().unwrap().word));
+    }
+    let mut weak = Vec::new();
+    let picker: Box<dyn WordPicker> = if let Some(number) = matches.value_of("number") {
+        let number: usize = number.parse().unwrap();
+        Box::new(SequentialPicker { next: number - 1 })
+    } else {
+        let random = match matches.value_of("seed") {
+            Some(seed) => RandomPicker::seeded(seed.parse().unwrap()),
+            None => RandomPicker::new(),
+        };
+        let records = if matches.is_present("avoid-recent") || matches.is_present("practice-weak") {
+            read_records(&commands::stats_path(config)?)?
+        } else {
+            Vec::new()
+        };
+        let random: Box<dyn WordPicker> = if matches.is_present("practice-weak") {
+            weak = letter_weakness(&records)
+                .iter()
+                .take(WEAK_LETTERS)
+                .map(|stats| stats.letter)
                 .collect();
+            if weak.is_empty() {
+                eprintln!("warning: no weak letters in the history yet, picking any solution");
+            }
+            Box::new(LetterBiasPicker::new(random, weak.clone()))
+        } else {
+            Box::new(random)
+        };
+        match matches.value_of("avoid-recent") {
+            Some(count) => {
+                let count: usize = count.parse().unwrap();
+                let recent = records.iter().rev().take(count);
+                Box::new(AvoidRecent::new(
+                    random,
+                    recent.map(|record| record.word.clone()).collect(),
+                ))
+            }
+            None => random,
+        }
+    };
+    Ok(Pick::List {
+        picker,
+        mode: "play",
+        pattern: matches
+            .value_of("pattern")
+            .map(|pattern| pattern.parse().unwrap()),
+        tags: cli::tag_filter(matches),
+        weak,
+    })
+}
 
-            let mut rng = rand::thread_rng();
-            let i = rng.gen::<usize>() % words.len();
-            word = words[i].clone();
+/// Runs the command given on the command line, returning the exit code.
+fn run() -> Result<i32, RordleError> {
+    let matches = cli::app().get_matches();
+    match matches.subcommand() {
+        Some(("config", config_matches)) => return commands::config(config_matches).map(|_| 0),
+        Some(("completions", completions_matches)) => {
+            let shell = completions_matches.value_of("shell").unwrap();
+            print!("{}", completions::script(&cli::app(), shell));
+            return Ok(0);
         }
-        None => {
-            // special list of words acceptable as solutions
-            let picked_word_str = include_str!("../data/picked_words.txt");
-            for line in picked_word_str.lines() {
-                words.push(line.to_string().to_lowercase());
+        Some(("__complete", _)) => {
+            // completion has to be quiet and fast, a broken config only
+            // hides its lists
+            let config = Config::load().map(|(config, _)| config).unwrap_or_default();
+            for name in completions::list_names(&config) {
+                println!("{}", name);
             }
-
-            let mut rng = rand::thread_rng();
-            let i = rng.gen::<usize>() % words.len();
-            word = words[i].clone();
-
-            // all other words
-            let valid_word_str = include_str!("../data/valid_words.txt");
-            for line in valid_word_str.lines() {
-                words.push(line.to_string().to_lowercase());
+            return Ok(0);
+        }
+        _ => (),
+    }
+    // a broken config file should not keep anyone from playing, a broken
+    // list only drops the list
+    let mut config = match Config::load() {
+        Ok((config, dropped)) => {
+            for error in dropped {
+                eprintln!("warning: {}, list ignored", error);
             }
+            config
+        }
+        Err(error) => {
+            eprintln!("warning: {}, using the default config", error);
+            Config::default()
         }
+    };
+    config.apply_env()?;
+    if let Some(profile) = cli::profile(&matches)? {
+        config.profile = Some(profile);
     }
 
-    let game_state = GameState::new(word, words, any_word);
-    game_state
+    match matches.subcommand() {
+        // a bare `rordle` on a terminal opens the menu, scripts and flags
+        // go straight to a game
+        None if std::env::args_os().len() == 1
+            && termion::is_tty(&stdin())
+            && termion::is_tty(&stdout()) =>
+        {
+            menu::run_menu(&mut config)
+        }
+        None => play_command(&matches, &config),
+        Some(("play", play_matches)) => play_command(play_matches, &config),
+        Some(("daily", daily_matches)) => daily_command(daily_matches, &config),
+        Some(("tutorial", tutorial_matches)) => tutorial::play_tutorial(tutorial_matches, &config),
+        Some(("demo", demo_matches)) => demo::demo(demo_matches, &config),
+        Some(("stats", stats_matches)) => commands::stats(stats_matches, &config).map(|_| 0),
+        Some(("profiles", _)) => commands::profiles(&config).map(|_| 0),
+        Some(("history", history_matches)) => {
+            commands::history(history_matches, &config).map(|_| 0)
+        }
+        Some(("status", status_matches)) => commands::status(status_matches, &config),
+        Some(("solve", solve_matches)) => {
+            let list_args = ListArgs::from_matches(solve_matches);
+            let word_list = load_word_list(&list_args, &config, false)?;
+            commands::solve(solve_matches, &word_list, &config).map(|_| 0)
+        }
+        Some(("bench", bench_matches)) => {
+            let list_args = ListArgs::from_matches(bench_matches);
+            let word_list = load_word_list(&list_args, &config, false)?;
+            commands::bench(bench_matches, &word_list, &config).map(|_| 0)
+        }
+        Some(("serve", serve_matches)) => server::serve(serve_matches, &config),
+        #[cfg(feature = "network")]
+        Some(("serve-leaderboard", serve_matches)) => leaderboards::serve(serve_matches, &config),
+        Some(("duel", duel_matches)) => duel::duel(duel_matches, &config),
+        Some(("battle", battle_matches)) => battle::battle(battle_matches, &config),
+        Some(("pick", pick_matches)) => {
+            let list_args = ListArgs::from_matches(pick_matches);
+            let word_list = load_word_list(&list_args, &config, false)?;
+            commands::pick(pick_matches, &word_list).map(|_| 0)
+        }
+        Some(("encode-pack", pack_matches)) => {
+            let file = pack_matches.value_of("file").unwrap();
+            let pack = Pack::read(Path::new(file))
+                .map_err(|error| RordleError::Pack(file.into(), error))?;
+            print!("{}", pack.to_file(true));
+            Ok(0)
+        }
+        Some(("list-words", list_matches)) => {
+            let list_args = ListArgs::from_matches(list_matches);
+            let word_list = load_word_list(&list_args, &config, false)?;
+            for word in &word_list.words {
+                println!("{}", word);
+            }
+            Ok(0)
+        }
+        Some(("validate-wordlist", validate_matches)) => {
+            commands::validate_wordlist(validate_matches)
+        }
+        Some((name, _)) => unreachable!("unknown subcommand {}", name),
+    }
 }
 
 fn main() {
-    let matches = App::new("Rordle")
-        .version("0.2.0")
-        .author("Niko Abeler <niko@rerere.org>")
-        .about("A Wordle clone for the terminal")
-        .arg(
-            Arg::new("any-word")
-                .short('a')
-                .long("any-word")
-                .takes_value(false)
-                .help("Allow any word to be guessed"),
-        )
-        .arg(
-            Arg::new("word-file")
-                .short('w')
-                .long("word-file")
-                .takes_value(true)
-                .help("Use a word list from a file"),
-        )
-        .get_matches();
-
-    let game_state = init_game(
-        matches.is_present("any-word"),
-        matches.value_of("word-file"),
-    );
-    game_loop(game_state)
+    match run() {
+        Ok(exit_code) => std::process::exit(exit_code),
+        Err(error) => {
+            eprintln!("rordle: {}", error);
+            std::process::exit(error.exit_code());
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::SeedableRng;
+    use rordle_core::game::GameError;
 
-    #[test]
-    fn test_new_guess() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        let result = game_state.guess("hello".to_string());
-        assert_eq!(result.unwrap(), true);
-        assert_eq!(game_state.guesses.len(), 1);
-        assert_eq!(game_state.guesses[0], "hello".to_string());
+    fn temp_word_file(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("rordle-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
     }
 
     #[test]
-    fn test_new_guess_umlaut() {
+    fn test_streamed_game_plays_like_in_memory_game() {
+        let file = temp_word_file("stream-game.txt", "hello\njolly\nab\n");
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let list = loop {
+            let list = stream_word_files(&[&file], false, &mut rng).unwrap();
+            if list.solutions[0] == "hello" {
+                break list;
+            }
+        };
         let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hällö".to_string()], false);
-        let result = game_state.guess("hällö".to_string());
-        assert_eq!(result.unwrap(), false);
-        assert_eq!(game_state.guesses.len(), 1);
-        assert_eq!(game_state.guesses[0], "hällö".to_string());
+            init_game(GameOptions::default(), list, &mut RandomPicker::new()).unwrap();
+        assert_eq!(game_state.word, "hello");
+        assert_eq!(
+            game_state.guess("ab".to_string()),
+            Err(GameError::WrongLength {
+                expected: 5,
+                actual: 2
+            })
+        );
+        assert_eq!(game_state.guess("jolly".to_string()), Ok(false));
+        assert_eq!(game_state.guess("hello".to_string()), Ok(true));
     }
 
     #[test]
-    fn test_new_guess_miss() {
-        let mut game_state = super::GameState::new(
-            "hello".to_string(),
-            vec!["hello".to_string(), "world".to_string()],
-            false,
+    fn test_init_game_keeps_words_of_solution_length() {
+        let game_state = init_game(
+            GameOptions::default(),
+            MergedWordList {
+                solutions: vec!["hello".to_string()],
+                words: ["hello", "ab", "jolly", "abc"]
+                    .iter()
+                    .map(|w| w.to_string())
+                    .collect(),
+                ..MergedWordList::default()
+            },
+            &mut RandomPicker::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            game_state.valid_words.iter().collect::<Vec<_>>(),
+            vec!["hello", "jolly"]
         );
-        let result = game_state.guess("world".to_string());
-        assert_eq!(result.unwrap(), false);
-        assert_eq!(game_state.guesses.len(), 1);
-        assert_eq!(game_state.guesses[0], "world".to_string());
     }
 
     #[test]
-    fn test_guess_rejects_word_of_wrong_length() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        let result = game_state.guess("hell".to_string());
-        match result {
-            Err(GameError::WrongLength) => assert!(true),
-            _ => assert!(false, "No error raised for wrong length"),
-        }
-        assert_eq!(game_state.guesses.len(), 0);
+    fn test_init_game_rejects_empty_word_list() {
+        let result = init_game(
+            GameOptions::default(),
+            MergedWordList {
+                solutions: Vec::new(),
+                words: Vec::new(),
+                ..MergedWordList::default()
+            },
+            &mut RandomPicker::new(),
+        );
+        assert!(matches!(result, Err(RordleError::EmptyWordList)));
     }
 
     #[test]
-    fn test_guess_rejects_invalid_words() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        let result = game_state.guess("jello".to_string());
-        match result {
-            Err(GameError::InvalidWord) => assert!(true),
-            _ => assert!(false, "No error raised for invalid word"),
+    fn test_recall_key() {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            ["hello", "jolly", "world"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(recall_key(&game_state, None, Key::Up), None);
+        game_state.guess("jolly".to_string()).unwrap();
+        game_state.guess("world".to_string()).unwrap();
+        let recalled = |recall, key| recall_key(&game_state, recall, key);
+        let row = |recall, row: &str| Some((recall, row.to_string()));
+        assert_eq!(recalled(None, Key::Up), row(Some(0), "world"));
+        assert_eq!(recalled(Some(0), Key::Up), row(Some(1), "jolly"));
+        // there is nothing before the first guess
+        assert_eq!(recalled(Some(1), Key::Up), None);
+        assert_eq!(recalled(Some(1), Key::Down), row(Some(0), "world"));
+        assert_eq!(recalled(Some(0), Key::Down), row(None, ""));
+        assert_eq!(recalled(None, Key::Down), None);
+        assert_eq!(recalled(None, Key::Left), None);
+
+        // the locked cells of prefill mode keep their letters
+        let game_state = GameState::new(
+            "hello".to_string(),
+            ["hello", "world"].iter().collect(),
+            GameOptions::default().prefill(2).seed(Some(1)),
+        )
+        .unwrap();
+        let row: Vec<char> = locked_row(&game_state, &"world".chars().collect::<Vec<_>>())
+            .chars()
+            .collect();
+        for (i, (c, (solution, guess))) in row
+            .iter()
+            .zip("hello".chars().zip("world".chars()))
+            .enumerate()
+        {
+            assert_eq!(
+                *c,
+                if game_state.prefilled(i) {
+                    solution
+                } else {
+                    guess
+                }
+            );
         }
-        assert_eq!(game_state.guesses.len(), 0);
+        assert_eq!(locked_row(&game_state, &[]), game_state.current_guess);
     }
 
     #[test]
-    fn test_get_guess_hits() {
-        let mut game_state = super::GameState::new(
+    fn test_recall_in_key_loop() {
+        let mut game_state = GameState::new(
             "hello".to_string(),
-            vec!["hello".to_string(), "jolly".to_string()],
-            false,
-        );
-        let result = game_state.guess("jolly".to_string());
-        assert_eq!(result.unwrap(), false);
-        let hits = game_state.get_guess_hits(0);
-        assert_eq!(hits.len(), 5);
-        assert_eq!(hits[0], HitInfo::Miss);
-        assert_eq!(hits[1], HitInfo::Contains);
-        assert_eq!(hits[2], HitInfo::Hit);
-        assert_eq!(hits[3], HitInfo::Hit);
-        assert_eq!(hits[4], HitInfo::Miss);
+            ["hello", "jolly", "holly"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        // holly is recalled, then jolly, which is edited, so Up starts over
+        // with holly and Down goes back to an empty row
+        let keys = "jolly\nholly\n"
+            .chars()
+            .map(Key::Char)
+            .chain([Key::Up, Key::Up, Key::Backspace, Key::Char('o')])
+            .chain([Key::Up, Key::Down])
+            .map(Ok);
+        let interface =
+            cli::interface(&cli::app().get_matches_from(["rordle"]), &Config::default());
+        key_loop(&mut game_state, keys, &mut Vec::new(), &interface).unwrap();
+        assert_eq!(game_state.current_guess, "");
     }
 
     #[test]
-    fn test_add_char() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('h');
-        assert_eq!(game_state.current_guess, "h".to_string());
-        game_state.add_char('e');
-        assert_eq!(game_state.current_guess, "he".to_string());
-        game_state.add_char('l');
-        assert_eq!(game_state.current_guess, "hel".to_string());
-        game_state.add_char('l');
-        assert_eq!(game_state.current_guess, "hell".to_string());
-        game_state.add_char('o');
-        assert_eq!(game_state.current_guess, "hello".to_string());
-        game_state.add_char('o');
-        assert_eq!(game_state.current_guess, "hello".to_string());
+    fn test_one_render_per_change() {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            ["hello", "jolly"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let keys = "jolxy\x7f\x7fly\nhello\n"
+            .chars()
+            .map(|c| match c {
+                '\x7f' => Key::Backspace,
+                c => Key::Char(c),
+            })
+            // keys after the game is won are not read
+            .chain([Key::Char('x'), Key::Up])
+            .map(Ok);
+        let interface =
+            cli::interface(&cli::app().get_matches_from(["rordle"]), &Config::default());
+        let mut output = Vec::new();
+        key_loop(&mut game_state, keys, &mut output, &interface).unwrap();
+        assert!(game_state.won());
+        let renders = String::from_utf8(output)
+            .unwrap()
+            .matches(&termion::clear::All.to_string())
+            .count();
+        // the board, then one render per key until the game is won
+        assert_eq!(renders, 1 + 16);
     }
 
     #[test]
-    fn test_add_char_test_umlaut() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('Ü');
-        assert_eq!(game_state.current_guess, "ü".to_string());
+    fn test_explore_after_game() {
+        let mut game_state = new_game(
+            GameOptions::default(),
+            MergedWordList {
+                words: ["hello", "cello", "world"]
+                    .iter()
+                    .map(|w| w.to_string())
+                    .collect(),
+                ..MergedWordList::default()
+            },
+            "hello".to_string(),
+        )
+        .unwrap();
+        let matches = cli::app().get_matches_from(["rordle", "--no-animations"]);
+        let interface = cli::interface(&matches, &Config::default());
+        // a won game, a won sandbox, a key back to the game, one to leave
+        let keys = "hello\neworld\nhello\n x".chars().map(|c| Ok(Key::Char(c)));
+        let mut output = Vec::new();
+        key_loop(&mut game_state, keys, &mut output, &interface).unwrap();
+        assert_eq!(game_state.guesses, ["hello"]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Press e to try other guesses"));
+        assert!(output.contains("Exploring, nothing is recorded (esc goes back)"));
+        // cello could have been it after world, not after hello
+        assert!(output.contains("2 left"));
+        assert!(output.contains("1 left"));
+        assert!(output.ends_with(&tr("explore-prompt", &[])));
     }
 
     #[test]
-    fn test_add_char_test_umlaut_length() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('Ü');
-        game_state.add_char('Ü');
-        game_state.add_char('Ü');
-        game_state.add_char('Ü');
-        game_state.add_char('Ü');
-        assert_eq!(game_state.current_guess, "üüüüü".to_string());
+    fn test_rarity_note() {
+        let mut game_state = GameState::new(
+            "ombre".to_string(),
+            ["ombre"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(rarity_note(&game_state), None);
+        let notes = [
+            (0.92, 0.05, "‘ombre’ is rarer than 92% of solutions"),
+            (0.3, 0.6, "‘ombre’ is more common than 60% of solutions"),
+            (0.02, 0.97, "‘ombre’ is a very common word"),
+        ];
+        for (commoner, rarer, note) in notes {
+            game_state.rarity = Rarity::Percentile { commoner, rarer };
+            assert_eq!(rarity_note(&game_state).as_deref(), Some(note));
+        }
+        game_state.rarity = Rarity::Unlisted;
+        assert!(rarity_note(&game_state)
+            .unwrap()
+            .contains("not in the frequencies"));
     }
 
     #[test]
-    fn test_add_char_converts_to_lowercase() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('H');
-        assert_eq!(game_state.current_guess, "h".to_string());
-        game_state.add_char('E');
-        assert_eq!(game_state.current_guess, "he".to_string());
+    fn test_tutorial_captions() {
+        let mut game_state = GameState::new(
+            "crane".to_string(),
+            ["crane"].iter().collect(),
+            GameOptions::default().any_word(true),
+        )
+        .unwrap();
+        let keys = "react\ncrane\n".chars().map(Key::Char).map(Ok);
+        let interface = Interface {
+            tutorial: true,
+            ..cli::interface(&cli::app().get_matches_from(["rordle"]), &Config::default())
+        };
+        let mut output = Vec::new();
+        key_loop(&mut game_state, keys, &mut output, &interface).unwrap();
+        assert!(game_state.won());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Type REACT and press enter"));
+        assert!(output.contains("R, letter 1, is yellow"));
+        assert!(output.contains("That is all there is to it"));
     }
 
     #[test]
-    fn test_back() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('h');
-        assert_eq!(game_state.current_guess, "h".to_string());
-        game_state.add_char('e');
-        assert_eq!(game_state.current_guess, "he".to_string());
-        game_state.add_char('l');
-        assert_eq!(game_state.current_guess, "hel".to_string());
-        game_state.back();
-        assert_eq!(game_state.current_guess, "he".to_string());
+    fn test_hint_menu() {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            ["hello", "jolly"].iter().collect(),
+            GameOptions::default().hint_budget(3),
+        )
+        .unwrap();
+        // asks for the e, eliminates letters, then opens and closes the menu
+        let keys = "\t2e\t3\tx"
+            .chars()
+            .map(Key::Char)
+            .chain([Key::Esc])
+            .chain("hello\n".chars().map(Key::Char))
+            .map(Ok);
+        let interface =
+            cli::interface(&cli::app().get_matches_from(["rordle"]), &Config::default());
+        let mut output = Vec::new();
+        key_loop(&mut game_state, keys, &mut output, &interface).unwrap();
+        assert!(game_state.won(), "Esc only closes the menu");
+        assert_eq!(game_state.hints().len(), 2);
+        assert_eq!(game_state.hint_points(), 1);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("The word contains 'e'"));
+        assert!(output.contains("Hint points: 1, tab opens the hints"));
+        assert_eq!(
+            end_message(&game_state, true),
+            "You won!\nHints used: 2, for 2 points"
+        );
     }
 
     #[test]
-    fn test_cofirm_with_too_few_chars() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('h');
-        game_state.add_char('e');
-        game_state.confirm();
-        assert_eq!(game_state.last_error.unwrap(), GameError::WrongLength);
-        assert_eq!(game_state.current_guess.len(), 0);
-        assert_eq!(game_state.guesses.len(), 0);
+    fn test_auto_play() {
+        let play = |aborted: bool| {
+            let mut game_state = GameState::new(
+                "jolly".to_string(),
+                ["hello", "holly", "jolly", "world"].iter().collect(),
+                GameOptions::default(),
+            )
+            .unwrap();
+            let interface =
+                cli::interface(&cli::app().get_matches_from(["rordle"]), &Config::default());
+            let mut output = Vec::new();
+            auto_loop(
+                &mut game_state,
+                || aborted,
+                &mut output,
+                &interface,
+                Duration::ZERO,
+            )
+            .unwrap();
+            game_state
+        };
+        let game_state = play(false);
+        assert_eq!(game_state.outcome(), Outcome::Won);
+        assert_eq!(game_state.guesses, vec!["hello", "jolly"]);
+        let game_state = play(true);
+        assert_eq!(game_state.outcome(), Outcome::Abandoned);
+        assert!(game_state.guesses.is_empty());
     }
 
     #[test]
-    fn test_cofirm_with_invalid_word() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('j');
-        game_state.add_char('e');
-        game_state.add_char('l');
-        game_state.add_char('l');
-        game_state.add_char('o');
-        game_state.confirm();
-        assert_eq!(game_state.last_error.unwrap(), GameError::InvalidWord);
-        assert_eq!(game_state.current_guess.len(), 0);
-        assert_eq!(game_state.guesses.len(), 0);
+    fn test_daily_date() {
+        // 2024-03-15 12:00 UTC
+        let timestamp = 1710504000;
+        assert_eq!(
+            daily_date(DailyEpoch::Rordle, timestamp).to_string(),
+            "2024-03-15"
+        );
+        assert!(local_utc_offset(timestamp).abs() <= 14 * 3600);
+        let number = DailyEpoch::Wordle.number(daily_date(DailyEpoch::Wordle, timestamp));
+        assert!((999..=1001).contains(&number));
     }
 
     #[test]
-    fn test_cofirm() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('h');
-        // produce error
-        game_state.confirm();
-        game_state.add_char('h');
-        game_state.add_char('e');
-        game_state.add_char('l');
-        game_state.add_char('l');
-        game_state.add_char('o');
-        game_state.confirm();
-        assert_eq!(game_state.last_error, None);
-        assert_eq!(game_state.current_guess.len(), 0);
-        assert_eq!(game_state.guesses.len(), 1);
+    fn test_daily_word_depends_on_date() {
+        let word_list = || MergedWordList {
+            solutions: vec!["hello".to_string(), "jolly".to_string()],
+            words: vec!["hello".to_string(), "jolly".to_string()],
+            ..MergedWordList::default()
+        };
+        let word = |days| {
+            init_game(
+                GameOptions::default(),
+                word_list(),
+                &mut DailyPicker {
+                    date: Date::from_days(days),
+                },
+            )
+            .unwrap()
+            .word
+        };
+        assert_eq!(word(0), "hello");
+        assert_eq!(word(1), "jolly");
+        assert_eq!(word(2), "hello");
     }
 
     #[test]
-    fn test_accepts_any_word() {
-        let mut game_state = super::GameState::new(
-            "hello".to_string(),
-            vec!["hello".to_string(), "jolly".to_string()],
-            true,
+    fn test_check_daily_date() {
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        let today = date("2024-11-05");
+        let epoch = DailyEpoch::Wordle;
+        assert_eq!(
+            check_daily_date(epoch, date("2024-11-03"), today).unwrap(),
+            date("2024-11-03")
         );
-        let result = game_state.guess("milli".to_string()).unwrap();
-        assert_eq!(result, false);
+        assert_eq!(check_daily_date(epoch, today, today).unwrap(), today);
+        assert!(matches!(
+            check_daily_date(epoch, date("2024-11-06"), today),
+            Err(RordleError::FutureDaily { .. })
+        ));
+        assert_eq!(
+            check_daily_date(epoch, date("2021-06-19"), today).unwrap(),
+            date("2021-06-19")
+        );
+        assert!(matches!(
+            check_daily_date(epoch, date("2021-06-18"), today),
+            Err(RordleError::BeforeFirstDaily { .. })
+        ));
     }
 
     #[test]
-    fn test_rendering_with_umlaut() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('Ü');
-        render_game_state(&game_state);
+    fn test_init_game_with_given_word() {
+        let game_state = new_game(
+            GameOptions::default(),
+            MergedWordList {
+                solutions: Vec::new(),
+                words: vec!["jolly".to_string()],
+                ..MergedWordList::default()
+            },
+            "Hello".to_string(),
+        )
+        .unwrap();
+        assert_eq!(game_state.word, "hello");
+        assert!(game_state.valid_words.contains("hello"));
+        assert!(game_state.valid_words.contains("jolly"));
     }
+
     #[test]
-    fn test_rendering_with_one_input() {
-        let mut game_state =
-            super::GameState::new("hello".to_string(), vec!["hello".to_string()], false);
-        game_state.add_char('w');
-        render_game_state(&game_state);
+    fn test_text_loop() {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            ["hello", "jolly"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        text_loop(
+            &mut game_state,
+            "Jolly\n\nabc\nhello\njolly\n".as_bytes(),
+            &mut output,
+            "",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "jolly -ygg-\nerror: Word is not the correct length, 3 letters instead of 5\nhello ggggg\nYou won!\n"
+        );
     }
 }
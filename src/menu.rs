@@ -0,0 +1,408 @@
+//! The menu opened by a bare `rordle` on a terminal: play a game or the
+//! daily puzzle, toggle hard mode, look at the statistics or change the
+//! settings, which are saved to the config file. On the first run it
+//! offers the tutorial first.
+
+use crate::error::RordleError;
+use crate::render::{render_menu, Layout};
+use crate::terminal::Terminal;
+use crate::tutorial::{first_run, play_tutorial};
+use crate::{cli, commands, daily_command, key_name, play_command, terminal_size};
+use rordle_core::config::{config_path, Config, Keybindings, Theme};
+use rordle_core::i18n::tr;
+use rordle_core::words::KeyboardLayout;
+use std::io::stdin;
+use termion::event::Key;
+use termion::input::TermRead;
+
+/// An entry of the menu, labelled by the message with its id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Tutorial,
+    Play,
+    Daily,
+    Hard,
+    Statistics,
+    Settings,
+    Quit,
+    Theme,
+    KeyboardLayout,
+    Back,
+}
+
+const MAIN_ITEMS: [Item; 6] = [
+    Item::Play,
+    Item::Daily,
+    Item::Hard,
+    Item::Statistics,
+    Item::Settings,
+    Item::Quit,
+];
+
+/// The main screen for players who have not played yet.
+const FIRST_RUN_ITEMS: [Item; 7] = [
+    Item::Tutorial,
+    Item::Play,
+    Item::Daily,
+    Item::Hard,
+    Item::Statistics,
+    Item::Settings,
+    Item::Quit,
+];
+
+const SETTINGS_ITEMS: [Item; 3] = [Item::Theme, Item::KeyboardLayout, Item::Back];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Screen {
+    Main,
+    Settings,
+    /// The lines of `rordle stats`, any key goes back.
+    Statistics(Vec<String>),
+}
+
+/// What the menu asks for after a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MenuAction {
+    /// Leave the menu and play the tutorial.
+    Tutorial,
+    /// Leave the menu and play a game with a random word.
+    Play,
+    /// Leave the menu and play the daily puzzle.
+    Daily,
+    /// Load the statistics and pass them to `Menu::show_statistics`.
+    Statistics,
+    /// Save the top level setting `key` of the config file.
+    Setting {
+        key: &'static str,
+        value: &'static str,
+    },
+    /// Leave the menu without playing.
+    Quit,
+}
+
+/// State of the menu, changed by `Menu::key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Menu {
+    screen: Screen,
+    selected: usize,
+    /// Offer the tutorial on the main screen.
+    first_run: bool,
+    /// Play in hard mode.
+    pub(crate) hard: bool,
+    /// Index of the theme in `Theme::NAMES`.
+    theme: usize,
+    /// Index of the layout in `KeyboardLayout::NAMES`.
+    keyboard_layout: usize,
+    /// Shown below the entries, e.g. why a setting was not saved.
+    pub(crate) message: Option<String>,
+}
+
+/// Index of the name `NAMES` parses to `value`.
+fn name_index<T: std::str::FromStr + PartialEq>(names: &[&str], value: &T) -> usize {
+    names
+        .iter()
+        .position(|name| name.parse::<T>().is_ok_and(|v| v == *value))
+        .unwrap_or(0)
+}
+
+impl Menu {
+    /// The main screen of the menu, with the settings of `config`.
+    pub(crate) fn new(config: &Config, first_run: bool) -> Menu {
+        Menu {
+            screen: Screen::Main,
+            selected: 0,
+            first_run,
+            hard: config.hard,
+            theme: name_index(&Theme::NAMES, &config.theme),
+            keyboard_layout: name_index(&KeyboardLayout::NAMES, &config.keyboard_layout),
+            message: None,
+        }
+    }
+
+    fn items(&self) -> &'static [Item] {
+        match self.screen {
+            Screen::Main if self.first_run => &FIRST_RUN_ITEMS,
+            Screen::Main => &MAIN_ITEMS,
+            Screen::Settings => &SETTINGS_ITEMS,
+            Screen::Statistics(_) => &[],
+        }
+    }
+
+    /// Shows the statistics asked for by `MenuAction::Statistics`.
+    pub(crate) fn show_statistics(&mut self, lines: Vec<String>) {
+        self.screen = Screen::Statistics(lines);
+    }
+
+    fn main_screen(&mut self, selected: Item) {
+        self.screen = Screen::Main;
+        self.selected = self
+            .items()
+            .iter()
+            .position(|&item| item == selected)
+            .unwrap();
+    }
+
+    /// Moves to the next (`step` 1) or previous (`step` -1) value of a
+    /// setting, which is then saved.
+    fn cycle(&mut self, item: Item, step: isize) -> Option<MenuAction> {
+        let (index, names, key): (&mut usize, &'static [&'static str], _) = match item {
+            Item::Theme => (&mut self.theme, &Theme::NAMES, "theme"),
+            Item::KeyboardLayout => (
+                &mut self.keyboard_layout,
+                &KeyboardLayout::NAMES,
+                "keyboard_layout",
+            ),
+            _ => return None,
+        };
+        *index = (*index as isize + step).rem_euclid(names.len() as isize) as usize;
+        Some(MenuAction::Setting {
+            key,
+            value: names[*index],
+        })
+    }
+
+    /// Changes the menu after `key` and returns what it asks for, if
+    /// anything. The quit key leaves the menu from the main screen and
+    /// goes back to it from the others.
+    pub(crate) fn key(&mut self, key: Key, bindings: &Keybindings) -> Option<MenuAction> {
+        self.message = None;
+        let quit = key_name(key) == Some(bindings.quit) || key == Key::Char('q');
+        if let Screen::Statistics(_) = self.screen {
+            self.main_screen(Item::Statistics);
+            return None;
+        }
+        let items = self.items();
+        let item = items[self.selected];
+        match key {
+            _ if quit && self.screen == Screen::Main => Some(MenuAction::Quit),
+            _ if quit => {
+                self.main_screen(Item::Settings);
+                None
+            }
+            Key::Up | Key::Char('k') => {
+                self.selected = (self.selected + items.len() - 1) % items.len();
+                None
+            }
+            Key::Down | Key::Char('j') => {
+                self.selected = (self.selected + 1) % items.len();
+                None
+            }
+            Key::Left => self.cycle(item, -1),
+            Key::Right => self.cycle(item, 1),
+            Key::Char('\n') => match item {
+                Item::Tutorial => Some(MenuAction::Tutorial),
+                Item::Play => Some(MenuAction::Play),
+                Item::Daily => Some(MenuAction::Daily),
+                Item::Hard => {
+                    self.hard = !self.hard;
+                    None
+                }
+                Item::Statistics => Some(MenuAction::Statistics),
+                Item::Settings => {
+                    self.screen = Screen::Settings;
+                    self.selected = 0;
+                    None
+                }
+                Item::Quit => Some(MenuAction::Quit),
+                Item::Theme | Item::KeyboardLayout => self.cycle(item, 1),
+                Item::Back => {
+                    self.main_screen(Item::Settings);
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn label(&self, item: Item) -> String {
+        let check = |on: bool| if on { "[x]" } else { "[ ]" };
+        match item {
+            Item::Tutorial => tr("menu-tutorial", &[]),
+            Item::Play => tr("menu-play", &[]),
+            Item::Daily => tr("menu-daily", &[]),
+            Item::Hard => format!("{} {}", check(self.hard), tr("menu-hard", &[])),
+            Item::Statistics => tr("menu-statistics", &[]),
+            Item::Settings => tr("menu-settings", &[]),
+            Item::Quit => tr("menu-quit", &[]),
+            Item::Theme => tr("menu-theme", &[("theme", &Theme::NAMES[self.theme])]),
+            Item::KeyboardLayout => tr(
+                "menu-keyboard-layout",
+                &[("layout", &KeyboardLayout::NAMES[self.keyboard_layout])],
+            ),
+            Item::Back => tr("menu-back", &[]),
+        }
+    }
+
+    /// The lines of the current screen, the selected entry marked by `>`.
+    pub(crate) fn lines(&self, bindings: &Keybindings) -> Vec<String> {
+        let mut lines = vec!["rordle".to_string(), String::new()];
+        if let Screen::Statistics(stats) = &self.screen {
+            lines.extend(stats.iter().cloned());
+            lines.push(String::new());
+            lines.push(tr("menu-any-key", &[]));
+            return lines;
+        }
+        for (i, &item) in self.items().iter().enumerate() {
+            let marker = if i == self.selected { ">" } else { " " };
+            lines.push(format!("{} {}", marker, self.label(item)));
+        }
+        lines.push(String::new());
+        let help = match self.screen {
+            Screen::Settings => "menu-settings-help",
+            _ => "menu-help",
+        };
+        lines.push(tr(help, &[("quit", &bindings.quit)]));
+        lines.extend(self.message.clone());
+        lines
+    }
+}
+
+/// Saves a setting chosen in the menu to the config file and to `config`.
+fn save_setting(config: &mut Config, key: &str, value: &str) -> Result<(), RordleError> {
+    let path = config_path().ok_or(RordleError::NoConfigDir)?;
+    Config::write_setting(&path, key, value)?;
+    match key {
+        "theme" => config.theme = value.parse().unwrap(),
+        _ => config.keyboard_layout = value.parse().unwrap(),
+    }
+    Ok(())
+}
+
+/// Shows the menu until a game is chosen, then plays it like `rordle` or
+/// `rordle daily` would.
+pub(crate) fn run_menu(config: &mut Config) -> Result<i32, RordleError> {
+    let mut menu = Menu::new(config, first_run(config));
+    let action = {
+        // the terminal is restored when `stdout` is dropped
+        let mut stdout = Terminal::open(true).map_err(RordleError::Terminal)?;
+        let terminal = terminal_size();
+        let mut keys = stdin().keys();
+        let action = loop {
+            let layout = Layout::from_config(config);
+            render_menu(
+                &mut stdout,
+                &menu.lines(&config.keybindings),
+                layout,
+                terminal,
+            );
+            let key = match keys.next() {
+                Some(key) => key.map_err(RordleError::Terminal)?,
+                None => break MenuAction::Quit,
+            };
+            match menu.key(key, &config.keybindings) {
+                Some(MenuAction::Statistics) => match commands::stats_lines(config) {
+                    Ok(lines) => menu.show_statistics(lines),
+                    Err(error) => menu.message = Some(error.to_string()),
+                },
+                Some(MenuAction::Setting { key, value }) => {
+                    if let Err(error) = save_setting(config, key, value) {
+                        menu.message = Some(tr("menu-not-saved", &[("error", &error)]));
+                    }
+                }
+                Some(action) => break action,
+                None => (),
+            }
+        };
+        render_menu(&mut stdout, &[], Layout::default(), terminal);
+        action
+    };
+    let hard = if menu.hard { "--hard" } else { "--no-hard" };
+    match action {
+        MenuAction::Play => play_command(&cli::app().get_matches_from(["rordle", hard]), config),
+        MenuAction::Tutorial => {
+            let matches = cli::app().get_matches_from(["rordle", "tutorial"]);
+            play_tutorial(matches.subcommand_matches("tutorial").unwrap(), config)
+        }
+        MenuAction::Daily => {
+            let matches = cli::app().get_matches_from(["rordle", "daily", hard]);
+            daily_command(matches.subcommand_matches("daily").unwrap(), config)
+        }
+        _ => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_keys() {
+        let bindings = Keybindings::default();
+        let mut menu = Menu::new(&Config::default(), false);
+        let press = |menu: &mut Menu, keys: &[Key]| {
+            keys.iter()
+                .map(|&key| menu.key(key, &bindings))
+                .collect::<Vec<_>>()
+        };
+        // hard mode is toggled in place
+        assert_eq!(
+            press(&mut menu, &[Key::Down, Key::Down, Key::Char('\n')]),
+            [None; 3]
+        );
+        assert!(menu.hard);
+        assert_eq!(menu.lines(&bindings)[4], "> [x] Hard mode");
+        // the selection wraps around
+        assert_eq!(
+            press(&mut menu, &[Key::Up, Key::Up, Key::Up, Key::Char('\n')]),
+            [None, None, None, Some(MenuAction::Quit)]
+        );
+        // settings are saved as they change, Esc goes back to the main screen
+        let actions = press(
+            &mut menu,
+            &[
+                Key::Down,
+                Key::Down,
+                Key::Down,
+                Key::Down,
+                Key::Down,
+                Key::Char('\n'),
+                Key::Left,
+            ],
+        );
+        assert_eq!(
+            actions[6],
+            Some(MenuAction::Setting {
+                key: "theme",
+                value: "monochrome"
+            })
+        );
+        assert_eq!(
+            press(&mut menu, &[Key::Down, Key::Char('\n')]),
+            [
+                None,
+                Some(MenuAction::Setting {
+                    key: "keyboard_layout",
+                    value: "qwertz"
+                })
+            ]
+        );
+        assert_eq!(menu.lines(&bindings)[3], "> Keyboard: qwertz");
+        assert_eq!(press(&mut menu, &[Key::Esc]), [None]);
+        assert_eq!(menu.lines(&bindings)[6], "> Settings");
+        assert_eq!(press(&mut menu, &[Key::Esc]), [Some(MenuAction::Quit)]);
+    }
+
+    #[test]
+    fn test_menu_statistics() {
+        let bindings = Keybindings::default();
+        let mut menu = Menu::new(&Config::default(), false);
+        for key in [Key::Down, Key::Down, Key::Down] {
+            menu.key(key, &bindings);
+        }
+        assert_eq!(
+            menu.key(Key::Char('\n'), &bindings),
+            Some(MenuAction::Statistics)
+        );
+        menu.show_statistics(vec!["Played:         2".to_string()]);
+        assert_eq!(menu.lines(&bindings)[2], "Played:         2");
+        // any key goes back, even the quit key
+        assert_eq!(menu.key(Key::Esc, &bindings), None);
+        assert_eq!(menu.lines(&bindings)[5], "> Statistics");
+        assert_eq!(menu.key(Key::Up, &bindings), None);
+        assert_eq!(menu.key(Key::Up, &bindings), None);
+        assert_eq!(
+            menu.key(Key::Char('\n'), &bindings),
+            Some(MenuAction::Daily)
+        );
+    }
+}
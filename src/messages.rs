@@ -0,0 +1,149 @@
+//! Messages shown below the board which go away by themselves. A message
+//! has an optional deadline, `Messages::tick` clears it once it passed, so
+//! the key loop draws the line again without waiting for a key. Sticky
+//! messages, without a deadline, stay until the game replaces them.
+
+use rordle_core::game::{GameError, GameState};
+use std::time::{Duration, Instant};
+
+/// How long a rejected guess or hint is explained, a typo is clear at a
+/// glance.
+const ERROR_TTL: Duration = Duration::from_secs(3);
+
+/// How long `error` is shown, `None` for the errors of hard mode, which
+/// name the letter a guess has to use.
+pub(crate) fn error_ttl(error: &GameError) -> Option<Duration> {
+    match error {
+        GameError::MissingLetter { .. } | GameError::MisplacedLetter { .. } => None,
+        _ => Some(ERROR_TTL),
+    }
+}
+
+/// A message and when it is cleared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Message {
+    pub(crate) text: String,
+    /// `None` for a sticky message.
+    deadline: Option<Instant>,
+}
+
+impl Message {
+    /// A message shown until it is replaced.
+    pub(crate) fn sticky(text: String) -> Message {
+        Message {
+            text,
+            deadline: None,
+        }
+    }
+
+    /// A message shown for `ttl` from `now`, or sticky without one.
+    pub(crate) fn fading(text: String, ttl: Option<Duration>, now: Instant) -> Message {
+        Message {
+            text,
+            deadline: ttl.map(|ttl| now + ttl),
+        }
+    }
+}
+
+/// The message below the board and the deadline of the error of the game,
+/// which the game keeps itself.
+#[derive(Debug, Default)]
+pub(crate) struct Messages {
+    message: Option<Message>,
+    /// When the error of the game is cleared, `None` if it is sticky.
+    error_deadline: Option<Instant>,
+}
+
+impl Messages {
+    /// The text of the message below the board.
+    pub(crate) fn text(&self) -> Option<&str> {
+        self.message.as_ref().map(|message| message.text.as_str())
+    }
+
+    /// Shows `message` instead of the one before.
+    pub(crate) fn show(&mut self, message: Message) {
+        self.message = Some(message);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.message = None;
+    }
+
+    /// Starts the time of the error of `game_state`, after a guess was
+    /// confirmed at `now`.
+    pub(crate) fn confirmed(&mut self, game_state: &GameState, now: Instant) {
+        self.error_deadline = game_state
+            .last_error
+            .as_ref()
+            .and_then(error_ttl)
+            .map(|ttl| now + ttl);
+    }
+
+    /// Clears what expired by `now`, the error of `game_state` included.
+    /// Whether anything was cleared, so the line needs to be drawn again.
+    pub(crate) fn tick(&mut self, game_state: &mut GameState, now: Instant) -> bool {
+        let expired = |deadline: Option<Instant>| deadline.is_some_and(|deadline| deadline <= now);
+        let mut cleared = false;
+        if expired(self.message.as_ref().and_then(|message| message.deadline)) {
+            self.message = None;
+            cleared = true;
+        }
+        if expired(self.error_deadline) {
+            self.error_deadline = None;
+            cleared |= game_state.last_error.take().is_some();
+        }
+        cleared
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rordle_core::game::GameOptions;
+
+    #[test]
+    fn test_tick() {
+        let words = ["hello", "jolly"].iter().collect();
+        let options = GameOptions::default().hard(true).any_word(true);
+        let mut game_state = GameState::new("hello".to_string(), words, options).unwrap();
+        let start = Instant::now();
+        let at = |seconds: f64| start + Duration::from_secs_f64(seconds);
+        let mut messages = Messages::default();
+
+        // a word too short fades after three seconds
+        game_state.add_char('h');
+        game_state.confirm();
+        messages.confirmed(&game_state, at(0.0));
+        assert!(!messages.tick(&mut game_state, at(2.9)));
+        assert!(game_state.last_error.is_some());
+        assert!(messages.tick(&mut game_state, at(3.0)));
+        assert_eq!(game_state.last_error, None);
+        assert!(!messages.tick(&mut game_state, at(10.0)));
+
+        // the errors of hard mode stay
+        game_state.guess("jolly".to_string()).unwrap();
+        game_state.current_guess = "xxxxx".to_string();
+        game_state.confirm();
+        assert!(matches!(
+            game_state.last_error,
+            Some(GameError::MisplacedLetter { .. })
+        ));
+        messages.confirmed(&game_state, at(10.0));
+        assert!(!messages.tick(&mut game_state, at(100.0)));
+        assert!(game_state.last_error.is_some());
+
+        let ttl = Some(Duration::from_secs(2));
+        messages.show(Message::fading(
+            "nothing to reveal".to_string(),
+            ttl,
+            at(0.0),
+        ));
+        assert!(!messages.tick(&mut game_state, at(1.0)));
+        assert_eq!(messages.text(), Some("nothing to reveal"));
+        assert!(messages.tick(&mut game_state, at(2.0)));
+        assert_eq!(messages.text(), None);
+        messages.show(Message::sticky("revealed".to_string()));
+        assert!(!messages.tick(&mut game_state, at(100.0)));
+        assert_eq!(messages.text(), Some("revealed"));
+    }
+}
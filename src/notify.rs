@@ -0,0 +1,104 @@
+//! `--notify`: a desktop notification once a game or `rordle bench` is
+//! done, for when rordle is left running in another window. Terminals show
+//! the OSC 9 and OSC 777 sequences as notifications, `notify-send` is used
+//! if asked for and available.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+/// How notifications are sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Notifier {
+    /// Escape sequences written to the terminal.
+    Terminal,
+    /// `notify-send`, falling back to the terminal if it can not be run.
+    Send,
+}
+
+impl Notifier {
+    /// Names accepted by `FromStr`.
+    pub(crate) const NAMES: [&'static str; 2] = ["terminal", "send"];
+}
+
+impl FromStr for Notifier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "terminal" => Ok(Notifier::Terminal),
+            "send" => Ok(Notifier::Send),
+            _ => Err(format!("expected one of {}", Notifier::NAMES.join(", "))),
+        }
+    }
+}
+
+/// `text` without control characters, which would end the sequence, and
+/// without `;` in the title, which separates the fields of OSC 777.
+fn sanitize(text: &str, separator: bool) -> String {
+    text.chars()
+        .filter(|c| !c.is_control())
+        .map(|c| if separator && c == ';' { ',' } else { c })
+        .collect()
+}
+
+/// The sequences showing a notification: OSC 9, understood by iTerm2 and
+/// others, and OSC 777, understood by foot, kitty and the terminals based
+/// on VTE. Within tmux they are passed through to the outer terminal.
+pub(crate) fn sequences(title: &str, body: &str, tmux: bool) -> String {
+    let (title, body) = (sanitize(title, true), sanitize(body, false));
+    [
+        format!("\x1b]9;{}: {}\x07", title, body),
+        format!("\x1b]777;notify;{};{}\x07", title, body),
+    ]
+    .iter()
+    .map(|sequence| {
+        if tmux {
+            format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+        } else {
+            sequence.clone()
+        }
+    })
+    .collect()
+}
+
+/// Sends a notification. It goes to the terminal itself rather than to the
+/// standard output, which may be piped. Failing to notify is not worth a
+/// warning, the result is printed anyway.
+pub(crate) fn notify(notifier: Notifier, title: &str, body: &str) {
+    if notifier == Notifier::Send {
+        let sent = Command::new("notify-send")
+            .args([title, body])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if sent.is_ok_and(|status| status.success()) {
+            return;
+        }
+    }
+    let tmux = std::env::var_os("TMUX").is_some();
+    if let Ok(mut tty) = std::fs::OpenOptions::new().write(true).open("/dev/tty") {
+        let _ = tty.write_all(sequences(title, body, tmux).as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequences() {
+        assert_eq!(
+            sequences("rordle; bench", "won\x07 98%", false),
+            "\x1b]9;rordle, bench: won 98%\x07\x1b]777;notify;rordle, bench;won 98%\x07"
+        );
+        assert_eq!(
+            sequences("rordle", "won", true),
+            "\x1bPtmux;\x1b\x1b]9;rordle: won\x07\x1b\\\
+             \x1bPtmux;\x1b\x1b]777;notify;rordle;won\x07\x1b\\"
+        );
+        assert_eq!("send".parse(), Ok(Notifier::Send));
+        assert!("mail".parse::<Notifier>().is_err());
+    }
+}
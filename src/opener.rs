@@ -0,0 +1,284 @@
+//! The opener cache. Ranking the first guess scores every word against
+//! the solutions, which takes a while on a full list, and gives the same
+//! ranking every time. The best openers of each list, word length and
+//! weighting are kept in the file `openers`, see [`crate::paths`]. Its
+//! first line is `rordle openers 1`, the version of the format. Each line
+//! after it holds a ranking as fields separated by tabs: the FNV-1a hash
+//! of the ranked words and their frequencies, the word length, the
+//! weighting and the openers separated by commas, e.g. `5f0c1d2e3a4b5c6d`,
+//! `5`, `balanced` and `slate,crane,trace`. A
+//! changed list has another hash, so its openers are ranked again. A file
+//! of another version or one which does not parse is ranked again as well.
+
+use crate::hash::fnv1a;
+use crate::solver::Weighting;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Version of the file format, the first line of the file.
+pub const VERSION: u32 = 1;
+
+/// Number of openers kept of each ranking, more than any suggestion shows.
+pub const KEPT: usize = 100;
+
+/// Number of rankings kept, the most recently ranked first.
+const MAX_ENTRIES: usize = 16;
+
+/// What a ranking of openers depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenerKey {
+    /// Hash of the ranked words and their frequencies.
+    pub list: u64,
+    /// Number of letters of the words, 0 if they differ.
+    pub length: usize,
+    /// How the words are ranked.
+    pub weighting: Weighting,
+}
+
+impl OpenerKey {
+    /// The key of ranking `words`, in their order, with `frequencies`.
+    pub fn new(words: &[&str], frequencies: &HashMap<String, u64>, weighting: Weighting) -> Self {
+        let mut bytes = Vec::new();
+        for word in words {
+            let frequency = frequencies
+                .get(*word)
+                .map_or(String::new(), |count| count.to_string());
+            for text in [word, frequency.as_str()] {
+                bytes.extend(text.bytes().chain([0]));
+            }
+        }
+        let mut lengths = words.iter().map(|word| word.chars().count());
+        let first = lengths.next().unwrap_or(0);
+        OpenerKey {
+            list: fnv1a(&bytes),
+            length: if lengths.all(|length| length == first) {
+                first
+            } else {
+                0
+            },
+            weighting,
+        }
+    }
+}
+
+/// Name of a weighting in the file.
+fn weighting_name(weighting: Weighting) -> &'static str {
+    match weighting {
+        Weighting::Info => "info",
+        Weighting::Freq => "freq",
+        Weighting::Balanced => "balanced",
+    }
+}
+
+/// The rankings of the cache file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpenerCache {
+    entries: Vec<(OpenerKey, Vec<String>)>,
+}
+
+impl OpenerCache {
+    /// Parses a cache file, `None` if it is of another version or broken.
+    pub fn parse(text: &str) -> Option<OpenerCache> {
+        let mut lines = text.lines();
+        if lines.next()? != format!("rordle openers {}", VERSION) {
+            return None;
+        }
+        let entries = lines
+            .map(|line| {
+                let mut fields = line.split('\t');
+                let list = u64::from_str_radix(fields.next()?, 16).ok()?;
+                let length = fields.next()?.parse().ok()?;
+                let weighting = fields.next()?.parse().ok()?;
+                let openers = fields.next()?.split(',').map(str::to_string).collect();
+                if fields.next().is_some() {
+                    return None;
+                }
+                let key = OpenerKey {
+                    list,
+                    length,
+                    weighting,
+                };
+                Some((key, openers))
+            })
+            .collect::<Option<_>>()?;
+        Some(OpenerCache { entries })
+    }
+
+    /// The openers ranked for `key`, best first.
+    pub fn get(&self, key: &OpenerKey) -> Option<&[String]> {
+        self.entries
+            .iter()
+            .find(|(known, _)| known == key)
+            .map(|(_, openers)| openers.as_slice())
+    }
+
+    /// Keeps the first `KEPT` of `openers` for `key`, replacing what was
+    /// ranked for it before and dropping the oldest ranking if there are
+    /// too many.
+    pub fn insert(&mut self, key: OpenerKey, openers: &[&str]) {
+        self.entries.retain(|(known, _)| *known != key);
+        let openers = openers.iter().take(KEPT).map(|w| w.to_string()).collect();
+        self.entries.insert(0, (key, openers));
+        self.entries.truncate(MAX_ENTRIES);
+    }
+}
+
+impl std::fmt::Display for OpenerCache {
+    /// Writes the cache file.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "rordle openers {}", VERSION)?;
+        for (key, openers) in &self.entries {
+            writeln!(
+                f,
+                "{:016x}\t{}\t{}\t{}",
+                key.list,
+                key.length,
+                weighting_name(key.weighting),
+                openers.join(",")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Where the openers of `openers` came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Read from the cache file.
+    Cached,
+    /// Ranked, and written to the cache file if there is one.
+    Ranked,
+}
+
+/// The openers of a ranking and where they came from.
+#[derive(Debug)]
+pub struct Openers {
+    /// The best openers, best first.
+    pub openers: Vec<String>,
+    /// Whether they were cached.
+    pub source: Source,
+    /// Why the ranked openers could not be written to the cache file. It
+    /// only costs the time of ranking again next time.
+    pub unsaved: Option<std::io::Error>,
+}
+
+/// The `KEPT` best openers for `key`, read from the cache file at `path`,
+/// see [`crate::paths::DataFile::Openers`], or ranked with `rank` and added
+/// to it. `rebuild` ranks them again even if they are cached.
+pub fn openers<F>(path: Option<&Path>, key: OpenerKey, rebuild: bool, rank: F) -> Openers
+where
+    F: FnOnce() -> Vec<String>,
+{
+    let mut cache = path
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| OpenerCache::parse(&text))
+        .unwrap_or_default();
+    if let Some(openers) = cache.get(&key).filter(|_| !rebuild) {
+        return Openers {
+            openers: openers.to_vec(),
+            source: Source::Cached,
+            unsaved: None,
+        };
+    }
+    let mut ranked = rank();
+    ranked.truncate(KEPT);
+    let mut unsaved = None;
+    if let Some(path) = path.filter(|_| !ranked.is_empty()) {
+        let words: Vec<&str> = ranked.iter().map(String::as_str).collect();
+        cache.insert(key, &words);
+        unsaved = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(path, cache.to_string()))
+            .err();
+    }
+    Openers {
+        openers: ranked,
+        source: Source::Ranked,
+        unsaved,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opener_key() {
+        let frequencies = HashMap::from([("slate".to_string(), 10)]);
+        let key = OpenerKey::new(&["slate", "crane"], &frequencies, Weighting::Info);
+        assert_eq!(key.length, 5);
+        assert_eq!(
+            key,
+            OpenerKey::new(&["slate", "crane"], &frequencies, Weighting::Info)
+        );
+        // any change of the list, its frequencies or the weighting counts
+        for other in [
+            OpenerKey::new(&["slate", "crate"], &frequencies, Weighting::Info),
+            OpenerKey::new(&["slate", "crane"], &HashMap::new(), Weighting::Info),
+            OpenerKey::new(&["slate", "crane"], &frequencies, Weighting::Freq),
+        ] {
+            assert_ne!(key, other);
+        }
+        let mixed = OpenerKey::new(&["slate", "cranes"], &frequencies, Weighting::Info);
+        assert_eq!(mixed.length, 0);
+    }
+
+    #[test]
+    fn test_cache_file() {
+        let key = OpenerKey::new(&["slate", "crane"], &HashMap::new(), Weighting::Balanced);
+        let mut cache = OpenerCache::default();
+        cache.insert(key, &["slate", "crane"]);
+        let text = cache.to_string();
+        assert!(text.starts_with("rordle openers 1\n"));
+        assert!(text.ends_with("\t5\tbalanced\tslate,crane\n"));
+        assert_eq!(OpenerCache::parse(&text), Some(cache.clone()));
+        assert_eq!(
+            cache.get(&key),
+            Some(["slate".to_string(), "crane".to_string()].as_slice())
+        );
+        // other versions and broken files are ranked again
+        assert_eq!(OpenerCache::parse(&text.replace(" 1\n", " 0\n")), None);
+        assert_eq!(OpenerCache::parse(&text.replace('\t', " ")), None);
+        assert_eq!(OpenerCache::parse(""), None);
+        for i in 0..MAX_ENTRIES {
+            let other = OpenerKey {
+                length: 10 + i,
+                ..key
+            };
+            cache.insert(other, &["slate"]);
+        }
+        assert_eq!(cache.get(&key), None);
+    }
+
+    #[test]
+    fn test_openers() {
+        let path = std::env::temp_dir().join(format!("rordle-openers-{}", std::process::id()));
+        let key = OpenerKey::new(&["slate"], &HashMap::new(), Weighting::Info);
+        let rank = || vec!["slate".to_string()];
+        let ranked = openers(Some(&path), key, false, rank);
+        assert_eq!(ranked.source, Source::Ranked);
+        assert!(ranked.unsaved.is_none());
+        let cached = openers(Some(&path), key, false, || unreachable!());
+        assert_eq!(cached.openers, ["slate"]);
+        assert_eq!(cached.source, Source::Cached);
+        assert_eq!(openers(Some(&path), key, true, rank).source, Source::Ranked);
+        // a corrupt cache is ranked again without complaint
+        std::fs::write(&path, "rordle openers 1\nnonsense\n").unwrap();
+        assert_eq!(
+            openers(Some(&path), key, false, rank).source,
+            Source::Ranked
+        );
+        assert_eq!(
+            openers(Some(&path), key, false, rank).source,
+            Source::Cached
+        );
+        assert_eq!(openers(None, key, false, rank).source, Source::Ranked);
+        // a cache which can not be written is left to the caller to report
+        let unwritable = path.join("openers");
+        let ranked = openers(Some(&unwritable), key, false, rank);
+        assert_eq!(ranked.openers, ["slate"]);
+        assert!(ranked.unsaved.is_some());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
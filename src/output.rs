@@ -0,0 +1,149 @@
+//! `--format` of `rordle stats` and `rordle history`: besides the tables
+//! for people, the same records as JSON or CSV for scripts and spreadsheets.
+
+use rordle_core::json::Json;
+use std::str::FromStr;
+
+/// How a command prints its records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    /// Aligned lines for people.
+    Table,
+    /// One JSON document.
+    Json,
+    /// Comma separated values with a header line.
+    Csv,
+}
+
+impl OutputFormat {
+    /// Names accepted by `FromStr`.
+    pub(crate) const NAMES: [&'static str; 3] = ["table", "json", "csv"];
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!(
+                "expected one of {}",
+                OutputFormat::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Rows of values under fixed field names, which are the keys of the JSON
+/// objects and the header of the CSV. They are part of the interface for
+/// scripts, so they are only ever added to.
+pub(crate) struct Records {
+    fields: &'static [&'static str],
+    rows: Vec<Vec<Json>>,
+}
+
+impl Records {
+    pub(crate) fn new(fields: &'static [&'static str]) -> Records {
+        Records {
+            fields,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds a row, with a value for each field.
+    pub(crate) fn push(&mut self, row: Vec<Json>) {
+        debug_assert_eq!(row.len(), self.fields.len());
+        self.rows.push(row);
+    }
+
+    /// The JSON object of the row `index`.
+    pub(crate) fn object(&self, index: usize) -> Json {
+        Json::object(self.fields.iter().copied().zip(self.rows[index].clone()))
+    }
+
+    /// All rows as an array of objects.
+    pub(crate) fn to_json(&self) -> Json {
+        Json::Array((0..self.rows.len()).map(|i| self.object(i)).collect())
+    }
+
+    /// The header and the rows, each line ending in a newline.
+    pub(crate) fn to_csv(&self) -> String {
+        let mut csv = csv_line(self.fields.iter().map(|field| field.to_string()));
+        for row in &self.rows {
+            csv.push_str(&csv_line(row.iter().map(csv_value)));
+        }
+        csv
+    }
+}
+
+fn csv_line(values: impl Iterator<Item = String>) -> String {
+    let values: Vec<String> = values.map(|value| csv_escape(&value)).collect();
+    format!("{}\n", values.join(","))
+}
+
+/// Quotes values containing separators, quotes or line breaks, doubling
+/// the quotes within.
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A value in a CSV cell: strings unquoted, arrays joined by commas and
+/// `null` left empty.
+fn csv_value(value: &Json) -> String {
+    match value {
+        Json::Null => String::new(),
+        Json::String(s) => s.clone(),
+        Json::Array(items) => items.iter().map(csv_value).collect::<Vec<_>>().join(","),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Records {
+        let mut records = Records::new(&["word", "guesses", "won", "puzzle"]);
+        records.push(vec![
+            Json::from("cigar"),
+            Json::Array(vec![Json::from("crane"), Json::from("cigar")]),
+            Json::from(true),
+            Json::Null,
+        ]);
+        records.push(vec![
+            Json::from("say \"hi\""),
+            Json::Array(vec![Json::from("crane")]),
+            Json::from(false),
+            Json::from(250),
+        ]);
+        records
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            records().to_csv(),
+            "word,guesses,won,puzzle\n\
+             cigar,\"crane,cigar\",true,\n\
+             \"say \"\"hi\"\"\",crane,false,250\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            records().to_json().to_string(),
+            r#"[{"word":"cigar","guesses":["crane","cigar"],"won":true,"puzzle":null},"#
+                .to_string()
+                + r#"{"word":"say \"hi\"","guesses":["crane"],"won":false,"puzzle":250}]"#
+        );
+        assert_eq!("csv".parse::<OutputFormat>(), Ok(OutputFormat::Csv));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
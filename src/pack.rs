@@ -0,0 +1,474 @@
+//! Puzzle packs: words played one after another, e.g. a week of puzzles
+//! set by a friend.
+//!
+//! A pack is a text file with a puzzle per line, its title and word
+//! separated by `|`, optionally followed by a limit of tries and a hint:
+//!
+//! ```text
+//! #! name: Kitchen week
+//! # lines starting with `#` are comments
+//! Breakfast | toast
+//! Dessert | crepe | tries: 4 | hint: thin and French
+//! ```
+//!
+//! With the header `#! codes: yes` the words are challenge codes, see
+//! [`crate::challenge`], so the pack can be shared without showing them.
+//! All words of a pack have the same length.
+//!
+//! The progress through packs is stored one pack per line, the id of the
+//! pack and the result of each finished puzzle separated by a tab, e.g.
+//! `2f0c7a1d9e3b5a40<TAB>3,x,5`, `x` for puzzles which were lost.
+
+use crate::challenge::Challenge;
+use crate::hash::fnv1a;
+use crate::stats::StatsError;
+use crate::words::HeaderError;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A puzzle of a pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackPuzzle {
+    /// Title shown above the board.
+    pub title: String,
+    /// The solution.
+    pub word: String,
+    /// Tries allowed instead of the default.
+    pub tries: Option<u16>,
+    /// Shown above the board to help with the word.
+    pub hint: Option<String>,
+}
+
+/// Puzzles played in order, see the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pack {
+    /// Display name of the pack.
+    pub name: Option<String>,
+    /// The puzzles, in the order they are played.
+    pub puzzles: Vec<PackPuzzle>,
+}
+
+/// Errors in a pack file. Lines are counted from 1.
+#[derive(Debug)]
+pub enum PackError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// A header is invalid.
+    Header(HeaderError),
+    /// A line is not of the form `title | word`.
+    Malformed {
+        /// Line of the puzzle.
+        line: usize,
+    },
+    /// A field after the word is not `tries` or `hint`.
+    UnknownField {
+        /// Line of the puzzle.
+        line: usize,
+        /// Key of the field.
+        key: String,
+    },
+    /// The tries are not a number of at least 1.
+    InvalidTries {
+        /// Line of the puzzle.
+        line: usize,
+        /// The tries as written.
+        tries: String,
+    },
+    /// A word contains something other than letters.
+    InvalidWord {
+        /// Line of the puzzle.
+        line: usize,
+        /// The word as written.
+        word: String,
+    },
+    /// A challenge code of a pack with `#! codes: yes` is invalid.
+    InvalidCode {
+        /// Line of the puzzle.
+        line: usize,
+    },
+    /// A word does not have the length of the first one.
+    WrongLength {
+        /// Line of the puzzle.
+        line: usize,
+        /// Letters of the word.
+        length: usize,
+        /// Letters of the first word.
+        expected: usize,
+    },
+    /// A word is the solution of an earlier puzzle too.
+    Duplicate {
+        /// Line of the puzzle.
+        line: usize,
+        /// Line of the earlier puzzle.
+        first: usize,
+    },
+    /// The pack has no puzzles.
+    Empty,
+}
+
+impl std::fmt::Display for PackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PackError::Io(error) => write!(f, "{}", error),
+            PackError::Header(error) => write!(f, "{}", error),
+            PackError::Malformed { line } => {
+                write!(f, "line {}: expected `title | word`", line)
+            }
+            PackError::UnknownField { line, key } => write!(
+                f,
+                "line {}: unknown field `{}`, expected `tries` or `hint`",
+                line, key
+            ),
+            PackError::InvalidTries { line, tries } => {
+                write!(f, "line {}: invalid number of tries `{}`", line, tries)
+            }
+            PackError::InvalidWord { line, word } => {
+                write!(f, "line {}: `{}` is not a word", line, word)
+            }
+            PackError::InvalidCode { line } => {
+                write!(f, "line {}: invalid challenge code", line)
+            }
+            PackError::WrongLength {
+                line,
+                length,
+                expected,
+            } => write!(
+                f,
+                "line {}: the word has {} letters, the first one has {}",
+                line, length, expected
+            ),
+            PackError::Duplicate { line, first } => {
+                write!(f, "line {}: the word of line {} again", line, first)
+            }
+            PackError::Empty => write!(f, "the pack contains no puzzles"),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+impl From<HeaderError> for PackError {
+    fn from(error: HeaderError) -> PackError {
+        PackError::Header(error)
+    }
+}
+
+/// Key and value of a `#! key: value` header.
+fn header(line: &str, line_no: usize) -> Result<(String, &str), HeaderError> {
+    let (key, value) = line
+        .trim_start()
+        .strip_prefix("#!")
+        .and_then(|header| header.split_once(':'))
+        .ok_or(HeaderError::Malformed { line: line_no })?;
+    let key = key.trim().to_lowercase();
+    let value = value.trim();
+    if key.is_empty() {
+        return Err(HeaderError::Malformed { line: line_no });
+    }
+    if value.is_empty() {
+        return Err(HeaderError::EmptyValue { line: line_no, key });
+    }
+    Ok((key, value))
+}
+
+/// Parses the line `line_no` of a pack, `codes` if its words are encoded.
+fn puzzle(line: &str, line_no: usize, codes: bool) -> Result<PackPuzzle, PackError> {
+    let mut fields = line.split('|').map(str::trim);
+    let (title, word) = match (fields.next(), fields.next()) {
+        (Some(title), Some(word)) if !title.is_empty() && !word.is_empty() => (title, word),
+        _ => return Err(PackError::Malformed { line: line_no }),
+    };
+    let word = if codes {
+        word.parse::<Challenge>()
+            .map_err(|_| PackError::InvalidCode { line: line_no })?
+            .word
+    } else {
+        word.to_lowercase()
+    };
+    if !word.chars().all(char::is_alphabetic) {
+        return Err(PackError::InvalidWord {
+            line: line_no,
+            word,
+        });
+    }
+    let mut puzzle = PackPuzzle {
+        title: title.to_string(),
+        word,
+        tries: None,
+        hint: None,
+    };
+    for field in fields {
+        let (key, value) = field
+            .split_once(':')
+            .map(|(key, value)| (key.trim().to_lowercase(), value.trim()))
+            .ok_or(PackError::Malformed { line: line_no })?;
+        match key.as_str() {
+            "tries" => {
+                let tries = value.parse().ok().filter(|tries| *tries > 0);
+                puzzle.tries = Some(tries.ok_or_else(|| PackError::InvalidTries {
+                    line: line_no,
+                    tries: value.to_string(),
+                })?);
+            }
+            "hint" => puzzle.hint = Some(value.to_string()),
+            _ => return Err(PackError::UnknownField { line: line_no, key }),
+        }
+    }
+    Ok(puzzle)
+}
+
+impl FromStr for Pack {
+    type Err = PackError;
+
+    fn from_str(contents: &str) -> Result<Pack, PackError> {
+        let mut name = None;
+        let mut codes = false;
+        let mut puzzles: Vec<PackPuzzle> = Vec::new();
+        // line of each puzzle, for errors about later ones
+        let mut lines = Vec::new();
+        for (i, line) in contents.lines().enumerate() {
+            let line_no = i + 1;
+            if puzzles.is_empty() && line.trim_start().starts_with("#!") {
+                match header(line, line_no)? {
+                    (key, value) if key == "name" => name = Some(value.to_string()),
+                    (key, value) if key == "codes" => codes = value == "yes",
+                    // unknown headers are left for later versions
+                    _ => {}
+                }
+                continue;
+            }
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let puzzle = puzzle(line, line_no, codes)?;
+            let length = puzzle.word.chars().count();
+            if let Some(first) = puzzles.first() {
+                let expected = first.word.chars().count();
+                if length != expected {
+                    return Err(PackError::WrongLength {
+                        line: line_no,
+                        length,
+                        expected,
+                    });
+                }
+            }
+            if let Some(earlier) = puzzles.iter().position(|p| p.word == puzzle.word) {
+                return Err(PackError::Duplicate {
+                    line: line_no,
+                    first: lines[earlier],
+                });
+            }
+            puzzles.push(puzzle);
+            lines.push(line_no);
+        }
+        if puzzles.is_empty() {
+            return Err(PackError::Empty);
+        }
+        Ok(Pack { name, puzzles })
+    }
+}
+
+impl Pack {
+    /// Reads and parses the pack file at `path`.
+    pub fn read(path: &Path) -> Result<Pack, PackError> {
+        std::fs::read_to_string(path)
+            .map_err(PackError::Io)?
+            .parse()
+    }
+
+    /// Identifies the pack in the progress file by its puzzles, so a
+    /// changed pack starts over.
+    pub fn id(&self) -> String {
+        let mut bytes = Vec::new();
+        for puzzle in &self.puzzles {
+            let tries = puzzle
+                .tries
+                .map_or(String::new(), |tries| tries.to_string());
+            for text in [&puzzle.word, &tries] {
+                bytes.extend(text.bytes().chain([0]));
+            }
+        }
+        format!("{:016x}", fnv1a(&bytes))
+    }
+
+    /// The pack as a file, the words written as challenge codes if `codes`.
+    pub fn to_file(&self, codes: bool) -> String {
+        let mut file = String::new();
+        if let Some(name) = &self.name {
+            file.push_str(&format!("#! name: {}\n", name));
+        }
+        if codes {
+            file.push_str("#! codes: yes\n");
+        }
+        for puzzle in &self.puzzles {
+            let word = if codes {
+                Challenge {
+                    word: puzzle.word.clone(),
+                }
+                .to_string()
+            } else {
+                puzzle.word.clone()
+            };
+            file.push_str(&format!("{} | {}", puzzle.title, word));
+            if let Some(tries) = puzzle.tries {
+                file.push_str(&format!(" | tries: {}", tries));
+            }
+            if let Some(hint) = &puzzle.hint {
+                file.push_str(&format!(" | hint: {}", hint));
+            }
+            file.push('\n');
+        }
+        file
+    }
+}
+
+/// Result of a finished puzzle: the number of guesses if it was solved.
+pub type PuzzleResult = Option<usize>;
+
+fn parse_results(results: &str) -> Option<Vec<PuzzleResult>> {
+    results
+        .split(',')
+        .filter(|result| !result.is_empty())
+        .map(|result| match result {
+            "x" => Some(None),
+            guesses => guesses.parse().ok().map(Some),
+        })
+        .collect()
+}
+
+fn read_progress_file(path: &Path) -> Result<String, StatsError> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(error) => Err(StatsError::Io(path.to_path_buf(), error)),
+    }
+}
+
+/// Results of the puzzles finished so far of the pack `id`, in order. A
+/// missing file or a malformed line hold no progress.
+pub fn read_progress(path: &Path, id: &str) -> Result<Vec<PuzzleResult>, StatsError> {
+    Ok(read_progress_file(path)?
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .find(|(line_id, _)| *line_id == id)
+        .and_then(|(_, results)| parse_results(results))
+        .unwrap_or_default())
+}
+
+/// Stores the results of the pack `id`, replacing the ones stored before.
+/// Empty results remove the pack from the file.
+pub fn write_progress(path: &Path, id: &str, results: &[PuzzleResult]) -> Result<(), StatsError> {
+    let error = |error| StatsError::Io(path.to_path_buf(), error);
+    let mut contents: String = read_progress_file(path)?
+        .lines()
+        .filter(|line| line.split_once('\t').map(|(line_id, _)| line_id) != Some(id))
+        .flat_map(|line| [line, "\n"])
+        .collect();
+    if !results.is_empty() {
+        let results: Vec<String> = results
+            .iter()
+            .map(|result| result.map_or("x".to_string(), |guesses| guesses.to_string()))
+            .collect();
+        contents.push_str(&format!("{}\t{}\n", id, results.join(",")));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(error)?;
+    }
+    std::fs::write(path, contents).map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACK: &str = "#! name: Kitchen week\n\
+                        # a comment\n\
+                        Breakfast | Toast\n\
+                        \n\
+                        Dessert | crepe | tries: 4 | hint: thin and French\n";
+
+    #[test]
+    fn test_parse_pack() {
+        let pack: Pack = PACK.parse().unwrap();
+        assert_eq!(pack.name.as_deref(), Some("Kitchen week"));
+        assert_eq!(
+            pack.puzzles,
+            [
+                PackPuzzle {
+                    title: "Breakfast".to_string(),
+                    word: "toast".to_string(),
+                    tries: None,
+                    hint: None,
+                },
+                PackPuzzle {
+                    title: "Dessert".to_string(),
+                    word: "crepe".to_string(),
+                    tries: Some(4),
+                    hint: Some("thin and French".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encoded_pack() {
+        let pack: Pack = PACK.parse().unwrap();
+        let file = pack.to_file(true);
+        assert!(!file.contains("toast") && !file.contains("crepe"));
+        assert_eq!(file.parse::<Pack>().unwrap(), pack);
+        assert_eq!(pack.to_file(false).parse::<Pack>().unwrap(), pack);
+    }
+
+    #[test]
+    fn test_invalid_packs() {
+        let error = |contents: &str| contents.parse::<Pack>().unwrap_err().to_string();
+        assert_eq!(error("toast\n"), "line 1: expected `title | word`");
+        assert_eq!(
+            error("a | toast\nb | bread\nc | toast\n"),
+            "line 3: the word of line 1 again"
+        );
+        assert_eq!(
+            error("a | toast\n\nb | cheese\n"),
+            "line 3: the word has 6 letters, the first one has 5"
+        );
+        assert_eq!(
+            error("a | toast | tries: 0\n"),
+            "line 1: invalid number of tries `0`"
+        );
+        assert_eq!(
+            error("a | toast | colour: red\n"),
+            "line 1: unknown field `colour`, expected `tries` or `hint`"
+        );
+        assert_eq!(error("a | to-st\n"), "line 1: `to-st` is not a word");
+        assert_eq!(
+            error("#! codes: yes\na | toast\n"),
+            "line 2: invalid challenge code"
+        );
+        assert_eq!(
+            error("#! name: Empty\n# nothing\n"),
+            "the pack contains no puzzles"
+        );
+    }
+
+    #[test]
+    fn test_progress() {
+        let path = std::env::temp_dir()
+            .join(format!("rordle-pack-{}", std::process::id()))
+            .join("packs");
+        let _ = std::fs::remove_file(&path);
+        let pack: Pack = PACK.parse().unwrap();
+        let id = pack.id();
+        assert_eq!(read_progress(&path, &id).unwrap(), []);
+        write_progress(&path, "other", &[Some(2)]).unwrap();
+        write_progress(&path, &id, &[Some(3)]).unwrap();
+        write_progress(&path, &id, &[Some(3), None]).unwrap();
+        assert_eq!(read_progress(&path, &id).unwrap(), [Some(3), None]);
+        assert_eq!(read_progress(&path, "other").unwrap(), [Some(2)]);
+        write_progress(&path, &id, &[]).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "other\t2\n");
+        // another word is another pack
+        let changed: Pack = PACK.replace("crepe", "crumb").parse().unwrap();
+        assert_ne!(changed.id(), id);
+    }
+}
@@ -0,0 +1,193 @@
+//! `rordle --pack FILE`: plays the puzzles of a pack one after another.
+//! The results are stored after each puzzle, so a pack can be stopped and
+//! continued later.
+
+use crate::cli::{game_options, hint_budget, language, players, ListArgs};
+use crate::error::RordleError;
+use crate::recorder::Recorder;
+use crate::{finish_game, load_word_list, new_game, play_game};
+use clap::ArgMatches;
+use rordle_core::config::Config;
+use rordle_core::i18n::{self, tr};
+use rordle_core::pack::{Pack, PackPuzzle, PuzzleResult};
+use std::io::stdin;
+use std::path::Path;
+use std::time::Instant;
+
+/// Label of puzzle `i` above the board, e.g.
+/// `Kitchen week  Puzzle 2/7: Dessert  (hint: thin and French)`.
+fn puzzle_label(pack: &Pack, i: usize) -> String {
+    let puzzle = &pack.puzzles[i];
+    let mut label = tr(
+        "pack-puzzle",
+        &[
+            ("number", &(i + 1)),
+            ("count", &pack.puzzles.len()),
+            ("title", &puzzle.title),
+        ],
+    );
+    if let Some(name) = &pack.name {
+        label = format!("{}  {}", name, label);
+    }
+    if let Some(hint) = &puzzle.hint {
+        label = format!("{}  ({})", label, tr("pack-hint", &[("hint", hint)]));
+    }
+    label
+}
+
+fn tries(puzzle: &PackPuzzle, max_tries: u16) -> u16 {
+    puzzle.tries.unwrap_or(max_tries)
+}
+
+/// Lines summing up a finished pack: the result of each puzzle, e.g.
+/// ` 1. Breakfast  toast  3/6`, then how many were solved.
+fn summary(pack: &Pack, results: &[PuzzleResult], max_tries: u16) -> Vec<String> {
+    let mut lines: Vec<String> = pack
+        .puzzles
+        .iter()
+        .zip(results)
+        .enumerate()
+        .map(|(i, (puzzle, result))| {
+            let guesses = result.map_or("x".to_string(), |guesses| guesses.to_string());
+            format!(
+                "{:>2}. {}  {}  {}/{}",
+                i + 1,
+                puzzle.title,
+                puzzle.word,
+                guesses,
+                tries(puzzle, max_tries)
+            )
+        })
+        .collect();
+    let solved: Vec<usize> = results.iter().flatten().copied().collect();
+    lines.push(tr(
+        "pack-summary",
+        &[("solved", &solved.len()), ("count", &results.len())],
+    ));
+    if !solved.is_empty() {
+        let average = solved.iter().sum::<usize>() as f64 / solved.len() as f64;
+        lines.push(tr(
+            "pack-average",
+            &[("average", &format!("{:.1}", average))],
+        ));
+    }
+    lines
+}
+
+/// Asks whether to go on with the next puzzle, `false` if the player
+/// stops or the input ends.
+fn next_puzzle() -> bool {
+    println!("{}", tr("pack-next", &[]));
+    let mut line = String::new();
+    match stdin().read_line(&mut line) {
+        Ok(0) | Err(_) => false,
+        Ok(_) => !line.trim().eq_ignore_ascii_case("q"),
+    }
+}
+
+/// Plays the pack in `file` from the first puzzle not finished before.
+/// Exits with 0 once the pack is finished, with 4 if it was stopped.
+pub(crate) fn play_pack(
+    matches: &ArgMatches,
+    config: &Config,
+    file: &str,
+) -> Result<i32, RordleError> {
+    i18n::set_language(language(matches, config));
+    let pack =
+        Pack::read(Path::new(file)).map_err(|error| RordleError::Pack(file.into(), error))?;
+    let word_list = load_word_list(&ListArgs::from_matches(matches), config, false)?;
+    let options = game_options(matches, config).hint_budget(hint_budget(matches));
+    let recorder = Recorder::new(matches, config);
+    let id = pack.id();
+    let mut results = recorder.pack_progress(&id)?;
+    let count = pack.puzzles.len();
+    if results.len() >= count {
+        println!("{}", tr("pack-restart", &[]));
+        results.clear();
+    }
+    let text = matches.is_present("stdin");
+    for i in results.len()..count {
+        let puzzle = &pack.puzzles[i];
+        let options = options.clone().max_tries(tries(puzzle, options.max_tries));
+        let mut game_state = new_game(options, word_list.clone(), puzzle.word.clone())?;
+        game_state.list_name = Some(puzzle_label(&pack, i));
+        game_state.players = players(matches);
+        game_state.team = matches.is_present("team");
+        if text {
+            // there is no board to show the label
+            println!("{}", puzzle_label(&pack, i));
+        }
+        let start = Instant::now();
+        let game_state = play_game(game_state, matches, config, false)?;
+        if !game_state.over() {
+            println!(
+                "{}",
+                tr("pack-stopped", &[("played", &i), ("count", &count)])
+            );
+            return Ok(4);
+        }
+        finish_game(
+            &game_state,
+            matches,
+            config,
+            Some("pack"),
+            None,
+            start.elapsed(),
+        );
+        results.push(game_state.won().then_some(game_state.guesses.len()));
+        recorder.save_pack_progress(&id, &results)?;
+        println!(
+            "{}",
+            tr("pack-complete", &[("number", &(i + 1)), ("count", &count)])
+        );
+        // plain text games go on with the next line of input
+        if i + 1 < count && !text && !next_puzzle() {
+            println!(
+                "{}",
+                tr("pack-stopped", &[("played", &(i + 1)), ("count", &count)])
+            );
+            return Ok(4);
+        }
+    }
+    println!();
+    for line in summary(&pack, &results, options.max_tries) {
+        println!("{}", line);
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack() -> Pack {
+        "#! name: Kitchen week\n\
+         Breakfast | toast\n\
+         Dessert | crepe | tries: 4 | hint: thin and French\n\
+         Dinner | pasta\n"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_puzzle_label() {
+        assert_eq!(
+            puzzle_label(&pack(), 1),
+            "Kitchen week  Puzzle 2/3: Dessert  (hint: thin and French)"
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(
+            summary(&pack(), &[Some(3), None, Some(4)], 6),
+            [
+                " 1. Breakfast  toast  3/6",
+                " 2. Dessert  crepe  x/4",
+                " 3. Dinner  pasta  4/6",
+                "Pack complete: 2 of 3 puzzles solved",
+                "3.5 guesses on average",
+            ]
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Spreading the work of the solver over threads. Built without the
+//! `parallel` feature, or with a single job, everything runs on the calling
+//! thread. Either way the results are the same: work is split into chunks
+//! whose results are put back together in order.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of threads to use, 0 for one per available core.
+static JOBS: AtomicUsize = AtomicUsize::new(0);
+
+/// Fewest items worth a thread of their own, fewer are cheaper to do on
+/// the calling thread than to hand over.
+const MIN_CHUNK: usize = 16;
+
+/// Sets the number of threads used, 0 for one per available core.
+pub fn set_jobs(jobs: usize) {
+    JOBS.store(jobs, Ordering::Relaxed);
+}
+
+/// Number of threads used.
+pub fn jobs() -> usize {
+    if !cfg!(feature = "parallel") {
+        return 1;
+    }
+    match JOBS.load(Ordering::Relaxed) {
+        0 => std::thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+        jobs => jobs,
+    }
+}
+
+/// `items` mapped with `f`, in order, split over up to `jobs()` threads.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let jobs = jobs().min(items.len() / MIN_CHUNK);
+    if jobs <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let f = &f;
+    std::thread::scope(|scope| {
+        let chunks: Vec<_> = items
+            .chunks(items.len().div_ceil(jobs))
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        chunks
+            .into_iter()
+            .flat_map(|chunk| chunk.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map() {
+        let items: Vec<usize> = (0..1000).collect();
+        let squares: Vec<usize> = items.iter().map(|i| i * i).collect();
+        for jobs in [1, 3, 8] {
+            set_jobs(jobs);
+            assert_eq!(map(&items, |i| i * i), squares);
+        }
+        set_jobs(0);
+        assert!(jobs() >= 1);
+        assert_eq!(map(&items[..3], |i| i + 1), [1, 2, 3]);
+        assert_eq!(map(&[] as &[usize], |i| i + 1), []);
+    }
+}
@@ -0,0 +1,168 @@
+//! Where rordle keeps what it saves. Every file of a player is found
+//! through [`path`], so none ends up outside their profile. The files are
+//! kept in the data directory, `$XDG_DATA_HOME/rordle`, or in the directory
+//! of the `stats_path` of the config file. With a profile, see
+//! [`Config::profile`], the files of a player are kept in `profiles/NAME`
+//! of it instead, so players sharing an account keep their stats apart.
+
+use crate::config::Config;
+use std::path::PathBuf;
+
+/// Directory the profiles are kept in, within the data directory.
+const PROFILES: &str = "profiles";
+
+/// A file saved by rordle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFile {
+    /// The records of the games played, also the history and whether the
+    /// puzzle of the day was played.
+    Stats,
+    /// The progress of the packs played.
+    Packs,
+    /// The hardcore run going on.
+    Hardcore,
+    /// The results not sent to a leaderboard yet.
+    LeaderboardQueue,
+    /// The results kept by `rordle serve-leaderboard`.
+    Leaderboard,
+    /// The best openers of the word lists, see [`crate::opener`].
+    Openers,
+}
+
+impl DataFile {
+    /// Name of the file within the directory of a profile.
+    fn name(self) -> &'static str {
+        match self {
+            DataFile::Stats => "stats",
+            DataFile::Packs => "packs",
+            DataFile::Hardcore => "hardcore",
+            DataFile::LeaderboardQueue => "leaderboard-queue",
+            DataFile::Leaderboard => "leaderboard.json",
+            DataFile::Openers => "openers",
+        }
+    }
+}
+
+/// The default data directory, `$XDG_DATA_HOME/rordle`. `None` if neither
+/// `XDG_DATA_HOME` nor `HOME` is set.
+pub fn default_data_dir() -> Option<PathBuf> {
+    let data_dir = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?)
+            .join(".local")
+            .join("share"),
+    };
+    Some(data_dir.join("rordle"))
+}
+
+/// The data directory of `config`, the directory of its stats file if set.
+pub fn data_dir(config: &Config) -> Option<PathBuf> {
+    match &config.stats_path {
+        Some(path) => Some(path.parent().map(PathBuf::from).unwrap_or_default()),
+        None => default_data_dir(),
+    }
+}
+
+/// Directory of the files of `profile`.
+pub fn profile_dir(config: &Config, profile: &str) -> Option<PathBuf> {
+    data_dir(config).map(|dir| dir.join(PROFILES).join(profile))
+}
+
+/// Location of `file` for the profile of `config`. `None` if there is no
+/// data directory.
+pub fn path(config: &Config, file: DataFile) -> Option<PathBuf> {
+    let name = match (file, &config.stats_path) {
+        (DataFile::Stats, Some(path)) => path.file_name()?.to_str()?,
+        _ => file.name(),
+    };
+    let dir = match &config.profile {
+        Some(profile) => profile_dir(config, profile)?,
+        None => data_dir(config)?,
+    };
+    Some(dir.join(name))
+}
+
+/// Names of the profiles which saved anything, sorted.
+pub fn profiles(config: &Config) -> Vec<String> {
+    let entries = data_dir(config).and_then(|dir| std::fs::read_dir(dir.join(PROFILES)).ok());
+    let mut names: Vec<String> = entries
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| validate_profile(name).is_ok())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Checks the name of a profile: letters, digits, `-` and `_`, so it is
+/// the name of a directory in every file system.
+pub fn validate_profile(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.chars().count() > 32 {
+        Err("a profile name has 1 to 32 characters".to_string())
+    } else if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        Err(format!(
+            "profile name `{}` may only have letters, digits, - and _",
+            name
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_path() {
+        let mut config = Config::default();
+        config.stats_path = Some(PathBuf::from("/data/games"));
+        assert_eq!(
+            path(&config, DataFile::Stats),
+            Some(PathBuf::from("/data/games"))
+        );
+        assert_eq!(
+            path(&config, DataFile::Packs),
+            Some(PathBuf::from("/data/packs"))
+        );
+        config.profile = Some("alice".to_string());
+        let files = [
+            DataFile::Stats,
+            DataFile::Packs,
+            DataFile::Hardcore,
+            DataFile::LeaderboardQueue,
+            DataFile::Leaderboard,
+            DataFile::Openers,
+        ];
+        for file in files {
+            let file = path(&config, file).unwrap();
+            assert_eq!(file.parent(), Some(Path::new("/data/profiles/alice")));
+        }
+    }
+
+    #[test]
+    fn test_profiles() {
+        let dir = std::env::temp_dir().join(format!("rordle-profiles-{}", std::process::id()));
+        let mut config = Config::default();
+        config.stats_path = Some(dir.join("stats"));
+        assert_eq!(profiles(&config), Vec::<String>::new());
+        for name in ["bob", "alice", ".hidden"] {
+            std::fs::create_dir_all(dir.join(PROFILES).join(name)).unwrap();
+        }
+        std::fs::write(dir.join(PROFILES).join("file"), "").unwrap();
+        assert_eq!(profiles(&config), ["alice", "bob"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(validate_profile("Zoë_2").is_ok());
+        for name in ["", "../bob", "a b", ".", &"x".repeat(33)] {
+            assert!(validate_profile(name).is_err(), "{}", name);
+        }
+    }
+}
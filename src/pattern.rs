@@ -0,0 +1,433 @@
+//! Regular expressions restricting the solutions of a game, e.g. `^s.*e$`
+//! for words starting with `s` and ending with `e`.
+//!
+//! Only the common subset is supported: literal letters, `.`, classes like
+//! `[aeiou]`, `[^a-m]`, the anchors `^` and `$`, groups `(...)`,
+//! alternatives `a|b` and the repetitions `*`, `+`, `?`, `{n}`, `{n,}` and
+//! `{n,m}`, of at most [`MAX_REPETITION`]. `\` makes the next character
+//! literal. Words are lowercase, so patterns match regardless of case.
+
+use std::str::FromStr;
+
+/// Most repetitions `{n}`, `{n,}` and `{n,m}` may ask for, more letters
+/// than any word has.
+pub const MAX_REPETITION: usize = 64;
+
+/// Errors parsing a pattern. Positions count characters from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// A `(` is never closed.
+    UnclosedGroup,
+    /// A `)` closes no group.
+    UnopenedGroup {
+        /// Position of the `)`.
+        position: usize,
+    },
+    /// A `[` is never closed.
+    UnclosedClass,
+    /// A class range does not go from a lower to a higher letter.
+    InvalidRange {
+        /// The range as written, e.g. `z-a`.
+        range: String,
+    },
+    /// A repetition follows nothing it could repeat.
+    NothingToRepeat {
+        /// Position of the repetition.
+        position: usize,
+    },
+    /// A `{` is not followed by `n}`, `n,}` or `n,m}` with `n <= m`.
+    InvalidRepetition {
+        /// Position of the `{`.
+        position: usize,
+    },
+    /// A repetition asks for more than [`MAX_REPETITION`].
+    RepetitionTooLong {
+        /// Position of the `{`.
+        position: usize,
+    },
+    /// The pattern ends with a `\`.
+    TrailingBackslash,
+}
+
+impl std::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PatternError::UnclosedGroup => write!(f, "unclosed group, missing `)`"),
+            PatternError::UnopenedGroup { position } => {
+                write!(f, "`)` at position {} closes no group", position + 1)
+            }
+            PatternError::UnclosedClass => write!(f, "unclosed class, missing `]`"),
+            PatternError::InvalidRange { range } => write!(f, "invalid range `{}`", range),
+            PatternError::NothingToRepeat { position } => {
+                write!(f, "nothing to repeat at position {}", position + 1)
+            }
+            PatternError::InvalidRepetition { position } => {
+                write!(f, "invalid repetition at position {}", position + 1)
+            }
+            PatternError::RepetitionTooLong { position } => write!(
+                f,
+                "repetition at position {} is longer than any word, at most {}",
+                position + 1,
+                MAX_REPETITION
+            ),
+            PatternError::TrailingBackslash => write!(f, "pattern ends with `\\`"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Literal(char),
+    Any,
+    /// Letters in one of the ranges, or in none of them if negated.
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    Start,
+    End,
+    Concat(Vec<Node>),
+    Alternatives(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+/// A parsed pattern, see the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// The pattern as given.
+    pub source: String,
+    root: Node,
+}
+
+impl FromStr for Pattern {
+    type Err = PatternError;
+
+    fn from_str(source: &str) -> Result<Pattern, PatternError> {
+        let chars: Vec<char> = source.to_lowercase().chars().collect();
+        let mut parser = Parser {
+            chars: &chars,
+            position: 0,
+        };
+        let root = parser.alternatives()?;
+        match parser.peek() {
+            Some(')') => Err(PatternError::UnopenedGroup {
+                position: parser.position,
+            }),
+            _ => Ok(Pattern {
+                source: source.to_string(),
+                root,
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Pattern {
+    /// Whether the pattern matches anywhere in `word`, unless anchored.
+    pub fn is_match(&self, word: &str) -> bool {
+        let chars: Vec<char> = word.to_lowercase().chars().collect();
+        (0..=chars.len()).any(|start| matches(&self.root, &chars, start, &mut |_| true))
+    }
+}
+
+struct Parser<'a> {
+    chars: &'a [char],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.position += 1;
+        c
+    }
+
+    fn alternatives(&mut self) -> Result<Node, PatternError> {
+        let mut alternatives = vec![self.concat()?];
+        while self.peek() == Some('|') {
+            self.position += 1;
+            alternatives.push(self.concat()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.pop().unwrap(),
+            _ => Node::Alternatives(alternatives),
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, PatternError> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            let position = self.position;
+            let node = match c {
+                '|' | ')' => break,
+                '*' | '+' | '?' | '{' => {
+                    let node = nodes
+                        .pop()
+                        .filter(|node| !matches!(node, Node::Start | Node::End))
+                        .ok_or(PatternError::NothingToRepeat { position })?;
+                    let (min, max) = self.repetition()?;
+                    Node::Repeat {
+                        node: Box::new(node),
+                        min,
+                        max,
+                    }
+                }
+                _ => self.atom()?,
+            };
+            nodes.push(node);
+        }
+        Ok(match nodes.len() {
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    /// Bounds of the repetition at the current position.
+    fn repetition(&mut self) -> Result<(usize, Option<usize>), PatternError> {
+        let position = self.position;
+        match self.next() {
+            Some('*') => return Ok((0, None)),
+            Some('+') => return Ok((1, None)),
+            Some('?') => return Ok((0, Some(1))),
+            _ => {}
+        }
+        let close = self.chars[self.position..]
+            .iter()
+            .position(|c| *c == '}')
+            .ok_or(PatternError::InvalidRepetition { position })?;
+        let bounds: String = self.chars[self.position..self.position + close]
+            .iter()
+            .collect();
+        self.position += close + 1;
+        let invalid = PatternError::InvalidRepetition { position };
+        let number = |text: &str| text.parse::<usize>().map_err(|_| invalid.clone());
+        let (min, max) = match bounds.split_once(',') {
+            None => (number(&bounds)?, Some(number(&bounds)?)),
+            Some((min, "")) => (number(min)?, None),
+            Some((min, max)) => (number(min)?, Some(number(max)?)),
+        };
+        match max {
+            Some(max) if max < min => Err(invalid),
+            _ if max.unwrap_or(min) > MAX_REPETITION => {
+                Err(PatternError::RepetitionTooLong { position })
+            }
+            _ => Ok((min, max)),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Node, PatternError> {
+        Ok(match self.next().unwrap() {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '(' => {
+                let node = self.alternatives()?;
+                if self.next() != Some(')') {
+                    return Err(PatternError::UnclosedGroup);
+                }
+                node
+            }
+            '[' => self.class()?,
+            '\\' => Node::Literal(self.next().ok_or(PatternError::TrailingBackslash)?),
+            c => Node::Literal(c),
+        })
+    }
+
+    /// A class after its `[`. A `]` right after the `[` or `[^` is a
+    /// letter of the class, as is a `-` at either end.
+    fn class(&mut self) -> Result<Node, PatternError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.position += 1;
+        }
+        let mut ranges = Vec::new();
+        loop {
+            let c = match self.next() {
+                None => return Err(PatternError::UnclosedClass),
+                Some(']') if !ranges.is_empty() => break,
+                Some('\\') => self.next().ok_or(PatternError::TrailingBackslash)?,
+                Some(c) => c,
+            };
+            let is_range = self.peek() == Some('-')
+                && !matches!(self.chars.get(self.position + 1), None | Some(']'));
+            if !is_range {
+                ranges.push((c, c));
+                continue;
+            }
+            self.position += 1;
+            let end = match self.next().unwrap() {
+                '\\' => self.next().ok_or(PatternError::TrailingBackslash)?,
+                end => end,
+            };
+            if end < c {
+                return Err(PatternError::InvalidRange {
+                    range: format!("{}-{}", c, end),
+                });
+            }
+            ranges.push((c, end));
+        }
+        Ok(Node::Class { negated, ranges })
+    }
+}
+
+/// Whether `node` matches `chars` from `position` such that `rest`
+/// accepts the position after it, trying the longest match first.
+fn matches(
+    node: &Node,
+    chars: &[char],
+    position: usize,
+    rest: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    let letter = |accept: &dyn Fn(char) -> bool| chars.get(position).is_some_and(|c| accept(*c));
+    match node {
+        Node::Literal(c) => letter(&|other| other == *c) && rest(position + 1),
+        Node::Any => letter(&|_| true) && rest(position + 1),
+        Node::Class { negated, ranges } => {
+            let in_class = |c: char| ranges.iter().any(|(from, to)| (*from..=*to).contains(&c));
+            letter(&|c| in_class(c) != *negated) && rest(position + 1)
+        }
+        Node::Start => position == 0 && rest(position),
+        Node::End => position == chars.len() && rest(position),
+        Node::Concat(nodes) => sequence(nodes, chars, position, rest),
+        Node::Alternatives(alternatives) => alternatives
+            .iter()
+            .any(|node| matches(node, chars, position, rest)),
+        Node::Repeat { node, min, max } => repeat(node, *min, *max, chars, position, rest),
+    }
+}
+
+fn sequence(
+    nodes: &[Node],
+    chars: &[char],
+    position: usize,
+    rest: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    match nodes.split_first() {
+        None => rest(position),
+        Some((first, others)) => matches(first, chars, position, &mut |next| {
+            sequence(others, chars, next, rest)
+        }),
+    }
+}
+
+/// Matches `node` at least `min` and at most `max` more times.
+fn repeat(
+    node: &Node,
+    min: usize,
+    max: Option<usize>,
+    chars: &[char],
+    position: usize,
+    rest: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    if max == Some(0) {
+        return rest(position);
+    }
+    let more = matches(node, chars, position, &mut |next| {
+        // an empty match can be repeated for the rest of `min`, repeating
+        // it more changes nothing
+        if next == position {
+            return min > 0 && rest(position);
+        }
+        repeat(
+            node,
+            min.saturating_sub(1),
+            max.map(|max| max - 1),
+            chars,
+            next,
+            rest,
+        )
+    });
+    more || (min == 0 && rest(position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(source: &str) -> Pattern {
+        source.parse().unwrap()
+    }
+
+    #[test]
+    fn test_matching() {
+        let cases = [
+            ("^s.*e$", "slate", true),
+            ("^s.*e$", "stale", true),
+            ("^s.*e$", "crane", false),
+            ("ll", "jolly", true),
+            ("ll", "world", false),
+            ("y$", "jolly", true),
+            ("^[^aeiou]", "crane", true),
+            ("^[^aeiou]", "olive", false),
+            ("^[a-c]", "crane", true),
+            ("^(ch|sh)", "shore", true),
+            ("^(ch|sh)", "store", false),
+            ("^.{5}$", "hello", true),
+            ("^.{5}$", "hell", false),
+            ("^h.{1,3}o", "hello", true),
+            ("^h.{1,2}o", "hello", false),
+            ("^h.{3,}$", "hell", true),
+            ("^h.{4,}$", "hell", false),
+            ("^colou?r$", "color", true),
+            ("^colou?r$", "colour", true),
+            ("^(a|)+b$", "aab", true),
+            ("^S", "slate", true),
+            ("^()*$", "", true),
+            ("^(a?){64}b$", "aab", true),
+            ("^(a|b?){3,}$", "a", true),
+            ("^(b?)+$", "", true),
+        ];
+        for (source, word, expected) in cases {
+            assert_eq!(
+                pattern(source).is_match(word),
+                expected,
+                "{} {}",
+                source,
+                word
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        let cases = [
+            ("(ab", PatternError::UnclosedGroup),
+            ("ab)", PatternError::UnopenedGroup { position: 2 }),
+            ("[ab", PatternError::UnclosedClass),
+            (
+                "[z-a]",
+                PatternError::InvalidRange {
+                    range: "z-a".to_string(),
+                },
+            ),
+            ("*a", PatternError::NothingToRepeat { position: 0 }),
+            ("^+", PatternError::NothingToRepeat { position: 1 }),
+            ("a{2", PatternError::InvalidRepetition { position: 1 }),
+            ("a{3,1}", PatternError::InvalidRepetition { position: 1 }),
+            (
+                "(a?){1000000}",
+                PatternError::RepetitionTooLong { position: 4 },
+            ),
+            ("a{1,65}", PatternError::RepetitionTooLong { position: 1 }),
+            ("a\\", PatternError::TrailingBackslash),
+        ];
+        for (source, error) in cases {
+            assert_eq!(source.parse::<Pattern>(), Err(error), "{}", source);
+        }
+    }
+}
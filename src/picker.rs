@@ -0,0 +1,219 @@
+//! Strategies picking the solution of a game from the solutions of a word
+//! list.
+
+use crate::date::Date;
+use rand::prelude::*;
+
+/// Picks solutions for games.
+pub trait WordPicker {
+    /// Picks one of `solutions`, which must not be empty.
+    fn pick(&mut self, solutions: &[String]) -> String;
+}
+
+impl<P: WordPicker + ?Sized> WordPicker for Box<P> {
+    fn pick(&mut self, solutions: &[String]) -> String {
+        (**self).pick(solutions)
+    }
+}
+
+/// Picks uniformly at random.
+#[derive(Debug, Clone)]
+pub struct RandomPicker {
+    rng: StdRng,
+}
+
+impl RandomPicker {
+    /// A picker seeded from the operating system.
+    pub fn new() -> RandomPicker {
+        RandomPicker {
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// A picker picking the same solutions for the same seed.
+    pub fn seeded(seed: u64) -> RandomPicker {
+        RandomPicker {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RandomPicker {
+    fn default() -> RandomPicker {
+        RandomPicker::new()
+    }
+}
+
+impl WordPicker for RandomPicker {
+    fn pick(&mut self, solutions: &[String]) -> String {
+        solutions.choose(&mut self.rng).unwrap().clone()
+    }
+}
+
+/// Picks at random, preferring solutions with some letters: one with `k`
+/// of them is `k + 1` times as likely as one with none.
+#[derive(Debug, Clone)]
+pub struct LetterBiasPicker {
+    rng: StdRng,
+    letters: Vec<char>,
+}
+
+impl LetterBiasPicker {
+    /// Prefers `letters`, picking like `random` otherwise.
+    pub fn new(random: RandomPicker, letters: Vec<char>) -> LetterBiasPicker {
+        LetterBiasPicker {
+            rng: random.rng,
+            letters,
+        }
+    }
+}
+
+impl WordPicker for LetterBiasPicker {
+    fn pick(&mut self, solutions: &[String]) -> String {
+        let weight = |word: &String| {
+            1 + self
+                .letters
+                .iter()
+                .filter(|letter| word.contains(**letter))
+                .count()
+        };
+        solutions
+            .choose_weighted(&mut self.rng, weight)
+            .unwrap()
+            .clone()
+    }
+}
+
+/// Picks the word of a day, the same for everyone using the same list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyPicker {
+    /// The day.
+    pub date: Date,
+}
+
+impl WordPicker for DailyPicker {
+    fn pick(&mut self, solutions: &[String]) -> String {
+        let index = self.date.days().rem_euclid(solutions.len() as i64);
+        solutions[index as usize].clone()
+    }
+}
+
+/// Picks the solutions in the order of the list, starting over after the
+/// last one, so every player of a tournament gets the same words.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SequentialPicker {
+    /// Index of the next solution.
+    pub next: usize,
+}
+
+impl WordPicker for SequentialPicker {
+    fn pick(&mut self, solutions: &[String]) -> String {
+        let word = solutions[self.next % solutions.len()].clone();
+        self.next += 1;
+        word
+    }
+}
+
+/// Picks with another picker from the solutions which were not played
+/// recently, including the ones picked by itself. Only if all solutions
+/// were played recently it picks from all of them.
+#[derive(Debug, Clone)]
+pub struct AvoidRecent<P: WordPicker> {
+    picker: P,
+    recent: Vec<String>,
+}
+
+impl<P: WordPicker> AvoidRecent<P> {
+    /// Avoids the `recent` solutions when picking with `picker`.
+    pub fn new(picker: P, recent: Vec<String>) -> AvoidRecent<P> {
+        AvoidRecent { picker, recent }
+    }
+}
+
+impl<P: WordPicker> WordPicker for AvoidRecent<P> {
+    fn pick(&mut self, solutions: &[String]) -> String {
+        let fresh: Vec<String> = solutions
+            .iter()
+            .filter(|word| !self.recent.contains(word))
+            .cloned()
+            .collect();
+        let word = if fresh.is_empty() {
+            self.picker.pick(solutions)
+        } else {
+            self.picker.pick(&fresh)
+        };
+        self.recent.push(word.clone());
+        word
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solutions(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn test_random_picker_is_seedable() {
+        let solutions = solutions(&["cigar", "rebut", "sissy", "humph", "awake"]);
+        let picks = |picker: &mut RandomPicker| -> Vec<String> {
+            (0..10).map(|_| picker.pick(&solutions)).collect()
+        };
+        let first = picks(&mut RandomPicker::seeded(7));
+        assert_eq!(first, picks(&mut RandomPicker::seeded(7)));
+        assert!(first.iter().all(|word| solutions.contains(word)));
+    }
+
+    #[test]
+    fn test_letter_bias_picker() {
+        let solutions = solutions(&["pizza", "plumb"]);
+        let mut picker = LetterBiasPicker::new(RandomPicker::seeded(3), vec!['z', 'i', 'a']);
+        let pizzas = (0..200)
+            .filter(|_| picker.pick(&solutions) == "pizza")
+            .count();
+        // four times as likely as plumb
+        assert!(pizzas > 130, "{}", pizzas);
+        assert!(pizzas < 200);
+    }
+
+    #[test]
+    fn test_daily_picker() {
+        let solutions = solutions(&["cigar", "rebut", "sissy"]);
+        let pick = |days| {
+            DailyPicker {
+                date: Date::from_days(days),
+            }
+            .pick(&solutions)
+        };
+        assert_eq!(pick(0), "cigar");
+        assert_eq!(pick(1), "rebut");
+        assert_eq!(pick(3), "cigar");
+        assert_eq!(pick(-1), "sissy");
+    }
+
+    #[test]
+    fn test_sequential_picker_starts_over() {
+        let solutions = solutions(&["cigar", "rebut", "sissy"]);
+        let mut picker = SequentialPicker { next: 1 };
+        let picks: Vec<String> = (0..4).map(|_| picker.pick(&solutions)).collect();
+        assert_eq!(picks, vec!["rebut", "sissy", "cigar", "rebut"]);
+    }
+
+    #[test]
+    fn test_avoid_recent() {
+        let solutions = solutions(&["cigar", "rebut", "sissy"]);
+        let mut picker = AvoidRecent::new(SequentialPicker::default(), vec!["cigar".to_string()]);
+        assert_eq!(picker.pick(&solutions), "rebut");
+        // the picker only gets the fresh solutions
+        assert_eq!(picker.pick(&solutions), "sissy");
+        // all solutions were played recently
+        assert!(solutions.contains(&picker.pick(&solutions)));
+
+        let mut picker = AvoidRecent::new(RandomPicker::seeded(1), Vec::new());
+        let mut picks: Vec<String> = (0..3).map(|_| picker.pick(&solutions)).collect();
+        picks.sort();
+        assert_eq!(picks, solutions);
+    }
+}
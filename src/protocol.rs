@@ -0,0 +1,354 @@
+//! Newline delimited JSON protocol, used by `rordle --json` to let other
+//! programs play a game.
+//!
+//! Every line is a JSON object with a `type`. The frontend sends commands:
+//!
+//! - `{"type":"guess","word":"crane"}` guesses a word.
+//! - `{"type":"state"}` asks for a `state` event.
+//! - `{"type":"quit"}` ends the game without a result.
+//!
+//! rordle answers with events:
+//!
+//! - `{"type":"state","length":5,"max_tries":6,"guesses":[{"word":"crane","hits":["miss","contains","miss","miss","hit"]}],"over":false}`
+//!   is sent when the game starts and when asked for.
+//! - `{"type":"feedback","word":"crane","hits":["miss","contains","miss","miss","hit"]}`
+//!   is sent for every accepted guess. A hit is one of `hit`, `contains`
+//!   and `miss`.
+//! - `{"type":"error","kind":"invalid_word","message":"Word is not valid"}`
+//!   is sent for a rejected guess or a malformed command. The kind is one
+//!   of `wrong_length`, `invalid_character`, `invalid_word`,
+//!   `repeated_guess`, `missing_letter`, `misplaced_letter`, `game_over`,
+//!   `invalid_json` and `invalid_command`.
+//! - `{"type":"result","won":true,"word":"cigar","guesses":3}` is sent after
+//!   the feedback of the last guess, then the game ends.
+//!
+//! The game also ends at the end of the input.
+
+use crate::digraph;
+use crate::game::{GameError, GameState, HitInfo};
+use crate::json::Json;
+use std::io::{BufRead, Write};
+
+/// A command sent by the frontend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Guess a word.
+    Guess(String),
+    /// Send the state of the game.
+    State,
+    /// End the game.
+    Quit,
+}
+
+impl Command {
+    /// Parses a line of input, an `Err` is the `error` event to reply with.
+    pub fn parse(line: &str) -> Result<Command, Event> {
+        let invalid = |message: &str| Event::Error {
+            kind: "invalid_command",
+            message: message.to_string(),
+        };
+        let json = Json::parse(line).map_err(|message| Event::Error {
+            kind: "invalid_json",
+            message,
+        })?;
+        match json.get("type").and_then(Json::as_str) {
+            Some("guess") => match json.get("word").and_then(Json::as_str) {
+                Some(word) => Ok(Command::Guess(word.to_lowercase())),
+                None => Err(invalid("a guess needs a `word` string")),
+            },
+            Some("state") => Ok(Command::State),
+            Some("quit") => Ok(Command::Quit),
+            Some(other) => Err(invalid(&format!("unknown command `{}`", other))),
+            None => Err(invalid("a command needs a `type` string")),
+        }
+    }
+}
+
+/// An event sent to the frontend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Snapshot of the game.
+    State {
+        /// Number of letters of the solution.
+        length: usize,
+        /// Number of guesses before the game is lost.
+        max_tries: u16,
+        /// Confirmed guesses and their hits.
+        guesses: Vec<(String, Vec<HitInfo>)>,
+        /// Whether the game has ended.
+        over: bool,
+    },
+    /// Hits of an accepted guess.
+    Feedback {
+        /// The guess.
+        word: String,
+        /// Hit of each letter.
+        hits: Vec<HitInfo>,
+    },
+    /// A rejected guess or command.
+    Error {
+        /// Machine readable kind of the error.
+        kind: &'static str,
+        /// Description of the error.
+        message: String,
+    },
+    /// End of the game.
+    Result {
+        /// Whether the solution was found.
+        won: bool,
+        /// The solution.
+        word: String,
+        /// Number of guesses made.
+        guesses: usize,
+    },
+}
+
+fn hit_name(hit: &HitInfo) -> &'static str {
+    match hit {
+        HitInfo::Hit => "hit",
+        HitInfo::Contains => "contains",
+        HitInfo::Miss => "miss",
+        HitInfo::TooMany => "too_many",
+        HitInfo::None => "none",
+    }
+}
+
+fn hits_json(hits: &[HitInfo]) -> Json {
+    Json::Array(hits.iter().map(|hit| Json::from(hit_name(hit))).collect())
+}
+
+fn error_kind(error: &GameError) -> &'static str {
+    match error {
+        GameError::WrongLength { .. } => "wrong_length",
+        GameError::InvalidCharacter { .. } => "invalid_character",
+        GameError::InvalidWord => "invalid_word",
+        GameError::NotAnAnswer => "not_an_answer",
+        GameError::RepeatedGuess => "repeated_guess",
+        GameError::MissingLetter { .. } => "missing_letter",
+        GameError::MisplacedLetter { .. } => "misplaced_letter",
+        GameError::GameOver => "game_over",
+        // hints can not be bought over the protocol
+        GameError::NotEnoughHintPoints { .. } => "not_enough_hint_points",
+        GameError::NothingToReveal => "nothing_to_reveal",
+    }
+}
+
+impl Event {
+    /// Snapshot of `game_state`.
+    pub fn state(game_state: &GameState) -> Event {
+        Event::State {
+            length: game_state.word.chars().count(),
+            max_tries: game_state.max_tries,
+            guesses: (0..game_state.guesses.len())
+                .map(|i| {
+                    let guess = digraph::untokenize(&game_state.guesses[i]);
+                    (guess, game_state.get_guess_hits(i))
+                })
+                .collect(),
+            over: game_state.over(),
+        }
+    }
+
+    /// The event as a JSON object.
+    pub fn to_json(&self) -> Json {
+        match self {
+            Event::State {
+                length,
+                max_tries,
+                guesses,
+                over,
+            } => Json::object([
+                ("type", Json::from("state")),
+                ("length", Json::from(*length)),
+                ("max_tries", Json::from(*max_tries as usize)),
+                (
+                    "guesses",
+                    Json::Array(
+                        guesses
+                            .iter()
+                            .map(|(word, hits)| {
+                                Json::object([
+                                    ("word", Json::from(word.as_str())),
+                                    ("hits", hits_json(hits)),
+                                ])
+                            })
+                            .collect(),
+                    ),
+                ),
+                ("over", Json::from(*over)),
+            ]),
+            Event::Feedback { word, hits } => Json::object([
+                ("type", Json::from("feedback")),
+                ("word", Json::from(word.as_str())),
+                ("hits", hits_json(hits)),
+            ]),
+            Event::Error { kind, message } => Json::object([
+                ("type", Json::from("error")),
+                ("kind", Json::from(*kind)),
+                ("message", Json::from(message.as_str())),
+            ]),
+            Event::Result { won, word, guesses } => Json::object([
+                ("type", Json::from("result")),
+                ("won", Json::from(*won)),
+                ("word", Json::from(word.as_str())),
+                ("guesses", Json::from(*guesses)),
+            ]),
+        }
+    }
+}
+
+fn send<W: Write>(output: &mut W, event: &Event) -> std::io::Result<()> {
+    writeln!(output, "{}", event.to_json())?;
+    output.flush()
+}
+
+/// Plays `game_state` with the commands read from `input`, writing events
+/// to `output`, until the game is over, the frontend quits or the input
+/// ends.
+pub fn serve<R: BufRead, W: Write>(
+    game_state: &mut GameState,
+    input: R,
+    mut output: W,
+) -> std::io::Result<()> {
+    send(&mut output, &Event::state(game_state))?;
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let command = match Command::parse(&line) {
+            Ok(command) => command,
+            Err(error) => {
+                send(&mut output, &error)?;
+                continue;
+            }
+        };
+        match command {
+            Command::Guess(word) => match game_state.guess(word.clone()) {
+                Ok(_) => {
+                    let hits = game_state.get_guess_hits(game_state.guesses.len() - 1);
+                    send(&mut output, &Event::Feedback { word, hits })?;
+                    if game_state.over() {
+                        let result = Event::Result {
+                            won: game_state.won(),
+                            word: digraph::untokenize(&game_state.word),
+                            guesses: game_state.guesses.len(),
+                        };
+                        return send(&mut output, &result);
+                    }
+                }
+                Err(error) => {
+                    let event = Event::Error {
+                        kind: error_kind(&error),
+                        message: error.to_string(),
+                    };
+                    send(&mut output, &event)?;
+                }
+            },
+            Command::State => send(&mut output, &Event::state(game_state))?,
+            Command::Quit => break,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameOptions;
+
+    fn new_game() -> GameState {
+        let words = ["hello", "jolly", "holly"].iter().collect();
+        GameState::new(
+            "hello".to_string(),
+            words,
+            GameOptions::default().max_tries(2),
+        )
+        .unwrap()
+    }
+
+    fn run(game_state: &mut GameState, input: &str) -> Vec<String> {
+        let mut output = Vec::new();
+        serve(game_state, input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            Command::parse(r#"{"type":"guess","word":"Crane"}"#),
+            Ok(Command::Guess("crane".to_string()))
+        );
+        assert_eq!(Command::parse(r#"{"type":"state"}"#), Ok(Command::State));
+        assert!(matches!(
+            Command::parse("{"),
+            Err(Event::Error {
+                kind: "invalid_json",
+                ..
+            })
+        ));
+        for line in [r#"{"type":"guess"}"#, r#"{"type":"jump"}"#, "[]"] {
+            assert!(matches!(
+                Command::parse(line),
+                Err(Event::Error {
+                    kind: "invalid_command",
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_serve_won_game() {
+        let mut game_state = new_game();
+        let output = run(
+            &mut game_state,
+            "{\"type\":\"guess\",\"word\":\"abc\"}\n\n{\"type\":\"guess\",\"word\":\"hello\"}\n{\"type\":\"state\"}\n",
+        );
+        assert_eq!(
+            output,
+            vec![
+                r#"{"type":"state","length":5,"max_tries":2,"guesses":[],"over":false}"#,
+                r#"{"type":"error","kind":"wrong_length","message":"Word is not the correct length, 3 letters instead of 5"}"#,
+                r#"{"type":"feedback","word":"hello","hits":["hit","hit","hit","hit","hit"]}"#,
+                r#"{"type":"result","won":true,"word":"hello","guesses":1}"#,
+            ]
+        );
+        assert!(game_state.won());
+    }
+
+    #[test]
+    fn test_serve_lost_game_and_quit() {
+        let mut game_state = new_game();
+        let output = run(
+            &mut game_state,
+            "{\"type\":\"guess\",\"word\":\"jolly\"}\n{\"type\":\"guess\",\"word\":\"holly\"}\n",
+        );
+        assert_eq!(
+            output.last().unwrap(),
+            r#"{"type":"result","won":false,"word":"hello","guesses":2}"#
+        );
+
+        let mut game_state = new_game();
+        let output = run(
+            &mut game_state,
+            "{\"type\":\"guess\",\"word\":\"jolly\"}\n{\"type\":\"quit\"}\n{\"type\":\"state\"}\n",
+        );
+        assert_eq!(output.len(), 2);
+        assert!(!game_state.over());
+    }
+
+    #[test]
+    fn test_state_event() {
+        let mut game_state = new_game();
+        game_state.guess("jolly".to_string()).unwrap();
+        assert_eq!(
+            Event::state(&game_state).to_json().to_string(),
+            r#"{"type":"state","length":5,"max_tries":2,"guesses":[{"word":"jolly","hits":["miss","contains","hit","hit","miss"]}],"over":false}"#
+        );
+    }
+}
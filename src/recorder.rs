@@ -0,0 +1,94 @@
+//! Everything a game writes to disk goes through a `Recorder`, so that
+//! `--no-stats` turns all of it off in one place.
+
+use crate::commands::{data_path, stats_path};
+use crate::error::RordleError;
+use clap::ArgMatches;
+use rordle_core::config::Config;
+use rordle_core::date::Date;
+use rordle_core::pack::{read_progress, write_progress, PuzzleResult};
+use rordle_core::paths::DataFile;
+use rordle_core::stats::{
+    append_record, played_daily, read_records, read_run, write_run, GameRecord,
+};
+use std::path::PathBuf;
+
+/// Writes the records of played games and the progress in packs, unless
+/// the run is for practice.
+pub(crate) struct Recorder<'a> {
+    config: &'a Config,
+    practice: bool,
+}
+
+impl<'a> Recorder<'a> {
+    /// A recorder for the run of `matches`, doing nothing with `--no-stats`.
+    pub(crate) fn new(matches: &ArgMatches, config: &'a Config) -> Recorder<'a> {
+        Recorder {
+            config,
+            practice: matches.is_present("no-stats"),
+        }
+    }
+
+    /// Whether nothing is written.
+    pub(crate) fn practice(&self) -> bool {
+        self.practice
+    }
+
+    /// Appends `record` to the stats file.
+    pub(crate) fn record(&self, record: &GameRecord) -> Result<(), RordleError> {
+        if self.practice {
+            return Ok(());
+        }
+        Ok(append_record(&stats_path(self.config)?, record)?)
+    }
+
+    /// Whether the daily puzzle of `date` was recorded before, which makes
+    /// playing it again a replay. Practice runs never record it.
+    pub(crate) fn played_daily(&self, date: Date) -> Result<bool, RordleError> {
+        Ok(played_daily(
+            &read_records(&stats_path(self.config)?)?,
+            date,
+        ))
+    }
+
+    /// The progress file of packs, next to the stats file.
+    fn progress_path(&self) -> Result<PathBuf, RordleError> {
+        data_path(self.config, DataFile::Packs)
+    }
+
+    /// The file of the hardcore run, next to the stats file.
+    fn run_path(&self) -> Result<PathBuf, RordleError> {
+        data_path(self.config, DataFile::Hardcore)
+    }
+
+    /// Number of hardcore games won in a row.
+    pub(crate) fn hardcore_run(&self) -> Result<u32, RordleError> {
+        Ok(read_run(&self.run_path()?)?)
+    }
+
+    /// Saves the hardcore run after a hardcore game.
+    pub(crate) fn save_hardcore_run(&self, run: u32) -> Result<(), RordleError> {
+        if self.practice {
+            return Ok(());
+        }
+        Ok(write_run(&self.run_path()?, run)?)
+    }
+
+    /// Results of the puzzles of pack `id` finished before. Practice runs
+    /// go on from there too, they only do not save their own results.
+    pub(crate) fn pack_progress(&self, id: &str) -> Result<Vec<PuzzleResult>, RordleError> {
+        Ok(read_progress(&self.progress_path()?, id)?)
+    }
+
+    /// Saves the results of the finished puzzles of pack `id`.
+    pub(crate) fn save_pack_progress(
+        &self,
+        id: &str,
+        results: &[PuzzleResult],
+    ) -> Result<(), RordleError> {
+        if self.practice {
+            return Ok(());
+        }
+        Ok(write_progress(&self.progress_path()?, id, results)?)
+    }
+}
@@ -0,0 +1,2026 @@
+use rordle_core::config::{Align, Config, Theme};
+use rordle_core::digraph;
+use rordle_core::game::{GameState, HitInfo, Knowledge};
+use rordle_core::i18n::tr;
+use rordle_core::opener::{openers, OpenerKey};
+use rordle_core::solver::{
+    assist, assist_opening, letter_frequencies, opening, possible_letters, rank_guesses,
+    remaining_counts, Assist, Weighting,
+};
+use rordle_core::words::KeyboardLayout;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+use termion::{color, style};
+
+/// Presentation settings of the game board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RenderOptions {
+    pub(crate) theme: Theme,
+    pub(crate) keyboard_layout: KeyboardLayout,
+    /// Show what is known about the word below the keyboard.
+    pub(crate) show_pattern: bool,
+    /// The game is a sandbox to try other guesses, see `GameState::sandbox`.
+    /// Its label above the board stands out.
+    pub(crate) exploring: bool,
+    /// Show the letters in uppercase, see `tile_letter`.
+    pub(crate) uppercase: bool,
+    /// Where the board is placed on the terminal.
+    pub(crate) layout: Layout,
+    /// Mirror the rows for a script written from right to left, `None` to
+    /// go by the alphabet, see `right_to_left`.
+    pub(crate) rtl: Option<bool>,
+    /// Mark the row taking the next guess in the margin left of the board.
+    pub(crate) row_marker: bool,
+    /// Show the rows not reached yet faint.
+    pub(crate) dim_future_rows: bool,
+    /// Number the rows in the margin left of the board, see
+    /// `row_number_width`.
+    pub(crate) row_numbers: bool,
+    /// Columns and rows of the terminal the board is fitted to, read once
+    /// by `on_terminal` so nothing else asks the terminal.
+    pub(crate) terminal: (u16, u16),
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            theme: Theme::default(),
+            keyboard_layout: KeyboardLayout::default(),
+            show_pattern: true,
+            exploring: false,
+            uppercase: true,
+            layout: Layout::default(),
+            rtl: None,
+            row_marker: false,
+            dim_future_rows: false,
+            row_numbers: true,
+            terminal: DEFAULT_SIZE,
+        }
+    }
+}
+
+/// Where a row of the board is in the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowState {
+    /// Holds a guess.
+    Committed,
+    /// Takes the next guess.
+    Active,
+    /// Not reached yet, or left empty when the game ended.
+    Future,
+}
+
+/// Where row `y` of the board is in the game.
+fn row_state(game_state: &GameState, y: usize) -> RowState {
+    if y < game_state.guesses.len() {
+        RowState::Committed
+    } else if y == game_state.guesses.len() && !game_state.over() {
+        RowState::Active
+    } else {
+        RowState::Future
+    }
+}
+
+/// Whether the board and the keyboard are mirrored, so the first letter
+/// of a word is on the right. Only the display is mirrored, guesses, hints
+/// and shared results keep the first letter first.
+pub(crate) fn right_to_left(game_state: &GameState, options: &RenderOptions) -> bool {
+    options
+        .rtl
+        .unwrap_or_else(|| game_state.alphabet.is_right_to_left())
+}
+
+/// The letter shown in column `x` of a row of `width` letters.
+fn letter_index(x: usize, width: usize, rtl: bool) -> usize {
+    if rtl {
+        width - 1 - x
+    } else {
+        x
+    }
+}
+
+/// A guess followed by its hints as text, `g` for a hit, `y` for a
+/// misplaced letter, `-` for a miss and `x` for a letter guessed too often
+/// with count hints, e.g. `crane -y--g`. The same pattern is accepted by
+/// `rordle solve`.
+pub(crate) fn plain_row(guess: &str, hits: &[HitInfo]) -> String {
+    let pattern: String = hits.iter().map(hit_char).collect();
+    format!("{} {}", digraph::untokenize(guess), pattern)
+}
+
+/// `c` as shown on a tile, uppercased with `uppercase`. Letters whose
+/// uppercase form has several characters, like the `SS` of `ß`, are kept as
+/// they are, which fit into a tile. Only the display changes, guesses are
+/// compared in lowercase.
+pub(crate) fn tile_letter(c: char, uppercase: bool) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(upper), None) if uppercase => upper,
+        _ => c,
+    }
+}
+
+/// The text of the tile of `c`, which is two or more letters for a
+/// digraph, see `rordle_core::digraph`.
+pub(crate) fn tile_text(c: char, uppercase: bool) -> String {
+    match digraph::text(c) {
+        Some(digraph) => digraph.chars().map(|c| tile_letter(c, uppercase)).collect(),
+        None => tile_letter(c, uppercase).to_string(),
+    }
+}
+
+/// `word` with its letters as shown on the tiles.
+pub(crate) fn tile_word(word: &str, uppercase: bool) -> String {
+    word.chars().map(|c| tile_text(c, uppercase)).collect()
+}
+
+/// Columns inside a cell of the board, more than one if a digraph is
+/// played.
+fn cell_width(game_state: &GameState) -> u16 {
+    game_state
+        .digraphs
+        .tokens()
+        .map(|token| tile_text(token, false).chars().count() as u16)
+        .max()
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Columns of the game board, from the first border to the last.
+fn board_width(game_state: &GameState) -> u16 {
+    game_state.letters().len() as u16 * (cell_width(game_state) + 1) + 1
+}
+
+/// Columns of the row `y` of the keyboard, which is indented by one more
+/// column than the row above it.
+fn keyboard_row_width(y: usize, row: &[char]) -> u16 {
+    y as u16
+        + row
+            .iter()
+            .map(|c| tile_text(*c, false).chars().count() as u16 + 1)
+            .sum::<u16>()
+}
+
+/// Letter of a hint in the patterns of `plain_row`.
+pub(crate) fn hit_char(hit: &HitInfo) -> char {
+    match hit {
+        HitInfo::Hit => 'g',
+        HitInfo::Contains => 'y',
+        HitInfo::Miss => '-',
+        HitInfo::TooMany => 'x',
+        HitInfo::None => '_',
+    }
+}
+
+/// Time between revealing two tiles of a guess.
+const REVEAL_DELAY: Duration = Duration::from_millis(120);
+
+/// Columns left of the game board, for the names of the players.
+const LABEL_WIDTH: u16 = 9;
+
+/// Columns of the numbers of the rows left of the board, with the space
+/// after them. As wide as the number of the last row, 0 without them.
+fn row_number_width(game_state: &GameState, options: &RenderOptions) -> u16 {
+    if options.row_numbers {
+        game_state.max_tries.to_string().len() as u16 + 1
+    } else {
+        0
+    }
+}
+
+/// Columns left of the game board, for the names of the players and the
+/// numbers of the rows.
+fn margin_width(game_state: &GameState, options: &RenderOptions) -> u16 {
+    LABEL_WIDTH + row_number_width(game_state, options)
+}
+
+/// The number of row `y` of the board, right aligned to `width` columns
+/// and faint.
+fn row_number(y: usize, width: u16) -> String {
+    format!(
+        "{}{:>width$}{}",
+        style::Faint,
+        y + 1,
+        style::Reset,
+        width = width as usize
+    )
+}
+
+/// Rows above the game board, for the name of the list and whose turn it is.
+const HEADER_HEIGHT: u16 = 3;
+
+/// Size of the terminal if it can not be asked.
+pub(crate) const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// Where the board is placed on the terminal, from `--align` and the
+/// margins or the config file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Layout {
+    pub(crate) align: Align,
+    /// Columns left of and rows above the board with `Align::Custom`.
+    pub(crate) margin: (u16, u16),
+}
+
+impl Layout {
+    /// The layout set in the config file.
+    pub(crate) fn from_config(config: &Config) -> Layout {
+        Layout {
+            align: config.align,
+            margin: (config.margin_left, config.margin_top),
+        }
+    }
+
+    /// Column and row of the top left corner of a block of `size` on a
+    /// `terminal` of that size, counted from 1. Margins which would push the
+    /// block off the terminal are cut down until it fits, a block larger
+    /// than the terminal is placed in the top left corner.
+    pub(crate) fn origin(self, size: (u16, u16), terminal: (u16, u16)) -> (u16, u16) {
+        let room = (
+            terminal.0.saturating_sub(size.0),
+            terminal.1.saturating_sub(size.1),
+        );
+        let (left, top) = match self.align {
+            Align::TopLeft => (0, 0),
+            Align::Center => (room.0 / 2, room.1 / 2),
+            Align::Custom => (self.margin.0.min(room.0), self.margin.1.min(room.1)),
+        };
+        (left + 1, top + 1)
+    }
+}
+
+/// Rows of the on-screen keyboard as shown on the terminal: fitting next
+/// to the labels and mirrored for a script written from right to left.
+/// The columns of the keys follow from the rows, see `keyboard_row_width`.
+pub(crate) fn keyboard_rows(game_state: &GameState, options: &RenderOptions) -> Vec<Vec<char>> {
+    let max_width = options
+        .terminal
+        .0
+        .saturating_sub(margin_width(game_state, options)) as usize;
+    let mut rows = game_state
+        .alphabet
+        .keyboard_rows(options.keyboard_layout, max_width);
+    if right_to_left(game_state, options) {
+        rows.iter_mut().for_each(|row| row.reverse());
+    }
+    rows
+}
+
+/// Column of the left edge and row of the first line of the game board,
+/// which everything else on the screen is placed relative to. The block
+/// placed by the layout is the board with the labels of the players and of
+/// the list, the numbers of the rows, the keyboard and the lines below it up
+/// to the error.
+fn board_origin(game_state: &GameState, options: &RenderOptions) -> (u16, u16) {
+    let (left, top) = options
+        .layout
+        .origin(board_size(game_state, options), options.terminal);
+    (
+        left + margin_width(game_state, options),
+        top + HEADER_HEIGHT,
+    )
+}
+
+/// Columns and rows of the block placed by the layout, see `board_origin`.
+fn board_size(game_state: &GameState, options: &RenderOptions) -> (u16, u16) {
+    let keyboard_rows = keyboard_rows(game_state, options);
+    let keyboard_width = keyboard_rows
+        .iter()
+        .enumerate()
+        .map(|(y, row)| keyboard_row_width(y, row))
+        .max()
+        .unwrap_or(0);
+    (
+        margin_width(game_state, options) + board_width(game_state).max(keyboard_width),
+        HEADER_HEIGHT + game_state.max_tries * 2 + keyboard_rows.len() as u16 + 2,
+    )
+}
+
+/// How much of the game is shown, the most which fits on the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Density {
+    /// The board of tiles with the keyboard below it.
+    Normal,
+    /// A line for each guess, see `compact_lines`.
+    Compact,
+    /// Only a message asking for a larger terminal.
+    Message,
+}
+
+/// The density for the terminal of `options`. The size of the whole game is
+/// checked, not of the rows guessed so far, so it stays the same while the
+/// game goes on.
+pub(crate) fn density(game_state: &GameState, options: &RenderOptions) -> Density {
+    let terminal = options.terminal;
+    let fits = |(width, height): (u16, u16)| width <= terminal.0 && height <= terminal.1;
+    if fits(board_size(game_state, options)) {
+        Density::Normal
+    } else if fits(compact_size(game_state, options)) {
+        Density::Compact
+    } else {
+        Density::Message
+    }
+}
+
+/// A color of the board. The named ones are the terminal's own and are
+/// exported with the colors of the default xterm palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Paint {
+    Green,
+    Yellow,
+    Black,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+impl Paint {
+    fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Paint::Green => (0, 205, 0),
+            Paint::Yellow => (205, 205, 0),
+            Paint::Black => (0, 0, 0),
+            Paint::White => (229, 229, 229),
+            Paint::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// The color in CSS, e.g. `#00cd00`.
+    pub(crate) fn css(self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    fn write_bg<W: Write>(self, stdout: &mut W) -> std::io::Result<()> {
+        match self {
+            Paint::Green => write!(stdout, "{}", color::Bg(color::Green)),
+            Paint::Yellow => write!(stdout, "{}", color::Bg(color::Yellow)),
+            Paint::Black => write!(stdout, "{}", color::Bg(color::Black)),
+            Paint::White => write!(stdout, "{}", color::Bg(color::White)),
+            Paint::Rgb(r, g, b) => write!(stdout, "{}", color::Bg(color::Rgb(r, g, b))),
+        }
+    }
+
+    fn write_fg<W: Write>(self, stdout: &mut W) -> std::io::Result<()> {
+        match self {
+            Paint::Green => write!(stdout, "{}", color::Fg(color::Green)),
+            Paint::Yellow => write!(stdout, "{}", color::Fg(color::Yellow)),
+            Paint::Black => write!(stdout, "{}", color::Fg(color::Black)),
+            Paint::White => write!(stdout, "{}", color::Fg(color::White)),
+            Paint::Rgb(r, g, b) => write!(stdout, "{}", color::Fg(color::Rgb(r, g, b))),
+        }
+    }
+}
+
+/// How a tile or key with a hint looks, on the terminal as in the HTML
+/// export. Without colors the terminal's own are used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct TileLook {
+    pub(crate) background: Option<Paint>,
+    pub(crate) foreground: Option<Paint>,
+    pub(crate) bold: bool,
+    /// Background and foreground swapped.
+    pub(crate) invert: bool,
+    pub(crate) underline: bool,
+    pub(crate) faint: bool,
+}
+
+/// The look of tiles with `hit_info` in `theme`.
+pub(crate) fn tile_look(hit_info: &HitInfo, theme: Theme) -> TileLook {
+    let colors = |background, foreground| TileLook {
+        background: Some(background),
+        foreground: Some(foreground),
+        ..TileLook::default()
+    };
+    match (hit_info, theme) {
+        (HitInfo::Hit, Theme::HighContrast) => colors(Paint::Rgb(245, 121, 58), Paint::Black),
+        (HitInfo::Contains, Theme::HighContrast) => colors(Paint::Rgb(133, 192, 249), Paint::Black),
+        (HitInfo::Hit, Theme::Monochrome) => TileLook {
+            bold: true,
+            invert: true,
+            ..TileLook::default()
+        },
+        (HitInfo::Contains, Theme::Monochrome) => TileLook {
+            underline: true,
+            ..TileLook::default()
+        },
+        (HitInfo::Miss, Theme::Monochrome) => TileLook {
+            faint: true,
+            ..TileLook::default()
+        },
+        // in the word, but not again: dark like a miss, in the color of a
+        // letter elsewhere
+        (HitInfo::TooMany, Theme::HighContrast) => colors(Paint::Black, Paint::Rgb(133, 192, 249)),
+        (HitInfo::TooMany, Theme::Monochrome) => TileLook {
+            underline: true,
+            faint: true,
+            ..TileLook::default()
+        },
+        (HitInfo::Hit, _) => colors(Paint::Green, Paint::Black),
+        (HitInfo::Contains, _) => colors(Paint::Yellow, Paint::Black),
+        (HitInfo::Miss, _) => colors(Paint::Black, Paint::White),
+        (HitInfo::TooMany, _) => colors(Paint::Black, Paint::Yellow),
+        (HitInfo::None, _) => TileLook::default(),
+    }
+}
+
+/// Steps of the brightness of `heat_look` above the darkest, for letters
+/// in none of the words still possible.
+const HEAT_STEPS: u8 = 4;
+
+/// The look of a key without a hint whose letter is in `frequency` of the
+/// words still possible, for `--assist`: the likelier, the brighter. Only
+/// shades of gray, dark like a miss, so the colors of the hints stay the
+/// ones standing out. In monochrome unlikely letters are dim and the ones
+/// of half the words or more bold.
+fn heat_look(frequency: f64, theme: Theme) -> TileLook {
+    if theme == Theme::Monochrome {
+        return TileLook {
+            faint: frequency < 0.1,
+            bold: frequency >= 0.5,
+            ..TileLook::default()
+        };
+    }
+    let step = (frequency.clamp(0.0, 1.0) * HEAT_STEPS as f64).round() as u8;
+    let gray = 40 + step * 24;
+    TileLook {
+        background: Some(Paint::Rgb(gray, gray, gray)),
+        foreground: Some(Paint::White),
+        ..TileLook::default()
+    }
+}
+
+fn write_hit_color<W: Write>(stdout: &mut W, hit_info: &HitInfo, theme: Theme) {
+    write_look(stdout, tile_look(hit_info, theme), theme);
+}
+
+fn write_look<W: Write>(stdout: &mut W, look: TileLook, theme: Theme) {
+    (|| {
+        if look == TileLook::default() {
+            return match theme {
+                Theme::Monochrome => write!(stdout, "{}", style::Reset),
+                _ => write!(
+                    stdout,
+                    "{}{}",
+                    color::Bg(color::Reset),
+                    color::Fg(color::Reset)
+                ),
+            };
+        }
+        if look.bold {
+            write!(stdout, "{}", style::Bold)?;
+        }
+        if look.invert {
+            write!(stdout, "{}", style::Invert)?;
+        }
+        if look.underline {
+            write!(stdout, "{}", style::Underline)?;
+        }
+        if look.faint {
+            write!(stdout, "{}", style::Faint)?;
+        }
+        if let Some(background) = look.background {
+            background.write_bg(stdout)?;
+        }
+        if let Some(foreground) = look.foreground {
+            foreground.write_fg(stdout)?;
+        }
+        Ok(())
+    })()
+    .unwrap();
+}
+
+/// How `theme` shows tiles with `hit_info`, e.g. `green`.
+pub(crate) fn tile_style(hit_info: &HitInfo, theme: Theme) -> String {
+    let id = match (hit_info, theme) {
+        (HitInfo::Hit, Theme::HighContrast) => "style-orange",
+        (HitInfo::Contains, Theme::HighContrast) => "style-blue",
+        (HitInfo::Hit, Theme::Monochrome) => "style-inverted",
+        (HitInfo::Contains, Theme::Monochrome) => "style-underlined",
+        (HitInfo::Miss | HitInfo::None, Theme::Monochrome) => "style-dim",
+        (HitInfo::TooMany, Theme::HighContrast) => "style-blue-on-black",
+        (HitInfo::TooMany, Theme::Monochrome) => "style-dim-underlined",
+        (HitInfo::TooMany, _) => "style-yellow-on-black",
+        (HitInfo::Hit, _) => "style-green",
+        (HitInfo::Contains, _) => "style-yellow",
+        (HitInfo::Miss | HitInfo::None, _) => "style-gray",
+    };
+    tr(id, &[])
+}
+
+pub(crate) fn render_game_state<W: Write>(
+    stdout: &mut W,
+    game_state: &GameState,
+    options: &RenderOptions,
+) {
+    let width = game_state.letters().len();
+    render_revealed(stdout, game_state, options, width);
+}
+
+/// Renders the last guess with one more tile revealed at a time.
+pub(crate) fn animate_last_guess<W: Write>(
+    stdout: &mut W,
+    game_state: &GameState,
+    options: &RenderOptions,
+) {
+    for revealed in 0..=game_state.letters().len() {
+        render_revealed(stdout, game_state, options, revealed);
+        stdout.flush().unwrap();
+        std::thread::sleep(REVEAL_DELAY);
+    }
+}
+
+/// Letters and hints of the row `y` of the board, a guess, the one being
+/// typed or an empty row. Only the first `revealed` tiles of the last guess
+/// show their hint, open tiles are `_`.
+pub(crate) fn board_row(
+    game_state: &GameState,
+    y: usize,
+    revealed: usize,
+) -> (Vec<char>, Vec<HitInfo>) {
+    let width = game_state.letters().len();
+    let guessed = game_state.guesses.len();
+    let mut letters: Vec<char> = if y < guessed {
+        game_state.guess_letters(y).to_vec()
+    } else if y == guessed {
+        game_state.current_guess.chars().collect()
+    } else {
+        Vec::new()
+    };
+    letters.resize(width, '_');
+    if y >= guessed {
+        for (x, letter) in letters.iter_mut().enumerate() {
+            if game_state.prefilled(x) {
+                *letter = game_state.letters()[x];
+            }
+        }
+    }
+
+    let hits = if y + 1 == guessed {
+        let mut hits = game_state.get_guess_hits(y);
+        hits.iter_mut()
+            .skip(revealed)
+            .for_each(|hit| *hit = HitInfo::None);
+        hits
+    } else if y < guessed {
+        game_state.get_guess_hits(y)
+    } else {
+        // prefilled letters are locked in as hits in every row
+        (0..width)
+            .map(|x| {
+                if game_state.prefilled(x) {
+                    HitInfo::Hit
+                } else {
+                    HitInfo::None
+                }
+            })
+            .collect()
+    };
+    (letters, hits)
+}
+
+/// The guesses of the board as lines of colored tiles, to be printed on the
+/// normal screen once the game is left. There is no row for the solution,
+/// an unguessed word stays hidden.
+pub(crate) fn board_lines(game_state: &GameState, options: &RenderOptions) -> Vec<String> {
+    let width = game_state.letters().len();
+    let cell = cell_width(game_state) as usize;
+    let rtl = right_to_left(game_state, options);
+    let numbers = row_number_width(game_state, options);
+    (0..game_state.guesses.len())
+        .map(|y| {
+            let (letters, hits) = board_row(game_state, y, width);
+            let mut line = Vec::new();
+            if numbers > 0 {
+                write!(line, "{} ", row_number(y, numbers - 1)).unwrap();
+            }
+            for x in 0..width {
+                let i = letter_index(x, width, rtl);
+                write_hit_color(&mut line, &hits[i], options.theme);
+                let letter = tile_text(letters[i], options.uppercase);
+                write!(line, " {:^cell$} ", letter, cell = cell).unwrap();
+                write!(line, "{}", style::Reset).unwrap();
+            }
+            String::from_utf8(line).unwrap()
+        })
+        .collect()
+}
+
+/// Renders the game, only the first `revealed` tiles of the last guess show
+/// their hit info.
+fn render_revealed<W: Write>(
+    stdout: &mut W,
+    game_state: &GameState,
+    options: &RenderOptions,
+    revealed: usize,
+) {
+    writeln!(stdout, "{}{}", termion::clear::All, termion::cursor::Hide).unwrap();
+    match density(game_state, options) {
+        Density::Normal => render_board(stdout, game_state, options, revealed),
+        Density::Compact => {
+            for (y, line) in compact_lines(game_state, options, revealed)
+                .iter()
+                .enumerate()
+            {
+                write!(stdout, "{}{}", termion::cursor::Goto(1, 1 + y as u16), line).unwrap();
+            }
+        }
+        Density::Message => {
+            let (width, height) = compact_size(game_state, options);
+            let message = tr(
+                "terminal-too-small",
+                &[("width", &width), ("height", &height)],
+            );
+            write!(stdout, "{}{}", termion::cursor::Goto(1, 1), message).unwrap();
+        }
+    }
+}
+
+/// Shape of the hint of a tile on a compact line, readable without colors.
+fn compact_char(hit: &HitInfo) -> char {
+    match hit {
+        HitInfo::Hit => '⣿',
+        HitInfo::Contains => '⣶',
+        HitInfo::Miss => '⣀',
+        HitInfo::TooMany => '⣤',
+        HitInfo::None => '⠤',
+    }
+}
+
+/// Columns and rows of `compact_lines` once all rows are guessed, with the
+/// error below them.
+fn compact_size(game_state: &GameState, options: &RenderOptions) -> (u16, u16) {
+    let width = game_state.letters().len() as u16;
+    let numbers = row_number_width(game_state, options);
+    (numbers + 2 * width + 1, game_state.max_tries + 1)
+}
+
+/// The game on a terminal too small for the board: each guess as a line of
+/// its hints and the word, e.g. `⣿⣶⣀⣀⣶ CRANE`, then the guess being typed
+/// after a `>` and the error. Only the first `revealed` tiles of the last
+/// guess show their hint.
+pub(crate) fn compact_lines(
+    game_state: &GameState,
+    options: &RenderOptions,
+    revealed: usize,
+) -> Vec<String> {
+    let width = game_state.letters().len();
+    let rtl = right_to_left(game_state, options);
+    let numbers = row_number_width(game_state, options);
+    let mut lines: Vec<String> = (0..game_state.guesses.len())
+        .map(|y| {
+            let (letters, hits) = board_row(game_state, y, revealed);
+            let mut line = Vec::new();
+            if numbers > 0 {
+                write!(line, "{} ", row_number(y, numbers - 1)).unwrap();
+            }
+            for x in 0..width {
+                let i = letter_index(x, width, rtl);
+                write_hit_color(&mut line, &hits[i], options.theme);
+                write!(line, "{}{}", compact_char(&hits[i]), style::Reset).unwrap();
+            }
+            let word: String = letters.iter().collect();
+            write!(line, " {}", tile_word(&word, options.uppercase)).unwrap();
+            String::from_utf8(line).unwrap()
+        })
+        .collect();
+    if row_state(game_state, game_state.guesses.len()) == RowState::Active {
+        let (letters, _) = board_row(game_state, game_state.guesses.len(), revealed);
+        let typed: String = letters
+            .iter()
+            .map(|c| tile_text(*c, options.uppercase))
+            .collect();
+        lines.push(format!(
+            "{:>width$}{}",
+            "> ",
+            typed,
+            width = numbers.max(2) as usize
+        ));
+    }
+    if let Some(error) = game_state.last_error {
+        lines.push(error.to_string());
+    }
+    lines
+}
+
+/// Renders the board of tiles, see `render_revealed`.
+fn render_board<W: Write>(
+    stdout: &mut W,
+    game_state: &GameState,
+    options: &RenderOptions,
+    revealed: usize,
+) {
+    let width = game_state.letters().len() as u16;
+    let cell = cell_width(game_state);
+    let rtl = right_to_left(game_state, options);
+    let height = game_state.max_tries;
+    let (m_left, m_top) = board_origin(game_state, options);
+    let numbers = row_number_width(game_state, options);
+    // label of the word list above the game board
+    if let Some(name) = &game_state.list_name {
+        write!(
+            stdout,
+            "{}",
+            termion::cursor::Goto(m_left, m_top - HEADER_HEIGHT)
+        )
+        .unwrap();
+        match (options.exploring, options.theme) {
+            (false, _) => {}
+            (true, Theme::Monochrome) => write!(stdout, "{}", style::Bold).unwrap(),
+            (true, _) => write!(stdout, "{}", color::Fg(color::LightMagenta)).unwrap(),
+        }
+        write!(stdout, "{}{}", name, style::Reset).unwrap();
+    }
+    let turn = game_state.current_player();
+    if let Some(player) = turn.filter(|_| game_state.team && !game_state.over()) {
+        write!(
+            stdout,
+            "{}{}{}{}",
+            termion::cursor::Goto(m_left, m_top - HEADER_HEIGHT + 1),
+            style::Bold,
+            tr("team-turn", &[("player", &player)]),
+            style::Reset
+        )
+        .unwrap();
+    }
+    for y in 0..height {
+        let state = row_state(game_state, y as usize);
+        let (faint, plain) = if options.dim_future_rows && state == RowState::Future {
+            (style::Faint.to_string(), style::Reset.to_string())
+        } else {
+            (String::new(), String::new())
+        };
+        write!(
+            stdout,
+            "{}{}{}{}",
+            termion::cursor::Goto(m_left, m_top + y * 2 - 1),
+            faint,
+            (0..board_width(game_state))
+                .map(|_| "-")
+                .collect::<String>(),
+            plain
+        )
+        .unwrap();
+
+        // who played the row, or whose turn it is
+        let player = match state {
+            RowState::Committed => game_state.player(y as usize),
+            RowState::Active => game_state.current_player(),
+            RowState::Future => None,
+        };
+        // the turn of a team is shown above the board, its rows are marked
+        // by initials
+        let length = if !game_state.team {
+            LABEL_WIDTH as usize - 1
+        } else if state == RowState::Committed {
+            1
+        } else {
+            0
+        };
+        if let Some(player) = player.filter(|_| length > 0) {
+            let label: String = player.chars().take(length).collect();
+            write!(
+                stdout,
+                "{}{}",
+                termion::cursor::Goto(m_left - margin_width(game_state, options), m_top + y * 2),
+                label
+            )
+            .unwrap();
+        }
+        if numbers > 0 {
+            write!(
+                stdout,
+                "{}{}",
+                termion::cursor::Goto(m_left - numbers, m_top + y * 2),
+                row_number(y as usize, numbers - 1)
+            )
+            .unwrap();
+        }
+        if options.row_marker && state == RowState::Active {
+            write!(
+                stdout,
+                "{}{}>{}",
+                termion::cursor::Goto(m_left - 1, m_top + y * 2),
+                style::Bold,
+                style::Reset
+            )
+            .unwrap();
+        }
+
+        let (line_guess, line_hits) = board_row(game_state, y as usize, revealed);
+
+        for x in 0..width {
+            // print each letter into a cell, the first letter goes right
+            // for a script written from right to left
+            write!(
+                stdout,
+                "{}{}|",
+                termion::cursor::Goto(m_left + x * (cell + 1), m_top + y * 2),
+                faint
+            )
+            .unwrap();
+            let i = letter_index(x as usize, width as usize, rtl);
+
+            // set color according to hit info
+            write_hit_color(stdout, &line_hits[i], options.theme);
+
+            let letter = tile_text(line_guess[i], options.uppercase);
+            write!(
+                stdout,
+                "{:^cell$}{}",
+                letter,
+                style::Reset,
+                cell = cell as usize
+            )
+            .unwrap();
+        }
+        // close cell
+        writeln!(stdout, "{}|{}", faint, plain).unwrap();
+    }
+
+    // print keyboard below game board
+    let letter_hits = game_state.letter_hits();
+    let keyboard_rows = keyboard_rows(game_state, options);
+    for (y, row) in keyboard_rows.iter().enumerate() {
+        write!(
+            stdout,
+            "{}",
+            termion::cursor::Goto(m_left + y as u16, m_top + height * 2 + 1 + y as u16)
+        )
+        .unwrap();
+        for c in row {
+            write_hit_color(
+                stdout,
+                letter_hits.get(c).unwrap_or(&HitInfo::None),
+                options.theme,
+            );
+            let letter = tile_text(*c, options.uppercase);
+            write!(stdout, "{}{} ", letter, style::Reset).unwrap();
+        }
+    }
+
+    // print what is known between the keyboard and the error
+    if options.show_pattern {
+        let knowledge = game_state.knowledge();
+        if let Some(pattern) = pattern_line(&knowledge) {
+            write!(
+                stdout,
+                "{}{}",
+                termion::cursor::Goto(m_left, m_top + height * 2 + 1 + keyboard_rows.len() as u16),
+                pattern
+            )
+            .unwrap();
+        }
+    }
+
+    // print error below keyboard
+    if let Some(error) = game_state.last_error {
+        writeln!(
+            stdout,
+            "{}{}",
+            termion::cursor::Goto(m_left, m_top + height * 2 + 2 + keyboard_rows.len() as u16),
+            error
+        )
+        .unwrap();
+    }
+}
+
+/// Excluded letters listed by `pattern_line`, more are only counted.
+const LISTED_EXCLUDED: usize = 6;
+
+/// What is known about the word on one line, e.g.
+/// `_ R A _ E  (+S somewhere, no T/L/N)`: the placed letters, then the
+/// letters in the word not placed yet and the letters ruled out. `None`
+/// while nothing is known.
+pub(crate) fn pattern_line(knowledge: &Knowledge) -> Option<String> {
+    if knowledge.placed.iter().all(Option::is_none)
+        && knowledge.unplaced.is_empty()
+        && knowledge.excluded.is_empty()
+    {
+        return None;
+    }
+    let letter = |c: char| {
+        digraph::text(c)
+            .unwrap_or_else(|| c.to_string())
+            .to_uppercase()
+    };
+    let upper = |letters: &[char]| {
+        let letters: Vec<String> = letters.iter().map(|c| letter(*c)).collect();
+        letters.join("/")
+    };
+    let placed: Vec<String> = knowledge
+        .placed
+        .iter()
+        .map(|c| c.map_or("_".to_string(), letter))
+        .collect();
+    let mut notes = Vec::new();
+    if !knowledge.unplaced.is_empty() {
+        notes.push(tr(
+            "pattern-unplaced",
+            &[("letters", &upper(&knowledge.unplaced))],
+        ));
+    }
+    if knowledge.excluded.len() > LISTED_EXCLUDED {
+        notes.push(tr(
+            "pattern-excluded-count",
+            &[("count", &knowledge.excluded.len())],
+        ));
+    } else if !knowledge.excluded.is_empty() {
+        notes.push(tr(
+            "pattern-excluded",
+            &[("letters", &upper(&knowledge.excluded))],
+        ));
+    }
+    let mut line = placed.join(" ");
+    if !notes.is_empty() {
+        line = format!("{}  ({})", line, notes.join(", "));
+    }
+    Some(line)
+}
+
+/// Writes `lines` below the keyboard and the error line, e.g. the menu of
+/// hints. On a compact board they follow its lines, with only a message
+/// they are left out.
+pub(crate) fn render_below<W: Write>(
+    stdout: &mut W,
+    game_state: &GameState,
+    lines: &[String],
+    options: &RenderOptions,
+) {
+    let (left, top) = match density(game_state, options) {
+        Density::Normal => {
+            let keyboard_rows = keyboard_rows(game_state, options);
+            let (left, top) = board_origin(game_state, options);
+            (
+                left,
+                top + game_state.max_tries * 2 + 3 + keyboard_rows.len() as u16,
+            )
+        }
+        Density::Compact => (1, 1 + compact_size(game_state, options).1),
+        Density::Message => return,
+    };
+    for (y, line) in lines.iter().enumerate() {
+        write!(
+            stdout,
+            "{}{}",
+            termion::cursor::Goto(left, top + y as u16),
+            line
+        )
+        .unwrap();
+    }
+}
+
+/// Clears the screen and writes the lines of the menu where the board would
+/// be. Without lines the screen is left clear, with the cursor shown again.
+pub(crate) fn render_menu<W: Write>(
+    stdout: &mut W,
+    lines: &[String],
+    layout: Layout,
+    terminal: (u16, u16),
+) {
+    write!(stdout, "{}", termion::clear::All).unwrap();
+    if lines.is_empty() {
+        write!(
+            stdout,
+            "{}{}",
+            termion::cursor::Goto(1, 1),
+            termion::cursor::Show
+        )
+        .unwrap();
+    } else {
+        write!(stdout, "{}", termion::cursor::Hide).unwrap();
+    }
+    let width = lines.iter().map(|line| line.chars().count()).max();
+    let size = (
+        LABEL_WIDTH + width.unwrap_or(0) as u16,
+        HEADER_HEIGHT + lines.len() as u16,
+    );
+    let (left, top) = layout.origin(size, terminal);
+    for (y, line) in lines.iter().enumerate() {
+        write!(
+            stdout,
+            "{}{}",
+            termion::cursor::Goto(left + LABEL_WIDTH, top + HEADER_HEIGHT + y as u16),
+            line
+        )
+        .unwrap();
+    }
+    stdout.flush().unwrap();
+}
+
+/// Letters shown of each set of possible letters before abbreviating.
+const SHOWN_LETTERS: usize = 5;
+
+/// The possible letters of each position on one line, e.g.
+/// `1:[b,c,s] 2:[l] 3:* 4:* 5:[e,y]`, `*` for positions not narrowed down.
+/// Sets are cut to fewer letters and a count of the rest, e.g. `1:[b,c+3]`,
+/// until the line fits `width`, then only their sizes are shown.
+pub(crate) fn constraints_line(letters: &[Option<Vec<char>>], width: usize) -> String {
+    let line = |shown: Option<usize>| {
+        let positions: Vec<String> = letters
+            .iter()
+            .enumerate()
+            .map(|(i, set)| {
+                let set = match (set, shown) {
+                    (None, _) => "*".to_string(),
+                    (Some(set), Some(shown)) if set.len() > shown => {
+                        let letters: Vec<String> =
+                            set[..shown].iter().map(char::to_string).collect();
+                        format!("[{}+{}]", letters.join(","), set.len() - shown)
+                    }
+                    // only the sizes of sets with more than one letter
+                    (Some(set), None) if set.len() > 1 => set.len().to_string(),
+                    (Some(set), _) => {
+                        let letters: Vec<String> = set.iter().map(char::to_string).collect();
+                        format!("[{}]", letters.join(","))
+                    }
+                };
+                format!("{}:{}", i + 1, set)
+            })
+            .collect();
+        positions.join(" ")
+    };
+    (1..=SHOWN_LETTERS)
+        .rev()
+        .map(|shown| line(Some(shown)))
+        .find(|line| line.chars().count() <= width)
+        .unwrap_or_else(|| line(None).chars().take(width).collect())
+}
+
+/// The line of `--show-constraints`, only worked out again once a guess
+/// was confirmed or a hint revealed a letter.
+#[derive(Debug, Default)]
+pub(crate) struct Constraints {
+    /// Guesses and hints the line was worked out for.
+    seen: Option<(usize, usize)>,
+    line: String,
+}
+
+impl Constraints {
+    pub(crate) fn line(&mut self, game_state: &GameState, options: &RenderOptions) -> &str {
+        let seen = (game_state.guesses.len(), game_state.hints().len());
+        if self.seen != Some(seen) {
+            let left = board_origin(game_state, options).0;
+            let width = options.terminal.0.saturating_sub(left) as usize;
+            self.line = constraints_line(&possible_letters(game_state), width);
+            self.seen = Some(seen);
+        }
+        &self.line
+    }
+}
+
+/// Answers and guesses shown by `--assist`.
+const ASSIST_TOP: usize = 3;
+
+/// A probability as a whole percentage, `<1%` for what would round to 0.
+fn percent(probability: f64) -> String {
+    if probability < 0.005 {
+        "<1%".to_string()
+    } else {
+        format!("{:.0}%", probability * 100.0)
+    }
+}
+
+/// The lines of the assist panel, e.g. `Answers: slate 12% · crate 12%`
+/// and `Best guesses: roate · slate`, and the legend of `render_heatmap`,
+/// none once no word fits.
+pub(crate) fn assist_lines(assist: &Assist) -> Vec<String> {
+    if assist.answers.is_empty() {
+        return Vec::new();
+    }
+    let answers: Vec<String> = assist
+        .answers
+        .iter()
+        .map(|(word, probability)| format!("{} {}", word, percent(*probability)))
+        .collect();
+    vec![
+        tr(
+            "assist-answers",
+            &[
+                ("answers", &answers.join(" · ")),
+                ("remaining", &assist.remaining),
+            ],
+        ),
+        tr(
+            "assist-guesses",
+            &[("guesses", &assist.guesses.join(" · "))],
+        ),
+        tr("assist-heatmap", &[]),
+    ]
+}
+
+/// The assist of a game, with the ranking of the openers of the list from
+/// the opener cache at `cache` until the game has begun, and why the
+/// ranking could not be cached.
+fn opening_assist<'a>(
+    game_state: &'a GameState,
+    cache: Option<&Path>,
+) -> (Assist<'a>, Option<std::io::Error>) {
+    let words = match opening(game_state) {
+        Some(words) => words,
+        None => return (assist(game_state, ASSIST_TOP), None),
+    };
+    let key = OpenerKey::new(&words, &HashMap::new(), Weighting::Info);
+    let ranking = openers(cache, key, false, || {
+        rank_guesses(&words, &words)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    });
+    let assist = assist_opening(game_state, ASSIST_TOP, Some(&ranking.openers));
+    (assist, ranking.unsaved)
+}
+
+/// The assist panel of `--assist`, only worked out again once a guess
+/// was confirmed or a hint revealed a letter.
+#[derive(Debug, Default)]
+pub(crate) struct AssistPanel {
+    /// Guesses and hints the lines were worked out for.
+    seen: Option<(usize, usize)>,
+    lines: Vec<String>,
+    /// Why the openers could not be cached, until reported.
+    unsaved: Option<std::io::Error>,
+}
+
+impl AssistPanel {
+    /// The lines of the panel, the openers kept in the cache file at
+    /// `cache`.
+    pub(crate) fn lines(&mut self, game_state: &GameState, cache: Option<&Path>) -> &[String] {
+        let seen = (game_state.guesses.len(), game_state.hints().len());
+        if self.seen != Some(seen) {
+            let (assist, unsaved) = opening_assist(game_state, cache);
+            self.lines = assist_lines(&assist);
+            self.unsaved = unsaved;
+            self.seen = Some(seen);
+        }
+        &self.lines
+    }
+
+    /// Why the openers could not be cached, once.
+    pub(crate) fn take_unsaved(&mut self) -> Option<std::io::Error> {
+        self.unsaved.take()
+    }
+}
+
+/// Writes the number of words which could still have been the solution
+/// next to each guessed row, `counts` as by `remaining_counts`. Only the
+/// normal board has room for them.
+pub(crate) fn render_counts<W: Write>(
+    stdout: &mut W,
+    game_state: &GameState,
+    counts: &[usize],
+    options: &RenderOptions,
+) {
+    if density(game_state, options) != Density::Normal {
+        return;
+    }
+    let (left, top) = board_origin(game_state, options);
+    let left = left + board_width(game_state) + 2;
+    for (y, count) in counts.iter().enumerate() {
+        write!(
+            stdout,
+            "{}{}",
+            termion::cursor::Goto(left, top + y as u16 * 2),
+            tr("explore-remaining", &[("count", count)])
+        )
+        .unwrap();
+    }
+}
+
+/// Shades the keys of the keyboard drawn by `render_game_state` which have
+/// no hint by `frequencies`, as by `letter_frequencies`, see `heat_look`.
+/// Keys with a hint and letters a hint ruled out keep their look. Only the
+/// normal board has a keyboard.
+pub(crate) fn render_heatmap<W: Write>(
+    stdout: &mut W,
+    game_state: &GameState,
+    frequencies: &HashMap<char, f64>,
+    options: &RenderOptions,
+) {
+    if density(game_state, options) != Density::Normal {
+        return;
+    }
+    let letter_hits = game_state.letter_hits();
+    let excluded = game_state.knowledge().excluded;
+    let (left, top) = board_origin(game_state, options);
+    let top = top + game_state.max_tries * 2 + 1;
+    for (y, row) in keyboard_rows(game_state, options).iter().enumerate() {
+        let mut x = left + y as u16;
+        for c in row {
+            let text = tile_text(*c, options.uppercase);
+            let width = text.chars().count() as u16;
+            if !letter_hits.contains_key(c) && !excluded.contains(c) {
+                let frequency = frequencies.get(c).copied().unwrap_or(0.0);
+                write!(stdout, "{}", termion::cursor::Goto(x, top + y as u16)).unwrap();
+                write_look(stdout, heat_look(frequency, options.theme), options.theme);
+                write!(stdout, "{}{}", text, style::Reset).unwrap();
+            }
+            x += width + 1;
+        }
+    }
+}
+
+/// The frequencies of `render_heatmap`, only worked out again once a guess
+/// was confirmed or a hint revealed a letter.
+#[derive(Debug, Default)]
+pub(crate) struct Heatmap {
+    /// Guesses and hints the frequencies were worked out for.
+    seen: Option<(usize, usize)>,
+    frequencies: HashMap<char, f64>,
+}
+
+impl Heatmap {
+    pub(crate) fn frequencies(&mut self, game_state: &GameState) -> &HashMap<char, f64> {
+        let seen = (game_state.guesses.len(), game_state.hints().len());
+        if self.seen != Some(seen) {
+            self.frequencies = letter_frequencies(game_state);
+            self.seen = Some(seen);
+        }
+        &self.frequencies
+    }
+}
+
+/// The counts of `render_counts`, only worked out again once a guess was
+/// confirmed.
+#[derive(Debug, Default)]
+pub(crate) struct RemainingCounts {
+    /// Guesses the counts were worked out for.
+    seen: Option<usize>,
+    counts: Vec<usize>,
+}
+
+impl RemainingCounts {
+    pub(crate) fn counts(&mut self, game_state: &GameState) -> &[usize] {
+        if self.seen != Some(game_state.guesses.len()) {
+            self.counts = remaining_counts(game_state);
+            self.seen = Some(game_state.guesses.len());
+        }
+        &self.counts
+    }
+}
+
+/// Renders the board of an opponent next to the game board, only the
+/// colors of the hints and never the letters. `status` is shown below it.
+/// Only the normal board has room for it.
+pub(crate) fn render_opponent<W: Write>(
+    stdout: &mut W,
+    game_state: &GameState,
+    rows: &[Vec<HitInfo>],
+    status: &str,
+    options: &RenderOptions,
+) {
+    if density(game_state, options) != Density::Normal {
+        return;
+    }
+    let width = game_state.letters().len();
+    let rtl = right_to_left(game_state, options);
+    let (left, top) = board_origin(game_state, options);
+    let left = left + board_width(game_state) + 5;
+    for y in 0..game_state.max_tries {
+        write!(stdout, "{}", termion::cursor::Goto(left, top + y)).unwrap();
+        let row = rows.get(y as usize);
+        for x in 0..width {
+            let i = letter_index(x, width, rtl);
+            match row.and_then(|row| row.get(i)) {
+                Some(hit) => {
+                    write_hit_color(stdout, hit, options.theme);
+                    // the pattern letter keeps the board readable without colors
+                    write!(stdout, "{}{}", hit_char(hit), style::Reset).unwrap();
+                }
+                None => write!(stdout, ".").unwrap(),
+            }
+        }
+    }
+    write!(
+        stdout,
+        "{}{}",
+        termion::cursor::Goto(left, top + game_state.max_tries + 1),
+        status
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rordle_core::game::GameOptions;
+    use rordle_core::words::WordStore;
+
+    fn word_store(words: &[&str]) -> WordStore {
+        words.iter().collect()
+    }
+
+    #[test]
+    fn test_rendering_with_umlaut() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('Ü');
+        render_game_state(&mut Vec::new(), &game_state, &RenderOptions::default());
+    }
+    #[test]
+    fn test_rendering_with_one_input() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.add_char('w');
+        render_game_state(&mut Vec::new(), &game_state, &RenderOptions::default());
+    }
+
+    #[test]
+    fn test_rendering_shows_list_name() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.list_name = Some("Deutsch".to_string());
+        let mut out = Vec::new();
+        render_game_state(&mut out, &game_state, &RenderOptions::default());
+        assert!(String::from_utf8(out).unwrap().contains("Deutsch"));
+    }
+
+    #[test]
+    fn test_rendering_with_theme() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("hello".to_string()).unwrap();
+        let options = RenderOptions {
+            theme: Theme::HighContrast,
+            ..RenderOptions::default()
+        };
+        let mut out = Vec::new();
+        render_game_state(&mut out, &game_state, &options);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(&color::Bg(color::Rgb(245, 121, 58)).to_string()));
+        assert!(!out.contains(&color::Bg(color::Green).to_string()));
+    }
+
+    #[test]
+    fn test_rendering_without_colors() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("jolly".to_string()).unwrap();
+        let options = RenderOptions {
+            theme: Theme::Monochrome,
+            ..RenderOptions::default()
+        };
+        let mut out = Vec::new();
+        render_game_state(&mut out, &game_state, &options);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(&style::Invert.to_string()));
+        assert!(out.contains(&style::Underline.to_string()));
+        for escape in [
+            color::Bg(color::Green).to_string(),
+            color::Bg(color::Yellow).to_string(),
+            color::Bg(color::Black).to_string(),
+            color::Fg(color::Black).to_string(),
+        ] {
+            assert!(!out.contains(&escape));
+        }
+    }
+
+    #[test]
+    fn test_board_lines() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("jolly".to_string()).unwrap();
+        let lines = board_lines(&game_state, &RenderOptions::default());
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with(&format!("{} ", row_number(0, 1))));
+        let hit = color::Bg(color::Green).to_string();
+        assert_eq!(lines[0].matches(&hit).count(), 2);
+        let unnumbered = RenderOptions {
+            row_numbers: false,
+            ..RenderOptions::default()
+        };
+        let letters: String = board_lines(&game_state, &unnumbered)[0]
+            .split(&style::Reset.to_string())
+            .filter_map(|tile| tile.rsplit('m').next())
+            .collect();
+        assert_eq!(letters, " J  O  L  L  Y ");
+        let options = RenderOptions {
+            theme: Theme::Monochrome,
+            ..RenderOptions::default()
+        };
+        let lines = board_lines(&game_state, &options);
+        assert!(lines[0].contains(&style::Underline.to_string()));
+        assert!(!lines[0].contains(&hit));
+    }
+
+    fn hebrew_game() -> GameState {
+        let list = include_str!("../tests/data/hebrew_words.txt");
+        let words: Vec<&str> = list.lines().filter(|line| !line.starts_with('#')).collect();
+        let mut game_state = super::GameState::new(
+            "שלום".to_string(),
+            word_store(&words),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("מלון".to_string()).unwrap();
+        game_state
+    }
+
+    #[test]
+    fn test_board_lines_right_to_left() {
+        let game_state = hebrew_game();
+        let options = RenderOptions {
+            row_numbers: false,
+            ..RenderOptions::default()
+        };
+        assert!(right_to_left(&game_state, &options));
+        let tiles = |options: &RenderOptions| -> String {
+            board_lines(&game_state, options)[0]
+                .split(&style::Reset.to_string())
+                .filter_map(|tile| tile.rsplit('m').next())
+                .collect::<String>()
+                .replace(' ', "")
+        };
+        // the first letter is on the right
+        assert_eq!(tiles(&options), "ןולמ");
+        let hit = color::Bg(color::Green).to_string();
+        assert!(
+            board_lines(&game_state, &options)[0].starts_with(&color::Bg(color::Black).to_string())
+        );
+        assert!(board_lines(&game_state, &options)[0].contains(&hit));
+        let ltr = RenderOptions {
+            rtl: Some(false),
+            ..options
+        };
+        assert_eq!(tiles(&ltr), "מלון");
+        // the hints keep the first letter first
+        assert_eq!(
+            plain_row(&game_state.guesses[0], &game_state.get_guess_hits(0)),
+            "מלון -gg-"
+        );
+    }
+
+    #[test]
+    fn test_rendering_right_to_left() {
+        let game_state = hebrew_game();
+        let mut out = Vec::new();
+        render_game_state(&mut out, &game_state, &RenderOptions::default());
+        let out = String::from_utf8(out).unwrap();
+        let (left, top) = board_origin(&game_state, &RenderOptions::default());
+        // the letter printed into the cell at column `x` of the first row
+        let cell = |x: u16| {
+            let goto = termion::cursor::Goto(left + x * 2, top).to_string();
+            let tile = out.split(&goto).nth(1).unwrap();
+            let tile = tile.split(&style::Reset.to_string()).next().unwrap();
+            tile.rsplit('m').next().unwrap().to_string()
+        };
+        let cells: Vec<String> = (0..4).map(cell).collect();
+        assert_eq!(cells, ["ן", "ו", "ל", "מ"]);
+        // the keyboard rows are mirrored too
+        let keyboard = out
+            .split(&termion::cursor::Goto(left, top + 13).to_string())
+            .nth(1)
+            .unwrap();
+        let keys: String = keyboard
+            .split(&style::Reset.to_string())
+            .filter_map(|key| key.rsplit('m').next())
+            .collect::<String>()
+            .replace(' ', "");
+        assert!(keys.starts_with("תשפןמםליחוהד"), "{:?}", keys);
+    }
+
+    #[test]
+    fn test_tile_letters() {
+        assert_eq!(tile_word("straße", true), "STRAßE");
+        assert_eq!(tile_word("ǆem", true), "ǄEM");
+        // the dotted i is lowercase as a pair, whose i is uppercased
+        assert_eq!(tile_word("i\u{307}", true), "I\u{307}");
+        assert_eq!(tile_word("straße", false), "straße");
+        let mut game_state = super::GameState::new(
+            "maße".to_string(),
+            word_store(&["maße", "muße"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("muße".to_string()).unwrap();
+        let out = |uppercase| {
+            // the pattern below the keyboard is always in uppercase
+            let options = RenderOptions {
+                uppercase,
+                show_pattern: false,
+                ..RenderOptions::default()
+            };
+            let mut out = Vec::new();
+            render_game_state(&mut out, &game_state, &options);
+            (
+                String::from_utf8(out).unwrap(),
+                board_lines(&game_state, &options),
+            )
+        };
+        let (board, lines) = out(true);
+        assert!(board.contains('U') && board.contains('ß') && !board.contains('u'));
+        assert!(lines[0].contains(" M ") && lines[0].contains(" ß "));
+        let (board, lines) = out(false);
+        assert!(board.contains('u') && !board.contains('U'));
+        assert!(lines[0].contains(" m "));
+    }
+
+    #[test]
+    fn test_rendering_hides_unrevealed_tiles() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("hello".to_string()).unwrap();
+        let mut out = Vec::new();
+        render_revealed(&mut out, &game_state, &RenderOptions::default(), 2);
+        let out = String::from_utf8(out).unwrap();
+        let hit = color::Bg(color::Green).to_string();
+        // two revealed tiles, the keyboard shows the letters h, e, l and o
+        assert_eq!(out.matches(&hit).count(), 2 + 4);
+    }
+
+    #[test]
+    fn test_rendering_prefilled_letters() {
+        let game_state = (0..)
+            .map(|seed| {
+                super::GameState::new(
+                    "hello".to_string(),
+                    word_store(&["hello"]),
+                    GameOptions::default().prefill(1).seed(Some(seed)),
+                )
+                .unwrap()
+            })
+            .find(|game_state| game_state.prefilled(0))
+            .unwrap();
+        let mut out = Vec::new();
+        render_game_state(&mut out, &game_state, &RenderOptions::default());
+        let out = String::from_utf8(out).unwrap();
+        let hit = color::Bg(color::Green).to_string();
+        // a green h in each of the six rows, nothing on the keyboard yet
+        assert_eq!(
+            out.matches(&format!("{}{}H", hit, color::Fg(color::Black)))
+                .count(),
+            6
+        );
+        assert_eq!(out.matches(&hit).count(), 6);
+    }
+
+    #[test]
+    fn test_rendering_opponent_hides_letters() {
+        let game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let rows = vec![rordle_core::game::score("jolly", "hello")];
+        let mut out = Vec::new();
+        render_opponent(
+            &mut out,
+            &game_state,
+            &rows,
+            "opponent",
+            &RenderOptions::default(),
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert!(!out.contains('j'));
+        assert_eq!(out.matches(&color::Bg(color::Green).to_string()).count(), 2);
+        assert_eq!(out.matches('.').count(), 5 * 5);
+        assert!(out.ends_with("opponent"));
+    }
+
+    #[test]
+    fn test_rendering_player_labels() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.players = vec!["Alice".to_string(), "Bartholomew".to_string()];
+        game_state.guess("jolly".to_string()).unwrap();
+        let mut out = Vec::new();
+        render_game_state(&mut out, &game_state, &RenderOptions::default());
+        let out = String::from_utf8(out).unwrap();
+        let label = |row: u16, name: &str| format!("{}{}", termion::cursor::Goto(1, row), name);
+        assert!(out.contains(&label(4, "Alice")));
+        // names are cut to fit into the margin
+        assert!(out.contains(&label(6, "Bartholo")));
+        assert!(!out.contains("Bartholom"));
+    }
+
+    #[test]
+    fn test_rendering_row_numbers() {
+        let options = GameOptions {
+            max_tries: 12,
+            ..GameOptions::default()
+        };
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            options,
+        )
+        .unwrap();
+        game_state.players = vec!["Alice".to_string(), "Bob".to_string()];
+        game_state.guess("jolly".to_string()).unwrap();
+        // too high for the terminal of the tests, the board is rendered
+        // all the same
+        let render = |options: &RenderOptions| {
+            let mut out = Vec::new();
+            let width = game_state.letters().len();
+            render_board(&mut out, &game_state, options, width);
+            String::from_utf8(out).unwrap()
+        };
+        let options = RenderOptions::default();
+        let (m_left, m_top) = board_origin(&game_state, &options);
+        // two digits and a space widen the margin, the labels stay left
+        assert_eq!(m_left, 1 + LABEL_WIDTH + 3);
+        let out = render(&options);
+        let at = |y: u16| termion::cursor::Goto(m_left - 3, m_top + y * 2);
+        assert!(out.contains(&format!("{}{}", at(0), row_number(0, 2))));
+        assert!(row_number(0, 2).contains(" 1"));
+        assert!(out.contains(&format!("{}{}", at(11), row_number(11, 2))));
+        let label = |row: u16, name: &str| format!("{}{}", termion::cursor::Goto(1, row), name);
+        assert!(out.contains(&label(4, "Alice")));
+        let unnumbered = RenderOptions {
+            row_numbers: false,
+            ..options
+        };
+        assert_eq!(board_origin(&game_state, &unnumbered).0, 1 + LABEL_WIDTH);
+        assert!(!render(&unnumbered).contains(&row_number(0, 2)));
+    }
+
+    #[test]
+    fn test_compact_lines() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("jolly".to_string()).unwrap();
+        game_state.add_char('h');
+        game_state.add_char('e');
+        let options = RenderOptions::default();
+        let tiles = |hits: &[HitInfo]| -> String {
+            let mut line = Vec::new();
+            for hit in hits {
+                write_hit_color(&mut line, hit, options.theme);
+                write!(line, "{}{}", compact_char(hit), style::Reset).unwrap();
+            }
+            String::from_utf8(line).unwrap()
+        };
+        use HitInfo::*;
+        let jolly = tiles(&[Miss, Contains, Hit, Hit, Miss]);
+        assert_eq!(
+            compact_lines(&game_state, &options, 5),
+            [
+                format!("{} {} JOLLY", row_number(0, 1), jolly),
+                "> HE___".to_string()
+            ]
+        );
+        // the last guess is revealed one tile after another
+        let half = tiles(&[Miss, Contains, None, None, None]);
+        assert_eq!(
+            compact_lines(&game_state, &options, 2)[0],
+            format!("{} {} JOLLY", row_number(0, 1), half)
+        );
+        // the shapes tell the hints apart without colors
+        assert_eq!(jolly.matches('⣿').count(), 2);
+        let unnumbered = RenderOptions {
+            row_numbers: false,
+            uppercase: false,
+            ..options
+        };
+        // a guess too short to confirm
+        game_state.confirm();
+        let lines = compact_lines(&game_state, &unnumbered, 5);
+        assert_eq!(lines[0], format!("{} jolly", jolly));
+        assert_eq!(
+            &lines[1..],
+            [
+                "> _____".to_string(),
+                game_state.last_error.unwrap().to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_density() {
+        let game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let options = RenderOptions::default();
+        let density = |terminal| {
+            let options = RenderOptions {
+                terminal,
+                ..options
+            };
+            density(&game_state, &options)
+        };
+        assert_eq!(density((80, 24)), Density::Normal);
+        assert_eq!(density(board_size(&game_state, &options)), Density::Normal);
+        // a phone at 20 columns
+        assert_eq!(density((20, 10)), Density::Compact);
+        assert_eq!(compact_size(&game_state, &options), (13, 7));
+        assert_eq!(density((13, 7)), Density::Compact);
+        assert_eq!(density((12, 7)), Density::Message);
+        assert_eq!(density((13, 6)), Density::Message);
+    }
+
+    #[test]
+    fn test_render_heatmap() {
+        let mut game_state = super::GameState::new(
+            "jolly".to_string(),
+            word_store(&["hello", "holly", "jolly", "world"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("world".to_string()).unwrap();
+        let options = RenderOptions::default();
+        let mut heatmap = Heatmap::default();
+        let mut out = Vec::new();
+        render_heatmap(
+            &mut out,
+            &game_state,
+            heatmap.frequencies(&game_state),
+            &options,
+        );
+        let out = String::from_utf8(out).unwrap();
+        let key = |frequency: f64, letter: &str| {
+            let mut look = Vec::new();
+            write_look(
+                &mut look,
+                heat_look(frequency, options.theme),
+                options.theme,
+            );
+            format!(
+                "{}{}{}",
+                String::from_utf8(look).unwrap(),
+                letter,
+                style::Reset
+            )
+        };
+        // holly and jolly are left
+        assert!(out.contains(&key(0.5, "J")));
+        assert!(out.contains(&key(0.0, "E")));
+        // the keys with a hint are drawn by the keyboard
+        for letter in ["W", "O", "R", "L", "D"] {
+            assert!(!out.contains(&format!("{}{}", letter, style::Reset)));
+        }
+        assert_ne!(key(0.0, "E"), key(1.0, "E"));
+        assert!(heat_look(0.0, Theme::Monochrome).faint);
+        assert!(heat_look(0.5, Theme::Monochrome).bold);
+    }
+
+    #[test]
+    fn test_rendering_row_marker() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let options = RenderOptions {
+            row_marker: true,
+            dim_future_rows: true,
+            ..RenderOptions::default()
+        };
+        let render = |game_state: &super::GameState| {
+            let mut out = Vec::new();
+            render_game_state(&mut out, game_state, &options);
+            String::from_utf8(out).unwrap()
+        };
+        let (m_left, m_top) = board_origin(&game_state, &options);
+        let marker = |y: u16| {
+            format!(
+                "{}{}>",
+                termion::cursor::Goto(m_left - 1, m_top + y * 2),
+                style::Bold
+            )
+        };
+        let out = render(&game_state);
+        assert!(out.contains(&marker(0)));
+        assert_eq!(out.matches('>').count(), 1);
+        // the rows after the active one are faint
+        let faint = format!("{}|", style::Faint);
+        assert_eq!(out.matches(&faint).count(), 5 * 6);
+        game_state.guess("jolly".to_string()).unwrap();
+        let out = render(&game_state);
+        assert!(!out.contains(&marker(0)));
+        assert!(out.contains(&marker(1)));
+        assert_eq!(out.matches(&faint).count(), 4 * 6);
+        game_state.guess("hello".to_string()).unwrap();
+        let out = render(&game_state);
+        assert!(!out.contains('>'));
+        // the rows left empty are faint
+        assert_eq!(out.matches(&faint).count(), 4 * 6);
+        // the marker is only shown on the terminal
+        assert!(!board_lines(&game_state, &options).concat().contains('>'));
+    }
+
+    #[test]
+    fn test_layout_origin() {
+        let layout = |align, margin| Layout { align, margin };
+        let terminal = (80, 24);
+        assert_eq!(
+            layout(Align::TopLeft, (5, 5)).origin((30, 20), terminal),
+            (1, 1)
+        );
+        assert_eq!(
+            layout(Align::Center, (0, 0)).origin((30, 20), terminal),
+            (26, 3)
+        );
+        assert_eq!(
+            layout(Align::Custom, (40, 2)).origin((30, 20), terminal),
+            (41, 3)
+        );
+        // margins which do not fit are cut down, a block larger than the
+        // terminal stays in the corner
+        assert_eq!(
+            layout(Align::Custom, (70, 10)).origin((30, 20), terminal),
+            (51, 5)
+        );
+        assert_eq!(
+            layout(Align::Center, (0, 0)).origin((100, 30), terminal),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn test_rendering_team() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.players = ["Mum", "Dad", "Kid"].map(String::from).to_vec();
+        game_state.team = true;
+        game_state.guess("jolly".to_string()).unwrap();
+        let mut out = Vec::new();
+        render_game_state(&mut out, &game_state, &RenderOptions::default());
+        let out = String::from_utf8(out).unwrap();
+        let at = |row: u16, column: u16| termion::cursor::Goto(column, row).to_string();
+        // only the initial marks the row
+        assert!(out.contains(&format!("{}M", at(4, 1))));
+        assert!(!out.contains(&format!("{}Mu", at(4, 1))));
+        assert!(out.contains(&format!("{}{}Dad's turn", at(2, 12), style::Bold)));
+        assert!(!out.contains(&format!("{}D", at(6, 1))));
+    }
+
+    /// Time to render a board with all rows guessed for growing word
+    /// lengths, run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_render_long_words() {
+        use std::time::Instant;
+        for length in [5, 20, 80] {
+            let word: String = (0..length)
+                .map(|i| (b'a' + (i % 26) as u8) as char)
+                .collect();
+            let mut game_state = GameState::new(
+                word.clone(),
+                word_store(&[]),
+                GameOptions::default().any_word(true).max_tries(10),
+            )
+            .unwrap();
+            // every letter moved along the alphabet, never the solution
+            for shift in 1..10 {
+                let guess: String = word
+                    .bytes()
+                    .map(|b| (b'a' + (b - b'a' + shift) % 26) as char)
+                    .collect();
+                game_state.guess(guess).unwrap();
+            }
+            let start = Instant::now();
+            for _ in 0..100 {
+                render_game_state(&mut Vec::new(), &game_state, &RenderOptions::default());
+            }
+            println!(
+                "{:>2} letters: {:?} per frame",
+                length,
+                start.elapsed() / 100
+            );
+        }
+    }
+
+    #[test]
+    fn test_constraints_line() {
+        let set = |s: &str| Some(s.chars().collect::<Vec<char>>());
+        let letters = [set("bcs"), set("l"), None, None, set("abcdefgh")];
+        assert_eq!(
+            constraints_line(&letters, 80),
+            "1:[b,c,s] 2:[l] 3:* 4:* 5:[a,b,c,d,e+3]"
+        );
+        // abbreviated until the line fits
+        assert_eq!(
+            constraints_line(&letters, 35),
+            "1:[b,c,s] 2:[l] 3:* 4:* 5:[a,b,c+5]"
+        );
+        assert_eq!(
+            constraints_line(&letters, 29),
+            "1:[b+2] 2:[l] 3:* 4:* 5:[a+7]"
+        );
+        assert_eq!(constraints_line(&letters, 28), "1:3 2:[l] 3:* 4:* 5:8");
+        assert_eq!(constraints_line(&letters, 5), "1:3 2");
+    }
+
+    #[test]
+    fn test_assist_lines() {
+        let assist = Assist {
+            remaining: 8,
+            answers: vec![("slate", 0.12), ("crate", 0.12), ("plate", 0.004)],
+            guesses: vec!["roate", "slate"],
+        };
+        assert_eq!(
+            assist_lines(&assist),
+            [
+                "Answers (8 left): slate 12% · crate 12% · plate <1%",
+                "Best guesses: roate · slate",
+                "Brighter keys are in more of the answers"
+            ]
+        );
+        let none = Assist {
+            remaining: 0,
+            answers: Vec::new(),
+            guesses: Vec::new(),
+        };
+        assert!(assist_lines(&none).is_empty());
+    }
+
+    #[test]
+    fn test_pattern_line() {
+        let knowledge = Knowledge {
+            placed: vec![None, Some('r'), Some('a'), None, Some('e')],
+            unplaced: vec!['s'],
+            excluded: vec!['l', 'n', 't'],
+        };
+        assert_eq!(
+            pattern_line(&knowledge).unwrap(),
+            "_ R A _ E  (+S somewhere, no L/N/T)"
+        );
+        let knowledge = Knowledge {
+            placed: vec![None; 5],
+            unplaced: vec![],
+            excluded: "bcdfghjk".chars().collect(),
+        };
+        assert_eq!(
+            pattern_line(&knowledge).unwrap(),
+            "_ _ _ _ _  (8 letters ruled out)"
+        );
+        let knowledge = Knowledge {
+            placed: vec![None, Some('ß')],
+            ..Knowledge::default()
+        };
+        assert_eq!(pattern_line(&knowledge).unwrap(), "_ SS");
+        let nothing = Knowledge {
+            placed: vec![None; 5],
+            ..Knowledge::default()
+        };
+        assert_eq!(pattern_line(&nothing), None);
+    }
+
+    #[test]
+    fn test_rendering_pattern() {
+        let mut game_state = super::GameState::new(
+            "hello".to_string(),
+            word_store(&["hello", "jolly"]),
+            GameOptions::default(),
+        )
+        .unwrap();
+        game_state.guess("jolly".to_string()).unwrap();
+        let render = |options: &RenderOptions| {
+            let mut out = Vec::new();
+            render_game_state(&mut out, &game_state, options);
+            String::from_utf8(out).unwrap()
+        };
+        let pattern = "_ _ L L _  (+O somewhere, no J/Y)";
+        assert!(render(&RenderOptions::default()).contains(pattern));
+        let hidden = RenderOptions {
+            show_pattern: false,
+            ..RenderOptions::default()
+        };
+        assert!(!render(&hidden).contains(pattern));
+    }
+}
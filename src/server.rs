@@ -0,0 +1,222 @@
+//! `rordle serve`: plays games over TCP, one game per connection with the
+//! plain text interface of `--stdin`, so anyone can play with `nc` or
+//! `telnet`.
+
+use crate::cli::{game_options, language, ListArgs};
+use crate::error::RordleError;
+use crate::{init_game, load_word_list, text_loop};
+use clap::ArgMatches;
+use rordle_core::config::Config;
+use rordle_core::date::Date;
+use rordle_core::digraph;
+use rordle_core::game::{GameOptions, GameState, Outcome};
+use rordle_core::i18n::{self, tr};
+use rordle_core::picker::{DailyPicker, RandomPicker, WordPicker};
+use rordle_core::words::MergedWordList;
+use std::io::{BufReader, ErrorKind, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Connections without input for this long are closed, so players who walk
+/// away do not keep their slot.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// How often the listener checks whether it was asked to shut down.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Set by the signal handler on Ctrl+C or SIGTERM.
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn request_shutdown(_signal: libc::c_int) {
+    SHUTDOWN.store(true, Ordering::SeqCst);
+}
+
+/// Catches Ctrl+C and SIGTERM, the server then stops at the next poll.
+fn install_signal_handlers() {
+    let handler = request_shutdown as extern "C" fn(libc::c_int);
+    // SAFETY: the handler only stores to an atomic, which is async signal safe
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+        libc::signal(libc::SIGTERM, handler as libc::sighandler_t);
+    }
+}
+
+/// Everything a connection needs to set up its game.
+struct Games {
+    word_list: MergedWordList,
+    options: GameOptions,
+    daily: bool,
+}
+
+impl Games {
+    fn new_game(&self) -> Result<GameState, RordleError> {
+        // the day is looked up per connection, so a server running over
+        // midnight moves on to the next word
+        let mut picker: Box<dyn WordPicker> = if self.daily {
+            Box::new(DailyPicker {
+                date: Date::today(),
+            })
+        } else {
+            Box::new(RandomPicker::new())
+        };
+        init_game(
+            self.options.clone(),
+            self.word_list.clone(),
+            picker.as_mut(),
+        )
+    }
+}
+
+/// Open connections, to close them when the server shuts down.
+type Connections = Arc<Mutex<Vec<(SocketAddr, TcpStream)>>>;
+
+/// Plays one game on `stream`.
+fn session(stream: &TcpStream, mut game_state: GameState) -> std::io::Result<GameState> {
+    let mut output = stream;
+    let length = game_state.word.chars().count();
+    let welcome = tr(
+        "welcome",
+        &[("length", &length), ("tries", &game_state.max_tries)],
+    );
+    writeln!(output, "{}", welcome)?;
+    writeln!(output, "{}", tr("hints", &[]))?;
+    text_loop(&mut game_state, BufReader::new(stream), &mut output, "> ")?;
+    Ok(game_state)
+}
+
+fn handle_connection(stream: TcpStream, peer: SocketAddr, games: &Games) {
+    let game_state = match games.new_game() {
+        Ok(game_state) => game_state,
+        Err(error) => {
+            eprintln!("{}: can not start a game: {}", peer, error);
+            return;
+        }
+    };
+    if let Err(error) = stream.set_read_timeout(Some(IDLE_TIMEOUT)) {
+        eprintln!("{}: {}", peer, error);
+        return;
+    }
+    match session(&stream, game_state) {
+        Ok(game_state) => {
+            let outcome = match game_state.outcome() {
+                Outcome::Won => "won",
+                Outcome::Lost => "lost",
+                Outcome::Abandoned => "left",
+            };
+            eprintln!(
+                "{}: {} after {} guesses, the word was {}",
+                peer,
+                outcome,
+                game_state.guesses.len(),
+                digraph::untokenize(&game_state.word)
+            );
+        }
+        Err(error) if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+            let _ = writeln!(&stream, "\n{}", tr("idle", &[]));
+            eprintln!("{}: idle, closed", peer);
+        }
+        Err(error) => eprintln!("{}: {}", peer, error),
+    }
+}
+
+/// Accepts connections until Ctrl+C, each one plays its own game on its own
+/// thread. Connections beyond `max_connections` are turned away.
+fn accept_loop(
+    listener: TcpListener,
+    games: Arc<Games>,
+    max_connections: usize,
+) -> std::io::Result<()> {
+    listener.set_nonblocking(true)?;
+    let connections: Connections = Arc::new(Mutex::new(Vec::new()));
+    let mut threads: Vec<JoinHandle<()>> = Vec::new();
+    while !SHUTDOWN.load(Ordering::SeqCst) {
+        let (mut stream, peer) = match listener.accept() {
+            Ok(connection) => connection,
+            Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(error) => {
+                // e.g. the peer reset the connection before it was accepted
+                eprintln!("can not accept a connection: {}", error);
+                continue;
+            }
+        };
+        threads.retain(|thread| !thread.is_finished());
+        let mut open = connections.lock().unwrap();
+        if open.len() >= max_connections {
+            let _ = writeln!(stream, "{}", tr("server-full", &[]));
+            eprintln!("{}: turned away, {} players connected", peer, open.len());
+            continue;
+        }
+        // accepted sockets may inherit the non-blocking mode of the listener
+        let registered = stream
+            .set_nonblocking(false)
+            .and_then(|_| stream.try_clone());
+        match registered {
+            Ok(clone) => open.push((peer, clone)),
+            Err(error) => {
+                eprintln!("{}: {}", peer, error);
+                continue;
+            }
+        }
+        drop(open);
+        eprintln!("{}: connected", peer);
+        let games = Arc::clone(&games);
+        let connections = Arc::clone(&connections);
+        threads.push(std::thread::spawn(move || {
+            handle_connection(stream, peer, &games);
+            connections
+                .lock()
+                .unwrap()
+                .retain(|(addr, _)| *addr != peer);
+        }));
+    }
+
+    eprintln!("shutting down");
+    for (_, stream) in connections.lock().unwrap().iter() {
+        let mut stream = stream;
+        let _ = writeln!(stream, "\n{}", tr("server-shutdown", &[]));
+        // wakes up the thread reading from the connection
+        let _ = stream.shutdown(Shutdown::Both);
+    }
+    for thread in threads {
+        let _ = thread.join();
+    }
+    Ok(())
+}
+
+/// `rordle serve`: listens for players until Ctrl+C.
+pub(crate) fn serve(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    i18n::set_language(language(matches, config));
+    let word_list = load_word_list(&ListArgs::from_matches(matches), config, false)?;
+    if word_list.solutions.is_empty() {
+        return Err(RordleError::EmptyWordList);
+    }
+    let games = Arc::new(Games {
+        word_list,
+        options: game_options(matches, config),
+        daily: matches.is_present("daily"),
+    });
+    let port: u16 = matches.value_of("port").unwrap().parse().unwrap();
+    let max_connections: usize = matches
+        .value_of("max-connections")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let address = (matches.value_of("bind").unwrap(), port);
+    let listener = TcpListener::bind(address).map_err(RordleError::Network)?;
+    let address = listener.local_addr().map_err(RordleError::Network)?;
+    install_signal_handlers();
+    eprintln!(
+        "listening on {}, play with `nc {} {}`",
+        address,
+        address.ip(),
+        address.port()
+    );
+    accept_loop(listener, games, max_connections).map_err(RordleError::Network)?;
+    Ok(0)
+}
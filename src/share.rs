@@ -0,0 +1,275 @@
+//! Results shared from the official Wordle, e.g.
+//!
+//! ```text
+//! Wordle 1,234 4/6*
+//!
+//! ⬛🟨⬛⬛⬛
+//! ⬛⬛🟩🟨⬛
+//! 🟩🟩🟩⬛🟩
+//! 🟩🟩🟩🟩🟩
+//! ```
+//!
+//! so that they can be imported into the stats. The words are not part of
+//! a share, only the hints, which are recorded as patterns like `-y---`.
+
+use crate::date::{DailyEpoch, Puzzle};
+use crate::game::HitInfo;
+use crate::stats::{GameRecord, IMPORTED};
+
+/// Number of guesses of the official game.
+const MAX_TRIES: usize = 6;
+
+/// A parsed share.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    /// Number of the puzzle, counted like the official Wordle.
+    pub number: i64,
+    /// Whether the game was played in hard mode, marked by a `*`.
+    pub hard: bool,
+    /// Whether the word was found.
+    pub won: bool,
+    /// Hints of each guess.
+    pub rows: Vec<Vec<HitInfo>>,
+}
+
+impl Share {
+    /// The record of the share, with an empty word and the patterns of the
+    /// hints as guesses. It is timestamped at noon UTC of the day of the
+    /// puzzle, which is that very day in most timezones.
+    pub fn record(&self) -> GameRecord {
+        let puzzle = Puzzle {
+            epoch: DailyEpoch::Wordle,
+            number: self.number,
+        };
+        GameRecord {
+            timestamp: puzzle.date().days() * 86400 + 43200,
+            mode: IMPORTED.to_string(),
+            word: String::new(),
+            guesses: self.rows.iter().map(|row| pattern(row)).collect(),
+            won: self.won,
+            puzzle: Some(puzzle),
+            times: Vec::new(),
+            count_hints: false,
+            answers_only: false,
+        }
+    }
+}
+
+/// Hints written as by `rordle solve`: `g` for a hit, `y` for a letter at
+/// another position and `-` for a miss.
+fn pattern(row: &[HitInfo]) -> String {
+    row.iter()
+        .map(|hit| match hit {
+            HitInfo::Hit => 'g',
+            HitInfo::Contains => 'y',
+            _ => '-',
+        })
+        .collect()
+}
+
+/// A share which was found but does not add up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareError {
+    /// Line of the header of the share, counted from 1.
+    pub line: usize,
+    /// Number of the puzzle.
+    pub number: i64,
+    /// What is wrong with it.
+    pub reason: String,
+}
+
+impl std::fmt::Display for ShareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: Wordle {}: {}",
+            self.line, self.number, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+/// The number, result and hard mode of a header like `Wordle 1,234 4/6*`,
+/// anywhere in the line. `None` for the result stands for `X`, a lost game.
+fn parse_header(line: &str) -> Option<(i64, Option<usize>, bool)> {
+    let mut words = line.split_whitespace();
+    words.find(|word| word.trim_start_matches(|c: char| !c.is_alphabetic()) == "Wordle")?;
+    let number = words.next()?.replace([',', '.'], "").parse().ok()?;
+    let result = words.next()?;
+    let (tries, max_tries) = result.split_once('/')?;
+    let hard = max_tries.ends_with('*');
+    if max_tries.trim_end_matches('*') != MAX_TRIES.to_string() {
+        return None;
+    }
+    let tries = match tries {
+        "X" | "x" => None,
+        tries => Some(
+            tries
+                .parse()
+                .ok()
+                .filter(|tries| (1..=MAX_TRIES).contains(tries))?,
+        ),
+    };
+    Some((number, tries, hard))
+}
+
+/// The hints of a row of squares. Dark and light mode use black and white
+/// squares for misses, high contrast mode orange and blue for hits and
+/// letters at other positions.
+fn parse_row(line: &str) -> Option<Vec<HitInfo>> {
+    let row: Vec<HitInfo> = line
+        .chars()
+        // emoji may be followed by a variation selector
+        .filter(|c| !c.is_whitespace() && *c != '\u{fe0f}')
+        .map(|c| match c {
+            '🟩' | '🟧' => Some(HitInfo::Hit),
+            '🟨' | '🟦' => Some(HitInfo::Contains),
+            '⬛' | '⬜' => Some(HitInfo::Miss),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if row.is_empty() {
+        return None;
+    }
+    Some(row)
+}
+
+/// Finds all shares in `text`, skipping any other lines around them. A
+/// header without rows below it, or with rows not matching its result, is
+/// an error.
+pub fn parse_shares(text: &str) -> Vec<Result<Share, ShareError>> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut shares = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let (number, tries, hard) = match parse_header(lines[i]) {
+            Some(header) => header,
+            None => {
+                i += 1;
+                continue;
+            }
+        };
+        let header_line = i + 1;
+        i += 1;
+        while i < lines.len() && lines[i].trim().is_empty() {
+            i += 1;
+        }
+        let mut rows = Vec::new();
+        while let Some(row) = lines.get(i).and_then(|line| parse_row(line)) {
+            rows.push(row);
+            i += 1;
+        }
+        let error = |reason: String| ShareError {
+            line: header_line,
+            number,
+            reason,
+        };
+        let won = tries.is_some();
+        let solved = |row: &Vec<HitInfo>| row.iter().all(|hit| *hit == HitInfo::Hit);
+        let expected = tries.unwrap_or(MAX_TRIES);
+        shares.push(if rows.len() != expected {
+            Err(error(format!(
+                "expected {} rows, found {}",
+                expected,
+                rows.len()
+            )))
+        } else if rows.iter().any(|row| row.len() != rows[0].len()) {
+            Err(error("rows of different lengths".to_string()))
+        } else if won != rows.last().is_some_and(solved) {
+            Err(error("the last row does not match the result".to_string()))
+        } else {
+            Ok(Share {
+                number,
+                hard,
+                won,
+                rows,
+            })
+        });
+    }
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shares() {
+        let text = "\
+notes from the group chat
+
+Wordle 812 4/6
+
+⬛🟨⬛⬛⬛
+⬛⬛🟩🟨⬛
+🟩🟩🟩⬛🟩
+🟩🟩🟩🟩🟩
+nice one!
+Wordle 1,234 X/6*
+⬜⬜🟦⬜⬜
+⬜🟧⬜⬜🟦
+🟧🟧⬜⬜⬜
+🟧🟧🟧⬜⬜
+🟧🟧🟧⬜🟧
+🟧🟧🟧🟦🟧
+Wordle 813 3/6
+⬛⬛⬛⬛⬛
+🟩🟩🟩🟩🟩
+I got Wordle 900 in my head
+";
+        let shares = parse_shares(text);
+        assert_eq!(shares.len(), 3);
+        let first = shares[0].as_ref().unwrap();
+        assert_eq!((first.number, first.hard, first.won), (812, false, true));
+        assert_eq!(first.record().guesses, ["-y---", "--gy-", "ggg-g", "ggggg"]);
+        assert_eq!(first.record().to_string(), {
+            let day = Puzzle {
+                epoch: DailyEpoch::Wordle,
+                number: 812,
+            }
+            .date();
+            format!(
+                "{}\timport\t\t-y---,--gy-,ggg-g,ggggg\twon\twordle:812",
+                day.days() * 86400 + 43200
+            )
+        });
+        let second = shares[1].as_ref().unwrap();
+        assert_eq!(
+            (second.number, second.hard, second.won),
+            (1234, true, false)
+        );
+        assert_eq!(
+            second.rows[5],
+            [
+                HitInfo::Hit,
+                HitInfo::Hit,
+                HitInfo::Hit,
+                HitInfo::Contains,
+                HitInfo::Hit
+            ]
+        );
+        assert_eq!(
+            shares[2].as_ref().unwrap_err().to_string(),
+            "line 17: Wordle 813: expected 3 rows, found 2"
+        );
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(parse_header("Wordle 812 4/6"), Some((812, Some(4), false)));
+        assert_eq!(
+            parse_header("🎉 Wordle 1.001 X/6* 🎉"),
+            Some((1001, None, true))
+        );
+        assert_eq!(parse_header("#Wordle 812 2/6"), Some((812, Some(2), false)));
+        assert_eq!(parse_header("Wordle 812 7/6"), None);
+        assert_eq!(parse_header("Wordle 812 4/8"), None);
+        assert_eq!(parse_header("wordle is fun"), None);
+        assert_eq!(
+            parse_row("🟩 🟩\u{fe0f}⬛"),
+            Some(vec![HitInfo::Hit, HitInfo::Hit, HitInfo::Miss])
+        );
+        assert_eq!(parse_row("🟩🟩 gg"), None);
+    }
+}
@@ -0,0 +1,854 @@
+//! Narrowing down the solution from the hints of earlier guesses.
+
+use crate::game::{score, score_letters, GameState, HitInfo, LetterCounts};
+use crate::parallel;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// A guess and the hints it was scored with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feedback {
+    /// The guessed word.
+    pub guess: String,
+    /// Hint of each letter of the guess.
+    pub hits: Vec<HitInfo>,
+}
+
+impl Feedback {
+    /// Whether `word` could be the solution given this feedback.
+    pub fn allows(&self, word: &str) -> bool {
+        self.allows_candidate(&Candidate::new(word))
+    }
+
+    /// Whether the candidate could be the solution, checking the hint of
+    /// each letter without scoring the guess.
+    fn allows_candidate(&self, candidate: &Candidate) -> bool {
+        candidate.letters.len() == self.hits.len()
+            && self
+                .guess
+                .chars()
+                .zip(&candidate.letters)
+                .zip(&self.hits)
+                .all(|((c, w), hit)| match hit {
+                    HitInfo::Hit => c == *w,
+                    HitInfo::Contains => c != *w && candidate.counts.get(c) > 0,
+                    HitInfo::Miss => candidate.counts.get(c) == 0,
+                    HitInfo::TooMany => c != *w && candidate.counts.get(c) == self.found(c),
+                    HitInfo::None => false,
+                })
+    }
+
+    /// Number of letters `c` of the guess which are hits or contained.
+    fn found(&self, c: char) -> u8 {
+        self.guess
+            .chars()
+            .zip(&self.hits)
+            .filter(|(g, hit)| *g == c && matches!(hit, HitInfo::Hit | HitInfo::Contains))
+            .count() as u8
+    }
+}
+
+/// A possible solution with its letters counted once, as it is checked
+/// against the feedback of every guess.
+struct Candidate<'a> {
+    word: &'a str,
+    letters: Vec<char>,
+    counts: LetterCounts,
+}
+
+impl<'a> Candidate<'a> {
+    fn new(word: &'a str) -> Candidate<'a> {
+        let letters: Vec<char> = word.chars().collect();
+        Candidate {
+            word,
+            counts: LetterCounts::new(&letters),
+            letters,
+        }
+    }
+}
+
+impl std::str::FromStr for Feedback {
+    type Err = String;
+
+    /// Parses `GUESS=PATTERN`, where the pattern has a `g` (green) for each
+    /// hit, a `y` (yellow) for each misplaced letter, a `-` for misses and
+    /// an `x` for letters guessed too often with count hints.
+    fn from_str(text: &str) -> Result<Feedback, String> {
+        let (guess, pattern) = text
+            .split_once('=')
+            .ok_or_else(|| format!("expected GUESS=PATTERN, not `{}`", text))?;
+        let guess = guess.trim().to_lowercase();
+        let hits = pattern
+            .trim()
+            .chars()
+            .map(|c| match c {
+                'g' => Ok(HitInfo::Hit),
+                'y' => Ok(HitInfo::Contains),
+                '-' => Ok(HitInfo::Miss),
+                'x' => Ok(HitInfo::TooMany),
+                c => Err(format!(
+                    "invalid hint `{}` in `{}`, expected g, y, - or x",
+                    c, text
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if guess.chars().count() != hits.len() {
+            return Err(format!(
+                "`{}` has {} letters but {} hints",
+                guess,
+                guess.chars().count(),
+                hits.len()
+            ));
+        }
+        Ok(Feedback { guess, hits })
+    }
+}
+
+/// Words which could be the solution given all feedback.
+pub fn candidates<'a, I>(words: I, feedback: &[Feedback]) -> Vec<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    words
+        .into_iter()
+        .map(Candidate::new)
+        .filter(|candidate| feedback.iter().all(|f| f.allows_candidate(candidate)))
+        .map(|candidate| candidate.word)
+        .collect()
+}
+
+/// Plays a game against `solution`, always guessing the first word still
+/// possible. Returns the guesses made, ending with the solution if it was
+/// found within `max_tries`.
+pub fn solve<'a>(solution: &str, words: &[&'a str], max_tries: u16) -> Vec<&'a str> {
+    solve_from(solution, words, None, max_tries)
+}
+
+/// Like `solve`, but guessing `opener` first if given, e.g. the best
+/// ranked one.
+pub fn solve_from<'a>(
+    solution: &str,
+    words: &[&'a str],
+    opener: Option<&'a str>,
+    max_tries: u16,
+) -> Vec<&'a str> {
+    let mut guesses = Vec::new();
+    let length = solution.chars().count();
+    // letters of the remaining words are counted once for all guesses
+    let mut remaining: Vec<Candidate> = words
+        .iter()
+        .map(|word| Candidate::new(word))
+        .filter(|candidate| candidate.letters.len() == length)
+        .collect();
+    while guesses.len() < max_tries as usize {
+        let guess = match (opener.filter(|_| guesses.is_empty()), remaining.first()) {
+            (Some(opener), _) => opener,
+            (None, Some(candidate)) => candidate.word,
+            (None, None) => break,
+        };
+        guesses.push(guess);
+        if guess == solution {
+            break;
+        }
+        let feedback = Feedback {
+            guess: guess.to_string(),
+            hits: score(guess, solution),
+        };
+        remaining.retain(|candidate| feedback.allows_candidate(candidate));
+    }
+    guesses
+}
+
+/// Hints of the guesses of a running game.
+fn game_feedback(game_state: &GameState) -> Vec<Feedback> {
+    game_state
+        .guesses
+        .iter()
+        .enumerate()
+        .map(|(i, guess)| Feedback {
+            guess: guess.clone(),
+            hits: game_state.get_guess_hits(i),
+        })
+        .collect()
+}
+
+/// Words a running game accepts with the length of the solution and its
+/// prefilled letters, before looking at any hints.
+fn playable<'a>(game_state: &'a GameState) -> impl Iterator<Item = Candidate<'a>> {
+    let solution = game_state.letters();
+    game_state
+        .guessable_words()
+        .iter()
+        .map(Candidate::new)
+        .filter(move |candidate| candidate.letters.len() == solution.len())
+        .filter(move |candidate| {
+            (0..solution.len())
+                .all(|i| !game_state.prefilled(i) || candidate.letters[i] == solution[i])
+        })
+}
+
+/// The guess the solver makes next in a running game: the first valid word
+/// which could still be the solution, given the hints of the guesses so
+/// far and the prefilled letters.
+pub fn suggest(game_state: &GameState) -> Option<&str> {
+    let feedback = game_feedback(game_state);
+    playable(game_state)
+        .filter(|candidate| !game_state.guesses.iter().any(|g| g == candidate.word))
+        .find(|candidate| feedback.iter().all(|f| f.allows_candidate(candidate)))
+        .map(|candidate| candidate.word)
+}
+
+/// Number of valid words which could still have been the solution after
+/// each guess of a running game, given the hints up to it.
+pub fn remaining_counts(game_state: &GameState) -> Vec<usize> {
+    let feedback = game_feedback(game_state);
+    let mut counts = vec![0; feedback.len()];
+    for candidate in playable(game_state) {
+        let allowed = feedback
+            .iter()
+            .take_while(|f| f.allows_candidate(&candidate))
+            .count();
+        counts[..allowed].iter_mut().for_each(|count| *count += 1);
+    }
+    counts
+}
+
+/// How likely each letter is to be in the solution of a running game: the
+/// fraction of the valid words which could still be the solution having
+/// it, a word with a letter twice counting once. Letters none of them has
+/// are left out.
+pub fn letter_frequencies(game_state: &GameState) -> HashMap<char, f64> {
+    let feedback = game_feedback(game_state);
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut remaining = 0;
+    for candidate in playable(game_state) {
+        if !feedback.iter().all(|f| f.allows_candidate(&candidate)) {
+            continue;
+        }
+        remaining += 1;
+        let letters: BTreeSet<char> = candidate.letters.iter().copied().collect();
+        for c in letters {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(c, count)| (c, count as f64 / remaining as f64))
+        .collect()
+}
+
+/// Letters still possible at each position of a running game: the letters
+/// there of the valid words which could still be the solution, sorted.
+/// `None` for positions the hints and prefilled letters did not narrow
+/// down, which allow every letter the valid words have there, unless a
+/// letter was found there.
+pub fn possible_letters(game_state: &GameState) -> Vec<Option<Vec<char>>> {
+    let length = game_state.letters().len();
+    let feedback = game_feedback(game_state);
+    let mut all = vec![BTreeSet::new(); length];
+    let mut possible = vec![BTreeSet::new(); length];
+    for candidate in playable(game_state) {
+        let allowed = feedback.iter().all(|f| f.allows_candidate(&candidate));
+        for (i, &c) in candidate.letters.iter().enumerate() {
+            all[i].insert(c);
+            if allowed {
+                possible[i].insert(c);
+            }
+        }
+    }
+    // found letters are known, even if every valid word has them
+    let found =
+        |i: usize| game_state.prefilled(i) || feedback.iter().any(|f| f.hits[i] == HitInfo::Hit);
+    all.into_iter()
+        .zip(possible)
+        .enumerate()
+        .map(|(i, (all, possible))| {
+            if all == possible && !found(i) {
+                None
+            } else {
+                Some(possible.into_iter().collect())
+            }
+        })
+        .collect()
+}
+
+/// How `rank` orders the candidates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Weighting {
+    /// Most informative guesses first, common words first among equally
+    /// informative ones.
+    Info,
+    /// Most common words first, informative words first among equally
+    /// common ones.
+    Freq,
+    /// By the mean of information and frequency, ties are broken by
+    /// information, then by frequency.
+    #[default]
+    Balanced,
+}
+
+impl Weighting {
+    /// Names accepted by `FromStr`.
+    pub const NAMES: [&'static str; 3] = ["info", "freq", "balanced"];
+}
+
+impl std::str::FromStr for Weighting {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Weighting, String> {
+        match name {
+            "info" => Ok(Weighting::Info),
+            "freq" => Ok(Weighting::Freq),
+            "balanced" => Ok(Weighting::Balanced),
+            _ => Err(format!(
+                "unknown weighting `{}`, expected one of {}",
+                name,
+                Weighting::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Guesses are scored against at most this many candidates, so ranking
+/// stays fast on lists without hints.
+const RANK_SAMPLE: usize = 256;
+
+/// Every n-th of `candidates`, at most `RANK_SAMPLE` of them.
+fn sample<'a>(candidates: &[&'a str]) -> Vec<Candidate<'a>> {
+    let step = candidates.len().div_ceil(RANK_SAMPLE).max(1);
+    candidates
+        .iter()
+        .step_by(step)
+        .map(|word| Candidate::new(word))
+        .collect()
+}
+
+/// Expected information of guessing `guess` in bits: the entropy of the
+/// hints it gets against the candidates.
+fn information(guess: &str, candidates: &[Candidate]) -> f64 {
+    let mut patterns: Vec<Vec<u8>> = candidates
+        .iter()
+        .map(|candidate| {
+            score_letters(guess.chars(), &candidate.letters, &candidate.counts)
+                .iter()
+                .map(|hit| match hit {
+                    HitInfo::Hit => 0,
+                    HitInfo::Contains => 1,
+                    HitInfo::Miss => 2,
+                    HitInfo::TooMany => 3,
+                    HitInfo::None => 4,
+                })
+                .collect()
+        })
+        .collect();
+    patterns.sort_unstable();
+    let mut sizes: Vec<usize> = patterns
+        .chunk_by(|a, b| a == b)
+        .map(|chunk| chunk.len())
+        .collect();
+    // summed in a fixed order, so guesses splitting the candidates alike
+    // get exactly the same information
+    sizes.sort_unstable();
+    let total = candidates.len() as f64;
+    sizes
+        .iter()
+        .map(|size| {
+            let p = *size as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Orders `candidates` as suggestions. Information and frequency are both
+/// scaled to 0..=1, frequencies logarithmically as counts of words span
+/// orders of magnitude. Words without a frequency count as the rarest.
+/// Candidates scoring the same keep their order.
+pub fn rank<'a>(
+    candidates: &[&'a str],
+    frequencies: &HashMap<String, u64>,
+    weighting: Weighting,
+) -> Vec<&'a str> {
+    let sample = sample(candidates);
+    let max_information = (sample.len() as f64).log2();
+    let max_frequency = candidates
+        .iter()
+        .filter_map(|word| frequencies.get(*word))
+        .max()
+        .map_or(0.0, |count| (*count as f64).ln_1p());
+
+    // scoring the guesses is most of the work, each one on its own
+    let mut scored: Vec<(&str, f64, f64)> = parallel::map(candidates, |word| {
+        let info = if max_information > 0.0 {
+            information(word, &sample) / max_information
+        } else {
+            0.0
+        };
+        let freq = match frequencies.get(*word) {
+            Some(count) if max_frequency > 0.0 => (*count as f64).ln_1p() / max_frequency,
+            _ => 0.0,
+        };
+        (*word, info, freq)
+    });
+    // stable, so ties keep the order of the list
+    scored.sort_by(|(_, info_a, freq_a), (_, info_b, freq_b)| {
+        let keys = |info: f64, freq: f64| match weighting {
+            Weighting::Info => [info, freq, 0.0],
+            Weighting::Freq => [freq, info, 0.0],
+            Weighting::Balanced => [(info + freq) / 2.0, info, freq],
+        };
+        let (a, b) = (keys(*info_a, *freq_a), keys(*info_b, *freq_b));
+        b.partial_cmp(&a).unwrap()
+    });
+    scored.into_iter().map(|(word, _, _)| word).collect()
+}
+
+/// Orders `guesses` by the information they gain about which of
+/// `candidates` is the solution, the most first. Guesses need not be
+/// candidates themselves. Guesses gaining the same keep their order.
+pub fn rank_guesses<'a>(guesses: &[&'a str], candidates: &[&str]) -> Vec<&'a str> {
+    let sample = sample(candidates);
+    let mut scored: Vec<(&str, f64)> =
+        parallel::map(guesses, |guess| (*guess, information(guess, &sample)));
+    scored.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+    scored.into_iter().map(|(guess, _)| guess).collect()
+}
+
+/// What the assist panel suggests in a running game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assist<'a> {
+    /// Number of valid words which could still be the solution.
+    pub remaining: usize,
+    /// The most promising of them with the probability of each being the
+    /// solution, every remaining word being equally likely.
+    pub answers: Vec<(&'a str, f64)>,
+    /// The guesses gaining the most information, whether they could be the
+    /// solution or not.
+    pub guesses: Vec<&'a str>,
+}
+
+/// The words `assist` ranks by information if nothing narrowed the
+/// solution down yet, no guess, revealed hint or prefilled letter. Their
+/// ranking is the same for every game of the list, so it may be cached
+/// and given to `assist_opening`. `None` once the game has begun.
+pub fn opening(game_state: &GameState) -> Option<Vec<&str>> {
+    let started = !game_state.guesses.is_empty()
+        || !game_state.hints().is_empty()
+        || (0..game_state.letters().len()).any(|i| game_state.prefilled(i));
+    if started {
+        return None;
+    }
+    Some(
+        playable(game_state)
+            .map(|candidate| candidate.word)
+            .collect(),
+    )
+}
+
+/// Suggests the `limit` best answers and guesses for a running game, see
+/// `Assist`. Words guessed before are not suggested.
+pub fn assist(game_state: &GameState, limit: usize) -> Assist<'_> {
+    assist_opening(game_state, limit, None)
+}
+
+/// Like `assist`, taking the ranking of the words of `opening` from
+/// `openers` while the game has not begun. `openers` must be at least
+/// `limit` long, as ranked by `rank_guesses`.
+pub fn assist_opening<'a>(
+    game_state: &'a GameState,
+    limit: usize,
+    openers: Option<&[String]>,
+) -> Assist<'a> {
+    let feedback = game_feedback(game_state);
+    let playable: Vec<Candidate> = playable(game_state)
+        .filter(|candidate| !game_state.guesses.iter().any(|g| g == candidate.word))
+        .collect();
+    let answers: Vec<&str> = playable
+        .iter()
+        .filter(|candidate| feedback.iter().all(|f| f.allows_candidate(candidate)))
+        .map(|candidate| candidate.word)
+        .collect();
+    let words: Vec<&str> = playable.iter().map(|candidate| candidate.word).collect();
+    // a single answer is the best guess
+    let ranked = match (answers.len(), openers) {
+        (0 | 1, _) => answers.clone(),
+        (_, Some(openers)) if feedback.is_empty() => {
+            let known: HashSet<&str> = words.iter().copied().collect();
+            openers
+                .iter()
+                .filter_map(|opener| known.get(opener.as_str()).copied())
+                .collect()
+        }
+        _ => rank_guesses(&words, &answers),
+    };
+    let probability = 1.0 / answers.len().max(1) as f64;
+    let is_answer: HashSet<&str> = answers.iter().copied().collect();
+    Assist {
+        remaining: answers.len(),
+        // the answers gaining the most, as with `Weighting::Info`
+        answers: ranked
+            .iter()
+            .filter(|word| is_answer.contains(*word))
+            .take(limit)
+            .map(|word| (*word, probability))
+            .collect(),
+        guesses: ranked.into_iter().take(limit).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feedback() {
+        let feedback: Feedback = "Crane=gy--g".parse().unwrap();
+        assert_eq!(feedback.guess, "crane");
+        assert_eq!(
+            feedback.hits,
+            vec![
+                HitInfo::Hit,
+                HitInfo::Contains,
+                HitInfo::Miss,
+                HitInfo::Miss,
+                HitInfo::Hit
+            ]
+        );
+        assert!("crane".parse::<Feedback>().is_err());
+        assert!("crane=gy-".parse::<Feedback>().is_err());
+        assert!("crane=gyzzg".parse::<Feedback>().is_err());
+        let feedback: Feedback = "lolly=xygg-".parse().unwrap();
+        assert_eq!(feedback.hits[0], HitInfo::TooMany);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let words = ["hello", "jolly", "holly", "world", "ab"];
+        let feedback = vec!["jolly=-ygg-".parse().unwrap()];
+        assert_eq!(candidates(words, &feedback), vec!["hello"]);
+        let feedback = vec!["hills=g-gg-".parse().unwrap()];
+        assert_eq!(candidates(words, &feedback), vec!["hello", "holly"]);
+        assert_eq!(candidates(words, &[]), words.to_vec());
+        // with count hints the word has exactly the two l found
+        let feedback = vec!["lolly=xygg-".parse().unwrap()];
+        let words = ["hello", "cello", "holly", "oelll"];
+        assert_eq!(candidates(words, &feedback), vec!["hello", "cello"]);
+    }
+
+    #[test]
+    fn test_solve() {
+        let words = ["hello", "holly", "jolly", "world"];
+        assert_eq!(solve("hello", &words, 6), vec!["hello"]);
+        assert_eq!(solve("jolly", &words, 6), vec!["hello", "jolly"]);
+        assert_eq!(solve("jolly", &words, 1), vec!["hello"]);
+        assert_eq!(
+            solve_from("hello", &words, Some("world"), 6),
+            vec!["world", "hello"]
+        );
+        assert_eq!(solve_from("world", &words, Some("world"), 6), vec!["world"]);
+    }
+
+    fn frequencies(counts: &[(&str, u64)]) -> HashMap<String, u64> {
+        counts
+            .iter()
+            .map(|(word, count)| (word.to_string(), *count))
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_rare_loses_to_common_of_equal_information() {
+        // every guess tells hello, jello and cello apart equally well
+        let words = ["cello", "hello", "jello"];
+        let counts = frequencies(&[("cello", 40), ("hello", 90_000), ("jello", 700)]);
+        for weighting in Weighting::NAMES {
+            assert_eq!(
+                rank(&words, &counts, weighting.parse().unwrap()),
+                vec!["hello", "jello", "cello"],
+                "{}",
+                weighting
+            );
+        }
+    }
+
+    #[test]
+    fn test_rank_weightings() {
+        // below gets the same hints against hello and jello, so it tells
+        // less than either of them
+        let words = ["hello", "jello", "below"];
+        let counts = frequencies(&[("hello", 1), ("jello", 2), ("below", 1_000_000)]);
+        assert_eq!(
+            rank(&words, &counts, Weighting::Info),
+            vec!["jello", "hello", "below"]
+        );
+        assert_eq!(
+            rank(&words, &counts, Weighting::Freq),
+            vec!["below", "jello", "hello"]
+        );
+        assert_eq!(
+            rank(&words, &counts, Weighting::Balanced),
+            vec!["below", "jello", "hello"]
+        );
+        // without frequencies informative words come first, ties keep the
+        // order of the list
+        assert_eq!(
+            rank(&words, &HashMap::new(), Weighting::Freq),
+            vec!["hello", "jello", "below"]
+        );
+        assert_eq!(
+            rank(&["crane"], &counts, Weighting::Balanced),
+            vec!["crane"]
+        );
+        assert!("popular".parse::<Weighting>().is_err());
+    }
+
+    #[test]
+    fn test_suggest() {
+        use crate::game::GameOptions;
+        let words = ["hello", "holly", "jolly", "world"];
+        let mut game_state = GameState::new(
+            "jolly".to_string(),
+            words.iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let mut guesses = Vec::new();
+        while let Some(guess) = suggest(&game_state) {
+            let guess = guess.to_string();
+            guesses.push(guess.clone());
+            if game_state.guess(guess).unwrap() {
+                break;
+            }
+        }
+        // the same guesses as solving against the known solution
+        assert_eq!(guesses, solve("jolly", &words, 6));
+        assert!(suggest(&game_state).is_none());
+    }
+
+    #[test]
+    fn test_letter_frequencies() {
+        use crate::game::GameOptions;
+        let words = ["hello", "holly", "jolly", "hills", "world"];
+        let mut game_state = GameState::new(
+            "jolly".to_string(),
+            words.iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let frequencies = letter_frequencies(&game_state);
+        // the l of every word counts once, even twice in a word
+        assert_eq!(frequencies[&'l'], 1.0);
+        assert_eq!(frequencies[&'o'], 0.8);
+        assert_eq!(frequencies[&'h'], 0.6);
+        assert_eq!(frequencies.get(&'q'), None);
+        // world -g-g- leaves holly and jolly
+        game_state.guess("world".to_string()).unwrap();
+        let frequencies = letter_frequencies(&game_state);
+        assert_eq!(frequencies[&'j'], 0.5);
+        assert_eq!(frequencies[&'y'], 1.0);
+        assert_eq!(frequencies.get(&'w'), None);
+        assert_eq!(frequencies.len(), 5);
+    }
+
+    #[test]
+    fn test_possible_letters() {
+        use crate::game::GameOptions;
+        let words = ["hello", "holly", "jolly", "hills", "world"];
+        let mut game_state = GameState::new(
+            "jolly".to_string(),
+            words.iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(possible_letters(&game_state), vec![None; 5]);
+        // world -g-g- leaves holly and jolly
+        game_state.guess("world".to_string()).unwrap();
+        let letters = |s: &str| Some(s.chars().collect::<Vec<char>>());
+        assert_eq!(
+            possible_letters(&game_state),
+            vec![
+                letters("hj"),
+                letters("o"),
+                letters("l"),
+                letters("l"),
+                letters("y")
+            ]
+        );
+        let prefilled = GameState::new(
+            "jolly".to_string(),
+            words.iter().collect(),
+            GameOptions::default().prefill(4).seed(Some(1)),
+        )
+        .unwrap();
+        let known = possible_letters(&prefilled);
+        for (i, letters) in known.iter().enumerate() {
+            if prefilled.prefilled(i) {
+                assert_eq!(letters, &Some(vec![prefilled.letters()[i]]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_remaining_counts() {
+        use crate::game::GameOptions;
+        let words = ["hello", "holly", "jolly", "golly", "ghost", "world"];
+        let mut game_state = GameState::new(
+            "jolly".to_string(),
+            words.iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        assert!(remaining_counts(&game_state).is_empty());
+        game_state.guess("world".to_string()).unwrap();
+        game_state.guess("ghost".to_string()).unwrap();
+        game_state.guess("jolly".to_string()).unwrap();
+        assert_eq!(remaining_counts(&game_state), [3, 1, 1]);
+    }
+
+    #[test]
+    fn test_assist() {
+        use crate::game::GameOptions;
+        let words = ["hello", "holly", "jolly", "golly", "ghost", "world"];
+        let mut game_state = GameState::new(
+            "jolly".to_string(),
+            words.iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        // world -g-g- leaves holly, jolly and golly
+        game_state.guess("world".to_string()).unwrap();
+        let assist = assist(&game_state, 2);
+        assert_eq!(assist.remaining, 3);
+        assert_eq!(assist.answers.len(), 2);
+        for (answer, probability) in &assist.answers {
+            assert!(["holly", "jolly", "golly"].contains(answer));
+            assert!((probability - 1.0 / 3.0).abs() < 1e-9);
+        }
+        // ghost tells all three apart, but can not be the solution
+        assert_eq!(
+            rank_guesses(&["jolly", "ghost"], &["holly", "jolly", "golly"]),
+            ["ghost", "jolly"]
+        );
+        assert!(!assist.guesses.contains(&"world"));
+        game_state.guess("jolly".to_string()).unwrap();
+        assert_eq!(super::assist(&game_state, 2).remaining, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-words")]
+    fn test_rank_any_jobs() {
+        let words = builtin_words();
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).take(500).collect();
+        let frequencies = frequencies(&[("crane", 5), ("slate", 5)]);
+        crate::parallel::set_jobs(1);
+        let ranked = rank(&words, &frequencies, Weighting::Balanced);
+        let guesses = rank_guesses(&words, &words[..100]);
+        crate::parallel::set_jobs(4);
+        assert_eq!(rank(&words, &frequencies, Weighting::Balanced), ranked);
+        assert_eq!(rank_guesses(&words, &words[..100]), guesses);
+        crate::parallel::set_jobs(0);
+    }
+
+    #[test]
+    fn test_assist_opening() {
+        use crate::game::GameOptions;
+        let words = ["hello", "holly", "jolly", "golly", "ghost", "world"];
+        let mut game_state = GameState::new(
+            "jolly".to_string(),
+            words.iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        let opening = opening(&game_state).unwrap();
+        assert_eq!(opening.len(), 6);
+        let ranked: Vec<String> = rank_guesses(&opening, &opening)
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        // the cached ranking gives what ranking the words again gives
+        assert_eq!(
+            assist_opening(&game_state, 3, Some(&ranked)),
+            assist(&game_state, 3)
+        );
+        game_state.guess("world".to_string()).unwrap();
+        assert_eq!(super::opening(&game_state), None);
+        assert_eq!(
+            assist_opening(&game_state, 3, Some(&ranked)),
+            assist(&game_state, 3)
+        );
+    }
+
+    /// The filter before letters were counted: scoring the guess against
+    /// every word.
+    #[cfg(feature = "bundled-words")]
+    fn naive_candidates<'a>(words: &[&'a str], feedback: &[Feedback]) -> Vec<&'a str> {
+        words
+            .iter()
+            .filter(|word| {
+                feedback.iter().all(|f| {
+                    word.chars().count() == f.hits.len() && score(&f.guess, word) == f.hits
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    #[cfg(feature = "bundled-words")]
+    fn builtin_words() -> Vec<String> {
+        use crate::words::{EmbeddedSource, WordSource};
+        EmbeddedSource.load().unwrap().words
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-words")]
+    fn test_candidates_match_scoring() {
+        let words = builtin_words();
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).step_by(13).collect();
+        for solution in words.iter().step_by(97) {
+            for guess in words.iter().step_by(89) {
+                let feedback = vec![Feedback {
+                    guess: guess.to_string(),
+                    hits: score(guess, solution),
+                }];
+                assert_eq!(
+                    candidates(words.iter().cloned(), &feedback),
+                    naive_candidates(&words, &feedback),
+                    "{} against {}",
+                    guess,
+                    solution
+                );
+            }
+        }
+    }
+
+    /// Compares filtering the full built-in dictionary with and without
+    /// counting letters, run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    #[cfg(feature = "bundled-words")]
+    fn bench_candidates() {
+        use std::time::Instant;
+        let words = builtin_words();
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+        let feedback: Vec<Feedback> = ["crane=-y--y", "slate=-g--g"]
+            .iter()
+            .map(|f| f.parse().unwrap())
+            .collect();
+
+        let start = Instant::now();
+        for _ in 0..20 {
+            naive_candidates(&words, &feedback);
+        }
+        let naive = start.elapsed() / 20;
+        let start = Instant::now();
+        for _ in 0..20 {
+            candidates(words.iter().cloned(), &feedback);
+        }
+        let counted = start.elapsed() / 20;
+        println!("words:   {}", words.len());
+        println!("filter:  scoring {:?}, counting {:?}", naive, counted);
+
+        let start = Instant::now();
+        for solution in words.iter().step_by(100) {
+            solve(solution, &words, 6);
+        }
+        println!("solve:   {:?}", start.elapsed());
+    }
+}
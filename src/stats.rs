@@ -0,0 +1,1078 @@
+//! Statistics of played games, stored one game per line.
+//!
+//! A line holds the timestamp, mode, solution, comma separated guesses and
+//! `won` or `lost`, separated by tabs, e.g.
+//! `1645747200<TAB>daily<TAB>cigar<TAB>crane,cigar<TAB>won`. Daily games
+//! add the epoch and number of their puzzle, e.g. `<TAB>wordle:250`.
+//! After it, empty for other games, come the milliseconds each guess took,
+//! e.g. `<TAB>12345,-,4100`, with `-` for a guess whose time is unknown.
+//! Games played with rules changing how hard they are end with a comma
+//! separated field of them: `counted` for count hints, which tell more than
+//! the plain ones, and `answers-only` for guesses limited to solutions.
+//! Past daily puzzles played later are recorded with the mode `BACKFILL`,
+//! games imported from shares of the official Wordle with `IMPORTED`.
+
+use crate::date::{Date, Puzzle};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Mode of past daily puzzles played later, which do not count for streaks.
+pub const BACKFILL: &str = "backfill";
+
+/// Mode of games imported from [`crate::share`]s. Their word is empty and
+/// their guesses are the patterns of the hints, e.g. `-y-g-`.
+pub const IMPORTED: &str = "import";
+
+/// A finished game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    /// Unix timestamp of the end of the game.
+    pub timestamp: i64,
+    /// How the solution was picked, e.g. `play` or `daily`.
+    pub mode: String,
+    /// The solution.
+    pub word: String,
+    /// Confirmed guesses, in order.
+    pub guesses: Vec<String>,
+    /// Whether the solution was found.
+    pub won: bool,
+    /// The puzzle of a daily game.
+    pub puzzle: Option<Puzzle>,
+    /// Time each guess took, `None` if it is unknown. Empty for games
+    /// recorded without times.
+    pub times: Vec<Option<Duration>>,
+    /// Whether the hints told how often each letter is in the word, see
+    /// [`crate::game::GameOptions::count_hints`].
+    pub count_hints: bool,
+    /// Whether only words which can be the solution were accepted, see
+    /// [`crate::game::GameOptions::answers_only`].
+    pub answers_only: bool,
+}
+
+/// Names of the rules in the last field of a line, see [`GameRecord::rules`].
+const COUNT_HINTS: &str = "counted";
+const ANSWERS_ONLY: &str = "answers-only";
+
+impl GameRecord {
+    fn parse(line: &str) -> Option<GameRecord> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (timestamp, mode, word, guesses, result, puzzle, times, rules) = match fields[..] {
+            [timestamp, mode, word, guesses, result] => {
+                (timestamp, mode, word, guesses, result, "", "", "")
+            }
+            [timestamp, mode, word, guesses, result, puzzle] => {
+                (timestamp, mode, word, guesses, result, puzzle, "", "")
+            }
+            [timestamp, mode, word, guesses, result, puzzle, times] => {
+                (timestamp, mode, word, guesses, result, puzzle, times, "")
+            }
+            [timestamp, mode, word, guesses, result, puzzle, times, rules] if !rules.is_empty() => {
+                (timestamp, mode, word, guesses, result, puzzle, times, rules)
+            }
+            _ => return None,
+        };
+        let rules: Vec<&str> = rules.split(',').filter(|rule| !rule.is_empty()).collect();
+        if rules
+            .iter()
+            .any(|rule| ![COUNT_HINTS, ANSWERS_ONLY].contains(rule))
+        {
+            return None;
+        }
+        let puzzle = match puzzle.split_once(':') {
+            Some((epoch, number)) => Some(Puzzle {
+                epoch: epoch.parse().ok()?,
+                number: number.parse().ok()?,
+            }),
+            None if puzzle.is_empty() => None,
+            None => return None,
+        };
+        Some(GameRecord {
+            timestamp: timestamp.parse().ok()?,
+            mode: mode.to_string(),
+            word: word.to_string(),
+            guesses: guesses
+                .split(',')
+                .filter(|guess| !guess.is_empty())
+                .map(|guess| guess.to_string())
+                .collect(),
+            won: match result {
+                "won" => true,
+                "lost" => false,
+                _ => return None,
+            },
+            puzzle,
+            times: times
+                .split(',')
+                .filter(|time| !time.is_empty())
+                .map(|time| match time {
+                    "-" => Some(None),
+                    time => Some(Some(Duration::from_millis(time.parse().ok()?))),
+                })
+                .collect::<Option<_>>()?,
+            count_hints: rules.contains(&COUNT_HINTS),
+            answers_only: rules.contains(&ANSWERS_ONLY),
+        })
+    }
+
+    /// The rules changing how hard the game was, as written to the stats
+    /// file, e.g. `["counted"]`.
+    pub fn rules(&self) -> Vec<&'static str> {
+        [
+            (self.count_hints, COUNT_HINTS),
+            (self.answers_only, ANSWERS_ONLY),
+        ]
+        .into_iter()
+        .filter_map(|(on, rule)| on.then_some(rule))
+        .collect()
+    }
+
+    /// Day the game ended on, in UTC.
+    pub fn date(&self) -> Date {
+        Date::from_timestamp(self.timestamp)
+    }
+
+    /// Day the game belongs to: the day of its puzzle for daily games, as
+    /// they may be played in a timezone which is a day ahead or behind UTC.
+    pub fn day(&self) -> Date {
+        self.puzzle
+            .map_or_else(|| self.date(), |puzzle| puzzle.date())
+    }
+}
+
+impl std::fmt::Display for GameRecord {
+    /// Formats the record as a line of the stats file, without a newline.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}",
+            self.timestamp,
+            self.mode,
+            self.word,
+            self.guesses.join(","),
+            if self.won { "won" } else { "lost" }
+        )?;
+        let puzzle = self
+            .puzzle
+            .map(|puzzle| format!("{}:{}", puzzle.epoch.name(), puzzle.number));
+        let rules = self.rules();
+        if self.times.is_empty() && rules.is_empty() {
+            return match puzzle {
+                Some(puzzle) => write!(f, "\t{}", puzzle),
+                None => Ok(()),
+            };
+        }
+        let times: Vec<String> = self
+            .times
+            .iter()
+            .map(|time| time.map_or("-".to_string(), |time| time.as_millis().to_string()))
+            .collect();
+        write!(f, "\t{}\t{}", puzzle.unwrap_or_default(), times.join(","))?;
+        if !rules.is_empty() {
+            write!(f, "\t{}", rules.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors reading or writing the stats file.
+#[derive(Debug)]
+pub enum StatsError {
+    /// The file could not be read or written.
+    Io(PathBuf, std::io::Error),
+    /// A line is not a valid record.
+    Malformed {
+        /// Path of the stats file.
+        path: PathBuf,
+        /// Line of the record, counted from 1.
+        line: usize,
+    },
+}
+
+impl std::fmt::Display for StatsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StatsError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            StatsError::Malformed { path, line } => {
+                write!(f, "{}:{}: malformed game record", path.display(), line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+/// Reads all records of the stats file, a missing file contains no games.
+pub fn read_records(path: &Path) -> Result<Vec<GameRecord>, StatsError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(StatsError::Io(path.to_path_buf(), error)),
+    };
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            GameRecord::parse(line).ok_or_else(|| StatsError::Malformed {
+                path: path.to_path_buf(),
+                line: i + 1,
+            })
+        })
+        .collect()
+}
+
+/// Whether the daily puzzle of `date` was recorded before, as `daily` or as
+/// [`BACKFILL`] and in either numbering. A puzzle is recorded once, later
+/// games of it are replays. Imported games are puzzles of another game.
+pub fn played_daily(records: &[GameRecord], date: Date) -> bool {
+    records.iter().any(|record| {
+        record.mode != IMPORTED && record.puzzle.is_some_and(|puzzle| puzzle.date() == date)
+    })
+}
+
+/// Appends a record to the stats file, creating it if needed.
+pub fn append_record(path: &Path, record: &GameRecord) -> Result<(), StatsError> {
+    let error = |error| StatsError::Io(path.to_path_buf(), error);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(error)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(error)?;
+    writeln!(file, "{}", record).map_err(error)
+}
+
+/// Replaces the stats file with `records`. The file is written next to it
+/// first, so that it is never left half written.
+pub fn write_records(path: &Path, records: &[GameRecord]) -> Result<(), StatsError> {
+    let error = |error| StatsError::Io(path.to_path_buf(), error);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(error)?;
+    }
+    let contents: String = records
+        .iter()
+        .map(|record| format!("{}\n", record))
+        .collect();
+    let temporary = path.with_extension("tmp");
+    std::fs::write(&temporary, contents).map_err(error)?;
+    std::fs::rename(&temporary, path).map_err(error)
+}
+
+/// Reads the hardcore run, the number of `--hardcore` games won in a row
+/// since the last one lost or abandoned. It is kept in a file of its own,
+/// a missing file is a run of 0.
+pub fn read_run(path: &Path) -> Result<u32, StatsError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(error) => return Err(StatsError::Io(path.to_path_buf(), error)),
+    };
+    contents.trim().parse().map_err(|_| StatsError::Malformed {
+        path: path.to_path_buf(),
+        line: 1,
+    })
+}
+
+/// Saves the hardcore run read by `read_run`.
+pub fn write_run(path: &Path, run: u32) -> Result<(), StatsError> {
+    let error = |error| StatsError::Io(path.to_path_buf(), error);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(error)?;
+    }
+    std::fs::write(path, format!("{}\n", run)).map_err(error)
+}
+
+/// Adds the `imported` records of daily puzzles, skipping puzzles which are
+/// recorded already, and sorts all records by time. Returns the number of
+/// records added.
+pub fn merge_records(records: &mut Vec<GameRecord>, imported: Vec<GameRecord>) -> usize {
+    let before = records.len();
+    for record in imported {
+        if !records.iter().any(|other| other.puzzle == record.puzzle) {
+            records.push(record);
+        }
+    }
+    records.sort_by_key(|record| record.timestamp);
+    records.len() - before
+}
+
+/// Summary of all recorded games.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of games played.
+    pub played: usize,
+    /// Number of games won.
+    pub won: usize,
+    /// Games won in a row, up to the last game. Back-filled puzzles
+    /// neither extend nor break streaks.
+    pub current_streak: usize,
+    /// Longest number of games won in a row.
+    pub max_streak: usize,
+    /// `distribution[i]` is the number of games won with `i + 1` guesses.
+    pub distribution: Vec<usize>,
+    /// Number of the games played with count hints, which are easier.
+    pub count_hints: usize,
+    /// Number of the games played with only solutions as guesses, which
+    /// are harder.
+    pub answers_only: usize,
+}
+
+impl Summary {
+    /// Summarizes records, which are expected in the order they were played.
+    pub fn new(records: &[GameRecord]) -> Summary {
+        let mut summary = Summary::default();
+        for record in records {
+            summary.played += 1;
+            if record.count_hints {
+                summary.count_hints += 1;
+            }
+            if record.answers_only {
+                summary.answers_only += 1;
+            }
+            let streak = record.mode != BACKFILL;
+            if !record.won {
+                if streak {
+                    summary.current_streak = 0;
+                }
+                continue;
+            }
+            summary.won += 1;
+            if streak {
+                summary.current_streak += 1;
+                summary.max_streak = summary.max_streak.max(summary.current_streak);
+            }
+            let tries = record.guesses.len().max(1);
+            if summary.distribution.len() < tries {
+                summary.distribution.resize(tries, 0);
+            }
+            summary.distribution[tries - 1] += 1;
+        }
+        summary
+    }
+
+    /// Percentage of games won, 0 if no game was played.
+    pub fn win_percentage(&self) -> usize {
+        match self.played {
+            0 => 0,
+            played => self.won * 100 / played,
+        }
+    }
+}
+
+/// Openers played fewer times than this are not telling yet.
+pub const CONFIDENT_SAMPLES: usize = 5;
+
+/// How the games opened with one first guess went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenerStats {
+    /// The first guess.
+    pub opener: String,
+    /// Number of games opened with it.
+    pub played: usize,
+    /// Number of those games won.
+    pub won: usize,
+    /// Guesses after the opener summed over the won games.
+    pub tries_after: usize,
+}
+
+impl OpenerStats {
+    /// Percentage of games won.
+    pub fn win_percentage(&self) -> usize {
+        self.won * 100 / self.played
+    }
+
+    /// Mean number of guesses the won games took after the opener, `None`
+    /// if none was won.
+    pub fn average_tries_after(&self) -> Option<f64> {
+        match self.won {
+            0 => None,
+            won => Some(self.tries_after as f64 / won as f64),
+        }
+    }
+
+    /// Whether too few games were opened with it to tell.
+    pub fn low_confidence(&self) -> bool {
+        self.played < CONFIDENT_SAMPLES
+    }
+
+    /// Whether it performed just like `other`.
+    pub fn ties(&self, other: &OpenerStats) -> bool {
+        self.won * other.played == other.won * self.played
+            && self.tries_after * other.won == other.tries_after * self.won
+    }
+}
+
+/// Aggregates the records by their first guess, best openers first: by win
+/// rate, then by the fewest guesses after the opener, then by the number of
+/// games. Games without guesses and imported ones, without words, are left
+/// out.
+pub fn opener_stats(records: &[GameRecord]) -> Vec<OpenerStats> {
+    let mut openers: Vec<OpenerStats> = Vec::new();
+    for record in records {
+        let opener = match record.guesses.first() {
+            Some(opener) if record.mode != IMPORTED => opener,
+            _ => continue,
+        };
+        let index = match openers.iter().position(|stats| &stats.opener == opener) {
+            Some(index) => index,
+            None => {
+                openers.push(OpenerStats {
+                    opener: opener.clone(),
+                    played: 0,
+                    won: 0,
+                    tries_after: 0,
+                });
+                openers.len() - 1
+            }
+        };
+        let stats = &mut openers[index];
+        stats.played += 1;
+        if record.won {
+            stats.won += 1;
+            stats.tries_after += record.guesses.len() - 1;
+        }
+    }
+    openers.sort_by(|a, b| {
+        // compared as fractions, a.won / a.played against b.won / b.played
+        (b.won * a.played)
+            .cmp(&(a.won * b.played))
+            .then_with(|| {
+                let average = |stats: &OpenerStats| stats.average_tries_after().unwrap_or(f64::MAX);
+                average(a).total_cmp(&average(b))
+            })
+            .then_with(|| b.played.cmp(&a.played))
+            .then_with(|| a.opener.cmp(&b.opener))
+    });
+    openers
+}
+
+/// Number of guesses by which a letter of the solution should be placed,
+/// letters still not placed after them count as missed.
+pub const PLACED_BY: usize = 3;
+
+/// How well one letter of the solutions was placed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterWeakness {
+    /// The letter.
+    pub letter: char,
+    /// Number of times it was in a solution, once per position.
+    pub seen: usize,
+    /// Number of those times it was not placed by guess [`PLACED_BY`].
+    pub missed: usize,
+}
+
+impl LetterWeakness {
+    /// Fraction of the times it was missed, from 0 to 1.
+    pub fn score(&self) -> f64 {
+        self.missed as f64 / self.seen as f64
+    }
+}
+
+/// Aggregates, for each letter of the solutions, how often it was still
+/// not placed after [`PLACED_BY`] guesses, weakest letters first. Letters
+/// seen fewer than [`CONFIDENT_SAMPLES`] times and letters never missed are
+/// left out, and so are imported games, without words.
+pub fn letter_weakness(records: &[GameRecord]) -> Vec<LetterWeakness> {
+    let mut letters: Vec<LetterWeakness> = Vec::new();
+    for record in records.iter().filter(|record| record.mode != IMPORTED) {
+        let guesses: Vec<Vec<char>> = record
+            .guesses
+            .iter()
+            .take(PLACED_BY)
+            .map(|guess| guess.chars().collect())
+            .collect();
+        for (i, letter) in record.word.chars().enumerate() {
+            let placed = guesses.iter().any(|guess| guess.get(i) == Some(&letter));
+            let index = match letters.iter().position(|stats| stats.letter == letter) {
+                Some(index) => index,
+                None => {
+                    letters.push(LetterWeakness {
+                        letter,
+                        seen: 0,
+                        missed: 0,
+                    });
+                    letters.len() - 1
+                }
+            };
+            letters[index].seen += 1;
+            if !placed {
+                letters[index].missed += 1;
+            }
+        }
+    }
+    letters.retain(|stats| stats.seen >= CONFIDENT_SAMPLES && stats.missed > 0);
+    letters.sort_by(|a, b| {
+        // compared as fractions, like the win rates of openers
+        (b.missed * a.seen)
+            .cmp(&(a.missed * b.seen))
+            .then_with(|| b.seen.cmp(&a.seen))
+            .then_with(|| a.letter.cmp(&b.letter))
+    });
+    letters
+}
+
+/// How often one letter of the solutions was found early.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterAccuracy {
+    /// The letter.
+    pub letter: char,
+    /// Number of times it was in a solution, twice for a word with two.
+    pub seen: usize,
+    /// Number of those times it was green or yellow by guess [`PLACED_BY`].
+    pub found: usize,
+}
+
+impl LetterAccuracy {
+    /// Number of the times it was not found.
+    pub fn missed(&self) -> usize {
+        self.seen - self.found
+    }
+
+    /// Fraction of the times it was not found, from 0 to 1.
+    pub fn miss_rate(&self) -> f64 {
+        self.missed() as f64 / self.seen as f64
+    }
+
+    /// Whether it was in too few solutions to tell.
+    pub fn low_confidence(&self) -> bool {
+        self.seen < CONFIDENT_SAMPLES
+    }
+}
+
+/// Aggregates, for each letter of the solutions, how often it was green or
+/// yellow in one of the first [`PLACED_BY`] guesses, the letters missed
+/// most often first. Like the hints of a guess, a letter twice in the
+/// solution is found twice only by a guess with it twice. Games shorter
+/// than [`PLACED_BY`] guesses count the guesses they had, imported games,
+/// without words, are left out.
+pub fn letter_accuracy(records: &[GameRecord]) -> Vec<LetterAccuracy> {
+    let count = |word: &[char], letter: char| word.iter().filter(|c| **c == letter).count();
+    let mut letters: Vec<LetterAccuracy> = Vec::new();
+    for record in records.iter().filter(|record| record.mode != IMPORTED) {
+        let word: Vec<char> = record.word.chars().collect();
+        let guesses: Vec<Vec<char>> = record
+            .guesses
+            .iter()
+            .take(PLACED_BY)
+            .map(|guess| guess.chars().collect())
+            .collect();
+        let mut letters_of_word = word.clone();
+        letters_of_word.sort_unstable();
+        letters_of_word.dedup();
+        for letter in letters_of_word {
+            let seen = count(&word, letter);
+            let found = guesses
+                .iter()
+                .map(|guess| count(guess, letter).min(seen))
+                .max()
+                .unwrap_or(0);
+            let index = match letters.iter().position(|stats| stats.letter == letter) {
+                Some(index) => index,
+                None => {
+                    letters.push(LetterAccuracy {
+                        letter,
+                        seen: 0,
+                        found: 0,
+                    });
+                    letters.len() - 1
+                }
+            };
+            letters[index].seen += seen;
+            letters[index].found += found;
+        }
+    }
+    letters.sort_by(|a, b| {
+        // compared as fractions, like the win rates of openers
+        (b.missed() * a.seen)
+            .cmp(&(a.missed() * b.seen))
+            .then_with(|| b.seen.cmp(&a.seen))
+            .then_with(|| a.letter.cmp(&b.letter))
+    });
+    letters
+}
+
+/// The games of the seven days up to a day, for `rordle stats --weekly`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekSummary {
+    /// The first of the seven days.
+    pub first_day: Date,
+    /// Games finished on each of the days.
+    pub games_per_day: [usize; 7],
+    /// Number of games won.
+    pub won: usize,
+    /// Number of daily puzzles finished, not counting back-filled ones.
+    pub dailies: usize,
+    /// Mean number of guesses of the won games, `None` without any.
+    pub average_guesses: Option<f64>,
+    /// The word found with the fewest guesses, and their number.
+    pub best: Option<(String, usize)>,
+    /// The first word not found, or else the one found with the most
+    /// guesses, and the number of guesses.
+    pub worst: Option<(String, usize)>,
+}
+
+impl WeekSummary {
+    /// Summarizes the records finished in the seven days ending with
+    /// `last_day`. `local_date` gives the day of a timestamp in the time
+    /// zone of the player, so that games late at night count for the day
+    /// they were played on.
+    pub fn new(
+        records: &[GameRecord],
+        last_day: Date,
+        local_date: impl Fn(i64) -> Date,
+    ) -> WeekSummary {
+        let first_day = Date::from_days(last_day.days() - 6);
+        let mut week = WeekSummary {
+            first_day,
+            games_per_day: [0; 7],
+            won: 0,
+            dailies: 0,
+            average_guesses: None,
+            best: None,
+            worst: None,
+        };
+        let mut guesses = 0;
+        let mut lost = false;
+        for record in records {
+            let day = local_date(record.timestamp).days() - first_day.days();
+            if !(0..7).contains(&day) {
+                continue;
+            }
+            week.games_per_day[day as usize] += 1;
+            if record.mode == "daily" {
+                week.dailies += 1;
+            }
+            let tries = record.guesses.len();
+            // imported games have no word to name
+            let named = record.mode != IMPORTED;
+            if !record.won {
+                if !lost && named {
+                    lost = true;
+                    week.worst = Some((record.word.clone(), tries));
+                }
+                continue;
+            }
+            week.won += 1;
+            guesses += tries;
+            if !named {
+                continue;
+            }
+            if week.best.as_ref().is_none_or(|(_, best)| tries < *best) {
+                week.best = Some((record.word.clone(), tries));
+            }
+            if !lost && week.worst.as_ref().is_none_or(|(_, worst)| tries > *worst) {
+                week.worst = Some((record.word.clone(), tries));
+            }
+        }
+        if week.won > 0 {
+            week.average_guesses = Some(guesses as f64 / week.won as f64);
+        }
+        week
+    }
+
+    /// Number of games finished in the week.
+    pub fn played(&self) -> usize {
+        self.games_per_day.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::DailyEpoch;
+
+    fn record(word: &str, guesses: &[&str], won: bool) -> GameRecord {
+        GameRecord {
+            timestamp: 1645747200,
+            mode: "play".to_string(),
+            word: word.to_string(),
+            guesses: guesses.iter().map(|guess| guess.to_string()).collect(),
+            won,
+            puzzle: None,
+            times: Vec::new(),
+            count_hints: false,
+            answers_only: false,
+        }
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let record = record("cigar", &["crane", "cigar"], true);
+        assert_eq!(
+            record.to_string(),
+            "1645747200\tplay\tcigar\tcrane,cigar\twon"
+        );
+        assert_eq!(GameRecord::parse(&record.to_string()), Some(record));
+        assert_eq!(
+            GameRecord::parse("1\tplay\tcigar\t\tlost")
+                .unwrap()
+                .guesses
+                .len(),
+            0
+        );
+        assert_eq!(GameRecord::parse("1\tplay\tcigar\tcigar\tmaybe"), None);
+        assert_eq!(GameRecord::parse("x\tplay\tcigar\tcigar\twon"), None);
+    }
+
+    #[test]
+    fn test_record_with_puzzle() {
+        let mut record = record("cigar", &["cigar"], true);
+        record.mode = "daily".to_string();
+        record.puzzle = Some(Puzzle {
+            epoch: DailyEpoch::Wordle,
+            number: 250,
+        });
+        assert_eq!(
+            record.to_string(),
+            "1645747200\tdaily\tcigar\tcigar\twon\twordle:250"
+        );
+        assert_eq!(GameRecord::parse(&record.to_string()), Some(record.clone()));
+        // played in the evening of 2022-02-24 in a timezone behind UTC
+        assert_eq!(record.date().to_string(), "2022-02-25");
+        assert_eq!(record.day().to_string(), "2022-02-24");
+        assert_eq!(
+            GameRecord::parse("1\tdaily\tcigar\tcigar\twon\tnext:1"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_played_daily() {
+        let date: Date = "2022-02-24".parse().unwrap();
+        let mut records = vec![record("cigar", &["cigar"], true)];
+        assert!(!played_daily(&records, date));
+        records[0].mode = IMPORTED.to_string();
+        records[0].puzzle = Some(Puzzle::of(DailyEpoch::Wordle, date));
+        assert!(!played_daily(&records, date));
+        records[0].mode = BACKFILL.to_string();
+        assert!(played_daily(&records, date));
+        // the same day numbered like rordle
+        records[0].mode = "daily".to_string();
+        records[0].puzzle = Some(Puzzle::of(DailyEpoch::Rordle, date));
+        assert!(played_daily(&records, date));
+        assert!(!played_daily(&records, "2022-02-25".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_record_with_times() {
+        let mut record = record("cigar", &["crane", "rebut", "cigar"], true);
+        record.times = vec![
+            Some(Duration::from_millis(12345)),
+            None,
+            Some(Duration::from_millis(900)),
+        ];
+        assert_eq!(
+            record.to_string(),
+            "1645747200\tplay\tcigar\tcrane,rebut,cigar\twon\t\t12345,-,900"
+        );
+        assert_eq!(GameRecord::parse(&record.to_string()), Some(record.clone()));
+        record.puzzle = Some(Puzzle {
+            epoch: DailyEpoch::Rordle,
+            number: 19048,
+        });
+        assert!(record
+            .to_string()
+            .ends_with("\twon\trordle:19048\t12345,-,900"));
+        assert_eq!(GameRecord::parse(&record.to_string()), Some(record));
+        assert_eq!(
+            GameRecord::parse("1\tplay\tcigar\tcigar\twon\t\tsoon"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_record_with_rules() {
+        let mut record = record("cigar", &["crane", "cigar"], true);
+        record.count_hints = true;
+        assert_eq!(
+            record.to_string(),
+            "1645747200\tplay\tcigar\tcrane,cigar\twon\t\t\tcounted"
+        );
+        assert_eq!(GameRecord::parse(&record.to_string()), Some(record.clone()));
+        record.times = vec![None, Some(Duration::from_millis(900))];
+        assert!(record.to_string().ends_with("\twon\t\t-,900\tcounted"));
+        assert_eq!(GameRecord::parse(&record.to_string()), Some(record.clone()));
+        record.answers_only = true;
+        assert!(record
+            .to_string()
+            .ends_with("\t-,900\tcounted,answers-only"));
+        assert_eq!(GameRecord::parse(&record.to_string()), Some(record.clone()));
+        record.count_hints = false;
+        assert_eq!(record.rules(), ["answers-only"]);
+        assert_eq!(GameRecord::parse(&record.to_string()), Some(record));
+        assert_eq!(
+            GameRecord::parse("1\tplay\tcigar\tcigar\twon\t\t\thard"),
+            None
+        );
+        assert_eq!(GameRecord::parse("1\tplay\tcigar\tcigar\twon\t\t\t"), None);
+    }
+
+    #[test]
+    fn test_read_and_append() {
+        let path = std::env::temp_dir()
+            .join(format!("rordle-stats-{}", std::process::id()))
+            .join("stats");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(read_records(&path).unwrap(), Vec::new());
+        let records = vec![
+            record("cigar", &["cigar"], true),
+            record("rebut", &["crane"], false),
+        ];
+        for record in &records {
+            append_record(&path, record).unwrap();
+        }
+        assert_eq!(read_records(&path).unwrap(), records);
+
+        std::fs::write(&path, "1\tplay\tcigar\tcigar\twon\n\nbroken\n").unwrap();
+        assert_eq!(
+            read_records(&path).unwrap_err().to_string(),
+            format!("{}:3: malformed game record", path.display())
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_read_and_write_run() {
+        let path = std::env::temp_dir()
+            .join(format!("rordle-run-{}", std::process::id()))
+            .join("hardcore");
+        assert_eq!(read_run(&path).unwrap(), 0);
+        write_run(&path, 14).unwrap();
+        assert_eq!(read_run(&path).unwrap(), 14);
+        std::fs::write(&path, "many\n").unwrap();
+        assert!(read_run(&path).is_err());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_merge_records() {
+        let daily = |timestamp, number, mode: &str| GameRecord {
+            timestamp,
+            mode: mode.to_string(),
+            puzzle: Some(Puzzle {
+                epoch: DailyEpoch::Wordle,
+                number,
+            }),
+            ..record("cigar", &["cigar"], true)
+        };
+        let mut records = vec![daily(100, 1, "daily"), daily(300, 3, IMPORTED)];
+        let imported = vec![
+            daily(300, 3, IMPORTED),
+            daily(200, 2, IMPORTED),
+            daily(100, 1, IMPORTED),
+            daily(400, 4, IMPORTED),
+        ];
+        assert_eq!(merge_records(&mut records, imported), 2);
+        let numbers: Vec<i64> = records
+            .iter()
+            .map(|record| record.puzzle.unwrap().number)
+            .collect();
+        assert_eq!(numbers, [1, 2, 3, 4]);
+        assert_eq!(records[0].mode, "daily");
+        assert!(opener_stats(&records[1..]).is_empty());
+    }
+
+    #[test]
+    fn test_summary() {
+        let counted = GameRecord {
+            count_hints: true,
+            ..record("a", &["x", "x", "a"], true)
+        };
+        let summary = Summary::new(&[
+            record("a", &["x", "a"], true),
+            record("a", &["a"], true),
+            record("a", &["x"], false),
+            counted,
+        ]);
+        assert_eq!(
+            summary,
+            Summary {
+                played: 4,
+                won: 3,
+                current_streak: 1,
+                max_streak: 2,
+                distribution: vec![1, 1, 1],
+                count_hints: 1,
+                answers_only: 0,
+            }
+        );
+        assert_eq!(summary.win_percentage(), 75);
+        assert_eq!(Summary::new(&[]).win_percentage(), 0);
+    }
+
+    #[test]
+    fn test_letter_weakness() {
+        let mut records = Vec::new();
+        // the z and the a are only found by the fourth guess
+        records
+            .extend((0..5).map(|_| record("pizza", &["crane", "pilot", "pitch", "pizza"], true)));
+        // the z is placed in a lost game, the a is not
+        records.push(record("pizza", &["pizzo"], false));
+        records.push(record("", &["g-y--"], true));
+        records[6].mode = IMPORTED.to_string();
+        let weakness = letter_weakness(&records);
+        let letters: Vec<(char, usize, usize)> = weakness
+            .iter()
+            .map(|stats| (stats.letter, stats.seen, stats.missed))
+            .collect();
+        // p and i were placed by the second guess
+        assert_eq!(letters, [('a', 6, 6), ('z', 12, 10)]);
+        assert_eq!(weakness[0].score(), 1.0);
+        // too few games to tell
+        assert!(letter_weakness(&records[..2]).is_empty());
+    }
+
+    #[test]
+    fn test_letter_accuracy() {
+        let mut records = vec![
+            // neither k is found by the third guess
+            record("knack", &["crane", "stomp", "blimp", "knack"], true),
+            // a game of two guesses finds every letter
+            record("anger", &["crane", "anger"], true),
+            // only one of the two n is found, by a guess with one n
+            record("ninja", &["crane"], false),
+            // kayak finds the k and both a, one p of two is found
+            record("kappa", &["crane", "stomp", "kayak"], false),
+            record("", &["g-y--"], true),
+        ];
+        records[4].mode = IMPORTED.to_string();
+        let accuracy = letter_accuracy(&records);
+        let letters: Vec<(char, usize, usize)> = accuracy
+            .iter()
+            .map(|stats| (stats.letter, stats.seen, stats.found))
+            .collect();
+        assert_eq!(
+            letters,
+            [
+                ('i', 1, 0),
+                ('j', 1, 0),
+                ('k', 3, 1),
+                ('p', 2, 1),
+                ('n', 4, 3),
+                ('a', 5, 5),
+                ('c', 1, 1),
+                ('e', 1, 1),
+                ('g', 1, 1),
+                ('r', 1, 1),
+            ]
+        );
+        assert_eq!(accuracy[2].missed(), 2);
+        assert_eq!(accuracy[3].miss_rate(), 0.5);
+        // the a is the only letter seen often enough to tell
+        assert!(!accuracy[5].low_confidence());
+        assert_eq!(
+            accuracy
+                .iter()
+                .filter(|stats| stats.low_confidence())
+                .count(),
+            9
+        );
+        assert!(letter_accuracy(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_opener_stats() {
+        let mut records = vec![
+            record("cigar", &["crane", "cigar"], true),
+            record("rebut", &["crane", "rebus", "rebut"], true),
+            record("sissy", &["crane"], false),
+            record("humph", &["slate", "humph"], true),
+            record("awake", &["react", "awake"], true),
+            record("blush", &[], false),
+        ];
+        records.extend((0..5).map(|_| record("cigar", &["adieu", "cigar"], true)));
+        let openers = opener_stats(&records);
+        let names: Vec<&str> = openers.iter().map(|stats| stats.opener.as_str()).collect();
+        // adieu won as often as slate and react with as many guesses, but
+        // was played more
+        assert_eq!(names, ["adieu", "react", "slate", "crane"]);
+        let crane = &openers[3];
+        assert_eq!(
+            (crane.played, crane.won, crane.win_percentage()),
+            (3, 2, 66)
+        );
+        assert_eq!(crane.average_tries_after(), Some(1.5));
+        assert!(crane.low_confidence());
+        assert!(!openers[0].low_confidence());
+        assert!(openers[1].ties(&openers[2]));
+        assert!(openers[0].ties(&openers[1]));
+        assert!(!openers[2].ties(&openers[3]));
+        assert_eq!(
+            opener_stats(&[record("a", &["b"], false)])[0].average_tries_after(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_week_summary() {
+        // 2022-02-25 00:00 UTC, a Friday
+        let midnight = 1645747200;
+        let at = |timestamp, word, guesses: &[&str], won| GameRecord {
+            timestamp,
+            ..record(word, guesses, won)
+        };
+        let records = [
+            // the evening before the week in UTC, on its first day in UTC+1
+            at(midnight - 1800, "early", &["early"], true),
+            at(midnight + 3600, "cigar", &["crane", "slate", "cigar"], true),
+            at(midnight + 86400, "rebut", &["crane", "rebut"], true),
+            at(midnight + 2 * 86400, "sissy", &["crane"], false),
+            at(midnight + 2 * 86400, "humph", &["humph", "humph"], false),
+            // after the week in UTC, on its last day in UTC-1
+            at(
+                midnight + 7 * 86400 + 1800,
+                "late",
+                &["x", "y", "z", "late"],
+                true,
+            ),
+            at(midnight + 7 * 86400 + 7200, "later", &["later"], true),
+        ];
+        let last_day = Date::from_days(Date::from_timestamp(midnight).days() + 6);
+        let utc = WeekSummary::new(&records, last_day, Date::from_timestamp);
+        assert_eq!(utc.first_day.to_string(), "2022-02-25");
+        assert_eq!(utc.games_per_day, [1, 1, 2, 0, 0, 0, 0]);
+        assert_eq!(utc.worst, Some(("sissy".to_string(), 1)));
+        assert_eq!(utc.best, Some(("rebut".to_string(), 2)));
+        assert_eq!(utc.average_guesses, Some(2.5));
+        let ahead = WeekSummary::new(&records, last_day, |timestamp| {
+            Date::from_timestamp_with_offset(timestamp, 3600)
+        });
+        assert_eq!(ahead.games_per_day, [2, 1, 2, 0, 0, 0, 0]);
+        let behind = WeekSummary::new(&records, last_day, |timestamp| {
+            Date::from_timestamp_with_offset(timestamp, -3600)
+        });
+        assert_eq!(behind.games_per_day, [2, 2, 0, 0, 0, 0, 1]);
+        assert_eq!(behind.played(), 5);
+        assert_eq!(behind.won, 3);
+
+        let won = WeekSummary::new(&records[..3], last_day, Date::from_timestamp);
+        assert_eq!(won.worst, Some(("cigar".to_string(), 3)));
+        let empty = WeekSummary::new(&[], last_day, Date::from_timestamp);
+        assert_eq!(
+            (empty.played(), empty.average_guesses, empty.best),
+            (0, None, None)
+        );
+    }
+
+    #[test]
+    fn test_backfill_keeps_streak() {
+        let backfill = |won| GameRecord {
+            mode: BACKFILL.to_string(),
+            ..record("a", &["a"], won)
+        };
+        let summary = Summary::new(&[
+            record("a", &["a"], true),
+            backfill(false),
+            record("a", &["a"], true),
+            backfill(true),
+            backfill(true),
+        ]);
+        assert_eq!(summary.played, 5);
+        assert_eq!(summary.won, 4);
+        assert_eq!(summary.current_streak, 2);
+        assert_eq!(summary.max_streak, 2);
+    }
+}
@@ -0,0 +1,214 @@
+//! The line summing up a game played on the terminal, printed once the
+//! terminal is restored, e.g.
+//! `result=won guesses=4 word_len=5 time=83s mode=daily day=812`, so that
+//! scripts and status bars need not read the board.
+
+use crate::hook::result_name;
+use rordle_core::date::Puzzle;
+use rordle_core::digraph;
+use rordle_core::game::GameState;
+use rordle_core::json::Json;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How the summary line is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SummaryFormat {
+    /// Space separated `key=value` pairs.
+    KeyValue,
+    /// A JSON object.
+    Json,
+}
+
+impl SummaryFormat {
+    /// Names accepted by `FromStr`.
+    pub(crate) const NAMES: [&'static str; 2] = ["kv", "json"];
+}
+
+impl FromStr for SummaryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kv" => Ok(SummaryFormat::KeyValue),
+            "json" => Ok(SummaryFormat::Json),
+            _ => Err(format!(
+                "expected one of {}",
+                SummaryFormat::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// The summary of a game which took `time`. `mode` is `None` for games with
+/// a solution given by `--word`, `puzzle` is set for daily puzzles and
+/// `hardcore_run` for `--hardcore` games, the run after the game. The
+/// solution of an unfinished daily puzzle is left out, it may still be
+/// played by others.
+pub(crate) fn summary_line(
+    game_state: &GameState,
+    mode: Option<&str>,
+    puzzle: Option<Puzzle>,
+    hardcore_run: Option<u32>,
+    time: Duration,
+    format: SummaryFormat,
+) -> String {
+    let mut members = vec![
+        ("result", Json::from(result_name(game_state.outcome()))),
+        ("guesses", Json::from(game_state.guesses.len())),
+        ("word_len", Json::from(game_state.word.chars().count())),
+        ("time", Json::from(time.as_secs() as usize)),
+    ];
+    if let Some(mode) = mode {
+        members.push(("mode", Json::from(mode)));
+    }
+    if let Some(puzzle) = puzzle {
+        members.push(("day", Json::Number(puzzle.number as f64)));
+    }
+    if let Some(run) = hardcore_run {
+        members.push(("hardcore_run", Json::from(run as usize)));
+    }
+    if game_state.count_hints() {
+        members.push(("count_hints", Json::from(true)));
+    }
+    if game_state.answers_only() {
+        members.push(("answers_only", Json::from(true)));
+    }
+    if puzzle.is_none() || game_state.over() {
+        members.push(("word", Json::from(digraph::untokenize(&game_state.word))));
+    }
+    match format {
+        SummaryFormat::Json => Json::object(members).to_string(),
+        SummaryFormat::KeyValue => {
+            let pairs: Vec<String> = members
+                .into_iter()
+                .map(|(key, value)| match value {
+                    Json::String(value) => format!("{}={}", key, value),
+                    value if key == "time" => format!("time={}s", value),
+                    value => format!("{}={}", key, value),
+                })
+                .collect();
+            pairs.join(" ")
+        }
+    }
+}
+
+/// A span of time of the pacing of a game: tenths of seconds below a
+/// minute, e.g. `0.4s` or `12.3s`, then minutes and seconds, e.g. `3m05s`,
+/// and hours and minutes from an hour on, e.g. `1h02m`.
+pub(crate) fn span(time: Duration) -> String {
+    let tenths = (time.as_millis() + 50) / 100;
+    if tenths < 600 {
+        return format!("{}.{}s", tenths / 10, tenths % 10);
+    }
+    let seconds = (time.as_millis() + 500) / 1000;
+    if seconds < 3600 {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h{:02}m", seconds / 3600, seconds / 60 % 60)
+    }
+}
+
+/// The time of each guess as a `span`, `None` for gaps, and the total of
+/// the known times.
+pub(crate) fn pacing(times: &[Option<Duration>]) -> (Vec<Option<String>>, String) {
+    let spans = times.iter().map(|time| time.map(span)).collect();
+    (spans, span(times.iter().flatten().sum()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rordle_core::date::DailyEpoch;
+    use rordle_core::game::GameOptions;
+
+    fn game(guesses: &[&str]) -> GameState {
+        let mut game_state = GameState::new(
+            "hello".to_string(),
+            ["hello", "jolly"].iter().collect(),
+            GameOptions::default(),
+        )
+        .unwrap();
+        for guess in guesses {
+            game_state.guess(guess.to_string()).unwrap();
+        }
+        game_state
+    }
+
+    #[test]
+    fn test_summary_line() {
+        let puzzle = Puzzle {
+            epoch: DailyEpoch::Wordle,
+            number: 812,
+        };
+        let time = Duration::from_millis(83_400);
+        assert_eq!(
+            summary_line(
+                &game(&["jolly", "hello"]),
+                Some("daily"),
+                Some(puzzle),
+                None,
+                time,
+                SummaryFormat::KeyValue
+            ),
+            "result=won guesses=2 word_len=5 time=83s mode=daily day=812 word=hello"
+        );
+        assert_eq!(
+            summary_line(
+                &game(&["jolly"]),
+                None,
+                None,
+                None,
+                time,
+                SummaryFormat::Json
+            ),
+            r#"{"result":"abandoned","guesses":1,"word_len":5,"time":83,"word":"hello"}"#
+        );
+        assert_eq!(
+            summary_line(
+                &game(&["jolly", "hello"]),
+                Some("play"),
+                None,
+                Some(15),
+                time,
+                SummaryFormat::KeyValue
+            ),
+            "result=won guesses=2 word_len=5 time=83s mode=play hardcore_run=15 word=hello"
+        );
+        // the solution of a daily puzzle is kept secret until it is finished
+        assert_eq!(
+            summary_line(
+                &game(&["jolly"]),
+                Some("daily"),
+                Some(puzzle),
+                None,
+                time,
+                SummaryFormat::KeyValue
+            ),
+            "result=abandoned guesses=1 word_len=5 time=83s mode=daily day=812"
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        assert_eq!(span(Duration::from_millis(0)), "0.0s");
+        assert_eq!(span(Duration::from_millis(370)), "0.4s");
+        assert_eq!(span(Duration::from_millis(12_340)), "12.3s");
+        // rounded up to a minute
+        assert_eq!(span(Duration::from_millis(59_960)), "1m00s");
+        assert_eq!(span(Duration::from_secs(185)), "3m05s");
+        assert_eq!(span(Duration::from_secs(47 * 60 + 9)), "47m09s");
+        assert_eq!(span(Duration::from_secs(3720)), "1h02m");
+        let (spans, total) = pacing(&[
+            Some(Duration::from_millis(400)),
+            None,
+            Some(Duration::from_secs(125)),
+        ]);
+        assert_eq!(
+            spans,
+            [Some("0.4s".to_string()), None, Some("2m05s".to_string())]
+        );
+        // the gap is not counted
+        assert_eq!(total, "2m05s");
+    }
+}
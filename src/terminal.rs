@@ -0,0 +1,219 @@
+//! The terminal during a session: in raw mode and, unless `--no-altscreen`
+//! is given, on the alternate screen, so the scrollback of the shell is
+//! left alone. The guard restores the terminal when it is dropped, and
+//! also when rordle panics or is suspended with Ctrl+Z. `TickingKeys`
+//! reads the keys typed, ticking while none are.
+
+use std::fs::File;
+use std::io::{stdout, Stdout, Write};
+use std::os::unix::io::AsRawFd;
+use std::sync::{Mutex, Once};
+use std::time::Duration;
+use termion::event::Key;
+use termion::input::{Keys, TermRead};
+use termion::raw::{IntoRawMode, RawTerminal};
+
+/// The open terminal, taken by whoever restores it first: the guard or the
+/// panic hook.
+static OPEN: Mutex<Option<Saved>> = Mutex::new(None);
+
+static PANIC_HOOK: Once = Once::new();
+
+/// What is needed to restore the terminal.
+#[derive(Clone, Copy)]
+struct Saved {
+    /// The settings before raw mode was entered.
+    termios: libc::termios,
+    altscreen: bool,
+}
+
+/// Raw mode and the alternate screen, left when dropped.
+pub(crate) struct Terminal {
+    raw: RawTerminal<Stdout>,
+}
+
+impl Terminal {
+    /// Enters raw mode and, with `altscreen`, the alternate screen.
+    pub(crate) fn open(altscreen: bool) -> std::io::Result<Terminal> {
+        let termios = get_termios()?;
+        let mut raw = stdout().into_raw_mode()?;
+        install_panic_hook();
+        *OPEN.lock().unwrap() = Some(Saved { termios, altscreen });
+        enter(&mut raw, altscreen)?;
+        Ok(Terminal { raw })
+    }
+}
+
+impl Write for Terminal {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.raw.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.raw.flush()
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let saved = OPEN.lock().map(|mut open| open.take());
+        if let Ok(Some(saved)) = saved {
+            // raw mode is left by `raw` itself
+            let _ = leave(&mut self.raw, saved.altscreen);
+        }
+    }
+}
+
+fn get_termios() -> std::io::Result<libc::termios> {
+    // SAFETY: `termios` is plain data, filled in by `tcgetattr`
+    unsafe {
+        let mut termios = std::mem::zeroed();
+        if libc::tcgetattr(stdout().as_raw_fd(), &mut termios) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(termios)
+    }
+}
+
+fn set_termios(termios: &libc::termios) -> std::io::Result<()> {
+    // SAFETY: `termios` is a valid setting, read by `tcgetattr`
+    if unsafe { libc::tcsetattr(stdout().as_raw_fd(), libc::TCSANOW, termios) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn enter<W: Write>(output: &mut W, altscreen: bool) -> std::io::Result<()> {
+    if altscreen {
+        write!(output, "{}", termion::screen::ToAlternateScreen)?;
+    }
+    output.flush()
+}
+
+fn leave<W: Write>(output: &mut W, altscreen: bool) -> std::io::Result<()> {
+    write!(output, "{}", termion::cursor::Show)?;
+    if altscreen {
+        write!(output, "{}", termion::screen::ToMainScreen)?;
+    }
+    output.flush()
+}
+
+/// Restores the terminal before the panic is reported, which would
+/// otherwise be lost with the alternate screen and garbled by raw mode.
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let report = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // the panic may have happened while `OPEN` was locked
+            let saved = OPEN.try_lock().map(|mut open| open.take());
+            if let Ok(Some(saved)) = saved {
+                let _ = leave(&mut stdout(), saved.altscreen);
+                let _ = set_termios(&saved.termios);
+            }
+            report(info);
+        }));
+    });
+}
+
+/// Time `TickingKeys` waits for a key before it ticks.
+const TICK: Duration = Duration::from_millis(100);
+
+/// A key typed, or a tick of the clock while none was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Input {
+    Key(Key),
+    Tick,
+}
+
+impl From<Key> for Input {
+    fn from(key: Key) -> Input {
+        Input::Key(key)
+    }
+}
+
+/// The keys typed on the terminal, with an `Input::Tick` after each `TICK`
+/// without a key, so what changes with time is shown without waiting for
+/// one. The terminal is read without a buffer, keys typed are never held
+/// back while it ticks.
+pub(crate) struct TickingKeys {
+    keys: Keys<File>,
+    fd: i32,
+}
+
+impl TickingKeys {
+    pub(crate) fn open() -> std::io::Result<TickingKeys> {
+        let tty = termion::get_tty()?;
+        let fd = tty.as_raw_fd();
+        Ok(TickingKeys {
+            keys: tty.keys(),
+            fd,
+        })
+    }
+}
+
+impl Iterator for TickingKeys {
+    type Item = std::io::Result<Input>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut poll = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `poll` points to one descriptor, which `keys` keeps open
+        let ready = unsafe { libc::poll(&mut poll, 1, TICK.as_millis() as i32) };
+        if ready < 0 {
+            let error = std::io::Error::last_os_error();
+            // a signal, e.g. of a resized terminal, is as good as a tick
+            if error.kind() != std::io::ErrorKind::Interrupted {
+                return Some(Err(error));
+            }
+        }
+        if ready <= 0 {
+            return Some(Ok(Input::Tick));
+        }
+        self.keys.next().map(|key| key.map(Input::Key))
+    }
+}
+
+/// Suspends rordle like Ctrl+Z does outside of raw mode, which passes it on
+/// as a key. The shell gets its screen back until rordle is resumed with
+/// `fg`, then the caller has to render everything again. `false` if no
+/// terminal is open, there is nothing to render then.
+pub(crate) fn suspend() -> std::io::Result<bool> {
+    let saved = match *OPEN.lock().unwrap() {
+        Some(saved) => saved,
+        None => return Ok(false),
+    };
+    let mut output = stdout();
+    leave(&mut output, saved.altscreen)?;
+    let raw = get_termios()?;
+    set_termios(&saved.termios)?;
+    // SAFETY: raising a signal has no preconditions, this returns once the
+    // process is continued
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+    set_termios(&raw)?;
+    write!(output, "{}", termion::cursor::Hide)?;
+    enter(&mut output, saved.altscreen)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suspend_without_terminal() {
+        assert!(!suspend().unwrap());
+        let mut output = Vec::new();
+        enter(&mut output, true).unwrap();
+        leave(&mut output, true).unwrap();
+        assert_eq!(output, b"\x1b[?1049h\x1b[?25h\x1b[?1049l");
+        let mut output = Vec::new();
+        enter(&mut output, false).unwrap();
+        leave(&mut output, false).unwrap();
+        assert_eq!(output, b"\x1b[?25h");
+    }
+}
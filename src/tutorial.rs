@@ -0,0 +1,151 @@
+//! `rordle tutorial`: a guided game, offered by the menu to players who have
+//! not played yet. It is a normal game with captions below the board,
+//! which explain typing until the first guess and then one tile of each
+//! color of it.
+
+use crate::cli::{self, Interface};
+use crate::commands::stats_path;
+use crate::error::RordleError;
+use crate::render::tile_style;
+use crate::{game_loop, load_word_list, new_game, outcome_exit_code};
+use clap::ArgMatches;
+use rordle_core::config::{Config, Keybindings, Theme};
+use rordle_core::game::{GameOptions, GameState, HitInfo};
+use rordle_core::i18n::{self, tr};
+
+/// Solution of the tutorial.
+const TUTORIAL_WORD: &str = "crane";
+
+/// The first guess the captions ask for. Against `TUTORIAL_WORD` it has a
+/// tile of each color to explain.
+const FIRST_GUESS: &str = "react";
+
+const TUTORIAL_SEED: u64 = 1;
+
+/// Whether nothing has been recorded yet, not even the directory of the
+/// stats file exists.
+pub(crate) fn first_run(config: &Config) -> bool {
+    match stats_path(config) {
+        Ok(path) => path.parent().is_some_and(|dir| !dir.exists()),
+        Err(_) => false,
+    }
+}
+
+/// What the tiles of each color mean, by the id of the caption.
+const TILE_CAPTIONS: [(HitInfo, &str); 3] = [
+    (HitInfo::Hit, "tutorial-hit"),
+    (HitInfo::Contains, "tutorial-contains"),
+    (HitInfo::Miss, "tutorial-miss"),
+];
+
+/// The captions below the board at this point of the tutorial. Once a guess
+/// is made, they point at the first tile of each color in it. A color the
+/// player's own first guess lacks is left out.
+pub(crate) fn tutorial_lines(
+    game_state: &GameState,
+    theme: Theme,
+    bindings: &Keybindings,
+) -> Vec<String> {
+    if game_state.over() {
+        return vec![tr("tutorial-done", &[])];
+    }
+    let first = match game_state.guesses.first() {
+        Some(first) => first,
+        None => {
+            return vec![
+                tr(
+                    "tutorial-welcome",
+                    &[("tries", &game_state.max_tries), ("quit", &bindings.quit)],
+                ),
+                tr(
+                    "tutorial-type",
+                    &[
+                        ("guess", &FIRST_GUESS.to_uppercase()),
+                        ("submit", &bindings.submit),
+                        ("delete", &bindings.delete),
+                    ],
+                ),
+            ]
+        }
+    };
+    let hits = game_state.get_guess_hits(0);
+    let letters: Vec<char> = first.chars().collect();
+    let mut lines: Vec<String> = TILE_CAPTIONS
+        .iter()
+        .filter_map(|(hit, id)| {
+            let position = hits.iter().position(|h| h == hit)?;
+            Some(tr(
+                id,
+                &[
+                    ("letter", &letters[position].to_uppercase()),
+                    ("position", &(position + 1)),
+                    ("style", &tile_style(hit, theme)),
+                ],
+            ))
+        })
+        .collect();
+    lines.push(tr("tutorial-go-on", &[]));
+    lines
+}
+
+/// Plays the tutorial on the terminal. It is never recorded.
+pub(crate) fn play_tutorial(matches: &ArgMatches, config: &Config) -> Result<i32, RordleError> {
+    i18n::set_language(cli::language(matches, config));
+    let word_list = load_word_list(&cli::ListArgs::from_matches(matches), config, false)?;
+    // the scripted guess need not be in the word list of the player
+    let options = GameOptions::default()
+        .any_word(true)
+        .seed(Some(TUTORIAL_SEED));
+    let game_state = new_game(options, word_list, TUTORIAL_WORD.to_string())?;
+    let interface = Interface {
+        tutorial: true,
+        ..cli::interface(matches, config)
+    };
+    let game_state = game_loop(game_state, &interface, false, None)?;
+    Ok(outcome_exit_code(game_state.outcome()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(guesses: &[&str]) -> GameState {
+        let mut game_state = GameState::new(
+            TUTORIAL_WORD.to_string(),
+            [TUTORIAL_WORD].iter().collect(),
+            GameOptions::default().any_word(true),
+        )
+        .unwrap();
+        for guess in guesses {
+            game_state.guess(guess.to_string()).unwrap();
+        }
+        game_state
+    }
+
+    #[test]
+    fn test_tutorial_lines() {
+        let bindings = Keybindings::default();
+        assert_eq!(
+            tutorial_lines(&game(&[]), Theme::Classic, &bindings)[1],
+            "Type REACT and press enter to guess it, backspace deletes a letter"
+        );
+        assert_eq!(
+            tutorial_lines(&game(&[FIRST_GUESS]), Theme::Classic, &bindings),
+            [
+                "A, letter 3, is green: it is in the word at this spot",
+                "R, letter 1, is yellow: it is in the word, but at another spot",
+                "T, letter 5, is gray: it is not in the word",
+                "Now find the word, every guess gets hints like these",
+            ]
+        );
+        // the colors are named as the theme shows them, missing ones are
+        // left out
+        assert_eq!(
+            tutorial_lines(&game(&["coast"]), Theme::HighContrast, &bindings)[..2],
+            [
+                "C, letter 1, is orange: it is in the word at this spot",
+                "O, letter 2, is gray: it is not in the word",
+            ]
+        );
+    }
+}
@@ -0,0 +1,2187 @@
+//! Loading and validating word lists.
+
+use crate::config::Config;
+use crate::digraph::Digraphs;
+use rand::prelude::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Errors in the `#!` header of a word list. Lines are counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderError {
+    /// A header line is not of the form `#! key: value`.
+    Malformed {
+        /// Line of the header.
+        line: usize,
+    },
+    /// A header has no value.
+    EmptyValue {
+        /// Line of the header.
+        line: usize,
+        /// Key of the header.
+        key: String,
+    },
+    /// An alphabet range does not go from a lower to a higher letter.
+    InvalidRange {
+        /// Line of the header.
+        line: usize,
+        /// The range as written, e.g. `z-a`.
+        range: String,
+    },
+    /// A digraph of the header is not made of two or more letters.
+    InvalidDigraphs {
+        /// Line of the header.
+        line: usize,
+        /// What is wrong with the digraphs.
+        reason: String,
+    },
+}
+
+impl HeaderError {
+    /// The line of the header.
+    pub fn line(&self) -> usize {
+        match self {
+            HeaderError::Malformed { line }
+            | HeaderError::EmptyValue { line, .. }
+            | HeaderError::InvalidRange { line, .. }
+            | HeaderError::InvalidDigraphs { line, .. } => *line,
+        }
+    }
+
+    /// What is wrong with the header, without its line.
+    pub fn reason(&self) -> String {
+        match self {
+            HeaderError::Malformed { .. } => {
+                "malformed header, expected `#! key: value`".to_string()
+            }
+            HeaderError::EmptyValue { key, .. } => format!("header `{}` has no value", key),
+            HeaderError::InvalidRange { range, .. } => {
+                format!("invalid alphabet range `{}`", range)
+            }
+            HeaderError::InvalidDigraphs { reason, .. } => format!("invalid digraphs: {}", reason),
+        }
+    }
+}
+
+impl std::fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line(), self.reason())
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+/// The set of letters a word list is written in, in keyboard order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    /// The letters, lowercase and without duplicates.
+    pub letters: Vec<char>,
+    /// Declared in a list header, as opposed to inferred from the words.
+    pub declared: bool,
+}
+
+impl Alphabet {
+    /// Parses an alphabet spec like `a-z äöüß`: whitespace separated tokens,
+    /// each either a range `x-y` or a run of single letters.
+    pub fn parse(spec: &str, line: usize) -> Result<Alphabet, HeaderError> {
+        let mut letters: Vec<char> = Vec::new();
+        for token in spec.split_whitespace() {
+            let chars: Vec<char> = token.chars().collect();
+            let range: Vec<char> = if chars.len() == 3 && chars[1] == '-' {
+                if chars[0] > chars[2] {
+                    return Err(HeaderError::InvalidRange {
+                        line,
+                        range: token.to_string(),
+                    });
+                }
+                (chars[0]..=chars[2]).collect()
+            } else {
+                chars
+            };
+            for c in range {
+                for l in c.to_lowercase() {
+                    if !letters.contains(&l) {
+                        letters.push(l);
+                    }
+                }
+            }
+        }
+        Ok(Alphabet {
+            letters,
+            declared: true,
+        })
+    }
+
+    /// Infers the alphabet as the sorted letters used by `words`.
+    pub fn from_words<'a>(words: impl IntoIterator<Item = &'a str>) -> Alphabet {
+        let mut letters: Vec<char> = words.into_iter().flat_map(|w| w.chars()).collect();
+        letters.sort_unstable();
+        letters.dedup();
+        Alphabet {
+            letters,
+            declared: false,
+        }
+    }
+
+    /// Whether `c` is a letter of the alphabet.
+    pub fn contains(&self, c: char) -> bool {
+        self.letters.contains(&c)
+    }
+
+    /// Whether `c` can be guessed: a letter of a declared alphabet, or any
+    /// letter for an inferred one, which may be missing some. Never
+    /// whitespace or punctuation, which have no place in a word.
+    pub fn accepts(&self, c: char) -> bool {
+        self.contains(c) || !self.declared && (c.is_alphabetic() || is_combining(c))
+    }
+
+    /// Whether the alphabet is written from right to left, that is most of
+    /// its letters are Hebrew or Arabic.
+    pub fn is_right_to_left(&self) -> bool {
+        let rtl = self
+            .letters
+            .iter()
+            .filter(|c| is_right_to_left(**c))
+            .count();
+        rtl * 2 > self.letters.len()
+    }
+
+    fn extend(&mut self, other: &Alphabet) {
+        for c in &other.letters {
+            if !self.contains(*c) {
+                self.letters.push(*c);
+            }
+        }
+    }
+
+    /// Rows of the on-screen keyboard fitting into `max_width` columns.
+    /// Alphabets of mostly latin letters follow the given layout, the other
+    /// letters are appended below it. Other alphabets, like Cyrillic or
+    /// Greek, get rows generated by `balanced_rows`, in the order of the
+    /// alphabet.
+    pub fn keyboard_rows(&self, layout: KeyboardLayout, max_width: usize) -> Vec<Vec<char>> {
+        let latin = self
+            .letters
+            .iter()
+            .filter(|c| c.is_ascii_lowercase())
+            .count();
+        if latin * 2 <= self.letters.len() {
+            return balanced_rows(&self.letters, max_width);
+        }
+        let mut rows: Vec<Vec<char>> = layout
+            .rows()
+            .iter()
+            .map(|row| row.chars().filter(|c| self.contains(*c)).collect())
+            .collect();
+        let others: Vec<char> = self
+            .letters
+            .iter()
+            .filter(|c| !c.is_ascii_lowercase())
+            .cloned()
+            .collect();
+        rows.extend(balanced_rows(&others, max_width));
+        rows.retain(|row| !row.is_empty());
+        rows
+    }
+}
+
+/// Keys of a generated row of the keyboard at most, about as many as on
+/// the rows of a typewriter.
+const MAX_ROW_KEYS: usize = 12;
+
+/// Splits `letters` into rows of a keyboard, keeping their order, so that
+/// the rows fit into `max_width` columns. Each key takes two columns and
+/// each row is indented by one more column than the row above it. The rows
+/// differ by one key at most, with the longer rows first.
+pub fn balanced_rows(letters: &[char], max_width: usize) -> Vec<Vec<char>> {
+    let keys = letters.len();
+    let mut rows = keys.div_ceil(MAX_ROW_KEYS);
+    while rows < keys && keys.div_ceil(rows) * 2 + rows - 1 > max_width {
+        rows += 1;
+    }
+    let mut letters = letters.iter().copied();
+    (0..rows)
+        .map(|y| {
+            let length = keys / rows + usize::from(y < keys % rows);
+            letters.by_ref().take(length).collect()
+        })
+        .collect()
+}
+
+/// Whether `c` is a letter of a script written from right to left: Hebrew,
+/// Arabic and their presentation forms.
+fn is_right_to_left(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0590}'..='\u{08ff}' | '\u{fb1d}'..='\u{fdff}' | '\u{fe70}'..='\u{feff}'
+    )
+}
+
+/// Arrangement of the latin letters on the on-screen keyboard.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyboardLayout {
+    /// The english layout.
+    #[default]
+    Qwerty,
+    /// The german layout.
+    Qwertz,
+    /// The french layout.
+    Azerty,
+}
+
+impl KeyboardLayout {
+    /// Names accepted by `from_str`.
+    pub const NAMES: [&'static str; 3] = ["qwerty", "qwertz", "azerty"];
+
+    fn rows(&self) -> [&'static str; 3] {
+        match self {
+            KeyboardLayout::Qwerty => ["qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            KeyboardLayout::Qwertz => ["qwertzuiop", "asdfghjkl", "yxcvbnm"],
+            KeyboardLayout::Azerty => ["azertyuiop", "qsdfghjklm", "wxcvbn"],
+        }
+    }
+}
+
+impl std::str::FromStr for KeyboardLayout {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<KeyboardLayout, String> {
+        match name {
+            "qwerty" => Ok(KeyboardLayout::Qwerty),
+            "qwertz" => Ok(KeyboardLayout::Qwertz),
+            "azerty" => Ok(KeyboardLayout::Azerty),
+            _ => Err(format!(
+                "unknown keyboard layout `{}`, expected one of {}",
+                name,
+                KeyboardLayout::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Metadata declared by `#! key: value` lines at the top of a word list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListHeader {
+    /// Display name of the list.
+    pub name: Option<String>,
+    /// Letters the words are written in.
+    pub alphabet: Option<Alphabet>,
+    /// Digraphs played as single letters, see `crate::digraph`.
+    pub digraphs: Option<Digraphs>,
+}
+
+#[derive(Debug)]
+struct WordList {
+    header: ListHeader,
+    words: Vec<String>,
+    frequencies: HashMap<String, u64>,
+    tags: HashMap<String, BTreeSet<String>>,
+    warnings: Vec<String>,
+}
+
+/// Whether a word of an external dictionary makes a fair puzzle: it is not
+/// a name or abbreviation written with capitals, has no apostrophes or
+/// periods and is more than a single letter. Only meaningful before the
+/// word is lowercased.
+pub fn is_clean_word(word: &str) -> bool {
+    word.chars().nth(1).is_some()
+        && !word.chars().any(char::is_uppercase)
+        && !word.contains(['\'', '’', '.'])
+}
+
+/// Warning reporting the entries dropped by cleaning, `None` if there were
+/// none.
+fn cleaning_warning(removed: usize) -> Option<String> {
+    (removed > 0).then(|| format!("cleaning removed {} entries", removed))
+}
+
+/// A normalized word of a list, its frequency and its tags.
+type Entry = (String, Option<u64>, Vec<String>);
+
+/// Line by line parser of word lists, so that lists can be streamed.
+#[derive(Debug, Default)]
+struct ListReader {
+    header: ListHeader,
+    /// Warnings and the lines they are about.
+    warnings: Vec<(usize, String)>,
+    line_no: usize,
+    // set once the first line which is not part of the header was read
+    past_header: bool,
+    /// Skip words which are not `is_clean_word`.
+    clean: bool,
+    /// Words skipped by cleaning.
+    removed: usize,
+}
+
+impl ListReader {
+    /// Reads the next line, returning the normalized word of the line if any,
+    /// its frequency and its tags. Lines of weighted lists are a word
+    /// followed by how often it occurs, e.g. `crane 1234`. Tags follow at
+    /// the end, e.g. `xylem #hard #science`.
+    fn line(&mut self, line: &str) -> Result<Option<Entry>, HeaderError> {
+        let (word, count, tags) = match self.entry(line)? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        // capitals only tell names apart before lowercasing
+        if self.clean && !is_clean_word(word) {
+            self.removed += 1;
+            return Ok(None);
+        }
+        let tags = tags.iter().map(|tag| tag.to_lowercase()).collect();
+        Ok(Some((word.to_lowercase(), count, tags)))
+    }
+
+    /// The warnings, each starting with its line.
+    fn lined_warnings(&self) -> Vec<String> {
+        self.warnings
+            .iter()
+            .map(|(line, warning)| format!("line {}: {}", line, warning))
+            .collect()
+    }
+
+    /// Reads the next line like `line`, but returns the word and tags as
+    /// written, before cleaning and lowercasing.
+    #[allow(clippy::type_complexity)]
+    fn entry<'a>(
+        &mut self,
+        line: &'a str,
+    ) -> Result<Option<(&'a str, Option<u64>, Vec<&'a str>)>, HeaderError> {
+        self.line_no += 1;
+        if !self.past_header && line.trim_start().starts_with("#!") {
+            self.header_line(line)?;
+            return Ok(None);
+        }
+        self.past_header = true;
+        let mut line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let mut tags = Vec::new();
+        // a line of only a tag is a word, if an odd one
+        while let Some((rest, tag)) = line.rsplit_once(char::is_whitespace) {
+            match tag.strip_prefix('#') {
+                Some(tag) if !tag.is_empty() => tags.insert(0, tag),
+                _ => break,
+            }
+            line = rest.trim_end();
+        }
+        let weighted = line
+            .rsplit_once(char::is_whitespace)
+            .and_then(|(word, count)| Some((word.trim_end(), count.parse().ok()?)))
+            .filter(|(word, _)| !word.contains(char::is_whitespace));
+        Ok(Some(match weighted {
+            Some((word, count)) => (word, Some(count), tags),
+            None => (line, None, tags),
+        }))
+    }
+
+    fn header_line(&mut self, line: &str) -> Result<(), HeaderError> {
+        let line_no = self.line_no;
+        let (key, value) = line.trim_start()[2..]
+            .split_once(':')
+            .ok_or(HeaderError::Malformed { line: line_no })?;
+        let key = key.trim().to_lowercase();
+        let value = value.trim();
+        if key.is_empty() {
+            return Err(HeaderError::Malformed { line: line_no });
+        }
+        if value.is_empty() {
+            return Err(HeaderError::EmptyValue { line: line_no, key });
+        }
+        match key.as_str() {
+            "name" => self.header.name = Some(value.to_string()),
+            "alphabet" => self.header.alphabet = Some(Alphabet::parse(value, line_no)?),
+            "digraphs" => {
+                let digraphs = value
+                    .parse()
+                    .map_err(|reason| HeaderError::InvalidDigraphs {
+                        line: line_no,
+                        reason,
+                    })?;
+                self.header.digraphs = Some(digraphs);
+            }
+            _ => self
+                .warnings
+                .push((line_no, format!("unknown header `{}`", key))),
+        }
+        Ok(())
+    }
+}
+
+/// Parses a word list, with `clean` skipping what `is_clean_word` rejects.
+fn parse_word_list(contents: &str, clean: bool) -> Result<WordList, HeaderError> {
+    let mut reader = ListReader {
+        clean,
+        ..ListReader::default()
+    };
+    let mut words = Vec::new();
+    let mut frequencies = HashMap::new();
+    let mut tags: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for line in contents.split('\n') {
+        if let Some((word, frequency, word_tags)) = reader.line(line)? {
+            if let Some(frequency) = frequency {
+                frequencies.entry(word.clone()).or_insert(frequency);
+            }
+            if !word_tags.is_empty() {
+                tags.entry(word.clone()).or_default().extend(word_tags);
+            }
+            words.push(word);
+        }
+    }
+    let mut warnings = reader.lined_warnings();
+    warnings.extend(cleaning_warning(reader.removed));
+    Ok(WordList {
+        header: reader.header,
+        words,
+        frequencies,
+        tags,
+        warnings,
+    })
+}
+
+/// How serious a [`Finding`] of [`lint_word_list`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The list loads, but likely not as intended.
+    Warning,
+    /// The list does not load, or has words which can not be played.
+    Error,
+}
+
+/// A problem [`lint_word_list`] found on a line of a word list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// The line, counted from 1.
+    pub line: usize,
+    /// Whether it is an error or a warning.
+    pub severity: Severity,
+    /// What is wrong.
+    pub message: String,
+}
+
+/// What [`lint_word_list`] found in a word list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListReport {
+    /// Number of words of each length, as loaded without cleaning.
+    pub lengths: std::collections::BTreeMap<usize, usize>,
+    /// The problems, in the order of their lines.
+    pub findings: Vec<Finding>,
+}
+
+impl ListReport {
+    /// Whether any of the findings is an error.
+    pub fn has_errors(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| finding.severity == Severity::Error)
+    }
+}
+
+/// Whether `c` is a combining mark, written after the letter it belongs
+/// to in decomposed (NFD) text.
+fn is_combining(c: char) -> bool {
+    matches!(c,
+        '\u{300}'..='\u{36f}'
+        | '\u{1ab0}'..='\u{1aff}'
+        | '\u{1dc0}'..='\u{1dff}'
+        | '\u{20d0}'..='\u{20ff}'
+        | '\u{fe20}'..='\u{fe2f}')
+}
+
+/// Whether `c` is a Latin letter with a diacritic in one character, as
+/// written in composed (NFC) text.
+fn is_precomposed(c: char) -> bool {
+    c.is_alphabetic() && matches!(c, '\u{c0}'..='\u{24f}' | '\u{1e00}'..='\u{1eff}')
+}
+
+/// Checks a word list the way it is loaded, reporting problems with their
+/// lines instead of stopping at the first error or fixing them silently.
+/// Errors are malformed headers, characters which are not letters, letters
+/// outside of a declared alphabet and words written with combining marks
+/// in a list which otherwise uses composed letters, or the other way
+/// round, as both look alike but never match. Warnings are duplicates,
+/// blank lines, Windows line endings and words `--clean` would drop.
+pub fn lint_word_list(contents: &str) -> ListReport {
+    let mut reader = ListReader::default();
+    let mut report = ListReport::default();
+    let mut finding = |line, severity, message: String| {
+        report.findings.push(Finding {
+            line,
+            severity,
+            message,
+        })
+    };
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut lengths = std::collections::BTreeMap::new();
+    // lines of the words using each normalization form
+    let (mut composed, mut decomposed) = (Vec::new(), Vec::new());
+    let (mut crlf, mut first_crlf) = (0, 0);
+    // a final newline ends the last line, it does not start a blank one
+    let lines = contents.strip_suffix('\n').unwrap_or(contents);
+    for line in lines.split('\n') {
+        let line_no = reader.line_no + 1;
+        if line.ends_with('\r') {
+            crlf += 1;
+            if first_crlf == 0 {
+                first_crlf = line_no;
+            }
+        }
+        let entry = match reader.entry(line) {
+            Ok(entry) => entry,
+            Err(error) => {
+                finding(error.line(), Severity::Error, error.reason());
+                continue;
+            }
+        };
+        let word = match entry {
+            Some((word, _, _)) => word,
+            None if reader.past_header && line.trim().is_empty() => {
+                finding(line_no, Severity::Warning, "blank line".to_string());
+                continue;
+            }
+            None => continue,
+        };
+        let lowercase = word.to_lowercase();
+        if let Some(c) = word
+            .chars()
+            .find(|c| !c.is_alphabetic() && !is_combining(*c))
+        {
+            finding(line_no, Severity::Error, format!("`{}` is not a letter", c));
+        } else if let Some(c) = reader.header.alphabet.as_ref().and_then(|alphabet| {
+            lowercase
+                .chars()
+                .find(|c| !alphabet.contains(*c) && !is_combining(*c))
+        }) {
+            finding(
+                line_no,
+                Severity::Error,
+                format!("`{}` is not in the alphabet of the header", c),
+            );
+        }
+        if word.chars().any(is_combining) {
+            decomposed.push(line_no);
+        } else if word.chars().any(is_precomposed) {
+            composed.push(line_no);
+        }
+        if !is_clean_word(word) {
+            finding(
+                line_no,
+                Severity::Warning,
+                format!("`{}` would be removed by cleaning", word),
+            );
+        }
+        match seen.get(&lowercase) {
+            Some(first) => finding(
+                line_no,
+                Severity::Warning,
+                format!("duplicate of line {}", first),
+            ),
+            None => {
+                *lengths.entry(lowercase.chars().count()).or_insert(0) += 1;
+                seen.insert(lowercase, line_no);
+            }
+        }
+    }
+    // the less common form is the odd one out
+    if !composed.is_empty() && !decomposed.is_empty() {
+        let (odd, form) = if decomposed.len() <= composed.len() {
+            (
+                decomposed,
+                "combining marks, other words use composed letters",
+            )
+        } else {
+            (
+                composed,
+                "composed letters, other words use combining marks",
+            )
+        };
+        for line in odd {
+            finding(line, Severity::Error, format!("written with {}", form));
+        }
+    }
+    if crlf == 1 {
+        finding(
+            first_crlf,
+            Severity::Warning,
+            "line ends with CRLF".to_string(),
+        );
+    } else if crlf > 1 {
+        finding(
+            first_crlf,
+            Severity::Warning,
+            format!("{} lines end with CRLF, the first here", crlf),
+        );
+    }
+    for (line, warning) in reader.warnings {
+        finding(line, Severity::Warning, warning);
+    }
+    report.findings.sort_by_key(|finding| finding.line);
+    report.lengths = lengths;
+    report
+}
+
+/// Merges the header of another list into `merged`. The first name wins,
+/// declared alphabets and digraphs are combined.
+fn merge_header(merged: &mut ListHeader, header: ListHeader) {
+    if merged.name.is_none() {
+        merged.name = header.name;
+    }
+    if let Some(digraphs) = header.digraphs {
+        match &mut merged.digraphs {
+            Some(merged_digraphs) => merged_digraphs.extend(&digraphs),
+            None => merged.digraphs = Some(digraphs),
+        }
+    }
+    if let Some(alphabet) = header.alphabet {
+        match &mut merged.alphabet {
+            Some(merged_alphabet) => merged_alphabet.extend(&alphabet),
+            None => merged.alphabet = Some(alphabet),
+        }
+    }
+}
+
+/// Words stored in one contiguous, sorted buffer instead of a heap
+/// allocated `String` per word.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordStore {
+    text: String,
+    // end offset of each word in `text`
+    ends: Vec<usize>,
+}
+
+impl WordStore {
+    /// Number of words.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Whether no word is stored.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// The `i`-th word in sorted order.
+    pub fn get(&self, i: usize) -> &str {
+        let start = if i == 0 { 0 } else { self.ends[i - 1] };
+        &self.text[start..self.ends[i]]
+    }
+
+    /// Iterates the words in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        (0..self.len()).map(move |i| self.get(i))
+    }
+
+    /// Whether `word` is stored, by binary search.
+    pub fn contains(&self, word: &str) -> bool {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            match self.get(mid).cmp(word) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for WordStore {
+    fn from_iter<I: IntoIterator<Item = S>>(words: I) -> WordStore {
+        // collect unsorted first, then copy over in sorted order
+        let mut unsorted = WordStore::default();
+        for word in words {
+            unsorted.text.push_str(word.as_ref());
+            unsorted.ends.push(unsorted.text.len());
+        }
+        let mut order: Vec<usize> = (0..unsorted.len()).collect();
+        order.sort_unstable_by_key(|i| unsorted.get(*i));
+        order.dedup_by_key(|i| unsorted.get(*i));
+
+        let mut store = WordStore {
+            text: String::with_capacity(unsorted.text.len()),
+            ends: Vec::with_capacity(order.len()),
+        };
+        for i in order {
+            store.text.push_str(unsorted.get(i));
+            store.ends.push(store.text.len());
+        }
+        store
+    }
+}
+
+/// Solutions and valid guesses of a game, merged from all word sources.
+#[derive(Debug, Clone, Default)]
+pub struct MergedWordList {
+    /// Header merged from all lists.
+    pub header: ListHeader,
+    /// Words a solution is picked from.
+    pub solutions: Vec<String>,
+    /// All valid guesses, including the solutions.
+    pub words: Vec<String>,
+    /// How often words occur, from weighted lists. Words without a
+    /// frequency are missing.
+    pub frequencies: HashMap<String, u64>,
+    /// Tags of the words, e.g. `easy`, without the `#`. Words without tags
+    /// are missing.
+    pub tags: HashMap<String, BTreeSet<String>>,
+    /// Problems found while loading, prefixed with the file name.
+    pub warnings: Vec<String>,
+}
+
+/// The tags solutions must carry, given by `--tags`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagFilter {
+    /// The tags, lowercase and without the `#`.
+    pub tags: Vec<String>,
+    /// A solution needs only one of the tags rather than all of them.
+    pub any: bool,
+}
+
+impl TagFilter {
+    /// Whether a word with `tags` passes the filter.
+    pub fn matches(&self, tags: Option<&BTreeSet<String>>) -> bool {
+        let has = |tag: &String| tags.is_some_and(|tags| tags.contains(tag));
+        if self.any {
+            self.tags.iter().any(has)
+        } else {
+            self.tags.iter().all(has)
+        }
+    }
+}
+
+impl std::fmt::Display for TagFilter {
+    /// Writes the tags like they are given, e.g. `easy,science`.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.tags.join(","))
+    }
+}
+
+impl std::str::FromStr for TagFilter {
+    type Err = String;
+
+    /// Parses comma separated tags, with or without `#`, needing all of them.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tags: Vec<String> = s
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches('#').to_lowercase())
+            .collect();
+        if tags.iter().any(String::is_empty) {
+            return Err("empty tag".to_string());
+        }
+        Ok(TagFilter { tags, any: false })
+    }
+}
+
+impl MergedWordList {
+    /// Keeps only solutions and valid guesses with `length` letters.
+    pub fn retain_length(&mut self, length: usize) {
+        self.solutions.retain(|word| word.chars().count() == length);
+        self.words.retain(|word| word.chars().count() == length);
+    }
+
+    /// Plays `digraphs` as single letters, on top of the digraphs of the
+    /// header: the words are tokenized and a declared alphabet gets the
+    /// digraphs as letters. See `crate::digraph`.
+    pub fn tokenize(&mut self, digraphs: &Digraphs) {
+        let mut all = self.header.digraphs.take().unwrap_or_default();
+        all.extend(digraphs);
+        if all.is_empty() {
+            return;
+        }
+        for word in self.words.iter_mut().chain(self.solutions.iter_mut()) {
+            *word = all.tokenize(word);
+        }
+        self.frequencies = std::mem::take(&mut self.frequencies)
+            .into_iter()
+            .map(|(word, frequency)| (all.tokenize(&word), frequency))
+            .collect();
+        self.tags = std::mem::take(&mut self.tags)
+            .into_iter()
+            .map(|(word, tags)| (all.tokenize(&word), tags))
+            .collect();
+        if let Some(alphabet) = &mut self.header.alphabet {
+            for token in all.tokens() {
+                if !alphabet.contains(token) {
+                    alphabet.letters.push(token);
+                }
+            }
+        }
+        self.header.digraphs = Some(all);
+    }
+
+    /// Keeps only the solutions passing `filter`. The other words can still
+    /// be guessed, tags do not matter for guesses.
+    pub fn retain_tagged(&mut self, filter: &TagFilter) {
+        let tags = &self.tags;
+        self.solutions.retain(|word| filter.matches(tags.get(word)));
+    }
+
+    /// Drops solutions which look like plurals: words ending in a single
+    /// `s` whose singular, the word without it, is in the list too. This is
+    /// only a heuristic for English, e.g. `cats` is dropped if `cat` exists
+    /// but `glass` and `lens` are kept. The plurals can still be guessed.
+    pub fn remove_plural_solutions(&mut self) {
+        let words: HashSet<&str> = self.words.iter().map(String::as_str).collect();
+        self.solutions.retain(|word| {
+            let singular = match word.strip_suffix('s') {
+                Some(singular) if !singular.ends_with('s') => singular,
+                _ => return true,
+            };
+            !words.contains(singular)
+        });
+    }
+
+    /// How rare `word` is among the solutions of its length, by the
+    /// frequencies of the list. Solutions without a frequency count as the
+    /// rarest, as they do for the solver.
+    pub fn rarity(&self, word: &str) -> Rarity {
+        if self.frequencies.is_empty() {
+            return Rarity::Unknown;
+        }
+        let frequency = match self.frequencies.get(word) {
+            Some(frequency) => *frequency,
+            None => return Rarity::Unlisted,
+        };
+        let length = word.chars().count();
+        let others: Vec<u64> = self
+            .solutions
+            .iter()
+            .filter(|solution| *solution != word && solution.chars().count() == length)
+            .map(|solution| self.frequencies.get(solution).copied().unwrap_or(0))
+            .collect();
+        if others.is_empty() {
+            return Rarity::Unlisted;
+        }
+        let commoner = others.iter().filter(|other| **other > frequency).count();
+        let rarer = others.iter().filter(|other| **other < frequency).count();
+        Rarity::Percentile {
+            commoner: commoner as f64 / others.len() as f64,
+            rarer: rarer as f64 / others.len() as f64,
+        }
+    }
+}
+
+/// How common a solution is compared to the other solutions of the list.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Rarity {
+    /// The list has no frequencies.
+    #[default]
+    Unknown,
+    /// The list has frequencies, but none for this word.
+    Unlisted,
+    /// Shares of the other solutions, from 0 to 1, more and less common
+    /// than this word.
+    Percentile {
+        /// Share of solutions more common than the word.
+        commoner: f64,
+        /// Share of solutions rarer than the word.
+        rarer: f64,
+    },
+}
+
+/// Errors loading a word list, naming the file or list that failed.
+#[derive(Debug)]
+pub enum WordFileError {
+    /// A file could not be read.
+    Io(String, std::io::Error),
+    /// A file has an invalid header.
+    Header(String, HeaderError),
+    /// No list of this name exists.
+    UnknownList {
+        /// The requested name.
+        name: String,
+        /// Names of all lists which exist.
+        available: Vec<String>,
+    },
+    /// A list could not be downloaded.
+    Download {
+        /// The URL of the list.
+        url: String,
+        /// What went wrong.
+        message: String,
+    },
+    /// No list was given and none is built in, see the `bundled-words`
+    /// feature.
+    NoBundledWords,
+    /// An entry of a list given with `--words` is not a word of the list.
+    InvalidWord {
+        /// Position of the entry, counted from 1.
+        entry: usize,
+        /// The entry as given.
+        word: String,
+        /// What is wrong with it.
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for WordFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WordFileError::Io(file, error) => write!(f, "{}: {}", file, error),
+            WordFileError::Header(file, error) => write!(f, "{}: {}", file, error),
+            WordFileError::UnknownList { name, available } if available.is_empty() => {
+                write!(f, "unknown list `{}`, no lists are available", name)
+            }
+            WordFileError::UnknownList { name, available } => write!(
+                f,
+                "unknown list `{}`, available lists: {}",
+                name,
+                available.join(", ")
+            ),
+            WordFileError::Download { url, message } => {
+                write!(f, "can not download {}: {}", url, message)
+            }
+            WordFileError::NoBundledWords => write!(
+                f,
+                "this build has no bundled word list, use --word-file or --list"
+            ),
+            WordFileError::InvalidWord {
+                entry,
+                word,
+                reason,
+            } => write!(f, "--words: entry {} `{}` {}", entry, word, reason),
+        }
+    }
+}
+
+impl std::error::Error for WordFileError {}
+
+/// Names of the lists embedded into the binary.
+#[cfg(feature = "bundled-words")]
+pub const BUILTIN_LISTS: &[&str] = &["english"];
+
+/// Names of the lists embedded into the binary, none without the
+/// `bundled-words` feature.
+#[cfg(not(feature = "bundled-words"))]
+pub const BUILTIN_LISTS: &[&str] = &[];
+
+/// A place word lists are loaded from. Every source normalizes its words
+/// itself, the game only sees the resulting `MergedWordList`.
+pub trait WordSource {
+    /// Name of the source in errors and warnings, e.g. a file name.
+    fn name(&self) -> String;
+
+    /// Loads the solutions and valid guesses of the source.
+    fn load(&self) -> Result<MergedWordList, WordFileError>;
+}
+
+/// Turns a parsed list into a `MergedWordList` whose words are all
+/// solutions, prefixing its warnings with `name`.
+fn from_parsed(name: &str, list: WordList) -> MergedWordList {
+    MergedWordList {
+        header: list.header,
+        solutions: list.words.clone(),
+        words: list.words,
+        frequencies: list.frequencies,
+        tags: list.tags,
+        warnings: list
+            .warnings
+            .into_iter()
+            .map(|warning| format!("{}: {}", name, warning))
+            .collect(),
+    }
+}
+
+/// The built-in list as static slices, generated from `data/` by the
+/// build script, so using it needs no parsing. They are empty without the
+/// `bundled-words` feature.
+pub mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/embedded_words.rs"));
+
+    /// One line explaining `word`, if it is a solution of the built-in
+    /// list the glossary knows.
+    pub fn gloss(word: &str) -> Option<&'static str> {
+        let i = GLOSSARY.binary_search_by_key(&word, |(w, _)| w).ok()?;
+        Some(GLOSSARY[i].1)
+    }
+}
+
+/// A list embedded into the binary, one of `BUILTIN_LISTS`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EmbeddedSource;
+
+impl WordSource for EmbeddedSource {
+    fn name(&self) -> String {
+        "english".to_string()
+    }
+
+    fn load(&self) -> Result<MergedWordList, WordFileError> {
+        if !cfg!(feature = "bundled-words") {
+            return Err(WordFileError::NoBundledWords);
+        }
+        // the words are lowercased by the build script already, but the
+        // list owns them like the words of any other source
+        let owned = |words: &[&str]| words.iter().map(|word| word.to_string()).collect();
+        Ok(MergedWordList {
+            header: ListHeader::default(),
+            solutions: owned(embedded::SOLUTIONS),
+            words: owned(embedded::WORDS),
+            frequencies: HashMap::new(),
+            tags: HashMap::new(),
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// A word file, one word per line after an optional `#!` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSource {
+    /// Path of the file.
+    pub path: String,
+    /// Only keep words with this number of letters.
+    pub length: Option<usize>,
+    /// Skip names, abbreviations and single letters, see `is_clean_word`.
+    pub clean: bool,
+}
+
+impl WordSource for FileSource {
+    fn name(&self) -> String {
+        self.path.clone()
+    }
+
+    fn load(&self) -> Result<MergedWordList, WordFileError> {
+        let mut contents = String::new();
+        File::open(&self.path)
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .map_err(|error| WordFileError::Io(self.name(), error))?;
+        let list = parse_word_list(&contents, self.clean)
+            .map_err(|error| WordFileError::Header(self.name(), error))?;
+        let mut list = from_parsed(&self.path, list);
+        if let Some(length) = self.length {
+            list.retain_length(length);
+        }
+        Ok(list)
+    }
+}
+
+/// A word list in the format of word files, downloaded over plain HTTP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlSource {
+    /// The `http://` URL of the list.
+    pub url: String,
+    /// Skip names, abbreviations and single letters, see `is_clean_word`.
+    pub clean: bool,
+}
+
+impl UrlSource {
+    /// Downloads the body of the URL with a minimal HTTP/1.0 request.
+    fn download(&self) -> Result<String, String> {
+        let rest = match self.url.strip_prefix("http://") {
+            Some(rest) => rest,
+            None if self.url.starts_with("https://") => {
+                return Err(
+                    "https is not supported, download the list and use it as a file".to_string(),
+                )
+            }
+            None => return Err("the URL must start with http://".to_string()),
+        };
+        let (host, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, "/"),
+        };
+        let address = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{}:80", host)
+        };
+        let mut stream = TcpStream::connect(&address).map_err(|e| e.to_string())?;
+        stream
+            .set_read_timeout(Some(Duration::from_secs(30)))
+            .map_err(|e| e.to_string())?;
+        write!(
+            stream,
+            "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: rordle\r\nConnection: close\r\n\r\n",
+            path, host
+        )
+        .map_err(|e| e.to_string())?;
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|e| e.to_string())?;
+        let response = String::from_utf8(response).map_err(|_| "the list is not UTF-8")?;
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or("malformed HTTP response")?;
+        let status = head.lines().next().unwrap_or_default();
+        match status.split_whitespace().nth(1) {
+            Some("200") => Ok(body.to_string()),
+            _ => Err(format!("the server answered `{}`", status)),
+        }
+    }
+}
+
+impl WordSource for UrlSource {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    fn load(&self) -> Result<MergedWordList, WordFileError> {
+        let contents = self.download().map_err(|message| WordFileError::Download {
+            url: self.name(),
+            message,
+        })?;
+        let list = parse_word_list(&contents, self.clean)
+            .map_err(|error| WordFileError::Header(self.name(), error))?;
+        Ok(from_parsed(&self.url, list))
+    }
+}
+
+/// A list given on the command line, comma separated, e.g.
+/// `apple,bread,crane`. All of its words are solutions and, unlike the words
+/// of files, they must all be letters and of the same length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineSource {
+    /// The comma separated words.
+    pub words: String,
+}
+
+impl WordSource for InlineSource {
+    fn name(&self) -> String {
+        "--words".to_string()
+    }
+
+    fn load(&self) -> Result<MergedWordList, WordFileError> {
+        let mut words: Vec<String> = Vec::new();
+        for (i, entry) in self.words.split(',').enumerate() {
+            let invalid = |reason: String| WordFileError::InvalidWord {
+                entry: i + 1,
+                word: entry.to_string(),
+                reason,
+            };
+            let word = entry.trim();
+            if word.is_empty() {
+                return Err(invalid("is empty".to_string()));
+            }
+            if let Some(c) = word
+                .chars()
+                .find(|c| !c.is_alphabetic() && !is_combining(*c))
+            {
+                return Err(invalid(format!("contains `{}`, which is not a letter", c)));
+            }
+            let word = word.to_lowercase();
+            let length = word.chars().count();
+            if let Some(first) = words.first() {
+                let first = first.chars().count();
+                if length != first {
+                    return Err(invalid(format!(
+                        "has {} letters, the first word has {}",
+                        length, first
+                    )));
+                }
+            }
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        }
+        Ok(MergedWordList {
+            solutions: words.clone(),
+            words,
+            ..MergedWordList::default()
+        })
+    }
+}
+
+/// The dictionary of the system, as installed by packages like `words`.
+/// It is always cleaned, see `is_clean_word`, and words with other non
+/// letters like hyphens are skipped too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemDictSource {
+    /// Path of the dictionary.
+    pub path: String,
+}
+
+impl Default for SystemDictSource {
+    fn default() -> SystemDictSource {
+        SystemDictSource {
+            path: "/usr/share/dict/words".to_string(),
+        }
+    }
+}
+
+impl WordSource for SystemDictSource {
+    fn name(&self) -> String {
+        self.path.clone()
+    }
+
+    fn load(&self) -> Result<MergedWordList, WordFileError> {
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|error| WordFileError::Io(self.name(), error))?;
+        let entries: Vec<&str> = contents
+            .lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .collect();
+        let words: Vec<String> = entries
+            .iter()
+            .filter(|word| is_clean_word(word) && word.chars().all(char::is_alphabetic))
+            .map(|word| word.to_string())
+            .collect();
+        let warnings = cleaning_warning(entries.len() - words.len())
+            .map(|warning| format!("{}: {}", self.path, warning));
+        Ok(MergedWordList {
+            header: ListHeader::default(),
+            solutions: words.clone(),
+            words,
+            frequencies: HashMap::new(),
+            tags: HashMap::new(),
+            warnings: warnings.into_iter().collect(),
+        })
+    }
+}
+
+/// Source of a word file given on the command line, a `UrlSource` for
+/// `http://` and `https://` URLs and a `FileSource` otherwise. `clean`
+/// skips names, abbreviations and single letters.
+pub fn word_file_source(file: &str, clean: bool) -> Box<dyn WordSource> {
+    if file.starts_with("http://") || file.starts_with("https://") {
+        Box::new(UrlSource {
+            url: file.to_string(),
+            clean,
+        })
+    } else {
+        Box::new(FileSource {
+            path: file.to_string(),
+            length: None,
+            clean,
+        })
+    }
+}
+
+/// Resolves a list name, looking at built-in lists before the lists
+/// registered in the config file.
+pub fn named_source(name: &str, config: &Config) -> Result<Box<dyn WordSource>, WordFileError> {
+    if BUILTIN_LISTS.contains(&name) {
+        return Ok(Box::new(EmbeddedSource));
+    }
+    match config.lists.get(name) {
+        Some(entry) => Ok(Box::new(FileSource {
+            path: entry.file.to_string_lossy().to_string(),
+            length: entry.length,
+            clean: false,
+        })),
+        None => Err(WordFileError::UnknownList {
+            name: name.to_string(),
+            available: BUILTIN_LISTS
+                .iter()
+                .map(|name| name.to_string())
+                .chain(config.lists.keys().cloned())
+                .collect(),
+        }),
+    }
+}
+
+/// Merges word lists, dropping duplicates. Only the solutions of the first
+/// list are solutions, unless `solutions_from_all` is set.
+fn merge_word_lists(lists: Vec<MergedWordList>, solutions_from_all: bool) -> MergedWordList {
+    let mut merged = MergedWordList::default();
+    let mut seen_solutions = HashSet::new();
+    let mut seen_words = HashSet::new();
+    for (i, list) in lists.into_iter().enumerate() {
+        merge_header(&mut merged.header, list.header);
+        merged.warnings.extend(list.warnings);
+        for (word, frequency) in list.frequencies {
+            merged.frequencies.entry(word).or_insert(frequency);
+        }
+        for (word, tags) in list.tags {
+            merged.tags.entry(word).or_default().extend(tags);
+        }
+        if i == 0 || solutions_from_all {
+            for word in list.solutions {
+                if seen_solutions.insert(word.clone()) {
+                    merged.solutions.push(word);
+                }
+            }
+        }
+        for word in list.words {
+            if seen_words.insert(word.clone()) {
+                merged.words.push(word);
+            }
+        }
+    }
+    merged
+}
+
+/// Loads and merges `sources`, dropping duplicates. Only the solutions of
+/// the first source are solutions, unless `solutions_from_all` is set.
+pub fn load_sources(
+    sources: &[Box<dyn WordSource>],
+    solutions_from_all: bool,
+) -> Result<MergedWordList, WordFileError> {
+    let lists = sources
+        .iter()
+        .map(|source| source.load())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(merge_word_lists(lists, solutions_from_all))
+}
+
+/// Total size of word files above which they are streamed instead of being
+/// read into memory.
+const STREAM_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// Whether the word files are large enough to be streamed.
+pub fn exceeds_stream_threshold(word_files: &[&str]) -> bool {
+    let size: u64 = word_files
+        .iter()
+        .filter_map(|file_name| std::fs::metadata(file_name).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    size > STREAM_THRESHOLD
+}
+
+/// Reads a word file line by line, passing each word to `f`.
+fn stream_word_file(
+    file_name: &str,
+    mut f: impl FnMut(String),
+) -> Result<ListReader, WordFileError> {
+    let io_error = |error| WordFileError::Io(file_name.to_string(), error);
+    let file = File::open(file_name).map_err(io_error)?;
+    let mut reader = ListReader::default();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(io_error)?;
+        let word = reader
+            .line(&line)
+            .map_err(|error| WordFileError::Header(file_name.to_string(), error))?;
+        // frequencies are only used by the solver and tags only to filter
+        // solutions, neither of which streams
+        if let Some((word, _, _)) = word {
+            f(word);
+        }
+    }
+    Ok(reader)
+}
+
+/// Loads huge word files without keeping all words in memory.
+///
+/// The first pass picks the solution by reservoir sampling, the second pass
+/// only keeps the words of the solution's length, as no other word can be
+/// a valid guess. The returned list has the picked word as its only solution.
+pub fn stream_word_files<R: Rng>(
+    word_files: &[&str],
+    solutions_from_all: bool,
+    rng: &mut R,
+) -> Result<MergedWordList, WordFileError> {
+    let mut header = ListHeader::default();
+    let mut warnings = Vec::new();
+    let mut solution = None;
+    let mut seen = 0;
+    for (i, file_name) in word_files.iter().enumerate() {
+        let is_solution_file = i == 0 || solutions_from_all;
+        let reader = stream_word_file(file_name, |word| {
+            if is_solution_file {
+                seen += 1;
+                if rng.gen_range(0..seen) == 0 {
+                    solution = Some(word);
+                }
+            }
+        })?;
+        for warning in reader.lined_warnings() {
+            warnings.push(format!("{}: {}", file_name, warning));
+        }
+        merge_header(&mut header, reader.header);
+    }
+
+    let solution = match solution {
+        Some(solution) => solution,
+        None => {
+            return Ok(MergedWordList {
+                header,
+                warnings,
+                ..MergedWordList::default()
+            })
+        }
+    };
+    // a digraph is a single letter
+    let digraphs = header.digraphs.clone().unwrap_or_default();
+    let letters = |word: &str| digraphs.tokenize(word).chars().count();
+    let length = letters(&solution);
+    let mut words = Vec::new();
+    let mut unique = HashSet::new();
+    for file_name in word_files {
+        stream_word_file(file_name, |word| {
+            if letters(&word) == length && unique.insert(word.clone()) {
+                words.push(word);
+            }
+        })?;
+    }
+    Ok(MergedWordList {
+        header,
+        solutions: vec![solution],
+        words,
+        frequencies: HashMap::new(),
+        tags: HashMap::new(),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_word_list_without_header() {
+        let list = parse_word_list("Gold  \n  Katze\n", false).unwrap();
+        assert_eq!(list.header, ListHeader::default());
+        assert_eq!(list.words, vec!["gold", "katze"]);
+        assert!(list.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_weighted_word_list() {
+        let list = parse_word_list("Crane 1234\nslate\t7\nice cream 3\nplain\n", false).unwrap();
+        assert_eq!(list.words, vec!["crane", "slate", "ice cream 3", "plain"]);
+        assert_eq!(list.frequencies.len(), 2);
+        assert_eq!(list.frequencies["crane"], 1234);
+        assert_eq!(list.frequencies["slate"], 7);
+    }
+
+    #[test]
+    fn test_parse_word_list_header() {
+        let list = parse_word_list(
+            "#! name: Deutsch\n#! alphabet: a-c äß\nbär\nab\n#! name: x",
+            false,
+        )
+        .unwrap();
+        assert_eq!(list.header.name, Some("Deutsch".to_string()));
+        let alphabet = list.header.alphabet.unwrap();
+        assert_eq!(alphabet.letters, vec!['a', 'b', 'c', 'ä', 'ß']);
+        assert!(alphabet.declared);
+        // only leading lines form the header
+        assert_eq!(list.words, vec!["bär", "ab", "#! name: x"]);
+    }
+
+    #[test]
+    fn test_lint_word_list() {
+        let report = lint_word_list(
+            "#! name: Test\n#! author: me\ncrane\r\nCrane\n\nab3de\nétude\ncafé\ne\u{301}tude\n",
+        );
+        let findings: Vec<(usize, Severity, &str)> = report
+            .findings
+            .iter()
+            .map(|finding| (finding.line, finding.severity, finding.message.as_str()))
+            .collect();
+        assert_eq!(
+            findings,
+            [
+                (2, Severity::Warning, "unknown header `author`"),
+                (3, Severity::Warning, "line ends with CRLF"),
+                (4, Severity::Warning, "`Crane` would be removed by cleaning"),
+                (4, Severity::Warning, "duplicate of line 3"),
+                (5, Severity::Warning, "blank line"),
+                (6, Severity::Error, "`3` is not a letter"),
+                (
+                    9,
+                    Severity::Error,
+                    "written with combining marks, other words use composed letters"
+                ),
+            ]
+        );
+        assert!(report.has_errors());
+        // the decomposed étude has one more character
+        assert_eq!(
+            report.lengths.into_iter().collect::<Vec<_>>(),
+            [(4, 1), (5, 3), (6, 1)]
+        );
+
+        let report = lint_word_list("#! name\n#! alphabet: a-c\nabd\n");
+        assert_eq!(
+            report.findings,
+            [
+                Finding {
+                    line: 1,
+                    severity: Severity::Error,
+                    message: "malformed header, expected `#! key: value`".to_string(),
+                },
+                Finding {
+                    line: 3,
+                    severity: Severity::Error,
+                    message: "`d` is not in the alphabet of the header".to_string(),
+                },
+            ]
+        );
+        assert!(!lint_word_list("crane\nslate 12\n").has_errors());
+        assert!(lint_word_list("crane\nslate 12\n").findings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_word_list_unknown_header_warns() {
+        let list = parse_word_list("#! name: X\n#! author: me\nab", false).unwrap();
+        assert_eq!(list.warnings, vec!["line 2: unknown header `author`"]);
+        assert_eq!(list.words, vec!["ab"]);
+    }
+
+    #[test]
+    fn test_parse_word_list_malformed_header() {
+        assert_eq!(
+            parse_word_list("#! name: X\n#! alphabet", false).unwrap_err(),
+            HeaderError::Malformed { line: 2 }
+        );
+        assert_eq!(
+            parse_word_list("#! : X", false).unwrap_err(),
+            HeaderError::Malformed { line: 1 }
+        );
+        assert_eq!(
+            parse_word_list("#! name:", false).unwrap_err(),
+            HeaderError::EmptyValue {
+                line: 1,
+                key: "name".to_string()
+            }
+        );
+        assert_eq!(
+            parse_word_list("#! name: X\n\n#! alphabet: z-a", false)
+                .unwrap()
+                .words,
+            vec!["#! alphabet: z-a"]
+        );
+        assert_eq!(
+            parse_word_list("#! name: X\n#! alphabet: z-a", false).unwrap_err(),
+            HeaderError::InvalidRange {
+                line: 2,
+                range: "z-a".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_alphabet_inferred_from_words() {
+        let alphabet = Alphabet::from_words(["bar", "öl"]);
+        assert_eq!(alphabet.letters, vec!['a', 'b', 'l', 'r', 'ö']);
+        assert!(!alphabet.declared);
+    }
+
+    #[test]
+    fn test_alphabet_keyboard_rows() {
+        let alphabet = Alphabet::parse("a-z äöüß", 1).unwrap();
+        let rows = alphabet.keyboard_rows(KeyboardLayout::Qwerty, 80);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].iter().collect::<String>(), "qwertyuiop");
+        assert_eq!(rows[3], vec!['ä', 'ö', 'ü', 'ß']);
+
+        let alphabet = Alphabet::parse("a b c", 1).unwrap();
+        assert_eq!(
+            alphabet.keyboard_rows(KeyboardLayout::Qwerty, 80),
+            vec![vec!['a'], vec!['c', 'b']]
+        );
+        assert_eq!(
+            alphabet.keyboard_rows(KeyboardLayout::Azerty, 80),
+            vec![vec!['a'], vec!['c', 'b']]
+        );
+
+        let alphabet = Alphabet::parse("а-я", 1).unwrap();
+        let rows = alphabet.keyboard_rows(KeyboardLayout::Qwerty, 80);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], 'а');
+        // Greek does not fit the latin layouts either
+        let alphabet = Alphabet::from_words(["αβγδε", "ζηθικ"]);
+        let rows = alphabet.keyboard_rows(KeyboardLayout::Qwerty, 80);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0], alphabet.letters);
+    }
+
+    #[test]
+    fn test_balanced_rows() {
+        let lengths = |letters: &[char], max_width| -> Vec<usize> {
+            balanced_rows(letters, max_width)
+                .iter()
+                .map(Vec::len)
+                .collect()
+        };
+        let latin: Vec<char> = ('a'..='z').collect();
+        assert_eq!(lengths(&latin, 80), [9, 9, 8]);
+        // the Russian alphabet, with ё after е
+        let cyrillic: Vec<char> = "абвгдеёжзийклмнопрстуфхцчшщъыьэюя".chars().collect();
+        assert_eq!(lengths(&cyrillic, 80), [11, 11, 11]);
+        let rows = balanced_rows(&cyrillic, 80);
+        assert_eq!(rows[0].iter().collect::<String>(), "абвгдеёжзий");
+        assert_eq!(rows[2].iter().collect::<String>(), "хцчшщъыьэюя");
+        assert_eq!(lengths(&latin[..6], 80), [6]);
+        // narrow terminals get more and shorter rows, which still fit
+        assert_eq!(lengths(&cyrillic, 16), [5, 5, 5, 5, 5, 4, 4]);
+        for (y, row) in balanced_rows(&cyrillic, 16).iter().enumerate() {
+            assert!(y + row.len() * 2 <= 16);
+        }
+        // too narrow for any row, one key each
+        assert_eq!(lengths(&latin[..6], 1), [1; 6]);
+        assert!(balanced_rows(&[], 80).is_empty());
+    }
+
+    #[test]
+    fn test_parse_tagged_word_list() {
+        let list = parse_word_list(
+            "crane #easy\nxylem 12 #Hard #science\nslate\n#dash\nc#sharp\n",
+            false,
+        )
+        .unwrap();
+        assert_eq!(list.words, ["crane", "xylem", "slate", "#dash", "c#sharp"]);
+        assert_eq!(list.frequencies["xylem"], 12);
+        assert_eq!(list.tags.len(), 2);
+        assert_eq!(
+            list.tags["xylem"].iter().collect::<Vec<_>>(),
+            ["hard", "science"]
+        );
+
+        let mut list = merge_word_lists(vec![from_parsed("tags", list)], false);
+        let filter = |filter: &str, any| TagFilter {
+            any,
+            ..filter.parse().unwrap()
+        };
+        list.retain_tagged(&filter("science,#easy", true));
+        assert_eq!(list.solutions, ["crane", "xylem"]);
+        list.retain_tagged(&filter("Science,hard", false));
+        assert_eq!(list.solutions, ["xylem"]);
+        // tags do not matter for guesses
+        assert_eq!(list.words.len(), 5);
+        assert!("easy,".parse::<TagFilter>().is_err());
+    }
+
+    #[test]
+    fn test_retain_length() {
+        let mut list = merge_word_lists(
+            vec![word_list(&["abc", "abcd"], ListHeader::default())],
+            false,
+        );
+        list.retain_length(4);
+        assert_eq!(list.solutions, vec!["abcd"]);
+        assert_eq!(list.words, vec!["abcd"]);
+    }
+
+    #[test]
+    fn test_keyboard_layouts() {
+        let alphabet = Alphabet::parse("a-z", 1).unwrap();
+        let rows = alphabet.keyboard_rows("qwertz".parse().unwrap(), 80);
+        assert_eq!(rows[2].iter().collect::<String>(), "yxcvbnm");
+        let rows = alphabet.keyboard_rows("azerty".parse().unwrap(), 80);
+        assert_eq!(rows[1].iter().collect::<String>(), "qsdfghjklm");
+        assert!("dvorak".parse::<KeyboardLayout>().is_err());
+    }
+
+    fn word_list(words: &[&str], header: ListHeader) -> MergedWordList {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        MergedWordList {
+            header,
+            solutions: words.clone(),
+            words,
+            ..MergedWordList::default()
+        }
+    }
+
+    /// A source of words in memory.
+    struct TestSource(MergedWordList);
+
+    impl WordSource for TestSource {
+        fn name(&self) -> String {
+            "test".to_string()
+        }
+
+        fn load(&self) -> Result<MergedWordList, WordFileError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_merge_word_lists() {
+        let mut lists = vec![
+            word_list(&["hello", "world"], ListHeader::default()),
+            word_list(&["world", "jolly"], ListHeader::default()),
+        ];
+        lists[0].frequencies.insert("world".to_string(), 9);
+        lists[1].frequencies.insert("world".to_string(), 1);
+        lists[1].frequencies.insert("jolly".to_string(), 2);
+        let merged = merge_word_lists(lists, false);
+        assert_eq!(merged.solutions, vec!["hello", "world"]);
+        assert_eq!(merged.words, vec!["hello", "world", "jolly"]);
+        // the first list giving a frequency wins
+        assert_eq!(merged.frequencies["world"], 9);
+        assert_eq!(merged.frequencies["jolly"], 2);
+    }
+
+    #[test]
+    fn test_remove_plural_solutions() {
+        let mut merged = merge_word_lists(
+            vec![word_list(
+                &[
+                    "glass", "cats", "cat", "lens", "dogs", "bus", "class", "clas",
+                ],
+                ListHeader::default(),
+            )],
+            false,
+        );
+        merged.remove_plural_solutions();
+        assert_eq!(
+            merged.solutions,
+            ["glass", "cat", "lens", "dogs", "bus", "class", "clas"]
+        );
+        // plurals can still be guessed
+        assert!(merged.words.contains(&"cats".to_string()));
+    }
+
+    #[test]
+    fn test_rarity() {
+        let mut merged = merge_word_lists(
+            vec![word_list(
+                &["hello", "jolly", "cello", "jello", "below", "cat"],
+                ListHeader::default(),
+            )],
+            false,
+        );
+        assert_eq!(merged.rarity("hello"), Rarity::Unknown);
+        for (word, frequency) in [
+            ("hello", 900),
+            ("jolly", 50),
+            ("cello", 40),
+            ("below", 1000),
+        ] {
+            merged.frequencies.insert(word.to_string(), frequency);
+        }
+        // `jello` has no frequency and counts as the rarest, `cat` is too short
+        assert_eq!(
+            merged.rarity("jolly"),
+            Rarity::Percentile {
+                commoner: 0.5,
+                rarer: 0.5
+            }
+        );
+        assert_eq!(
+            merged.rarity("below"),
+            Rarity::Percentile {
+                commoner: 0.0,
+                rarer: 1.0
+            }
+        );
+        assert_eq!(merged.rarity("jello"), Rarity::Unlisted);
+    }
+
+    #[test]
+    fn test_merge_word_lists_solutions_from_all() {
+        let lists = vec![
+            word_list(&["hello", "world"], ListHeader::default()),
+            word_list(&["world", "jolly"], ListHeader::default()),
+        ];
+        let merged = merge_word_lists(lists, true);
+        assert_eq!(merged.solutions, vec!["hello", "world", "jolly"]);
+        assert_eq!(merged.words, merged.solutions);
+    }
+
+    #[test]
+    fn test_merge_word_lists_headers() {
+        let lists = vec![
+            word_list(&["hello"], ListHeader::default()),
+            word_list(
+                &["hallo"],
+                ListHeader {
+                    name: Some("Deutsch".to_string()),
+                    alphabet: Some(Alphabet::parse("a-c ä", 1).unwrap()),
+                    digraphs: None,
+                },
+            ),
+            word_list(
+                &["bär"],
+                ListHeader {
+                    name: Some("Other".to_string()),
+                    alphabet: Some(Alphabet::parse("a b ß", 1).unwrap()),
+                    digraphs: None,
+                },
+            ),
+        ];
+        let merged = merge_word_lists(lists, false);
+        assert_eq!(merged.header.name, Some("Deutsch".to_string()));
+        assert_eq!(
+            merged.header.alphabet.unwrap().letters,
+            vec!['a', 'b', 'c', 'ä', 'ß']
+        );
+    }
+
+    #[test]
+    fn test_load_sources() {
+        let mut guesses = word_list(&["hello", "jolly", "milli"], ListHeader::default());
+        guesses.solutions = vec!["jolly".to_string()];
+        let sources: Vec<Box<dyn WordSource>> = vec![
+            Box::new(TestSource(word_list(&["hello"], ListHeader::default()))),
+            Box::new(TestSource(guesses)),
+        ];
+        let merged = load_sources(&sources, false).unwrap();
+        assert_eq!(merged.solutions, vec!["hello"]);
+        assert_eq!(merged.words, vec!["hello", "jolly", "milli"]);
+        // only words marked as solutions by their source become solutions
+        let merged = load_sources(&sources, true).unwrap();
+        assert_eq!(merged.solutions, vec!["hello", "jolly"]);
+    }
+
+    #[test]
+    fn test_file_source_error_names_file() {
+        let source = word_file_source("does/not/exist.txt", false);
+        let error = source.load().err().unwrap();
+        assert!(error.to_string().starts_with("does/not/exist.txt: "));
+    }
+
+    #[test]
+    fn test_inline_source() {
+        let source = |words: &str| {
+            InlineSource {
+                words: words.to_string(),
+            }
+            .load()
+        };
+        let list = source("Apple, bread,crane,apple").unwrap();
+        assert_eq!(list.solutions, ["apple", "bread", "crane"]);
+        assert_eq!(list.words, list.solutions);
+        assert_eq!(source("maße,äste").unwrap().words, ["maße", "äste"]);
+        let error = |words| source(words).err().unwrap().to_string();
+        assert_eq!(error("apple,,crane"), "--words: entry 2 `` is empty");
+        assert_eq!(
+            error("apple,cr4ne"),
+            "--words: entry 2 `cr4ne` contains `4`, which is not a letter"
+        );
+        assert_eq!(
+            error("apple, pear"),
+            "--words: entry 2 ` pear` has 4 letters, the first word has 5"
+        );
+    }
+
+    #[test]
+    fn test_named_source() {
+        let mut config = Config::parse(
+            "[lists]\nexample = { file = \"example_word_list.txt\", length = 5 }",
+            "c",
+        )
+        .unwrap();
+        assert!(config.validate().is_empty());
+        let list = named_source("example", &config).unwrap().load().unwrap();
+        assert_eq!(list.words, vec!["katze"]);
+        assert_eq!(list.solutions, vec!["katze"]);
+        let english = named_source("english", &config);
+        if cfg!(feature = "bundled-words") {
+            assert!(english
+                .unwrap()
+                .load()
+                .unwrap()
+                .words
+                .contains(&"cigar".to_string()));
+        } else {
+            assert!(english.is_err());
+        }
+    }
+
+    #[test]
+    fn test_named_source_unknown() {
+        let config = Config::parse("[lists]\nexample = \"example_word_list.txt\"", "c").unwrap();
+        let error = named_source("german", &config).err().unwrap();
+        let available = if cfg!(feature = "bundled-words") {
+            "english, example"
+        } else {
+            "example"
+        };
+        assert_eq!(
+            error.to_string(),
+            format!("unknown list `german`, available lists: {}", available)
+        );
+        let error = named_source("german", &Config::default()).err().unwrap();
+        if BUILTIN_LISTS.is_empty() {
+            assert_eq!(
+                error.to_string(),
+                "unknown list `german`, no lists are available"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "bundled-words"))]
+    fn test_without_bundled_words() {
+        assert!(BUILTIN_LISTS.is_empty());
+        assert!(embedded::WORDS.is_empty());
+        assert_eq!(embedded::gloss("react"), None);
+        assert_eq!(
+            EmbeddedSource.load().err().unwrap().to_string(),
+            "this build has no bundled word list, use --word-file or --list"
+        );
+    }
+
+    const MESSY_WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/messy_words.txt");
+
+    #[test]
+    fn test_right_to_left_alphabet() {
+        let list = parse_word_list(include_str!("../tests/data/hebrew_words.txt"), false).unwrap();
+        let alphabet = Alphabet::from_words(list.words.iter().map(String::as_str));
+        assert!(alphabet.is_right_to_left());
+        assert!(!Alphabet::from_words(["hello", "jolly"]).is_right_to_left());
+        assert!(Alphabet::from_words(["كتاب"]).is_right_to_left());
+        // a few latin letters do not turn a list around
+        assert!(!Alphabet::from_words(["hello", "שלום"]).is_right_to_left());
+    }
+
+    #[test]
+    fn test_is_clean_word() {
+        for word in ["apple", "étude", "x-ray", "zoo"] {
+            assert!(is_clean_word(word), "{}", word);
+        }
+        for word in [
+            "Paris", "NASA", "iPhone", "o'clock", "don’t", "etc.", "a", "I",
+        ] {
+            assert!(!is_clean_word(word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_clean_file_source() {
+        let source = |clean| FileSource {
+            path: MESSY_WORDS.to_string(),
+            length: None,
+            clean,
+        };
+        let list = source(true).load().unwrap();
+        assert_eq!(list.words, ["apple", "zoo", "x-ray", "étude", "crane"]);
+        assert_eq!(list.frequencies["crane"], 120);
+        assert_eq!(
+            list.warnings,
+            [format!("{}: cleaning removed 10 entries", MESSY_WORDS)]
+        );
+        // without cleaning everything is lowercased and kept
+        let list = source(false).load().unwrap();
+        assert_eq!(list.words.len(), 15);
+        assert!(list.words.contains(&"paris".to_string()));
+        assert!(list.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_system_dict_skips_names_and_abbreviations() {
+        let path = MESSY_WORDS.to_string();
+        let list = SystemDictSource { path }.load().unwrap();
+        // hyphens are skipped too, as well as the frequency
+        assert_eq!(list.words, vec!["apple", "zoo", "étude"]);
+        assert_eq!(list.solutions, list.words);
+        assert_eq!(
+            list.warnings,
+            [format!("{}: cleaning removed 12 entries", MESSY_WORDS)]
+        );
+    }
+
+    #[test]
+    fn test_url_source() {
+        use std::net::TcpListener;
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/words.txt", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            for status in ["200 OK", "404 Not Found"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut lines = BufReader::new(&stream).lines();
+                assert_eq!(lines.next().unwrap().unwrap(), "GET /words.txt HTTP/1.0");
+                // closing the connection with unread request headers resets it
+                for line in lines {
+                    if line.unwrap().is_empty() {
+                        break;
+                    }
+                }
+                write!(stream, "HTTP/1.0 {}\r\n\r\nHello\nworld\n", status).unwrap();
+            }
+        });
+        let source = word_file_source(&url, false);
+        assert_eq!(source.load().unwrap().words, vec!["hello", "world"]);
+        let error = source.load().err().unwrap();
+        assert!(error
+            .to_string()
+            .ends_with("the server answered `HTTP/1.0 404 Not Found`"));
+        server.join().unwrap();
+
+        let error = word_file_source("https://example.com/words.txt", false)
+            .load()
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("https is not supported"));
+    }
+
+    fn temp_word_file(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("rordle-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_stream_word_files_matches_in_memory_loading() {
+        let big = temp_word_file(
+            "stream-big.txt",
+            "#! name: Big\nHello\nworld\nab\njolly\n\nabc\n",
+        );
+        let small = temp_word_file("stream-small.txt", "hello\nmilli\nxy\n");
+        let files = [big.as_str(), small.as_str()];
+        let sources: Vec<Box<dyn WordSource>> =
+            files.iter().map(|f| word_file_source(f, false)).collect();
+        let in_memory = load_sources(&sources, false).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let streamed = stream_word_files(&files, false, &mut rng).unwrap();
+            assert_eq!(streamed.header, in_memory.header);
+            assert_eq!(streamed.solutions.len(), 1);
+            let solution = &streamed.solutions[0];
+            assert!(in_memory.solutions.contains(solution));
+            let length = solution.chars().count();
+            let expected: Vec<&String> = in_memory
+                .words
+                .iter()
+                .filter(|w| w.chars().count() == length)
+                .collect();
+            assert_eq!(streamed.words.iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_stream_word_files_samples_all_solutions() {
+        let file = temp_word_file("stream-sample.txt", "aa\nbb\ncc\n");
+        let extra = temp_word_file("stream-sample-extra.txt", "dd\n");
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let mut picked = HashSet::new();
+        for _ in 0..100 {
+            let list = stream_word_files(&[&file, &extra], false, &mut rng).unwrap();
+            picked.insert(list.solutions[0].clone());
+        }
+        assert_eq!(picked.len(), 3);
+        assert!(!picked.contains("dd"));
+        for _ in 0..100 {
+            let list = stream_word_files(&[&file, &extra], true, &mut rng).unwrap();
+            picked.insert(list.solutions[0].clone());
+        }
+        assert!(picked.contains("dd"));
+    }
+
+    #[test]
+    fn test_stream_word_file_error_names_file() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let error = stream_word_files(&["does/not/exist.txt"], false, &mut rng)
+            .err()
+            .unwrap();
+        assert!(error.to_string().starts_with("does/not/exist.txt: "));
+    }
+
+    #[test]
+    fn test_word_store() {
+        let store: WordStore = ["jolly", "hällö", "hello", "ab", "jolly"].iter().collect();
+        assert_eq!(store.len(), 4);
+        assert_eq!(
+            store.iter().collect::<Vec<_>>(),
+            vec!["ab", "hello", "hällö", "jolly"]
+        );
+        assert_eq!(store.get(2), "hällö");
+        for word in ["ab", "hello", "hällö", "jolly"] {
+            assert!(store.contains(word));
+        }
+        for word in ["", "a", "hallo", "jolly ", "zzz"] {
+            assert!(!store.contains(word));
+        }
+    }
+
+    #[test]
+    fn test_word_store_empty() {
+        let store: WordStore = Vec::<String>::new().into_iter().collect();
+        assert_eq!(store.len(), 0);
+        assert!(!store.contains("hello"));
+        assert_eq!(store.iter().count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-words")]
+    fn test_embedded_words() {
+        // the first solutions of the original Wordle
+        assert_eq!(embedded::SOLUTIONS[..3], ["cigar", "rebut", "sissy"]);
+        assert_eq!(embedded::WORDS[..3], embedded::SOLUTIONS[..3]);
+        assert_eq!(
+            embedded::gloss("react"),
+            Some("to act in response to something")
+        );
+        assert_eq!(embedded::gloss("zzzzz"), None);
+    }
+
+    /// Compares the generated slices against parsing the embedded files at
+    /// startup, run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    #[cfg(feature = "bundled-words")]
+    fn bench_embedded_source() {
+        use std::time::Instant;
+        let parse = || {
+            let solutions: Vec<String> = include_str!("../data/picked_words.txt")
+                .lines()
+                .map(|line| line.to_lowercase())
+                .collect();
+            let mut words = solutions.clone();
+            for line in include_str!("../data/valid_words.txt").lines() {
+                words.push(line.to_lowercase());
+            }
+            (solutions, words)
+        };
+        assert_eq!(parse(), {
+            let list = EmbeddedSource.load().unwrap();
+            (list.solutions, list.words)
+        });
+        let start = Instant::now();
+        for _ in 0..100 {
+            assert_eq!(parse().1.len(), embedded::WORDS.len());
+        }
+        let parsed = start.elapsed() / 100;
+        let start = Instant::now();
+        for _ in 0..100 {
+            assert_eq!(
+                EmbeddedSource.load().unwrap().words.len(),
+                embedded::WORDS.len()
+            );
+        }
+        let loaded = start.elapsed() / 100;
+        println!("parse the files:  {:?}", parsed);
+        println!("load the slices:  {:?}", loaded);
+    }
+
+    /// Compares the word store against a `HashSet<String>` on the built-in
+    /// list, run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_word_store() {
+        use std::time::Instant;
+        let words = EmbeddedSource.load().unwrap().words;
+        let lookups: Vec<&String> = words.iter().step_by(7).collect();
+
+        let start = Instant::now();
+        let set: HashSet<String> = words.iter().cloned().collect();
+        let build_set = start.elapsed();
+        let start = Instant::now();
+        let store: WordStore = words.iter().collect();
+        let build_store = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..100 {
+            assert!(lookups.iter().all(|w| set.contains(*w)));
+        }
+        let lookup_set = start.elapsed();
+        let start = Instant::now();
+        for _ in 0..100 {
+            assert!(lookups.iter().all(|w| store.contains(w)));
+        }
+        let lookup_store = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..100 {
+            assert!(set.iter().filter(|w| w.starts_with('s')).count() > 0);
+        }
+        let iter_set = start.elapsed();
+        let start = Instant::now();
+        for _ in 0..100 {
+            assert!(store.iter().filter(|w| w.starts_with('s')).count() > 0);
+        }
+        let iter_store = start.elapsed();
+
+        // heap usage: a String header plus its buffer per word, ignoring the
+        // hash table's own overhead
+        let set_bytes: usize = set
+            .iter()
+            .map(|w| std::mem::size_of::<String>() + w.capacity())
+            .sum();
+        let store_bytes =
+            store.text.capacity() + store.ends.capacity() * std::mem::size_of::<usize>();
+        println!("words:  {}", words.len());
+        println!("memory: set {} B, store {} B", set_bytes, store_bytes);
+        println!("build:  set {:?}, store {:?}", build_set, build_store);
+        println!("lookup: set {:?}, store {:?}", lookup_set, lookup_store);
+        println!("iter:   set {:?}, store {:?}", iter_set, iter_store);
+    }
+}
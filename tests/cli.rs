@@ -0,0 +1,1358 @@
+use std::process::Command;
+use std::sync::Mutex;
+
+/// The config and data directory of the running test, so no test sees what
+/// another saved. It is emptied the first time the test uses it.
+fn test_home() -> std::path::PathBuf {
+    static EMPTIED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    let name = std::thread::current()
+        .name()
+        .unwrap_or("main")
+        .replace("::", "-");
+    let dir = std::env::temp_dir().join(format!("rordle-cli-test-{}", name));
+    let mut emptied = EMPTIED.lock().unwrap();
+    if !emptied.contains(&name) {
+        let _ = std::fs::remove_dir_all(&dir);
+        emptied.push(name);
+    }
+    dir
+}
+
+fn rordle(args: &[&str]) -> std::process::Output {
+    // keep a config file of the user out of the tests
+    rordle_with_config(&test_home(), args)
+}
+
+fn rordle_with_config(config_home: &std::path::Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_rordle"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", config_home)
+        .env("XDG_DATA_HOME", config_home)
+        // messages are in English unless a test selects another language
+        .env("LC_ALL", "C")
+        .output()
+        .unwrap()
+}
+
+/// Runs rordle with `input` piped to stdin.
+fn rordle_with_input(args: &[&str], input: &str) -> std::process::Output {
+    rordle_with_config_and_input(&test_home(), args, input)
+}
+
+fn rordle_with_config_and_input(
+    config_home: &std::path::Path,
+    args: &[&str],
+    input: &str,
+) -> std::process::Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rordle"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", config_home)
+        .env("XDG_DATA_HOME", config_home)
+        .env("LC_ALL", "C")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    // rordle may exit with an error before reading its input
+    let _ = std::io::Write::write_all(&mut child.stdin.take().unwrap(), input.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn temp_config_home(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("rordle-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_missing_word_file() {
+    let output = rordle(&["--word-file", "does/not/exist.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "rordle: does/not/exist.txt: No such file or directory (os error 2)\n"
+    );
+}
+
+#[test]
+fn test_empty_word_list() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-empty-{}.txt", std::process::id()));
+    std::fs::write(&path, "#! name: Empty\n\n").unwrap();
+    let output = rordle(&["--word-file", &path.to_string_lossy()]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "rordle: the word list contains no words\n"
+    );
+}
+
+#[test]
+fn test_config_path_and_init() {
+    let home = temp_config_home("init");
+    let path = home.join("rordle").join("config.toml");
+    let output = rordle_with_config(&home, &["config", "--path"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{}\n", path.display())
+    );
+
+    let output = rordle_with_config(&home, &["config", "--init"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .starts_with("# Configuration of rordle."));
+    // an existing config file is not overwritten
+    let output = rordle_with_config(&home, &["config", "--init"]);
+    assert_eq!(output.status.code(), Some(2));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_broken_config_falls_back_to_defaults() {
+    let home = temp_config_home("broken");
+    std::fs::create_dir_all(home.join("rordle")).unwrap();
+    let path = home.join("rordle").join("config.toml");
+    std::fs::write(&path, "bell = true\ntheme = \"pink\"\n").unwrap();
+    let output = rordle_with_config(&home, &["list-words"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "warning: {}:2: unknown theme `pink`, expected one of classic, high-contrast, monochrome, using the default config\n",
+            path.display()
+        )
+    );
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .any(|word| word == "cigar"));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_default_list_from_config() {
+    let home = temp_config_home("default-list");
+    std::fs::create_dir_all(home.join("rordle")).unwrap();
+    let words = home.join("words.txt");
+    std::fs::write(&words, "hello\njolly\n").unwrap();
+    std::fs::write(
+        home.join("rordle").join("config.toml"),
+        format!(
+            "default_list = \"mine\"\n[lists]\nmine = \"{}\"\n",
+            words.display()
+        ),
+    )
+    .unwrap();
+    let output = rordle_with_config(&home, &["list-words"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "hello\njolly\n");
+    // --list takes precedence over the config file
+    let output = rordle_with_config(&home, &["list-words", "--list", "english"]);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .any(|word| word == "cigar"));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_missing_list_keeps_config() {
+    let home = temp_config_home("missing-list");
+    std::fs::create_dir_all(home.join("rordle")).unwrap();
+    let words = home.join("words.txt");
+    std::fs::write(&words, "hello\n").unwrap();
+    let stats = home.join("kept").join("stats");
+    std::fs::write(
+        home.join("rordle").join("config.toml"),
+        format!(
+            "stats_path = \"{}\"\n[lists]\ngone = \"{}\"\n",
+            stats.display(),
+            home.join("gone.txt").display()
+        ),
+    )
+    .unwrap();
+    let path = words.to_string_lossy();
+    let output = rordle_with_config_and_input(&home, &["--stdin", "-w", &path], "hello\n");
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("warning: "));
+    assert!(stderr.contains("list `gone` refers to missing file"));
+    assert!(stderr.trim_end().ends_with("list ignored"));
+    // the stats still go where the config says
+    assert!(std::fs::read_to_string(&stats)
+        .unwrap()
+        .contains("\thello\t"));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_completions() {
+    let home = temp_config_home("completions");
+    std::fs::create_dir_all(home.join("rordle")).unwrap();
+    let words = home.join("words.txt");
+    std::fs::write(&words, "hallo\n").unwrap();
+    std::fs::write(
+        home.join("rordle").join("config.toml"),
+        format!(
+            "[lists]\ngerman = \"{0}\"\nenglish = \"{0}\"\n",
+            words.display()
+        ),
+    )
+    .unwrap();
+    let output = rordle_with_config(&home, &["__complete", "lists"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "english\ngerman\n"
+    );
+    for shell in ["bash", "zsh", "fish"] {
+        let output = rordle(&["completions", shell]);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("rordle __complete lists"));
+    }
+    assert!(!rordle(&["completions", "tcsh"]).status.success());
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_word_file_from_environment() {
+    let home = temp_config_home("env");
+    std::fs::create_dir_all(home.join("rordle")).unwrap();
+    let config_words = home.join("config-words.txt");
+    std::fs::write(&config_words, "hello\n").unwrap();
+    let env_words = home.join("env-words.txt");
+    std::fs::write(&env_words, "jolly\n").unwrap();
+    std::fs::write(
+        home.join("rordle").join("config.toml"),
+        format!(
+            "default_list = \"mine\"\n[lists]\nmine = \"{}\"\n",
+            config_words.display()
+        ),
+    )
+    .unwrap();
+    let list_words = |args: &[&str], env: Option<&std::path::Path>| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_rordle"));
+        command
+            .arg("list-words")
+            .args(args)
+            .env("XDG_CONFIG_HOME", &home)
+            .env_remove("RORDLE_WORD_FILE");
+        if let Some(file) = env {
+            command.env("RORDLE_WORD_FILE", file);
+        }
+        String::from_utf8(command.output().unwrap().stdout).unwrap()
+    };
+    assert_eq!(list_words(&[], None), "hello\n");
+    assert_eq!(list_words(&[], Some(&env_words)), "jolly\n");
+    assert_eq!(
+        list_words(&["-w", &config_words.to_string_lossy()], Some(&env_words)),
+        "hello\n"
+    );
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_invalid_environment_variable() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rordle"))
+        .args(["list-words"])
+        .env("RORDLE_HARD", "maybe")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("rordle: RORDLE_HARD: expected one of"));
+}
+
+#[test]
+fn test_stats_and_history() {
+    let home = temp_config_home("stats");
+    std::fs::create_dir_all(home.join("rordle")).unwrap();
+    std::fs::write(
+        home.join("rordle").join("stats"),
+        "1645747200\tdaily\tcigar\tcrane,cigar\twon\n1645833600\tplay\trebut\tcrane\tlost\n",
+    )
+    .unwrap();
+    let output = rordle_with_config(&home, &["stats"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Played:         2\nWin %:          50\n"));
+
+    let output = rordle_with_config(&home, &["history", "--limit", "1"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2022-02-26  play   rebut  lost\n"
+    );
+
+    let output = rordle_with_config(&home, &["stats", "--openers"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Opener  Games  Win %  Tries after\n\
+         crane?      2     50          1.0\n\
+         ? fewer than 5 games, or tied with a neighbour\n"
+    );
+
+    // the b, u and t of rebut are missed, the r and e found by crane
+    let output = rordle_with_config(&home, &["stats", "--letters"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 11);
+    assert_eq!(lines[0], "Letter  Seen  Found by guess 3  Miss %");
+    assert_eq!(lines[1], "b?         1                 0     100");
+    assert_eq!(lines[4], "r?         2                 2       0");
+    assert_eq!(lines[10], "? in fewer than 5 solutions");
+    let output = rordle_with_config(&home, &["stats", "--letters", "--format", "csv"]);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("letter,seen,found,miss_rate,low_confidence\nb,1,0,1,true\n"));
+
+    let output = rordle_with_config(&home, &["stats", "--format", "json"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "{\"played\":2,\"won\":1,\"win_percentage\":50,\"current_streak\":0,\
+         \"max_streak\":1,\"distribution\":[0,1],\"count_hints\":0,\"answers_only\":0}\n"
+    );
+    let output = rordle_with_config(&home, &["history", "--format", "csv"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "timestamp,mode,word,guesses,won,puzzle\n\
+         1645833600,play,rebut,crane,false,\n\
+         1645747200,daily,cigar,\"crane,cigar\",true,\n"
+    );
+    let output = rordle_with_config(&home, &["history", "-n", "1", "--format", "json"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "[{\"timestamp\":1645833600,\"mode\":\"play\",\"word\":\"rebut\",\
+         \"guesses\":[\"crane\"],\"won\":false,\"puzzle\":null}]\n"
+    );
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_status() {
+    let home = temp_config_home("status");
+    std::fs::create_dir_all(home.join("rordle")).unwrap();
+    let status = |records: &str, quiet: bool| {
+        std::fs::write(home.join("rordle").join("stats"), records).unwrap();
+        let mut args = vec!["status", "--daily-epoch", "wordle", "--date", "2022-02-25"];
+        if quiet {
+            args.push("--quiet");
+        }
+        let output = rordle_with_config(&home, &args);
+        (
+            output.status.code(),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    };
+    // a game of the day which is not the daily puzzle does not count
+    let not_played = "1645790400\tplay\tcigar\tcigar\twon\n";
+    let won = "1645790400\tdaily\tcigar\tcrane,rebut,sissy,cigar\twon\twordle:251\n";
+    let lost = "1645790400\tdaily\tcigar\tcrane\tlost\twordle:251\n";
+    assert_eq!(
+        status(not_played, false),
+        (Some(0), "Wordle #251: not played\n".to_string())
+    );
+    assert_eq!(
+        status(not_played, true),
+        (Some(0), "Wordle #251: not played\n".to_string())
+    );
+    assert_eq!(
+        status(won, false),
+        (Some(0), "Wordle #251: ✓ 4/6\n".to_string())
+    );
+    assert_eq!(
+        status(lost, false),
+        (Some(0), "Wordle #251: ✗\n".to_string())
+    );
+    assert_eq!(status(won, true), (Some(1), String::new()));
+    assert_eq!(status(lost, true), (Some(1), String::new()));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_validate_wordlist() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-lint-{}.txt", std::process::id()));
+    std::fs::write(&path, "crane\nslate 12\ncrane\n").unwrap();
+    let file = path.to_string_lossy();
+    let output = rordle(&["validate-wordlist", &file]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "{}:3: warning: duplicate of line 1\n5 letters: 2\n0 errors, 1 warnings\n",
+            file
+        )
+    );
+    std::fs::write(&path, "crane\nx-ray\n").unwrap();
+    let output = rordle(&["validate-wordlist", &file]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with(&format!("{}:2: error: `-` is not a letter\n", file)));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_count_hints() {
+    let home = temp_config_home("count-hints");
+    let path = home.join("words.txt");
+    std::fs::create_dir_all(&home).unwrap();
+    std::fs::write(&path, "hello\nlolly\n").unwrap();
+    let path = path.to_string_lossy();
+    let output = rordle_with_config_and_input(
+        &home,
+        &["--stdin", "-w", &path, "--number", "1", "--count-hints"],
+        "lolly\nhello\n",
+    );
+    // both l of hello are hit, the first l of lolly is one too many
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("lolly xygg-\nhello ggggg\n"));
+    let line = std::fs::read_to_string(home.join("rordle").join("stats")).unwrap();
+    assert!(line.ends_with("\tcounted\n"));
+    let output = rordle_with_config(&home, &["history"]);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .ends_with("hello  won in 2  count hints\n"));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_answers_only() {
+    let home = temp_config_home("answers-only");
+    let path = home.join("words.txt");
+    std::fs::create_dir_all(&home).unwrap();
+    std::fs::write(&path, "hello #daily\nouija\njolly #daily\n").unwrap();
+    let path = path.to_string_lossy();
+    let args = ["--stdin", "-w", &path, "--tags", "daily"];
+    // ouija is a valid guess, but never the solution
+    let output = rordle_with_input(&args, "ouija\nhello\njolly\n");
+    assert!(!String::from_utf8(output.stdout).unwrap().contains("error"));
+    let args = [&args[..], &["--number", "1", "--answers-only"]].concat();
+    let output = rordle_with_config_and_input(&home, &args, "ouija\njolly\nhello\n");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("error: Word must be a possible answer\njolly -ygg-\nhello ggggg\n"));
+    let line = std::fs::read_to_string(home.join("rordle").join("stats")).unwrap();
+    assert!(line.ends_with("\tanswers-only\n"));
+    let output = rordle_with_config(&home, &["history"]);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .ends_with("hello  won in 2  answers only\n"));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_history_show_times() {
+    let home = temp_config_home("times");
+    let path = std::env::temp_dir().join(format!("rordle-cli-times-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\njolly\n").unwrap();
+    let path = path.to_string_lossy();
+    rordle_with_config_and_input(
+        &home,
+        &["--stdin", "-w", &path, "--number", "1"],
+        "jolly\nhello\n",
+    );
+    // the times are recorded without the timer being shown
+    let stats = home.join("rordle").join("stats");
+    let line = std::fs::read_to_string(&stats).unwrap();
+    let fields: Vec<&str> = line.trim_end().split('\t').collect();
+    assert_eq!(fields.len(), 7);
+    assert_eq!(fields[6].split(',').count(), 2);
+
+    std::fs::write(
+        &stats,
+        "1645747200\tplay\tcigar\tcrane,rebut,cigar\twon\t\t12345,-,65000\n\
+         1645833600\tplay\trebut\tcrane\tlost\n",
+    )
+    .unwrap();
+    let output = rordle_with_config(&home, &["history", "--show-times"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2022-02-26  play   rebut  lost\n\
+         2022-02-25  play   cigar  won in 3  12.3s - 1m05s = 1m17s\n"
+    );
+    let output = rordle_with_config(&home, &["history", "--show-times", "--format", "csv"]);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .ends_with(",true,,\"12345,,65000\"\n"));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_stats_weekly() {
+    let home = temp_config_home("weekly");
+    std::fs::create_dir_all(home.join("rordle")).unwrap();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    // two games this week, one ten days ago
+    std::fs::write(
+        home.join("rordle").join("stats"),
+        format!(
+            "{}\tplay\tcigar\tcrane,cigar\twon\n\
+             {}\tdaily\trebut\tcrane,rebus,rebut\twon\n\
+             {}\tplay\tsissy\tcrane\tlost\n",
+            now - 10 * 86400,
+            now - 60,
+            now - 30
+        ),
+    )
+    .unwrap();
+    let output = rordle_with_config(&home, &["stats", "--weekly"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Games:        2 (+1)\n"));
+    assert!(stdout.contains("Won:          1 (+0)\n"));
+    assert!(stdout.contains("Dailies:      1\n"));
+    assert!(stdout.contains("Avg. guesses: 3.0 (+1.0)\n"));
+    assert!(stdout.contains("Best:         rebut in 3\nWorst:        sissy, lost\n"));
+
+    let output = rordle_with_config(&home, &["stats", "--weekly", "--format", "json"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(
+        "\"played\":2,\"won\":1,\"dailies\":1,\"average_guesses\":3,\
+         \"best\":\"rebut\",\"worst\":\"sissy\",\"previous_played\":1,"
+    ));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_import_shares() {
+    let home = temp_config_home("import-shares");
+    std::fs::create_dir_all(&home).unwrap();
+    let shares = home.join("shares.txt");
+    std::fs::write(
+        &shares,
+        "Wordle 250 3/6\n\n⬛🟨⬛⬛⬛\n⬛⬛🟩🟨⬛\n🟩🟩🟩🟩🟩\n\
+         what a day\nWordle 251 X/6\n⬛⬛⬛⬛⬛\n",
+    )
+    .unwrap();
+    let shares = shares.to_str().unwrap();
+    let output = rordle_with_config(&home, &["stats", "--import-shares", shares]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Imported 1 games, 0 were recorded already\n"
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("line 7: Wordle 251: expected 6 rows, found 1"));
+    let output = rordle_with_config(&home, &["stats", "--import-shares", shares]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Imported 0 games, 1 were recorded already\n"
+    );
+    let output = rordle_with_config(&home, &["history"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2022-02-24  import   won in 3  Wordle #250\n"
+    );
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_solve() {
+    let output = rordle(&["solve", "crane=g-g-g"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "chase\nchafe\ncease\n"
+    );
+    let output = rordle(&["solve", "crane=gx"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_solve_prefers_common_words() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-weighted-{}.txt", std::process::id()));
+    std::fs::write(&path, "cello 40\nhello 90000\njello 700\n").unwrap();
+    for weighting in ["info", "freq", "balanced"] {
+        let output = rordle(&[
+            "solve",
+            "--word-file",
+            path.to_str().unwrap(),
+            "--solver-weighting",
+            weighting,
+        ]);
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "hello\njello\ncello\n"
+        );
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_bench() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-bench-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\njolly\nholly\n").unwrap();
+    let output = rordle(&[
+        "bench",
+        "--games",
+        "5",
+        "--seed",
+        "1",
+        "-w",
+        &path.to_string_lossy(),
+    ]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Games:           5\nWon:             5 (100%)\n"));
+    // the games and their results do not depend on the number of threads
+    let results = |jobs: &str| {
+        let output = rordle(&[
+            "bench",
+            "--games",
+            "40",
+            "--seed",
+            "3",
+            "--jobs",
+            jobs,
+            "-w",
+            &path.to_string_lossy(),
+        ]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        // without the `parallel` feature everything runs on one thread
+        let jobs = if cfg!(feature = "parallel") {
+            jobs
+        } else {
+            "1"
+        };
+        assert!(stdout.contains(&format!("Jobs:            {} (", jobs)));
+        stdout.lines().take(3).collect::<Vec<_>>().join("\n")
+    };
+    assert_eq!(results("1"), results("4"));
+    assert!(!rordle(&["bench", "--jobs", "0"]).status.success());
+}
+
+#[test]
+fn test_bench_opener_cache() {
+    let home = temp_config_home("openers");
+    let path = home.join("words.txt");
+    std::fs::create_dir_all(&home).unwrap();
+    std::fs::write(&path, "hello\njolly\nholly\n").unwrap();
+    let bench = |args: &[&str]| {
+        let output = rordle_with_config(
+            &home,
+            &[
+                &["bench", "--games", "3", "-w", &path.to_string_lossy()],
+                args,
+            ]
+            .concat(),
+        );
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        stdout.lines().last().unwrap().to_string()
+    };
+    assert!(bench(&[]).contains("(ranked in "));
+    assert!(bench(&[]).contains("(cached in "));
+    assert!(bench(&["--rebuild-cache"]).contains("(ranked in "));
+    // a changed list or weighting is ranked again
+    assert!(bench(&["--solver-weighting", "info"]).contains("(ranked in "));
+    std::fs::write(&path, "hello\njolly\n").unwrap();
+    assert!(bench(&[]).contains("(ranked in "));
+    // a corrupt cache is silently ranked again
+    std::fs::write(home.join("rordle/openers"), "rordle openers 1\n???\n").unwrap();
+    let output = rordle_with_config(
+        &home,
+        &["bench", "--games", "1", "-w", &path.to_string_lossy()],
+    );
+    assert!(output.stderr.is_empty());
+    assert!(bench(&[]).contains("(cached in "));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_pick_and_play_challenge() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-pick-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\njolly\nholly\nab\n").unwrap();
+    let path = path.to_string_lossy();
+    let pick = |args: &[&str]| rordle(&[&["pick", "-w", &path], args].concat());
+
+    let output = pick(&["--count", "2", "--seed", "7", "--length", "5"]);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("contains the solutions"));
+    let words = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(words.lines().count(), 2);
+    // the same seed picks the same words
+    let again = pick(&["--count", "2", "--seed", "7", "--length", "5", "--quiet"]);
+    assert_eq!(String::from_utf8(again.stdout).unwrap(), words);
+    assert!(again.stderr.is_empty());
+
+    // more words than solutions prints all of them once
+    let output = pick(&["--count", "9", "--length", "5", "--codes", "-q"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines: Vec<(&str, &str)> = stdout
+        .lines()
+        .map(|line| line.split_once('\t').unwrap())
+        .collect();
+    lines.sort();
+    assert_eq!(
+        lines.iter().map(|(word, _)| *word).collect::<Vec<_>>(),
+        vec!["hello", "holly", "jolly"]
+    );
+
+    let (word, code) = lines[0];
+    assert!(!code.contains(word));
+    let output = rordle_with_input(
+        &["--stdin", "-w", &path, "--challenge", code],
+        "jolly\nhello\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .ends_with("hello ggggg\nYou won!\n"));
+    assert!(!rordle(&["--challenge", "0000"]).status.success());
+}
+
+#[test]
+fn test_pick_solution_by_number_and_seed() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-number-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\njolly\nholly\n").unwrap();
+    let path = path.to_string_lossy();
+    let output = rordle_with_input(&["--stdin", "-w", &path, "--number", "2"], "jolly\n");
+    assert_eq!(output.status.code(), Some(0));
+    // the word of a seed does not change between games
+    let play = |seed: &str| {
+        let output = rordle_with_input(&["--stdin", "-w", &path, "--seed", seed], "");
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(play("3"), play("3"));
+    assert!(!rordle(&["--number", "0"]).status.success());
+}
+
+#[test]
+fn test_hardcore_run() {
+    let home = temp_config_home("hardcore");
+    let path = std::env::temp_dir().join(format!("rordle-cli-hardcore-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\njolly\n").unwrap();
+    let path = path.to_string_lossy();
+    let args = ["--stdin", "--hardcore", "-w", &path, "--number", "2"];
+    let run = || std::fs::read_to_string(home.join("rordle").join("hardcore")).unwrap();
+    rordle_with_config_and_input(&home, &args, "jolly\n");
+    rordle_with_config_and_input(&home, &args, "jolly\n");
+    assert_eq!(run(), "2\n");
+    // leaving before the game is over ends the run
+    let output = rordle_with_config_and_input(&home, &args, "hello\n");
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(run(), "0\n");
+    // the games count for the stats as usual
+    let stats = std::fs::read_to_string(home.join("rordle").join("stats")).unwrap();
+    assert_eq!(stats.lines().count(), 2);
+    assert!(!rordle(&["--hardcore", "--no-stats"]).status.success());
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_profiles() {
+    let home = temp_config_home("profiles");
+    let path = std::env::temp_dir().join(format!("rordle-cli-profiles-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\njolly\n").unwrap();
+    let path = path.to_string_lossy();
+    let play = |profile: &str| {
+        let args = [
+            "--profile",
+            profile,
+            "--stdin",
+            "-w",
+            &path,
+            "--number",
+            "2",
+        ];
+        rordle_with_config_and_input(&home, &args, "jolly\n")
+    };
+    assert_eq!(play("alice").status.code(), Some(0));
+    assert_eq!(play("bob").status.code(), Some(0));
+    assert_eq!(play("bob").status.code(), Some(0));
+    let stats = |profile: &str| {
+        let file = home
+            .join("rordle")
+            .join("profiles")
+            .join(profile)
+            .join("stats");
+        std::fs::read_to_string(file).unwrap().lines().count()
+    };
+    assert_eq!((stats("alice"), stats("bob")), (1, 2));
+    assert!(!home.join("rordle").join("stats").exists());
+    let output = rordle_with_config(&home, &["stats", "--profile", "alice", "--format", "json"]);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("\"played\":1"));
+
+    let output = rordle_with_config(&home, &["--profile", "bob", "profiles"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "  alice\n* bob\n"
+    );
+    // two different profiles are rejected before anything is recorded
+    let output = rordle_with_config(&home, &["--profile", "alice", "stats", "--profile", "bob"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "rordle: --profile alice and --profile bob select different profiles, give only one\n"
+    );
+    let output = rordle_with_config(&home, &["--profile", "../bob", "profiles"]);
+    assert_eq!(output.status.code(), Some(2));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_practice_weak() {
+    let home = temp_config_home("practice-weak");
+    let path = std::env::temp_dir().join(format!("rordle-cli-weak-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\n").unwrap();
+    let path = path.to_string_lossy();
+    let args = ["--stdin", "--practice-weak", "-w", &path];
+    let output = rordle_with_config_and_input(&home, &args, "hello\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "warning: no weak letters in the history yet, picking any solution\n"
+    );
+    // the o of hello is only found by the fourth guess
+    let stats = home.join("rordle").join("stats");
+    let line = "1645747200\tplay\thello\tjolly,jolly,jolly,hello\twon\n";
+    std::fs::write(&stats, line.repeat(5)).unwrap();
+    let output = rordle_with_config_and_input(&home, &args, "hello\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stderr.is_empty());
+    assert!(!rordle(&["--practice-weak", "--number", "1"])
+        .status
+        .success());
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_pattern() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-pattern-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\njolly\nholly\nhi\n").unwrap();
+    let path = path.to_string_lossy();
+    // only jolly matches, hello can still be guessed
+    let args = ["--stdin", "-w", &path, "--pattern", "^J.*Y$"];
+    let output = rordle_with_input(&args, "hello\njolly\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "hello --ggy\njolly ggggg\nYou won!\n"
+    );
+    // hi matches, but not the length
+    let args = [
+        "--stdin",
+        "-w",
+        &path,
+        "--length",
+        "5",
+        "--pattern",
+        "^h.?$",
+    ];
+    let output = rordle_with_input(&args, "");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "rordle: no solution of the word list matches `^h.?$`\n"
+    );
+    let output = rordle(&["--pattern", "(ll"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("unclosed group"));
+}
+
+#[test]
+fn test_inline_words() {
+    let args = ["--stdin", "--words", "apple,Bread,crane", "--word", "crane"];
+    let output = rordle_with_input(&args, "bread\nslate\ncrane\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "bread -gyy-\nerror: Word is not valid\ncrane ggggg\nYou won!\n"
+    );
+    let output = rordle_with_input(&["--stdin", "--words", "apple,pear"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "rordle: --words: entry 2 `pear` has 4 letters, the first word has 5\n"
+    );
+}
+
+#[test]
+fn test_digraphs() {
+    let args = [
+        "--stdin",
+        "--words",
+        "llave,calle,perro",
+        "--digraphs",
+        "ll,rr",
+        "--word",
+        "llave",
+    ];
+    let output = rordle_with_input(&args, "perro\ncalle\nllave\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "perro -y--\ncalle -gyg\nllave gggg\nYou won!\n"
+    );
+    // the digraphs may also be declared by the word list
+    let path = std::env::temp_dir().join(format!("rordle-cli-digraphs-{}.txt", std::process::id()));
+    std::fs::write(&path, "#! digraphs: ll\nllave\ncalle\n").unwrap();
+    let path = path.to_string_lossy();
+    let args = ["--stdin", "-w", &path, "--word", "calle"];
+    let output = rordle_with_input(&args, "llave\ncalle\n");
+    std::fs::remove_file(&*path).unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "llave yg-g\ncalle gggg\nYou won!\n"
+    );
+}
+
+#[test]
+fn test_tags() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-tags-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello #easy\njolly #hard #fun\nholly #fun\n").unwrap();
+    let path = path.to_string_lossy();
+    // only jolly has both tags, hello can still be guessed
+    let args = ["--stdin", "-w", &path, "--tags", "fun,hard"];
+    let output = rordle_with_input(&args, "hello\njolly\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "hello --ggy\njolly ggggg\nYou won!\n"
+    );
+    let args = ["--stdin", "-w", &path, "--tags", "easy,hard", "--tags-any"];
+    let output = rordle_with_input(&args, "jolly\nholly\nhello\n");
+    assert_eq!(output.status.code(), Some(0));
+    let args = ["--stdin", "-w", &path, "--tags", "easy,fun"];
+    let output = rordle_with_input(&args, "");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "rordle: no solution of the word list has the tags `easy,fun`\n"
+    );
+}
+
+#[test]
+fn test_pack() {
+    let home = temp_config_home("pack");
+    std::fs::create_dir_all(&home).unwrap();
+    let path = home.join("pack.txt");
+    std::fs::write(
+        &path,
+        "#! name: Test\nFirst | hello\nSecond | jolly | tries: 2\n",
+    )
+    .unwrap();
+    let path = path.to_string_lossy();
+    let words = home.join("words.txt");
+    std::fs::write(&words, "hello\njolly\n").unwrap();
+    let words = words.to_string_lossy();
+    let play = |input: &str| {
+        let args = ["--stdin", "--pack", &path, "-w", &words];
+        let output = rordle_with_config_and_input(&home, &args, input);
+        (
+            output.status.code(),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    };
+    // the input ends during the second puzzle
+    let (code, output) = play("hello\n");
+    assert_eq!(code, Some(4));
+    assert!(output.starts_with("Test  Puzzle 1/2: First\nhello ggggg\nYou won!\n"));
+    assert!(output.contains("Puzzle 1/2 complete\nTest  Puzzle 2/2: Second\n"));
+    assert!(output.ends_with("Progress saved after 1 of 2 puzzles\n"));
+    // the next run goes on with the second puzzle
+    let (code, output) = play("hello\njolly\n");
+    assert_eq!(code, Some(0));
+    assert!(output.starts_with("Test  Puzzle 2/2: Second\n"));
+    assert!(output.ends_with(
+        " 1. First  hello  1/6\n 2. Second  jolly  2/2\n\
+         Pack complete: 2 of 2 puzzles solved\n1.5 guesses on average\n"
+    ));
+
+    let output = rordle(&["encode-pack", &path]);
+    let encoded = String::from_utf8(output.stdout).unwrap();
+    assert!(encoded.starts_with("#! name: Test\n#! codes: yes\nFirst | "));
+    assert!(!encoded.contains("hello"));
+
+    std::fs::write(home.join("broken.txt"), "a | hello\nb | hello\n").unwrap();
+    let broken = home.join("broken.txt");
+    let output = rordle(&["--pack", &broken.to_string_lossy(), "-w", &words]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "rordle: {}: line 2: the word of line 1 again\n",
+            broken.display()
+        )
+    );
+}
+
+#[test]
+fn test_prefill() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-prefill-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello\n").unwrap();
+    let path = path.to_string_lossy();
+    let play = |seed: &str| {
+        let args = ["--stdin", "-w", &path, "--seed", seed, "--prefill", "3"];
+        let output = rordle_with_input(&args, "hello\n");
+        assert_eq!(output.status.code(), Some(0));
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let output = play("5");
+    let (pattern, rest) = output.split_once('\n').unwrap();
+    assert_eq!(pattern.matches('_').count(), 2, "{}", pattern);
+    assert!(pattern
+        .chars()
+        .zip("hello".chars())
+        .all(|(p, c)| p == '_' || p == c));
+    assert_eq!(rest, "hello ggggg\nYou won!\n");
+    assert_eq!(play("5"), output);
+    assert!(!rordle(&["--word", "hello", "--prefill", "5"])
+        .status
+        .success());
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_stdin_won_game() {
+    let output = rordle_with_input(
+        &["--stdin", "--word", "hello"],
+        "crane\nslate\nhellp\nhello\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "crane ----y\nslate -y--y\nerror: Word is not valid\nhello ggggg\nYou won!\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_coop_game() {
+    let output = rordle_with_input(
+        &["--stdin", "--word", "hello", "--coop", "Alice, Bob"],
+        "crane\nhellp\nslate\nhello\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Alice: crane ----y\nerror: Word is not valid\nBob: slate -y--y\nAlice: hello ggggg\n\
+         You won!\nAlice found it on Bob's setup\n"
+    );
+    assert!(!rordle(&["--word", "hello", "--coop", "Alice"])
+        .status
+        .success());
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_team_game() {
+    let output = rordle_with_input(
+        &["--stdin", "--word", "hello", "--team", "Mum,Dad,Kid"],
+        "crane\nhellp\nslate\nhells\nhello\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mum: crane ----y\nerror: Word is not valid\nDad: slate -y--y\nKid: hells gggg-\n\
+         Mum: hello ggggg\nYou won!\nMum found it\nMum: greens discovered: 1\n\
+         Dad: greens discovered: 0\nKid: greens discovered: 4\n"
+    );
+    assert!(!rordle(&["--coop", "Alice,Bob", "--team", "Mum,Dad"])
+        .status
+        .success());
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_german_messages() {
+    let output = rordle_with_input(
+        &["--stdin", "--word", "hello", "--lang", "de", "-t", "2"],
+        "hellp\ncrane\nslate\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Fehler: Das Wort ist nicht gültig\ncrane ----y\nslate -y--y\nVerloren! Das Wort war: hello\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_stdin_lost_game() {
+    let output = rordle_with_input(
+        &["play", "--stdin", "--word", "hello", "--max-tries", "2"],
+        "crane\nslate\nhello\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "crane ----y\nslate -y--y\nYou lost! The word was: hello\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_stdin_input_ends() {
+    let output = rordle_with_input(&["--stdin", "--word", "hello"], "crane\n");
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "crane ----y\nGame abandoned. The word was: hello\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_json_exit_codes() {
+    let won = rordle_with_input(
+        &["--json", "--word", "hello"],
+        "{\"type\":\"guess\",\"word\":\"hello\"}\n",
+    );
+    assert_eq!(won.status.code(), Some(0));
+    let lost = rordle_with_input(
+        &["--json", "--word", "hello", "-t", "1", "--any-word"],
+        "{\"type\":\"guess\",\"word\":\"jolly\"}\n",
+    );
+    assert_eq!(lost.status.code(), Some(1));
+    let abandoned = rordle_with_input(&["--json", "--word", "hello"], "{\"type\":\"quit\"}\n");
+    assert_eq!(abandoned.status.code(), Some(4));
+}
+
+#[test]
+fn test_any_word_is_made_of_letters() {
+    // a tab or comma in a guess would break the line of its record
+    let args = [
+        "--stdin",
+        "--any-word",
+        "--words",
+        "hello,world",
+        "--seed",
+        "1",
+    ];
+    let output = rordle_with_input(&args, "ab\tcd\nab,cd\nworld\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("'\t' is not in the alphabet"), "{}", stdout);
+    assert!(stdout.contains("',' is not in the alphabet"), "{}", stdout);
+    let output = rordle(&["stats"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("Played:         1\n"));
+}
+
+#[test]
+fn test_exit_codes_in_help() {
+    let output = rordle(&["--help"]);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("EXIT CODES:\n    0    The word was found\n"));
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_post_game_hook() {
+    let dir = temp_config_home("hook");
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("hook.sh");
+    let log = dir.join("hook.log");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\ncat > '{0}'\necho \"$RORDLE_RESULT $RORDLE_GUESSES $RORDLE_DAY\" >> '{0}'\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    let hook = format!("sh '{}'", script.display());
+    let output = rordle_with_input(
+        &["--stdin", "--word", "hello", "--post-game-hook", &hook],
+        "crane\nhello\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let logged = std::fs::read_to_string(&log).unwrap();
+    let lines: Vec<&str> = logged.lines().collect();
+    assert!(lines[0]
+        .starts_with("{\"result\":\"won\",\"word\":\"hello\",\"guesses\":[\"crane\",\"hello\"],"));
+    assert!(lines[1].starts_with("won 2 20"), "{}", lines[1]);
+
+    // a failing hook does not change the outcome of the game
+    let output = rordle_with_input(
+        &["--stdin", "--word", "hello", "--post-game-hook", "exit 1"],
+        "hello\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "warning: post-game hook failed: it exited with exit status: 1\n"
+    );
+}
+
+#[test]
+fn test_past_daily() {
+    let home = temp_config_home("past-daily");
+    std::fs::create_dir_all(&home).unwrap();
+    let words = home.join("words.txt");
+    std::fs::write(&words, "hello\n").unwrap();
+    let words = words.to_string_lossy();
+    let daily = |date: &str, more: &[&str]| {
+        let mut args = vec!["daily", "--stdin", "--word-file", &words, "--date", date];
+        args.extend(more);
+        rordle_with_config_and_input(&home, &args, "hello\n")
+    };
+    let output = daily("2999-01-01", &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("rordle: there is no puzzle for 2999-01-01 yet"));
+    let output = daily("2021-06-18", &["--daily-epoch", "wordle"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "rordle: there is no puzzle for 2021-06-18, the first one is for 2021-06-19\n"
+    );
+    assert_eq!(daily("2024-02-30", &[]).status.code(), Some(2));
+
+    assert_eq!(daily("2024-11-03", &[]).status.code(), Some(0));
+    assert_eq!(
+        daily("2024-11-04", &["--count-streak"]).status.code(),
+        Some(0)
+    );
+    // a puzzle is only recorded once, in either numbering
+    let output = daily("2024-11-03", &["--daily-epoch", "wordle"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "warning: Wordle #1233 was played before, this replay is not recorded\n"
+    );
+    let output = rordle_with_config(&home, &["history"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2024-11-04  daily  hello  won in 1  Rordle #20031\n\
+         2024-11-03  backfill hello  won in 1  Rordle #20030\n"
+    );
+    let output = rordle_with_config(&home, &["stats"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Current streak: 1\n"), "{}", stdout);
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_no_stats() {
+    let home = temp_config_home("no-stats");
+    std::fs::create_dir_all(&home).unwrap();
+    let words = home.join("words.txt");
+    std::fs::write(&words, "hello\n").unwrap();
+    let pack = home.join("pack.txt");
+    std::fs::write(&pack, "First | hello\nSecond | jolly\n").unwrap();
+    let (words, pack) = (words.to_string_lossy(), pack.to_string_lossy());
+    let runs: [&[&str]; 3] = [
+        &["--no-stats", "--stdin", "--word-file", &words],
+        &["daily", "--no-stats", "--stdin", "--word-file", &words],
+        &[
+            "--no-stats",
+            "--stdin",
+            "--pack",
+            &pack,
+            "--word-file",
+            &words,
+        ],
+    ];
+    for args in runs {
+        let output = rordle_with_config_and_input(&home, args, "hello\n");
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("hello ggggg\nYou won!\n"));
+    }
+    let output = rordle_with_config(&home, &["history"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "");
+    assert!(!home.join("rordle").join("packs").exists());
+    // the daily puzzle played for practice is no replay
+    let output = rordle_with_config_and_input(
+        &home,
+        &["daily", "--stdin", "--word-file", &words],
+        "hello\n",
+    );
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_no_plurals() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-plurals-{}.txt", std::process::id()));
+    std::fs::write(&path, "cat\ncats\nglass\nlens\n").unwrap();
+    let path = path.to_string_lossy();
+    // the singular of a longer word counts even if the length skips it
+    let output = rordle(&[
+        "pick",
+        "--quiet",
+        "-n",
+        "10",
+        "-w",
+        &path,
+        "--length",
+        "4",
+        "--no-plurals",
+    ]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "lens\n");
+    // plurals can still be guessed
+    let output = rordle_with_input(
+        &["--stdin", "-w", &path, "--length", "4", "--no-plurals"],
+        "cats\nlens\n",
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "cats ---g\nlens gggg\nYou won!\n"
+    );
+}
+
+#[test]
+fn test_export_html() {
+    let home = temp_config_home("export-html");
+    std::fs::create_dir_all(&home).unwrap();
+    let words = home.join("words.txt");
+    std::fs::write(&words, "hello\njolly\n").unwrap();
+    let (words, board) = (words.to_string_lossy(), home.join("board.html"));
+    let board_path = board.to_string_lossy();
+    let args = [
+        "--stdin",
+        "--word",
+        "hello",
+        "--word-file",
+        &words,
+        "--export-html",
+        &board_path,
+    ];
+    rordle_with_config_and_input(&home, &args, "jolly\nhello\n");
+    let html = std::fs::read_to_string(&board).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h1>Rordle 2/6</h1>"));
+    assert!(html.contains(">J</td>"));
+
+    let args = [&args[..], &["--no-letters"]].concat();
+    rordle_with_config_and_input(&home, &args, "jolly\nhello\n");
+    let html = std::fs::read_to_string(&board).unwrap();
+    assert!(!html.contains(">J</td>"));
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_demo_invalid_script() {
+    let path = std::env::temp_dir().join(format!("rordle-cli-demo-{}.txt", std::process::id()));
+    std::fs::write(&path, "word crane\ntype crane\npause soon\n").unwrap();
+    let output = rordle(&["demo", "--script", &path.to_string_lossy()]);
+    assert_eq!(output.status.code(), Some(2));
+    // reported before the terminal is set up, nothing is rendered
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "rordle: {}: line 3: `soon` is not a duration like 120ms or 1.5s\n",
+            path.display()
+        )
+    );
+    std::fs::remove_file(&path).unwrap();
+}
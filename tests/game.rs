@@ -0,0 +1,90 @@
+use rordle_core::game::{score, GameError, GameOptions, GameState, HitInfo};
+use rordle_core::words::{load_sources, FileSource, WordStore};
+
+fn type_and_confirm(game_state: &mut GameState, word: &str) {
+    for c in word.chars() {
+        game_state.add_char(c);
+    }
+    game_state.confirm();
+}
+
+#[test]
+#[cfg(feature = "bundled-words")]
+fn test_full_game_is_won() {
+    use rordle_core::words::{EmbeddedSource, WordSource};
+    let word_list = EmbeddedSource.load().unwrap();
+    let valid_words: WordStore = word_list.words.iter().collect();
+    let mut game_state =
+        GameState::new("cigar".to_string(), valid_words, GameOptions::default()).unwrap();
+
+    type_and_confirm(&mut game_state, "xyzzy");
+    assert_eq!(game_state.last_error, Some(GameError::InvalidWord));
+    assert!(game_state.guesses.is_empty());
+
+    type_and_confirm(&mut game_state, "Rebut");
+    assert_eq!(game_state.last_error, None);
+    assert_eq!(
+        game_state.get_guess_hits(0),
+        vec![
+            HitInfo::Contains,
+            HitInfo::Miss,
+            HitInfo::Miss,
+            HitInfo::Miss,
+            HitInfo::Miss
+        ]
+    );
+    assert!(!game_state.won());
+
+    type_and_confirm(&mut game_state, "cigar");
+    assert!(game_state.won());
+    assert_eq!(game_state.guesses, vec!["rebut", "cigar"]);
+}
+
+#[test]
+fn test_full_game_is_lost() {
+    let path = std::env::temp_dir().join(format!("rordle-it-{}.txt", std::process::id()));
+    std::fs::write(
+        &path,
+        "#! name: Test\nhello\njolly\nholly\nbelly\ndolly\nfolly\ngolly\n",
+    )
+    .unwrap();
+    let file = path.to_string_lossy().to_string();
+    let word_list = load_sources(
+        &[Box::new(FileSource {
+            path: file,
+            length: None,
+            clean: false,
+        })],
+        false,
+    )
+    .unwrap();
+    assert_eq!(word_list.header.name, Some("Test".to_string()));
+    let valid_words: WordStore = word_list.words.iter().collect();
+    let mut game_state =
+        GameState::new("hello".to_string(), valid_words, GameOptions::default()).unwrap();
+
+    for word in ["jolly", "holly", "belly", "dolly", "folly", "golly"] {
+        type_and_confirm(&mut game_state, word);
+        assert_eq!(game_state.last_error, None);
+    }
+    assert_eq!(game_state.guesses.len(), game_state.max_tries as usize);
+    assert!(!game_state.won());
+
+    type_and_confirm(&mut game_state, "hello");
+    assert_eq!(game_state.last_error, Some(GameError::GameOver));
+}
+
+#[test]
+fn test_score() {
+    assert_eq!(
+        score("jolly", "hello"),
+        vec![
+            HitInfo::Miss,
+            HitInfo::Contains,
+            HitInfo::Hit,
+            HitInfo::Hit,
+            HitInfo::Miss
+        ]
+    );
+    assert_eq!(score("hello", "hello"), vec![HitInfo::Hit; 5]);
+}
@@ -0,0 +1,132 @@
+//! Drives games over the JSON protocol of `rordle --json` through pipes.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+struct Frontend {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Frontend {
+    fn start(name: &str, words: &str, args: &[&str]) -> Frontend {
+        let dir = std::env::temp_dir().join(format!("rordle-json-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let word_file = dir.join("words.txt");
+        std::fs::write(&word_file, words).unwrap();
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rordle"))
+            .arg("--json")
+            .arg("--word-file")
+            .arg(&word_file)
+            .args(args)
+            // keep the config and stats of the user out of the tests
+            .env("XDG_CONFIG_HOME", &dir)
+            .env("XDG_DATA_HOME", &dir)
+            .env("LC_ALL", "C")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Frontend {
+            child,
+            stdin,
+            stdout,
+        }
+    }
+
+    fn receive(&mut self) -> String {
+        let mut line = String::new();
+        self.stdout.read_line(&mut line).unwrap();
+        line.trim_end().to_string()
+    }
+
+    /// Sends a command and returns the reply.
+    fn send(&mut self, command: &str) -> String {
+        writeln!(self.stdin, "{}", command).unwrap();
+        self.stdin.flush().unwrap();
+        self.receive()
+    }
+
+    fn finish(mut self) -> std::process::ExitStatus {
+        drop(self.stdin);
+        let mut rest = String::new();
+        std::io::Read::read_to_string(&mut self.stdout, &mut rest).unwrap();
+        assert_eq!(rest, "", "no output after the end of the game");
+        self.child.wait().unwrap()
+    }
+}
+
+#[test]
+fn test_won_game() {
+    let mut frontend = Frontend::start("won", "hello\n", &[]);
+    assert_eq!(
+        frontend.receive(),
+        r#"{"type":"state","length":5,"max_tries":6,"guesses":[],"over":false}"#
+    );
+    assert_eq!(
+        frontend.send(r#"{"type":"guess","word":"hel"}"#),
+        r#"{"type":"error","kind":"wrong_length","message":"Word is not the correct length, 3 letters instead of 5"}"#
+    );
+    assert_eq!(
+        frontend.send(r#"{"type":"guess","word":"jolly"}"#),
+        r#"{"type":"error","kind":"invalid_word","message":"Word is not valid"}"#
+    );
+    assert_eq!(
+        frontend.send("guess hello"),
+        r#"{"type":"error","kind":"invalid_json","message":"unexpected `g` at 0"}"#
+    );
+    assert_eq!(
+        frontend.send(r#"{"type":"guess","word":"HELLO"}"#),
+        r#"{"type":"feedback","word":"hello","hits":["hit","hit","hit","hit","hit"]}"#
+    );
+    assert_eq!(
+        frontend.receive(),
+        r#"{"type":"result","won":true,"word":"hello","guesses":1}"#
+    );
+    assert_eq!(frontend.finish().code(), Some(0));
+}
+
+#[test]
+fn test_lost_game() {
+    let mut frontend = Frontend::start("lost", "hello\n", &["--any-word", "--max-tries", "2"]);
+    frontend.receive();
+    assert_eq!(
+        frontend.send(r#"{"type":"guess","word":"jolly"}"#),
+        r#"{"type":"feedback","word":"jolly","hits":["miss","contains","hit","hit","miss"]}"#
+    );
+    assert_eq!(
+        frontend.send(r#"{"type":"state"}"#),
+        r#"{"type":"state","length":5,"max_tries":2,"guesses":[{"word":"jolly","hits":["miss","contains","hit","hit","miss"]}],"over":false}"#
+    );
+    assert_eq!(
+        frontend.send(r#"{"type":"guess","word":"world"}"#),
+        r#"{"type":"feedback","word":"world","hits":["miss","contains","miss","hit","miss"]}"#
+    );
+    assert_eq!(
+        frontend.receive(),
+        r#"{"type":"result","won":false,"word":"hello","guesses":2}"#
+    );
+    assert_eq!(frontend.finish().code(), Some(1));
+}
+
+#[test]
+fn test_quit() {
+    let mut frontend = Frontend::start("quit", "hello\n", &[]);
+    frontend.receive();
+    writeln!(frontend.stdin, r#"{{"type":"quit"}}"#).unwrap();
+    assert_eq!(frontend.finish().code(), Some(4));
+}
+
+#[test]
+fn test_deeply_nested_command() {
+    let mut frontend = Frontend::start("nested", "hello\n", &[]);
+    frontend.receive();
+    assert_eq!(
+        frontend.send(&"[".repeat(20000)),
+        r#"{"type":"error","kind":"invalid_json","message":"too deeply nested"}"#
+    );
+    assert_eq!(frontend.finish().code(), Some(4));
+}
@@ -0,0 +1,94 @@
+//! Plays games against `rordle serve` over TCP.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+/// Starts a server for a word list with the only word `hello` and
+/// returns it with the address it listens on.
+fn start(name: &str, args: &[&str]) -> (Child, String) {
+    let dir = std::env::temp_dir().join(format!("rordle-serve-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let word_file = dir.join("words.txt");
+    std::fs::write(&word_file, "hello\n").unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rordle"))
+        .args(["serve", "--bind", "127.0.0.1", "--port", "0", "--word-file"])
+        .arg(&word_file)
+        .args(args)
+        .env("XDG_CONFIG_HOME", &dir)
+        .env("XDG_DATA_HOME", &dir)
+        .env("LC_ALL", "C")
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stderr.as_mut().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let address = line
+        .strip_prefix("listening on ")
+        .and_then(|rest| rest.split(',').next())
+        .unwrap_or_else(|| panic!("unexpected first line {:?}", line))
+        .to_string();
+    (child, address)
+}
+
+/// Interrupts the server like Ctrl+C and returns its exit code.
+fn interrupt(mut child: Child) -> Option<i32> {
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    child.wait().unwrap().code()
+}
+
+fn read_all(stream: &mut TcpStream) -> String {
+    let mut output = String::new();
+    stream.read_to_string(&mut output).unwrap();
+    output
+}
+
+#[test]
+fn test_play_over_tcp() {
+    let (child, address) = start("play", &["--max-tries", "3", "--any-word"]);
+    let mut stream = TcpStream::connect(&address).unwrap();
+    stream.write_all(b"jolly\r\nhel\r\nhello\r\n").unwrap();
+    assert_eq!(
+        read_all(&mut stream),
+        "Welcome to rordle! Guess the 5-letter word in 3 tries.\n\
+         Hints: g = right letter, right spot; y = in the word, wrong spot; - = not in the word\n\
+         > jolly -ygg-\n\
+         > error: Word is not the correct length, 3 letters instead of 5\n\
+         > hello ggggg\n\
+         You won!\n"
+    );
+
+    // the server keeps running after a player disconnects early
+    drop(TcpStream::connect(&address).unwrap());
+    let mut stream = TcpStream::connect(&address).unwrap();
+    stream.write_all(b"hello\n").unwrap();
+    assert!(read_all(&mut stream).ends_with("> hello ggggg\nYou won!\n"));
+    assert_eq!(interrupt(child), Some(0));
+}
+
+#[test]
+fn test_connection_limit_and_shutdown() {
+    let (child, address) = start("limit", &["--max-connections", "1", "--daily"]);
+    let first = TcpStream::connect(&address).unwrap();
+    let mut welcome = String::new();
+    let mut reader = BufReader::new(first.try_clone().unwrap());
+    reader.read_line(&mut welcome).unwrap();
+    assert!(welcome.starts_with("Welcome to rordle!"));
+
+    let mut second = TcpStream::connect(&address).unwrap();
+    assert_eq!(
+        read_all(&mut second),
+        "The server is full, try again later.\n"
+    );
+
+    // open games are ended when the server shuts down
+    assert_eq!(interrupt(child), Some(0));
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert!(rest.contains("The server is shutting down."), "{}", rest);
+}